@@ -5,9 +5,12 @@
 -- ============================================================================
 
 -- Drop all tables (in reverse order of dependencies)
+DROP TABLE IF EXISTS webhook_dead_letters CASCADE;
+DROP TABLE IF EXISTS audit_log CASCADE;
 DROP TABLE IF EXISTS sensors CASCADE;
 DROP TABLE IF EXISTS gyros_versions CASCADE;
 DROP TABLE IF EXISTS game_version_channels CASCADE;
+DROP TABLE IF EXISTS arcade_allowed_channels CASCADE;
 DROP TABLE IF EXISTS arcade_game_assignments CASCADE;
 DROP TABLE IF EXISTS game_versions CASCADE;
 DROP TABLE IF EXISTS games CASCADE;
@@ -54,7 +57,8 @@ CREATE TABLE customers (
     name VARCHAR(255) NOT NULL,
     phone_number VARCHAR(50),
     email VARCHAR(255),
-    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMP WITH TIME ZONE  -- Soft delete marker, NULL while active
 );
 
 CREATE INDEX idx_customers_name ON customers(name);
@@ -63,6 +67,7 @@ COMMENT ON TABLE customers IS 'Customers who own arcade installations';
 COMMENT ON COLUMN customers.name IS 'Customer display name';
 COMMENT ON COLUMN customers.phone_number IS 'Contact phone number (optional)';
 COMMENT ON COLUMN customers.email IS 'Contact email address (optional)';
+COMMENT ON COLUMN customers.deleted_at IS 'When the customer was deleted; deleted customers are hidden but can be restored';
 
 -- ============================================================================
 -- ARCADES TABLE
@@ -98,14 +103,19 @@ COMMENT ON COLUMN arcades.installed_games IS 'JSON map of installed games: {"gam
 -- ============================================================================
 CREATE TABLE games (
     id SERIAL PRIMARY KEY,
-    name VARCHAR(255) UNIQUE NOT NULL,
-    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+    name VARCHAR(255) NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMP WITH TIME ZONE  -- Soft delete marker, NULL while active
 );
 
 -- Index for game name lookups
 CREATE INDEX idx_games_name ON games(name);
 
+-- Names only need to be unique among active games, so a deleted game's name can be reused
+CREATE UNIQUE INDEX idx_games_name_active ON games(name) WHERE deleted_at IS NULL;
+
 COMMENT ON TABLE games IS 'VR games available in the system';
+COMMENT ON COLUMN games.deleted_at IS 'When the game was deleted; deleted games are hidden but can be restored';
 
 -- ============================================================================
 -- ARCADE_GAME_ASSIGNMENTS TABLE
@@ -115,6 +125,7 @@ CREATE TABLE arcade_game_assignments (
     arcade_id INTEGER NOT NULL REFERENCES arcades(id) ON DELETE CASCADE,
     game_id INTEGER NOT NULL REFERENCES games(id) ON DELETE CASCADE,
     created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMP WITH TIME ZONE,  -- Set together with games.deleted_at on a forced delete
     PRIMARY KEY (arcade_id, game_id)
 );
 
@@ -122,6 +133,20 @@ CREATE INDEX idx_arcade_game_assignments_arcade_id ON arcade_game_assignments(ar
 CREATE INDEX idx_arcade_game_assignments_game_id ON arcade_game_assignments(game_id);
 
 COMMENT ON TABLE arcade_game_assignments IS 'Explicit game assignments per arcade. Arcade only receives games listed here.';
+COMMENT ON COLUMN arcade_game_assignments.deleted_at IS 'Matches the deleted_at of the game it was deleted with, so restoring the game restores it';
+
+-- ============================================================================
+-- ARCADE_ALLOWED_CHANNELS TABLE
+-- Release channels an arcade may request besides its own (e.g. beta for one game)
+-- ============================================================================
+CREATE TABLE arcade_allowed_channels (
+    arcade_id INTEGER NOT NULL REFERENCES arcades(id) ON DELETE CASCADE,
+    channel_id INTEGER NOT NULL REFERENCES release_channels(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (arcade_id, channel_id)
+);
+
+COMMENT ON TABLE arcade_allowed_channels IS 'Extra release channels an arcade may request with ?channel=; its own channel is always allowed';
 
 -- ============================================================================
 -- GAME_VERSIONS TABLE
@@ -297,6 +322,48 @@ COMMENT ON COLUMN sensors.mac_address IS 'BLE MAC address of the sensor';
 COMMENT ON COLUMN sensors.firmware_version IS 'Currently installed firmware version';
 COMMENT ON COLUMN sensors.arcade_id IS 'Arcade this sensor belongs to (matched by machine_id)';
 
+-- ============================================================================
+-- AUDIT LOG TABLE
+-- Records who created, updated or deleted admin entities
+-- ============================================================================
+CREATE TABLE audit_log (
+    id BIGSERIAL PRIMARY KEY,
+    actor VARCHAR(255) NOT NULL,
+    action VARCHAR(50) NOT NULL,
+    entity_type VARCHAR(50) NOT NULL,
+    entity_id INTEGER NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_audit_log_entity ON audit_log(entity_type, entity_id);
+CREATE INDEX idx_audit_log_actor ON audit_log(actor);
+CREATE INDEX idx_audit_log_created_at ON audit_log(created_at DESC);
+
+COMMENT ON TABLE audit_log IS 'Admin mutations made through Giratina, one row per change';
+COMMENT ON COLUMN audit_log.actor IS 'IAP-authenticated email of the admin who made the change';
+COMMENT ON COLUMN audit_log.action IS 'create, update, delete, restore, publish, assign_games, ...';
+COMMENT ON COLUMN audit_log.entity_id IS 'ID of the changed row; not a foreign key so entries outlive deletions';
+
+-- ============================================================================
+-- WEBHOOK DEAD LETTERS TABLE
+-- Webhook deliveries that failed every attempt
+-- ============================================================================
+CREATE TABLE webhook_dead_letters (
+    id BIGSERIAL PRIMARY KEY,
+    event VARCHAR(100) NOT NULL,
+    url TEXT NOT NULL,
+    payload JSONB NOT NULL,
+    last_error TEXT NOT NULL,
+    attempts INTEGER NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_webhook_dead_letters_created_at ON webhook_dead_letters(created_at DESC);
+
+COMMENT ON TABLE webhook_dead_letters IS 'Catalog change notifications that could not be delivered';
+COMMENT ON COLUMN webhook_dead_letters.event IS 'Event name such as game_version.publish';
+COMMENT ON COLUMN webhook_dead_letters.payload IS 'Exact JSON body that was POSTed, for replaying';
+
 -- ============================================================================
 -- SCRIPT COMPLETE
 -- All tables have been recreated with the new schema
@@ -36,7 +36,6 @@ impl From<MachineId> for String {
 /// IAP (Identity-Aware Proxy) authenticated user
 /// Extracts user email from Google Cloud IAP headers
 pub struct IapUser {
-    #[allow(dead_code)]
     pub email: String,
 }
 
@@ -2,20 +2,23 @@ use crate::{
     api::IapUser,
     error::{AppError, Result},
     models::{
-        Arcade, CreateChannelRequest, Customer, Game, GameVersion, GameVersionWithChannels,
-        GyrosVersion, PublishVersionRequest, ReleaseChannel, SnorlaxVersion,
-        UpdateArcadeChannelRequest, UpdateChannelRequest,
+        Arcade, AuditLogEntry, AuditLogFilter, CreateChannelRequest, Customer, Game, GameVersion,
+        GameVersionWithChannels, GyrosVersion, Page, PageParams, Pagination, PublishVersionRequest,
+        ReleaseChannel, SearchResults, SnorlaxVersion, UpdateArcadeAllowedChannelsRequest, UpdateArcadeChannelRequest, UpdateChannelRequest,
     },
-    services::{AdminService, GcsService, GyrosService, SnorlaxService},
+    services::{AdminService, AuditService, GyrosService, ObjectStorage, SnorlaxService, WebhookService},
 };
 use axum::{
-    extract::{Path, State},
+    extract::{rejection::QueryRejection, Path, Query, State},
     http::StatusCode,
-    Json,
+    Extension, Json,
 };
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::sync::Arc;
 
+use super::query_params;
+
 // ============================================================================
 // REQUEST/RESPONSE TYPES
 // ============================================================================
@@ -44,6 +47,49 @@ pub struct ArcadeWithGames {
     pub assigned_game_ids: Vec<i32>,
 }
 
+/// Most assignments accepted in one bulk request
+const MAX_BULK_ASSIGNMENTS: usize = 1000;
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct GameAssignment {
+    pub arcade_id: i32,
+    pub game_id: i32,
+}
+
+/// Either explicit (arcade, game) pairs or one game for many arcades
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum BulkAssignmentRequest {
+    Pairs { assignments: Vec<GameAssignment> },
+    GameToArcades { game_id: i32, arcade_ids: Vec<i32> },
+}
+
+impl BulkAssignmentRequest {
+    fn into_assignments(self) -> Vec<GameAssignment> {
+        match self {
+            Self::Pairs { assignments } => assignments,
+            Self::GameToArcades { game_id, arcade_ids } => arcade_ids
+                .into_iter()
+                .map(|arcade_id| GameAssignment { arcade_id, game_id })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AssignmentStatus {
+    Assigned,
+    AlreadyAssigned,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkAssignmentResult {
+    #[serde(flatten)]
+    pub assignment: GameAssignment,
+    pub status: AssignmentStatus,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CreateGameRequest {
     pub name: String,
@@ -157,9 +203,49 @@ pub struct GameWithBackground {
     pub id: i32,
     pub name: String,
     pub created_at: chrono::DateTime<chrono::Utc>,
+    pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
     pub background_url: Option<String>,
 }
 
+/// `?include_deleted=true` shows soft-deleted rows, for recovering them
+#[derive(Debug, Default, Deserialize)]
+pub struct DeletedFilter {
+    #[serde(default)]
+    pub include_deleted: bool,
+}
+
+/// `?q=` for the admin search
+#[derive(Debug, Default, Deserialize)]
+pub struct SearchParams {
+    #[serde(default)]
+    pub q: String,
+}
+
+/// Query filters for the game list
+#[derive(Debug, Default, Deserialize)]
+pub struct GameListFilter {
+    #[serde(default)]
+    pub include_deleted: bool,
+    /// Case-insensitive substring of the game name
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct DeleteGameParams {
+    /// Also delete the game's arcade assignments instead of refusing
+    #[serde(default)]
+    pub force: bool,
+}
+
+// ============================================================================
+// PAGINATION
+// ============================================================================
+
+/// Validate `?limit=&offset=`, reporting malformed values with the JSON error shape
+fn pagination(query: std::result::Result<Query<PageParams>, QueryRejection>) -> Result<Pagination> {
+    query_params(query)?.validate()
+}
+
 // ============================================================================
 // CUSTOMER ENDPOINTS
 // ============================================================================
@@ -167,7 +253,8 @@ pub struct GameWithBackground {
 /// POST /api/admin/customers
 pub async fn create_customer(
     State(service): State<Arc<AdminService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    user: IapUser,
     Json(payload): Json<CreateCustomerRequest>,
 ) -> Result<(StatusCode, Json<CustomerWithArcades>)> {
     let customer = service
@@ -177,6 +264,7 @@ pub async fn create_customer(
             payload.email.as_deref(),
         )
         .await?;
+    audit.record(&user.email, "create", "customer", customer.id).await;
     Ok((StatusCode::CREATED, Json(CustomerWithArcades {
         customer,
         arcade_ids: vec![],
@@ -187,8 +275,12 @@ pub async fn create_customer(
 pub async fn list_customers(
     State(service): State<Arc<AdminService>>,
     _user: IapUser,
-) -> Result<Json<Vec<CustomerWithArcades>>> {
-    let customers = service.list_customers().await?;
+    query: std::result::Result<Query<PageParams>, QueryRejection>,
+    filter: std::result::Result<Query<DeletedFilter>, QueryRejection>,
+) -> Result<Json<Page<CustomerWithArcades>>> {
+    let pagination = pagination(query)?;
+    let filter = query_params(filter)?;
+    let (customers, total) = service.list_customers(pagination, filter.include_deleted).await?;
     let mut result = Vec::with_capacity(customers.len());
     for customer in customers {
         let arcade_ids = service.get_customer_arcade_ids(customer.id).await?;
@@ -197,7 +289,7 @@ pub async fn list_customers(
             arcade_ids,
         });
     }
-    Ok(Json(result))
+    Ok(Json(Page::new(result, total, pagination)))
 }
 
 /// GET /api/admin/customers/{id}
@@ -205,8 +297,12 @@ pub async fn get_customer(
     State(service): State<Arc<AdminService>>,
     _user: IapUser,
     Path(id): Path<i32>,
+    filter: std::result::Result<Query<DeletedFilter>, QueryRejection>,
 ) -> Result<Json<CustomerWithArcades>> {
-    let (customer, arcade_ids) = service.get_customer_with_arcade_ids(id).await?;
+    let filter = query_params(filter)?;
+    let (customer, arcade_ids) = service
+        .get_customer_with_arcade_ids(id, filter.include_deleted)
+        .await?;
     Ok(Json(CustomerWithArcades {
         customer,
         arcade_ids,
@@ -216,7 +312,8 @@ pub async fn get_customer(
 /// PUT /api/admin/customers/{id}
 pub async fn update_customer(
     State(service): State<Arc<AdminService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    user: IapUser,
     Path(id): Path<i32>,
     Json(payload): Json<UpdateCustomerRequest>,
 ) -> Result<Json<CustomerWithArcades>> {
@@ -228,10 +325,12 @@ pub async fn update_customer(
             payload.email.as_deref(),
         )
         .await?;
+    audit.record(&user.email, "update", "customer", id).await;
 
     // Update arcade assignments if provided
     if let Some(ref arcade_ids) = payload.arcade_ids {
         service.set_customer_arcades(id, arcade_ids).await?;
+        audit.record(&user.email, "assign_arcades", "customer", id).await;
     }
 
     let arcade_ids = service.get_customer_arcade_ids(id).await?;
@@ -244,13 +343,31 @@ pub async fn update_customer(
 /// DELETE /api/admin/customers/{id}
 pub async fn delete_customer(
     State(service): State<Arc<AdminService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    user: IapUser,
     Path(id): Path<i32>,
 ) -> Result<StatusCode> {
     service.delete_customer(id).await?;
+    audit.record(&user.email, "delete", "customer", id).await;
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// POST /api/admin/customers/{id}/restore
+pub async fn restore_customer(
+    State(service): State<Arc<AdminService>>,
+    Extension(audit): Extension<Arc<AuditService>>,
+    user: IapUser,
+    Path(id): Path<i32>,
+) -> Result<Json<CustomerWithArcades>> {
+    let customer = service.restore_customer(id).await?;
+    audit.record(&user.email, "restore", "customer", id).await;
+    let arcade_ids = service.get_customer_arcade_ids(id).await?;
+    Ok(Json(CustomerWithArcades {
+        customer,
+        arcade_ids,
+    }))
+}
+
 // ============================================================================
 // ARCADE ENDPOINTS
 // ============================================================================
@@ -258,11 +375,16 @@ pub async fn delete_customer(
 /// POST /api/admin/arcades
 pub async fn create_arcade(
     State(service): State<Arc<AdminService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    Extension(webhooks): Extension<Arc<WebhookService>>,
+    user: IapUser,
     Json(payload): Json<CreateArcadeRequest>,
 ) -> Result<(StatusCode, Json<ArcadeWithGames>)> {
     let arcade = service.create_arcade(&payload.name, &payload.machine_id, payload.channel_id).await?;
+    audit.record(&user.email, "create", "arcade", arcade.id).await;
     service.set_game_assignments(arcade.id, &payload.game_ids).await?;
+    audit.record(&user.email, "assign_games", "arcade", arcade.id).await;
+    webhooks.notify(&user.email, "assign_games", "arcade", arcade.id, json!({ "assigned_game_ids": &payload.game_ids }));
     Ok((StatusCode::CREATED, Json(ArcadeWithGames {
         arcade,
         assigned_game_ids: payload.game_ids,
@@ -302,23 +424,31 @@ pub async fn get_arcade(
 /// PUT /api/admin/arcades/{id}
 pub async fn update_arcade(
     State(service): State<Arc<AdminService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    Extension(webhooks): Extension<Arc<WebhookService>>,
+    user: IapUser,
     Path(id): Path<i32>,
     Json(payload): Json<UpdateArcadeRequest>,
 ) -> Result<Json<ArcadeWithGames>> {
     let mut arcade = service.update_arcade(id, &payload.name, &payload.status).await?;
+    audit.record(&user.email, "update", "arcade", id).await;
 
     // Update channel if provided
     if let Some(channel_id) = payload.channel_id {
         arcade = service.update_arcade_channel(id, channel_id).await?;
+        audit.record(&user.email, "assign_channel", "arcade", id).await;
     }
 
     // Update game assignments if provided
     if let Some(ref game_ids) = payload.game_ids {
         service.set_game_assignments(id, game_ids).await?;
+        audit.record(&user.email, "assign_games", "arcade", id).await;
     }
 
     let assigned_game_ids = service.get_assigned_game_ids(id).await?;
+    if payload.game_ids.is_some() {
+        webhooks.notify(&user.email, "assign_games", "arcade", id, json!({ "assigned_game_ids": assigned_game_ids }));
+    }
     Ok(Json(ArcadeWithGames {
         arcade,
         assigned_game_ids,
@@ -328,24 +458,98 @@ pub async fn update_arcade(
 /// DELETE /api/admin/arcades/{id}
 pub async fn delete_arcade(
     State(service): State<Arc<AdminService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    user: IapUser,
     Path(id): Path<i32>,
 ) -> Result<StatusCode> {
     service.delete_arcade(id).await?;
+    audit.record(&user.email, "delete", "arcade", id).await;
     Ok(StatusCode::NO_CONTENT)
 }
 
 /// PUT /api/admin/arcades/{id}/channel
 pub async fn update_arcade_channel(
     State(service): State<Arc<AdminService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    user: IapUser,
     Path(id): Path<i32>,
     Json(payload): Json<UpdateArcadeChannelRequest>,
 ) -> Result<Json<Arcade>> {
     let arcade = service.update_arcade_channel(id, payload.channel_id).await?;
+    audit.record(&user.email, "assign_channel", "arcade", id).await;
     Ok(Json(arcade))
 }
 
+/// GET /api/admin/arcades/{id}/allowed-channels
+pub async fn get_arcade_allowed_channels(
+    State(service): State<Arc<AdminService>>,
+    _user: IapUser,
+    Path(id): Path<i32>,
+) -> Result<Json<Vec<i32>>> {
+    let channel_ids = service.get_arcade_allowed_channels(id).await?;
+    Ok(Json(channel_ids))
+}
+
+/// PUT /api/admin/arcades/{id}/allowed-channels
+/// Channels other than its own that the arcade may request with `?channel=`
+pub async fn update_arcade_allowed_channels(
+    State(service): State<Arc<AdminService>>,
+    Extension(audit): Extension<Arc<AuditService>>,
+    user: IapUser,
+    Path(id): Path<i32>,
+    Json(payload): Json<UpdateArcadeAllowedChannelsRequest>,
+) -> Result<Json<Vec<i32>>> {
+    let channel_ids = service.set_arcade_allowed_channels(id, &payload.channel_ids).await?;
+    audit.record(&user.email, "allow_channels", "arcade", id).await;
+    Ok(Json(channel_ids))
+}
+
+/// POST /api/admin/assignments/bulk
+/// Existing assignments are reported as `already_assigned` rather than failing the batch.
+pub async fn bulk_assign_games(
+    State(service): State<Arc<AdminService>>,
+    Extension(audit): Extension<Arc<AuditService>>,
+    Extension(webhooks): Extension<Arc<WebhookService>>,
+    user: IapUser,
+    Json(payload): Json<BulkAssignmentRequest>,
+) -> Result<Json<Vec<BulkAssignmentResult>>> {
+    let assignments = payload.into_assignments();
+    if assignments.len() > MAX_BULK_ASSIGNMENTS {
+        return Err(AppError::BadRequest(format!(
+            "At most {} assignments per request",
+            MAX_BULK_ASSIGNMENTS
+        )));
+    }
+
+    let pairs: Vec<(i32, i32)> = assignments
+        .iter()
+        .map(|a| (a.arcade_id, a.game_id))
+        .collect();
+    let assigned = service.add_game_assignments(&pairs).await?;
+
+    let mut results = Vec::with_capacity(assigned.len());
+    let mut changed_arcade_ids = Vec::new();
+    for (assignment, newly_assigned) in assignments.into_iter().zip(assigned) {
+        let status = if newly_assigned {
+            audit.record(&user.email, "assign_games", "arcade", assignment.arcade_id).await;
+            changed_arcade_ids.push(assignment.arcade_id);
+            AssignmentStatus::Assigned
+        } else {
+            AssignmentStatus::AlreadyAssigned
+        };
+        results.push(BulkAssignmentResult { assignment, status });
+    }
+
+    changed_arcade_ids.sort_unstable();
+    changed_arcade_ids.dedup();
+    for arcade_id in changed_arcade_ids {
+        let assigned_game_ids = service.get_assigned_game_ids(arcade_id).await?;
+        webhooks.notify(&user.email, "assign_games", "arcade", arcade_id, json!({ "assigned_game_ids": assigned_game_ids }));
+    }
+
+    Ok(Json(results))
+}
+
 // ============================================================================
 // RELEASE CHANNEL ENDPOINTS
 // ============================================================================
@@ -372,31 +576,37 @@ pub async fn get_channel(
 /// POST /api/admin/channels
 pub async fn create_channel(
     State(service): State<Arc<AdminService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    user: IapUser,
     Json(payload): Json<CreateChannelRequest>,
 ) -> Result<(StatusCode, Json<ReleaseChannel>)> {
     let channel = service.create_channel(&payload.name, payload.description.as_deref()).await?;
+    audit.record(&user.email, "create", "channel", channel.id).await;
     Ok((StatusCode::CREATED, Json(channel)))
 }
 
 /// PUT /api/admin/channels/{id}
 pub async fn update_channel(
     State(service): State<Arc<AdminService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    user: IapUser,
     Path(id): Path<i32>,
     Json(payload): Json<UpdateChannelRequest>,
 ) -> Result<Json<ReleaseChannel>> {
     let channel = service.update_channel(id, payload.description.as_deref()).await?;
+    audit.record(&user.email, "update", "channel", id).await;
     Ok(Json(channel))
 }
 
 /// DELETE /api/admin/channels/{id}
 pub async fn delete_channel(
     State(service): State<Arc<AdminService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    user: IapUser,
     Path(id): Path<i32>,
 ) -> Result<StatusCode> {
     service.delete_channel(id).await?;
+    audit.record(&user.email, "delete", "channel", id).await;
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -407,34 +617,45 @@ pub async fn delete_channel(
 /// POST /api/admin/games
 pub async fn create_game(
     State(service): State<Arc<AdminService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    Extension(webhooks): Extension<Arc<WebhookService>>,
+    user: IapUser,
     Json(payload): Json<CreateGameRequest>,
 ) -> Result<(StatusCode, Json<Game>)> {
     let game = service.create_game(&payload.name).await?;
+    audit.record(&user.email, "create", "game", game.id).await;
+    webhooks.notify(&user.email, "create", "game", game.id, &game);
     Ok((StatusCode::CREATED, Json(game)))
 }
 
 /// GET /api/admin/games
 pub async fn list_games(
-    State((admin_service, gcs_service)): State<(Arc<AdminService>, Arc<GcsService>)>,
+    State((admin_service, storage)): State<(Arc<AdminService>, Arc<dyn ObjectStorage>)>,
     _user: IapUser,
-) -> Result<Json<Vec<GameWithBackground>>> {
-    let games = admin_service.list_games().await?;
+    query: std::result::Result<Query<PageParams>, QueryRejection>,
+    filter: std::result::Result<Query<GameListFilter>, QueryRejection>,
+) -> Result<Json<Page<GameWithBackground>>> {
+    let pagination = pagination(query)?;
+    let filter = query_params(filter)?;
+    let (games, total) = admin_service
+        .list_games(pagination, filter.include_deleted, filter.name.as_deref())
+        .await?;
 
     let mut games_with_bg = Vec::new();
     for game in games {
         let bg_path = format!("{}/{}BG.jpg", game.name, game.name);
-        let background_url = gcs_service.generate_signed_download_url(&bg_path).await.ok();
+        let background_url = storage.signed_download_url(&bg_path).await.ok().map(|signed| signed.url);
 
         games_with_bg.push(GameWithBackground {
             id: game.id,
             name: game.name,
             created_at: game.created_at,
+            deleted_at: game.deleted_at,
             background_url,
         });
     }
 
-    Ok(Json(games_with_bg))
+    Ok(Json(Page::new(games_with_bg, total, pagination)))
 }
 
 /// GET /api/admin/games/{id}
@@ -442,32 +663,58 @@ pub async fn get_game(
     State(service): State<Arc<AdminService>>,
     _user: IapUser,
     Path(id): Path<i32>,
+    filter: std::result::Result<Query<DeletedFilter>, QueryRejection>,
 ) -> Result<Json<Game>> {
-    let game = service.get_game(id).await?;
+    let filter = query_params(filter)?;
+    let game = service.find_game(id, filter.include_deleted).await?;
     Ok(Json(game))
 }
 
 /// PUT /api/admin/games/{id}
 pub async fn update_game(
     State(service): State<Arc<AdminService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    Extension(webhooks): Extension<Arc<WebhookService>>,
+    user: IapUser,
     Path(id): Path<i32>,
     Json(payload): Json<UpdateGameRequest>,
 ) -> Result<Json<Game>> {
     let game = service.update_game(id, &payload.name).await?;
+    audit.record(&user.email, "update", "game", id).await;
+    webhooks.notify(&user.email, "update", "game", id, &game);
     Ok(Json(game))
 }
 
-/// DELETE /api/admin/games/{id}
+/// DELETE /api/admin/games/{id}?force=true
 pub async fn delete_game(
     State(service): State<Arc<AdminService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    Extension(webhooks): Extension<Arc<WebhookService>>,
+    user: IapUser,
     Path(id): Path<i32>,
+    params: std::result::Result<Query<DeleteGameParams>, QueryRejection>,
 ) -> Result<StatusCode> {
-    service.delete_game(id).await?;
+    let params = query_params(params)?;
+    service.delete_game(id, params.force).await?;
+    audit.record(&user.email, "delete", "game", id).await;
+    webhooks.notify(&user.email, "delete", "game", id, ());
     Ok(StatusCode::NO_CONTENT)
 }
 
+/// POST /api/admin/games/{id}/restore
+pub async fn restore_game(
+    State(service): State<Arc<AdminService>>,
+    Extension(audit): Extension<Arc<AuditService>>,
+    Extension(webhooks): Extension<Arc<WebhookService>>,
+    user: IapUser,
+    Path(id): Path<i32>,
+) -> Result<Json<Game>> {
+    let game = service.restore_game(id).await?;
+    audit.record(&user.email, "restore", "game", id).await;
+    webhooks.notify(&user.email, "restore", "game", id, &game);
+    Ok(Json(game))
+}
+
 // ============================================================================
 // GAME VERSION ENDPOINTS
 // ============================================================================
@@ -475,13 +722,17 @@ pub async fn delete_game(
 /// POST /api/admin/games/{game_id}/versions
 pub async fn create_game_version(
     State(service): State<Arc<AdminService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    Extension(webhooks): Extension<Arc<WebhookService>>,
+    user: IapUser,
     Path(game_id): Path<i32>,
     Json(payload): Json<CreateGameVersionRequest>,
 ) -> Result<(StatusCode, Json<GameVersion>)> {
     let version = service
         .create_game_version(game_id, &payload.version, &payload.gcs_path)
         .await?;
+    audit.record(&user.email, "create", "game_version", version.id).await;
+    webhooks.notify(&user.email, "create", "game_version", version.id, &version);
     Ok((StatusCode::CREATED, Json(version)))
 }
 
@@ -490,9 +741,13 @@ pub async fn list_game_versions(
     State(service): State<Arc<AdminService>>,
     _user: IapUser,
     Path(game_id): Path<i32>,
-) -> Result<Json<Vec<GameVersionWithChannels>>> {
-    let versions = service.list_game_versions_with_channels(game_id).await?;
-    Ok(Json(versions))
+    query: std::result::Result<Query<PageParams>, QueryRejection>,
+) -> Result<Json<Page<GameVersionWithChannels>>> {
+    let pagination = pagination(query)?;
+    let (versions, total) = service
+        .list_game_versions_with_channels(game_id, pagination)
+        .await?;
+    Ok(Json(Page::new(versions, total, pagination)))
 }
 
 /// GET /api/admin/games/{game_id}/versions/{version_id}
@@ -508,32 +763,42 @@ pub async fn get_game_version(
 /// PUT /api/admin/games/{game_id}/versions/{version_id}
 pub async fn update_game_version(
     State(service): State<Arc<AdminService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    Extension(webhooks): Extension<Arc<WebhookService>>,
+    user: IapUser,
     Path((_game_id, version_id)): Path<(i32, i32)>,
     Json(payload): Json<UpdateGameVersionRequest>,
 ) -> Result<Json<GameVersion>> {
     let version = service
         .update_game_version(version_id, &payload.version, &payload.gcs_path)
         .await?;
+    audit.record(&user.email, "update", "game_version", version_id).await;
+    webhooks.notify(&user.email, "update", "game_version", version_id, &version);
     Ok(Json(version))
 }
 
 /// DELETE /api/admin/games/{game_id}/versions/{version_id}
 pub async fn delete_game_version(
-    State((admin_service, gcs_service)): State<(Arc<AdminService>, Arc<GcsService>)>,
-    _user: IapUser,
+    State((admin_service, storage)): State<(Arc<AdminService>, Arc<dyn ObjectStorage>)>,
+    Extension(audit): Extension<Arc<AuditService>>,
+    Extension(webhooks): Extension<Arc<WebhookService>>,
+    user: IapUser,
     Path((_game_id, version_id)): Path<(i32, i32)>,
 ) -> Result<StatusCode> {
     let game_version = admin_service.get_game_version(version_id).await?;
-    gcs_service.delete_folder(&game_version.gcs_path).await?;
+    storage.delete_folder(&game_version.gcs_path).await?;
     admin_service.delete_game_version(version_id).await?;
+    audit.record(&user.email, "delete", "game_version", version_id).await;
+    webhooks.notify(&user.email, "delete", "game_version", version_id, ());
     Ok(StatusCode::NO_CONTENT)
 }
 
 /// POST /api/admin/games/{game_id}/versions/{version_id}/publish
 pub async fn publish_version(
     State(service): State<Arc<AdminService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    Extension(webhooks): Extension<Arc<WebhookService>>,
+    user: IapUser,
     Path((game_id, version_id)): Path<(i32, i32)>,
     Json(payload): Json<PublishVersionRequest>,
 ) -> Result<Json<GameVersionWithChannels>> {
@@ -547,6 +812,8 @@ pub async fn publish_version(
     let version = service
         .replace_version_channels(version_id, &payload.channel_ids)
         .await?;
+    audit.record(&user.email, "publish", "game_version", version_id).await;
+    webhooks.notify(&user.email, "publish", "game_version", version_id, &version);
 
     Ok(Json(version))
 }
@@ -554,7 +821,9 @@ pub async fn publish_version(
 /// DELETE /api/admin/games/{game_id}/versions/{version_id}/publish
 pub async fn unpublish_version(
     State(service): State<Arc<AdminService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    Extension(webhooks): Extension<Arc<WebhookService>>,
+    user: IapUser,
     Path((game_id, version_id)): Path<(i32, i32)>,
 ) -> Result<StatusCode> {
     let version = service.get_game_version(version_id).await?;
@@ -565,6 +834,8 @@ pub async fn unpublish_version(
     }
 
     service.unpublish_version_from_all(version_id).await?;
+    audit.record(&user.email, "unpublish", "game_version", version_id).await;
+    webhooks.notify(&user.email, "unpublish", "game_version", version_id, ());
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -574,7 +845,7 @@ pub async fn unpublish_version(
 
 /// POST /api/admin/games/{game_id}/versions/generate-upload-url
 pub async fn generate_game_version_upload_url(
-    State((admin_service, gcs_service)): State<(Arc<AdminService>, Arc<GcsService>)>,
+    State((admin_service, storage)): State<(Arc<AdminService>, Arc<dyn ObjectStorage>)>,
     _user: IapUser,
     Path(game_id): Path<i32>,
     Json(payload): Json<GenerateUploadUrlRequest>,
@@ -587,7 +858,7 @@ pub async fn generate_game_version_upload_url(
 
     let gcs_folder = format!("{}/{}", game.name, payload.version);
     let gcs_path = format!("{}/game.zip", gcs_folder);
-    let upload_url = gcs_service.generate_signed_upload_url(&gcs_path, 3600).await?;
+    let upload_url = storage.signed_upload_url(&gcs_path, 3600).await?;
 
     Ok(Json(GenerateUploadUrlResponse {
         upload_url,
@@ -597,7 +868,7 @@ pub async fn generate_game_version_upload_url(
 
 /// POST /api/admin/games/{game_id}/versions/generate-batch-upload-urls
 pub async fn generate_batch_upload_urls(
-    State((admin_service, gcs_service)): State<(Arc<AdminService>, Arc<GcsService>)>,
+    State((admin_service, storage)): State<(Arc<AdminService>, Arc<dyn ObjectStorage>)>,
     _user: IapUser,
     Path(game_id): Path<i32>,
     Json(payload): Json<GenerateBatchUploadUrlsRequest>,
@@ -615,11 +886,11 @@ pub async fn generate_batch_upload_urls(
     let gcs_folder = format!("{}/{}", game.name, payload.version);
 
     let url_futures: Vec<_> = payload.files.iter().map(|file_path| {
-        let gcs_service = gcs_service.clone();
+        let storage = storage.clone();
         let full_path = format!("{}/{}", gcs_folder, file_path);
         let file_path = file_path.clone();
         async move {
-            let upload_url = gcs_service.generate_signed_upload_url(&full_path, 3600).await?;
+            let upload_url = storage.signed_upload_url(&full_path, 3600).await?;
             Ok::<FileUploadUrl, AppError>(FileUploadUrl {
                 path: file_path,
                 upload_url,
@@ -639,7 +910,9 @@ pub async fn generate_batch_upload_urls(
 /// POST /api/admin/games/{game_id}/versions/confirm-upload
 pub async fn confirm_game_version_upload(
     State(admin_service): State<Arc<AdminService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    Extension(webhooks): Extension<Arc<WebhookService>>,
+    user: IapUser,
     Path(game_id): Path<i32>,
     Json(payload): Json<ConfirmGameVersionUploadRequest>,
 ) -> Result<(StatusCode, Json<GameVersion>)> {
@@ -650,19 +923,21 @@ pub async fn confirm_game_version_upload(
     let game_version = admin_service
         .create_game_version(game_id, &payload.version, &payload.gcs_path)
         .await?;
+    audit.record(&user.email, "create", "game_version", game_version.id).await;
+    webhooks.notify(&user.email, "create", "game_version", game_version.id, &game_version);
 
     Ok((StatusCode::CREATED, Json(game_version)))
 }
 
 /// POST /api/admin/games/{game_id}/background/generate-upload-url
 pub async fn generate_background_upload_url(
-    State((admin_service, gcs_service)): State<(Arc<AdminService>, Arc<GcsService>)>,
+    State((admin_service, storage)): State<(Arc<AdminService>, Arc<dyn ObjectStorage>)>,
     _user: IapUser,
     Path(game_id): Path<i32>,
 ) -> Result<Json<GenerateUploadUrlResponse>> {
     let game = admin_service.get_game(game_id).await?;
     let gcs_path = format!("{}/{}BG.jpg", game.name, game.name);
-    let upload_url = gcs_service.generate_signed_upload_url(&gcs_path, 1800).await?;
+    let upload_url = storage.signed_upload_url(&gcs_path, 1800).await?;
 
     Ok(Json(GenerateUploadUrlResponse {
         upload_url,
@@ -686,20 +961,24 @@ pub async fn list_snorlax_versions(
 /// POST /api/admin/snorlax/versions
 pub async fn create_snorlax_version(
     State(service): State<Arc<SnorlaxService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    user: IapUser,
     Json(payload): Json<CreateSnorlaxVersionRequest>,
 ) -> Result<(StatusCode, Json<SnorlaxVersion>)> {
     let version = service.create_version(&payload.version, &payload.gcs_path).await?;
+    audit.record(&user.email, "create", "snorlax_version", version.id).await;
     Ok((StatusCode::CREATED, Json(version)))
 }
 
 /// PUT /api/admin/snorlax/versions/{id}/set-current
 pub async fn set_current_snorlax_version(
     State(service): State<Arc<SnorlaxService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    user: IapUser,
     Path(id): Path<i32>,
 ) -> Result<Json<AdminActionResponse>> {
     service.set_current_version(id).await?;
+    audit.record(&user.email, "set_current", "snorlax_version", id).await;
     Ok(Json(AdminActionResponse {
         message: format!("Version {} set as current", id),
     }))
@@ -708,22 +987,24 @@ pub async fn set_current_snorlax_version(
 /// DELETE /api/admin/snorlax/versions/{id}
 pub async fn delete_snorlax_version(
     State(service): State<Arc<SnorlaxService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    user: IapUser,
     Path(id): Path<i32>,
 ) -> Result<StatusCode> {
     service.delete_version(id).await?;
+    audit.record(&user.email, "delete", "snorlax_version", id).await;
     Ok(StatusCode::NO_CONTENT)
 }
 
 /// POST /api/admin/snorlax/generate-upload-url
 pub async fn generate_snorlax_upload_url(
-    State(gcs_service): State<Arc<GcsService>>,
+    State(storage): State<Arc<dyn ObjectStorage>>,
     _user: IapUser,
     Json(payload): Json<GenerateUploadUrlRequest>,
 ) -> Result<Json<GenerateUploadUrlResponse>> {
     let gcs_path = format!("Snorlax/{}", payload.version);
     let apk_path = format!("{}/Snorlax.apk", gcs_path);
-    let upload_url = gcs_service.generate_signed_upload_url(&apk_path, 3600).await?;
+    let upload_url = storage.signed_upload_url(&apk_path, 3600).await?;
 
     Ok(Json(GenerateUploadUrlResponse {
         upload_url,
@@ -734,12 +1015,14 @@ pub async fn generate_snorlax_upload_url(
 /// POST /api/admin/snorlax/confirm-upload
 pub async fn confirm_snorlax_upload(
     State(snorlax_service): State<Arc<SnorlaxService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    user: IapUser,
     Json(payload): Json<ConfirmSnorlaxUploadRequest>,
 ) -> Result<(StatusCode, Json<SnorlaxVersion>)> {
     let snorlax_version = snorlax_service
         .create_version(&payload.version, &payload.gcs_path)
         .await?;
+    audit.record(&user.email, "create", "snorlax_version", snorlax_version.id).await;
 
     Ok((StatusCode::CREATED, Json(snorlax_version)))
 }
@@ -760,20 +1043,24 @@ pub async fn list_gyros_versions(
 /// POST /api/admin/gyros/versions
 pub async fn create_gyros_version(
     State(service): State<Arc<GyrosService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    user: IapUser,
     Json(payload): Json<CreateGyrosVersionRequest>,
 ) -> Result<(StatusCode, Json<GyrosVersion>)> {
     let version = service.create_version(&payload.version, &payload.gcs_path).await?;
+    audit.record(&user.email, "create", "gyros_version", version.id).await;
     Ok((StatusCode::CREATED, Json(version)))
 }
 
 /// PUT /api/admin/gyros/versions/{id}/set-current
 pub async fn set_current_gyros_version(
     State(service): State<Arc<GyrosService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    user: IapUser,
     Path(id): Path<i32>,
 ) -> Result<Json<AdminActionResponse>> {
     service.set_current_version(id).await?;
+    audit.record(&user.email, "set_current", "gyros_version", id).await;
     Ok(Json(AdminActionResponse {
         message: format!("Version {} set as current", id),
     }))
@@ -782,22 +1069,24 @@ pub async fn set_current_gyros_version(
 /// DELETE /api/admin/gyros/versions/{id}
 pub async fn delete_gyros_version(
     State(service): State<Arc<GyrosService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    user: IapUser,
     Path(id): Path<i32>,
 ) -> Result<StatusCode> {
     service.delete_version(id).await?;
+    audit.record(&user.email, "delete", "gyros_version", id).await;
     Ok(StatusCode::NO_CONTENT)
 }
 
 /// POST /api/admin/gyros/generate-upload-url
 pub async fn generate_gyros_upload_url(
-    State(gcs_service): State<Arc<GcsService>>,
+    State(storage): State<Arc<dyn ObjectStorage>>,
     _user: IapUser,
     Json(payload): Json<GenerateUploadUrlRequest>,
 ) -> Result<Json<GenerateUploadUrlResponse>> {
     let gcs_path = format!("Gyros/{}", payload.version);
     let firmware_path = format!("{}/Gyros.bin", gcs_path);
-    let upload_url = gcs_service.generate_signed_upload_url(&firmware_path, 3600).await?;
+    let upload_url = storage.signed_upload_url(&firmware_path, 3600).await?;
 
     Ok(Json(GenerateUploadUrlResponse {
         upload_url,
@@ -808,12 +1097,48 @@ pub async fn generate_gyros_upload_url(
 /// POST /api/admin/gyros/confirm-upload
 pub async fn confirm_gyros_upload(
     State(gyros_service): State<Arc<GyrosService>>,
-    _user: IapUser,
+    Extension(audit): Extension<Arc<AuditService>>,
+    user: IapUser,
     Json(payload): Json<ConfirmGyrosUploadRequest>,
 ) -> Result<(StatusCode, Json<GyrosVersion>)> {
     let gyros_version = gyros_service
         .create_version(&payload.version, &payload.gcs_path)
         .await?;
+    audit.record(&user.email, "create", "gyros_version", gyros_version.id).await;
 
     Ok((StatusCode::CREATED, Json(gyros_version)))
 }
+
+// ============================================================================
+// AUDIT LOG ENDPOINTS
+// ============================================================================
+
+/// GET /api/admin/audit-log
+/// Filterable by `entity_type`, `entity_id` and `actor`
+pub async fn list_audit_log(
+    State(service): State<Arc<AuditService>>,
+    _user: IapUser,
+    filter: std::result::Result<Query<AuditLogFilter>, QueryRejection>,
+    query: std::result::Result<Query<PageParams>, QueryRejection>,
+) -> Result<Json<Page<AuditLogEntry>>> {
+    let filter = query_params(filter)?;
+    let pagination = pagination(query)?;
+    let (entries, total) = service.list(&filter, pagination).await?;
+    Ok(Json(Page::new(entries, total, pagination)))
+}
+
+// ============================================================================
+// SEARCH ENDPOINTS
+// ============================================================================
+
+/// GET /api/admin/search?q=
+/// Case-insensitive substring match on arcade names and machine IDs and game names
+pub async fn search(
+    State(service): State<Arc<AdminService>>,
+    _user: IapUser,
+    params: std::result::Result<Query<SearchParams>, QueryRejection>,
+) -> Result<Json<SearchResults>> {
+    let params = query_params(params)?;
+    let results = service.search(&params.q).await?;
+    Ok(Json(results))
+}
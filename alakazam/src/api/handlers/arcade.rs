@@ -5,11 +5,25 @@ use crate::{
     services::ArcadeService,
 };
 use axum::{
-    extract::State,
+    extract::{Query, State},
     Json,
 };
+use serde::Deserialize;
 use std::sync::Arc;
 
+/// `?channel=<name>` on the arcade game endpoints, to follow a release channel
+/// other than the arcade's own (e.g. a beta channel for one game)
+#[derive(Debug, Default, Deserialize)]
+pub struct ChannelQuery {
+    pub channel: Option<String>,
+}
+
+impl ChannelQuery {
+    pub fn channel(&self) -> Option<&str> {
+        self.channel.as_deref().map(str::trim).filter(|c| !c.is_empty())
+    }
+}
+
 /// GET /api/arcade/config
 /// Returns arcade configuration (authenticated by machine ID header)
 pub async fn get_arcade_config(
@@ -25,7 +39,8 @@ pub async fn get_arcade_config(
 pub async fn get_arcade_games(
     State(service): State<Arc<ArcadeService>>,
     MachineId(machine_id): MachineId,
+    Query(query): Query<ChannelQuery>,
 ) -> Result<Json<Vec<GameAssignmentResponse>>> {
-    let games = service.get_arcade_games(&machine_id).await?;
+    let games = service.get_arcade_games(&machine_id, query.channel()).await?;
     Ok(Json(games))
 }
@@ -1,11 +1,19 @@
 use crate::{
     api::MachineId,
     error::{AppError, Result},
-    services::{ArcadeService, GcsService},
+    services::{ArcadeService, ObjectStorage, StoredFile},
+};
+use super::arcade::ChannelQuery;
+use super::query_params;
+use axum::{
+    extract::{rejection::QueryRejection, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
 };
-use axum::{extract::{Path, State}, Json};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::sync::Arc;
 
 /// Response for game download request
@@ -26,20 +34,42 @@ pub struct GameDownloadResponse {
 pub struct GameFile {
     pub path: String,
     pub download_url: String,
+    /// File size in bytes, used by clients to report resumable progress
+    pub size: Option<u64>,
+    /// Base64-encoded MD5 digest for verifying the downloaded file
+    pub md5_hash: Option<String>,
+}
+
+/// Optional validity for signed URLs, for large games over slow connections
+#[derive(Debug, Deserialize)]
+pub struct UrlExpiryQuery {
+    /// Seconds the URLs stay valid, up to the configured maximum
+    pub expires_in: Option<u32>,
 }
 
 /// GET /api/arcade/games/{game_id}/download
 /// Returns signed download URLs for all files in the game version
+///
+/// The response carries an ETag over the version, its files and the background
+/// image (not the signed URLs), and `If-None-Match` with a current ETag gets 304
+/// before anything is signed. Clients should only revalidate while the URLs they
+/// hold are still before `expires_at`.
 pub async fn get_game_download_urls(
-    State((arcade_service, gcs_service)): State<(Arc<ArcadeService>, Arc<GcsService>)>,
+    State((arcade_service, storage)): State<(Arc<ArcadeService>, Arc<dyn ObjectStorage>)>,
     Path(game_id): Path<i32>,
     MachineId(machine_id): MachineId,
-) -> Result<Json<GameDownloadResponse>> {
+    Query(query): Query<ChannelQuery>,
+    expiry: std::result::Result<Query<UrlExpiryQuery>, QueryRejection>,
+    headers: HeaderMap,
+) -> Result<Response> {
+    let expiry = query_params(expiry)?;
+    let duration_secs = storage.download_url_duration(expiry.expires_in)?;
+
     // Authenticate the arcade
     let _arcade = arcade_service.get_arcade_config(&machine_id).await?;
 
     // Get the arcade's game assignments
-    let games = arcade_service.get_arcade_games(&machine_id).await?;
+    let games = arcade_service.get_arcade_games(&machine_id, query.channel()).await?;
 
     // Find the requested game
     let game_assignment = games
@@ -50,25 +80,49 @@ pub async fn get_game_download_urls(
     // Use the assigned version
     let version = &game_assignment.assigned_version;
 
-    // List all files in the GCS path and generate signed URLs
-    let files = gcs_service
-        .list_and_sign_folder(&version.gcs_path)
-        .await?;
+    // List all files in the version folder; nothing is signed until the ETag is checked
+    let stored_files = storage.list_folder(&version.gcs_path).await?;
 
     // Background image path: <GameName>/<GameName>BG.jpg
-    let background_image_url = {
-        let bg_path = format!("{}/{}BG.jpg", game_assignment.game_name, game_assignment.game_name);
-        gcs_service
-            .generate_signed_download_url(&bg_path)
+    let bg_path = format!("{}/{}BG.jpg", game_assignment.game_name, game_assignment.game_name);
+    let background = storage.file_info(&bg_path).await;
+    if let Err(e) = &background {
+        tracing::warn!(game_id, error = %e, "Failed to look up background image");
+    }
+
+    let etag = manifest_etag(
+        game_assignment.game_id,
+        version.version_id,
+        &version.gcs_path,
+        &stored_files,
+        background.as_ref().ok().and_then(Option::as_ref),
+    );
+    if etag_matches(&headers, &etag) {
+        return Ok((StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response());
+    }
+
+    let folder = storage.sign_files(stored_files, duration_secs).await?;
+    let files = folder.files;
+
+    // A missing image gets no URL; if the lookup failed, sign it anyway as before
+    let background_image_url = match background {
+        Ok(None) => None,
+        Ok(Some(_)) | Err(_) => storage
+            .signed_download_url_valid_for(&bg_path, duration_secs)
             .await
-            .ok()
+            .ok(),
     };
 
-    // Calculate expiration time
-    let duration_secs = gcs_service.get_url_duration_secs();
-    let expires_at = Utc::now() + chrono::Duration::seconds(duration_secs as i64);
+    // URLs may come from the signing cache, so report when the first one really expires
+    let expires_at = folder
+        .expires_at
+        .into_iter()
+        .chain(background_image_url.as_ref().map(|signed| signed.expires_at))
+        .min()
+        .unwrap_or_else(|| Utc::now() + chrono::Duration::seconds(duration_secs as i64));
+    let background_image_url = background_image_url.map(|signed| signed.url);
 
-    Ok(Json(GameDownloadResponse {
+    let response = GameDownloadResponse {
         game_id: game_assignment.game_id,
         game_name: game_assignment.game_name.clone(),
         version: version.version.clone(),
@@ -77,9 +131,180 @@ pub async fn get_game_download_urls(
         files,
         background_image_url,
         expires_at,
+    };
+
+    Ok(([(header::ETAG, etag)], Json(response)).into_response())
+}
+
+/// Strong ETag over everything in the manifest except the signed URLs, which
+/// differ between requests even when nothing changed
+fn manifest_etag(
+    game_id: i32,
+    version_id: i32,
+    gcs_path: &str,
+    files: &[StoredFile],
+    background: Option<&StoredFile>,
+) -> String {
+    let mut files: Vec<&StoredFile> = files.iter().collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let describe = |file: &StoredFile| {
+        format!(
+            "{}\t{}\t{}\n",
+            file.path,
+            file.size.map(|s| s.to_string()).unwrap_or_default(),
+            file.md5_hash.as_deref().unwrap_or_default()
+        )
+    };
+
+    let mut hasher = Sha256::new();
+    hasher.update(format!("{}\n{}\n{}\n", game_id, version_id, gcs_path));
+    for file in files {
+        hasher.update(describe(file));
+    }
+    match background {
+        Some(background) => hasher.update(format!("background\t{}", describe(background))),
+        None => hasher.update("no background\n"),
+    }
+
+    format!("\"{}\"", &hex::encode(hasher.finalize())[..32])
+}
+
+/// Whether `If-None-Match` names `etag` (weak comparison, as RFC 9110 asks for GET)
+fn etag_matches(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/") == etag)
+}
+
+/// Most files that may be named in one signed URL request
+const MAX_SIGNED_URL_PATHS: usize = 10_000;
+
+/// Request for signed URLs of a game version's files
+#[derive(Debug, Deserialize)]
+pub struct SignedUrlsRequest {
+    /// Paths relative to the version folder; every file in it when omitted
+    pub paths: Option<Vec<String>>,
+}
+
+/// Signed URL for one file, or why it couldn't be signed
+#[derive(Debug, Serialize)]
+pub struct SignedFileUrl {
+    pub path: String,
+    pub download_url: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SignedUrlsResponse {
+    pub game_id: i32,
+    pub version_id: i32,
+    pub files: Vec<SignedFileUrl>,
+    pub expires_at: chrono::DateTime<Utc>,
+}
+
+/// POST /api/arcade/games/{game_id}/versions/{version_id}/signed-urls
+/// Signs all (or the requested) files of the arcade's assigned version in one
+/// go, concurrently. A file that fails to sign is reported on its own entry
+/// rather than failing the whole request.
+pub async fn sign_game_version_files(
+    State((arcade_service, storage)): State<(Arc<ArcadeService>, Arc<dyn ObjectStorage>)>,
+    Path((game_id, version_id)): Path<(i32, i32)>,
+    MachineId(machine_id): MachineId,
+    Query(query): Query<ChannelQuery>,
+    expiry: std::result::Result<Query<UrlExpiryQuery>, QueryRejection>,
+    Json(payload): Json<SignedUrlsRequest>,
+) -> Result<Json<SignedUrlsResponse>> {
+    let expiry = query_params(expiry)?;
+    let duration_secs = storage.download_url_duration(expiry.expires_in)?;
+    let games = arcade_service.get_arcade_games(&machine_id, query.channel()).await?;
+    let game_assignment = games
+        .iter()
+        .find(|g| g.game_id == game_id)
+        .ok_or(AppError::GameNotFound)?;
+
+    // Only the version the arcade is assigned may be downloaded
+    let version = &game_assignment.assigned_version;
+    if version.version_id != version_id {
+        return Err(AppError::GameVersionNotFound);
+    }
+
+    let (paths, object_paths): (Vec<String>, Vec<String>) = match payload.paths {
+        Some(paths) => {
+            if paths.len() > MAX_SIGNED_URL_PATHS {
+                return Err(AppError::BadRequest(format!(
+                    "At most {} paths per request",
+                    MAX_SIGNED_URL_PATHS
+                )));
+            }
+            if let Some(path) = paths.iter().find(|p| !is_relative_file_path(p)) {
+                return Err(AppError::BadRequest(format!("Invalid file path '{}'", path)));
+            }
+            paths
+                .into_iter()
+                .map(|path| {
+                    let object_path = format!("{}/{}", version.gcs_path, path);
+                    (path, object_path)
+                })
+                .unzip()
+        }
+        None => storage
+            .list_folder(&version.gcs_path)
+            .await?
+            .into_iter()
+            .map(|file| (file.path, file.key))
+            .unzip(),
+    };
+
+    let urls = storage.signed_download_urls(&object_paths, duration_secs).await;
+
+    // URLs may come from the signing cache, so report when the first one really expires
+    let expires_at = urls
+        .iter()
+        .filter_map(|url| url.as_ref().ok())
+        .map(|signed| signed.expires_at)
+        .min()
+        .unwrap_or_else(|| Utc::now() + chrono::Duration::seconds(duration_secs as i64));
+
+    let files = paths
+        .into_iter()
+        .zip(urls)
+        .map(|(path, url)| match url {
+            Ok(signed) => SignedFileUrl {
+                path,
+                download_url: Some(signed.url),
+                error: None,
+            },
+            Err(e) => {
+                tracing::warn!(game_id, version_id, path = %path, error = %e, "Failed to sign game file");
+                SignedFileUrl {
+                    path,
+                    download_url: None,
+                    error: Some(e.to_string()),
+                }
+            }
+        })
+        .collect();
+
+    Ok(Json(SignedUrlsResponse {
+        game_id,
+        version_id,
+        files,
+        expires_at,
     }))
 }
 
+/// A non-empty path inside a folder, without `..` or empty segments
+fn is_relative_file_path(path: &str) -> bool {
+    !path.is_empty()
+        && !path.starts_with('/')
+        && path.split('/').all(|segment| !segment.is_empty() && segment != "." && segment != "..")
+}
+
 /// Request for reporting installed games
 #[derive(Debug, Deserialize)]
 pub struct ReportInstallationsRequest {
@@ -9,3 +9,12 @@ pub use arcade::*;
 pub use game::*;
 pub use sensor::*;
 pub use snorlax::*;
+
+use crate::error::{AppError, Result};
+use axum::extract::{rejection::QueryRejection, Query};
+
+/// Unwrap query parameters, reporting malformed values with the JSON error shape
+pub(crate) fn query_params<T>(query: std::result::Result<Query<T>, QueryRejection>) -> Result<T> {
+    let Query(params) = query.map_err(|e| AppError::BadRequest(e.body_text()))?;
+    Ok(params)
+}
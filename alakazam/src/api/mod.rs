@@ -1,6 +1,8 @@
 pub mod auth;
 pub mod handlers;
+pub mod rate_limit;
 pub mod routes;
 
 pub use auth::{IapUser, MachineId};
+pub use rate_limit::{AuthLimiter, AuthRateLimit, InMemoryAuthLimiter};
 pub use routes::create_api_router;
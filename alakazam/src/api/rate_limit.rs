@@ -0,0 +1,262 @@
+use crate::config::RateLimitConfig;
+use crate::error::AppError;
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Failed authentication tracking, so another store (e.g. Redis) can be swapped in
+pub trait AuthLimiter: Send + Sync {
+    /// Time left until `ip` may try again, `None` if it may go ahead now
+    fn blocked_for(&self, ip: IpAddr) -> Option<Duration>;
+
+    fn record_failure(&self, ip: IpAddr);
+
+    /// A successful authentication clears the failures of `ip`
+    fn record_success(&self, ip: IpAddr);
+}
+
+/// Entries kept before expired windows are pruned
+const PRUNE_THRESHOLD: usize = 10_000;
+
+struct Failures {
+    count: u32,
+    window_start: Instant,
+}
+
+/// Counts failures per IP in fixed windows, in process memory
+pub struct InMemoryAuthLimiter {
+    max_failures: u32,
+    window: Duration,
+    failures: Mutex<HashMap<IpAddr, Failures>>,
+}
+
+impl InMemoryAuthLimiter {
+    pub fn new(config: &RateLimitConfig) -> Self {
+        Self {
+            max_failures: config.auth_max_failures,
+            window: Duration::from_secs(config.auth_failure_window_secs),
+            failures: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl InMemoryAuthLimiter {
+    fn blocked_for_at(&self, ip: IpAddr, now: Instant) -> Option<Duration> {
+        if self.max_failures == 0 {
+            return None;
+        }
+
+        let failures = self.failures.lock().unwrap();
+        let entry = failures.get(&ip)?;
+        let elapsed = now.duration_since(entry.window_start);
+
+        (entry.count >= self.max_failures && elapsed < self.window).then(|| self.window - elapsed)
+    }
+
+    fn record_failure_at(&self, ip: IpAddr, now: Instant) {
+        let mut failures = self.failures.lock().unwrap();
+
+        if failures.len() > PRUNE_THRESHOLD {
+            let window = self.window;
+            failures.retain(|_, f| now.duration_since(f.window_start) < window);
+        }
+
+        let entry = failures.entry(ip).or_insert(Failures {
+            count: 0,
+            window_start: now,
+        });
+        if now.duration_since(entry.window_start) >= self.window {
+            entry.count = 0;
+            entry.window_start = now;
+        }
+        entry.count += 1;
+    }
+}
+
+impl AuthLimiter for InMemoryAuthLimiter {
+    fn blocked_for(&self, ip: IpAddr) -> Option<Duration> {
+        self.blocked_for_at(ip, Instant::now())
+    }
+
+    fn record_failure(&self, ip: IpAddr) {
+        self.record_failure_at(ip, Instant::now())
+    }
+
+    fn record_success(&self, ip: IpAddr) {
+        self.failures.lock().unwrap().remove(&ip);
+    }
+}
+
+/// State of `limit_auth_attempts`
+#[derive(Clone)]
+pub struct AuthRateLimit {
+    pub limiter: Arc<dyn AuthLimiter>,
+    /// Trust the address appended to `X-Forwarded-For` by the proxy in front of us
+    pub trust_forwarded_for: bool,
+}
+
+impl AuthRateLimit {
+    pub fn new(limiter: Arc<dyn AuthLimiter>, config: &RateLimitConfig) -> Self {
+        Self {
+            limiter,
+            trust_forwarded_for: config.trust_forwarded_for,
+        }
+    }
+}
+
+/// Client address: the one our trusted proxy (Cloud Run) appended to `X-Forwarded-For`
+/// when `trust_forwarded_for` is set, otherwise the peer of the connection
+fn client_ip(request: &Request, trust_forwarded_for: bool) -> Option<IpAddr> {
+    let forwarded = trust_forwarded_for
+        .then(|| request.headers().get("X-Forwarded-For"))
+        .flatten()
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.rsplit(',').next())
+        .and_then(|ip| ip.trim().parse().ok());
+
+    forwarded.or_else(|| {
+        request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip())
+    })
+}
+
+/// Reject clients with too many failed machine ID authentications with 429
+pub async fn limit_auth_attempts(
+    State(state): State<AuthRateLimit>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let limiter = &state.limiter;
+    let Some(ip) = client_ip(&request, state.trust_forwarded_for) else {
+        return next.run(request).await;
+    };
+
+    if let Some(remaining) = limiter.blocked_for(ip) {
+        tracing::warn!("Rate limiting machine ID authentication from {}", ip);
+        return AppError::TooManyRequests {
+            retry_after_secs: remaining.as_secs().max(1),
+        }
+        .into_response();
+    }
+
+    let response = next.run(request).await;
+    match response.status() {
+        StatusCode::UNAUTHORIZED => limiter.record_failure(ip),
+        status if status.is_success() => limiter.record_success(ip),
+        _ => {}
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn config(max_failures: u32, window_secs: u64) -> RateLimitConfig {
+        RateLimitConfig {
+            auth_max_failures: max_failures,
+            auth_failure_window_secs: window_secs,
+            trust_forwarded_for: false,
+        }
+    }
+
+    fn ip(last: u8) -> IpAddr {
+        IpAddr::from([203, 0, 113, last])
+    }
+
+    #[test]
+    fn blocks_once_the_limit_is_hit() {
+        let limiter = InMemoryAuthLimiter::new(&config(3, 60));
+        let start = Instant::now();
+
+        limiter.record_failure_at(ip(1), start);
+        limiter.record_failure_at(ip(1), start);
+        assert_eq!(limiter.blocked_for_at(ip(1), start), None);
+
+        limiter.record_failure_at(ip(1), start);
+        let later = start + Duration::from_secs(20);
+        assert_eq!(limiter.blocked_for_at(ip(1), later), Some(Duration::from_secs(40)));
+        assert_eq!(limiter.blocked_for_at(ip(2), later), None);
+    }
+
+    #[test]
+    fn window_resets_the_count() {
+        let limiter = InMemoryAuthLimiter::new(&config(2, 60));
+        let start = Instant::now();
+
+        limiter.record_failure_at(ip(1), start);
+        limiter.record_failure_at(ip(1), start);
+        assert!(limiter.blocked_for_at(ip(1), start).is_some());
+
+        let next_window = start + Duration::from_secs(60);
+        assert_eq!(limiter.blocked_for_at(ip(1), next_window), None);
+
+        // A failure in the new window starts counting from one again
+        limiter.record_failure_at(ip(1), next_window);
+        assert_eq!(limiter.blocked_for_at(ip(1), next_window), None);
+    }
+
+    #[test]
+    fn success_clears_failures() {
+        let limiter = InMemoryAuthLimiter::new(&config(1, 60));
+        limiter.record_failure(ip(1));
+        assert!(limiter.blocked_for(ip(1)).is_some());
+
+        limiter.record_success(ip(1));
+        assert_eq!(limiter.blocked_for(ip(1)), None);
+    }
+
+    #[test]
+    fn forwarded_for_is_only_used_when_trusted() {
+        let mut request = axum::http::Request::builder()
+            .header("X-Forwarded-For", "198.51.100.7, 198.51.100.9")
+            .body(Body::empty())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([203, 0, 113, 1], 5000))));
+
+        assert_eq!(client_ip(&request, false), Some(ip(1)));
+        assert_eq!(client_ip(&request, true), Some(IpAddr::from([198, 51, 100, 9])));
+    }
+
+    #[tokio::test]
+    async fn repeated_unauthorized_responses_get_429() {
+        let state = AuthRateLimit {
+            limiter: Arc::new(InMemoryAuthLimiter::new(&config(2, 60))),
+            trust_forwarded_for: false,
+        };
+        let app = Router::new()
+            .route("/arcade/config", get(|| async { StatusCode::UNAUTHORIZED }))
+            .layer(axum::middleware::from_fn_with_state(state, limit_auth_attempts));
+
+        let request = || {
+            let mut request = axum::http::Request::builder().uri("/arcade/config").body(Body::empty()).unwrap();
+            request
+                .extensions_mut()
+                .insert(ConnectInfo(SocketAddr::from(([203, 0, 113, 1], 5000))));
+            request
+        };
+
+        for _ in 0..2 {
+            let response = app.clone().oneshot(request()).await.unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        let response = app.oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(axum::http::header::RETRY_AFTER));
+    }
+}
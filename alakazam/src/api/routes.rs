@@ -1,17 +1,22 @@
-use crate::{api::handlers, services::{AdminService, ArcadeService, GcsService, GyrosService, SensorService, SnorlaxService}};
+use crate::{api::{handlers, rate_limit::{self, AuthRateLimit}}, services::{AdminService, ArcadeService, AuditService, GyrosService, ObjectStorage, SensorService, SnorlaxService, WebhookService}};
 use axum::{
+    middleware,
     routing::{delete, get, post, put},
-    Router,
+    Extension, Router,
 };
 use std::sync::Arc;
 
+#[allow(clippy::too_many_arguments)]
 pub fn create_api_router(
     arcade_service: Arc<ArcadeService>,
-    gcs_service: Arc<GcsService>,
+    storage: Arc<dyn ObjectStorage>,
     snorlax_service: Arc<SnorlaxService>,
     gyros_service: Arc<GyrosService>,
     admin_service: Arc<AdminService>,
     sensor_service: Arc<SensorService>,
+    audit_service: Arc<AuditService>,
+    webhook_service: Arc<WebhookService>,
+    auth_rate_limit: AuthRateLimit,
 ) -> Router {
     // Arcade endpoints
     let arcade_router = Router::new()
@@ -25,7 +30,11 @@ pub fn create_api_router(
             "/arcade/games/{game_id}/download",
             get(handlers::get_game_download_urls),
         )
-        .with_state((arcade_service.clone(), gcs_service.clone()));
+        .route(
+            "/arcade/games/{game_id}/versions/{version_id}/signed-urls",
+            post(handlers::sign_game_version_files),
+        )
+        .with_state((arcade_service.clone(), storage.clone()));
 
     let game_status_router = Router::new()
         .route("/arcade/games/status", post(handlers::report_installations))
@@ -48,6 +57,7 @@ pub fn create_api_router(
             get(handlers::get_customer)
                 .put(handlers::update_customer)
                 .delete(handlers::delete_customer))
+        .route("/admin/customers/{id}/restore", post(handlers::restore_customer))
         // Arcade management
         .route("/admin/arcades",
             post(handlers::create_arcade)
@@ -57,6 +67,11 @@ pub fn create_api_router(
                 .put(handlers::update_arcade)
                 .delete(handlers::delete_arcade))
         .route("/admin/arcades/{id}/channel", put(handlers::update_arcade_channel))
+        .route("/admin/arcades/{id}/allowed-channels",
+            get(handlers::get_arcade_allowed_channels)
+                .put(handlers::update_arcade_allowed_channels))
+        .route("/admin/assignments/bulk", post(handlers::bulk_assign_games))
+        .route("/admin/search", get(handlers::search))
         // Release channel management
         .route("/admin/channels",
             post(handlers::create_channel)
@@ -72,6 +87,7 @@ pub fn create_api_router(
             get(handlers::get_game)
                 .put(handlers::update_game)
                 .delete(handlers::delete_game))
+        .route("/admin/games/{id}/restore", post(handlers::restore_game))
         // Game version management
         .route("/admin/games/{game_id}/versions",
             post(handlers::create_game_version)
@@ -84,14 +100,14 @@ pub fn create_api_router(
                 .delete(handlers::unpublish_version))
         .with_state(admin_service.clone());
 
-    // Game endpoints that require GCS service
-    let game_gcs_router = Router::new()
+    // Game endpoints that require object storage
+    let game_storage_router = Router::new()
         .route("/admin/games", get(handlers::list_games))
         .route("/admin/games/{game_id}/versions/{version_id}", delete(handlers::delete_game_version))
         .route("/admin/games/{game_id}/versions/generate-upload-url", post(handlers::generate_game_version_upload_url))
         .route("/admin/games/{game_id}/versions/generate-batch-upload-urls", post(handlers::generate_batch_upload_urls))
         .route("/admin/games/{game_id}/background/generate-upload-url", post(handlers::generate_background_upload_url))
-        .with_state((admin_service.clone(), gcs_service.clone()));
+        .with_state((admin_service.clone(), storage.clone()));
 
     // Game version confirmation endpoint
     let game_confirm_router = Router::new()
@@ -110,7 +126,7 @@ pub fn create_api_router(
     // Snorlax direct upload endpoints
     let snorlax_upload_router = Router::new()
         .route("/admin/snorlax/generate-upload-url", post(handlers::generate_snorlax_upload_url))
-        .with_state(gcs_service.clone());
+        .with_state(storage.clone());
 
     let snorlax_confirm_router = Router::new()
         .route("/admin/snorlax/confirm-upload", post(handlers::confirm_snorlax_upload))
@@ -128,7 +144,7 @@ pub fn create_api_router(
     // Gyros direct upload endpoints
     let gyros_upload_router = Router::new()
         .route("/admin/gyros/generate-upload-url", post(handlers::generate_gyros_upload_url))
-        .with_state(gcs_service.clone());
+        .with_state(storage.clone());
 
     let gyros_confirm_router = Router::new()
         .route("/admin/gyros/confirm-upload", post(handlers::confirm_gyros_upload))
@@ -144,13 +160,23 @@ pub fn create_api_router(
         .route("/arcade/sensors/report", post(handlers::report_sensor))
         .with_state(sensor_service);
 
-    // Merge routers
-    arcade_router
+    // Audit log endpoint
+    let audit_router = Router::new()
+        .route("/admin/audit-log", get(handlers::list_audit_log))
+        .with_state(audit_service.clone());
+
+    // Endpoints authenticated by machine ID, limited against guessing IDs
+    let machine_router = arcade_router
         .merge(game_download_router)
         .merge(game_status_router)
         .merge(snorlax_router)
+        .merge(sensor_arcade_router)
+        .layer(middleware::from_fn_with_state(auth_rate_limit, rate_limit::limit_auth_attempts));
+
+    // Merge routers
+    machine_router
         .merge(admin_router)
-        .merge(game_gcs_router)
+        .merge(game_storage_router)
         .merge(game_confirm_router)
         .merge(snorlax_admin_router)
         .merge(snorlax_upload_router)
@@ -159,5 +185,9 @@ pub fn create_api_router(
         .merge(gyros_upload_router)
         .merge(gyros_confirm_router)
         .merge(sensor_admin_router)
-        .merge(sensor_arcade_router)
+        .merge(audit_router)
+        // Mutating admin handlers record who changed what
+        .layer(Extension(audit_service))
+        // and notify integrators of catalog changes
+        .layer(Extension(webhook_service))
 }
@@ -4,8 +4,10 @@ use serde::Deserialize;
 pub struct Config {
     pub server: ServerConfig,
     pub database: DatabaseConfig,
-    pub gcs: GcsConfig,
+    pub storage: StorageConfig,
     pub cors: CorsConfig,
+    pub rate_limit: RateLimitConfig,
+    pub webhooks: WebhookConfig,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -19,10 +21,37 @@ pub struct DatabaseConfig {
     pub url: String,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct StorageConfig {
+    pub backend: StorageBackend,
+    pub signed_url_duration_secs: u32,
+    /// Cap on the `expires_in` clients may request for download URLs
+    pub max_signed_url_duration_secs: u32,
+}
+
+/// Object storage backend, selected with `STORAGE_BACKEND=gcs|s3`
+#[derive(Debug, Clone, Deserialize)]
+pub enum StorageBackend {
+    Gcs(GcsConfig),
+    S3(S3Config),
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct GcsConfig {
     pub bucket_name: String,
-    pub signed_url_duration_secs: u32,
+}
+
+/// S3-compatible storage such as AWS S3 or MinIO
+#[derive(Debug, Clone, Deserialize)]
+pub struct S3Config {
+    pub bucket_name: String,
+    pub region: String,
+    /// e.g. "https://s3.eu-west-1.amazonaws.com" or "http://minio:9000"
+    pub endpoint: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Address the bucket in the path rather than the host name (required by MinIO)
+    pub path_style: bool,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -30,6 +59,36 @@ pub struct CorsConfig {
     pub allowed_origin: String,
 }
 
+/// Per source IP limit on failed machine ID authentications
+#[derive(Debug, Clone, Deserialize)]
+pub struct RateLimitConfig {
+    /// Failures allowed within a window before requests get 429 (0 = no limit)
+    pub auth_max_failures: u32,
+    pub auth_failure_window_secs: u64,
+    /// Take the client address from `X-Forwarded-For` (`TRUST_X_FORWARDED_FOR`)
+    /// Only enable behind a proxy that sets the header, such as Cloud Run;
+    /// otherwise clients can pick the address they are limited by.
+    pub trust_forwarded_for: bool,
+}
+
+/// Outgoing notifications about catalog changes
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookConfig {
+    pub endpoints: Vec<WebhookEndpoint>,
+    /// Key for the `X-Alakazam-Signature` HMAC; payloads go unsigned without one
+    pub secret: Option<String>,
+    /// Delivery attempts per endpoint before the event is dead-lettered
+    pub max_attempts: u32,
+}
+
+/// `WEBHOOK_ENDPOINTS` entry, e.g. `game_version.publish=https://example.com/hook`
+#[derive(Debug, Clone, Deserialize)]
+pub struct WebhookEndpoint {
+    /// Event name, `<entity type>.*` or `*`
+    pub event: String,
+    pub url: String,
+}
+
 impl Config {
     pub fn from_env() -> anyhow::Result<Self> {
         dotenvy::dotenv().ok();
@@ -44,16 +103,93 @@ impl Config {
             database: DatabaseConfig {
                 url: std::env::var("DATABASE_URL")?,
             },
-            gcs: GcsConfig {
-                bucket_name: std::env::var("GCS_BUCKET_NAME")?,
-                signed_url_duration_secs: std::env::var("GCS_SIGNED_URL_DURATION_SECS")
+            storage: StorageConfig {
+                backend: StorageBackend::from_env()?,
+                // GCS_SIGNED_URL_DURATION_SECS is still honored for existing deployments
+                signed_url_duration_secs: std::env::var("SIGNED_URL_DURATION_SECS")
+                    .or_else(|_| std::env::var("GCS_SIGNED_URL_DURATION_SECS"))
                     .unwrap_or_else(|_| "3600".to_string())
                     .parse()?,
+                max_signed_url_duration_secs: std::env::var("MAX_SIGNED_URL_DURATION_SECS")
+                    .unwrap_or_else(|_| "86400".to_string())
+                    .parse()?,
             },
             cors: CorsConfig {
                 allowed_origin: std::env::var("CORS_ALLOWED_ORIGIN")
                     .unwrap_or_else(|_| "http://localhost:5173".to_string()),
             },
+            rate_limit: RateLimitConfig {
+                auth_max_failures: std::env::var("AUTH_MAX_FAILURES")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()?,
+                auth_failure_window_secs: std::env::var("AUTH_FAILURE_WINDOW_SECS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()?,
+                trust_forwarded_for: std::env::var("TRUST_X_FORWARDED_FOR")
+                    .unwrap_or_else(|_| "false".to_string())
+                    .parse()?,
+            },
+            webhooks: WebhookConfig {
+                endpoints: WebhookEndpoint::list_from_env()?,
+                secret: std::env::var("WEBHOOK_SECRET").ok().filter(|s| !s.is_empty()),
+                max_attempts: std::env::var("WEBHOOK_MAX_ATTEMPTS")
+                    .unwrap_or_else(|_| "5".to_string())
+                    .parse()?,
+            },
         })
     }
 }
+
+impl StorageBackend {
+    fn from_env() -> anyhow::Result<Self> {
+        let backend = std::env::var("STORAGE_BACKEND").unwrap_or_else(|_| "gcs".to_string());
+
+        match backend.to_lowercase().as_str() {
+            "gcs" => Ok(StorageBackend::Gcs(GcsConfig {
+                bucket_name: std::env::var("GCS_BUCKET_NAME")?,
+            })),
+            "s3" => {
+                let region = std::env::var("S3_REGION").unwrap_or_else(|_| "us-east-1".to_string());
+                let custom_endpoint = std::env::var("S3_ENDPOINT").ok();
+                let path_style = match std::env::var("S3_FORCE_PATH_STYLE") {
+                    Ok(value) => value.parse()?,
+                    // Custom endpoints are almost always MinIO, which needs path-style URLs
+                    Err(_) => custom_endpoint.is_some(),
+                };
+
+                Ok(StorageBackend::S3(S3Config {
+                    bucket_name: std::env::var("S3_BUCKET_NAME")?,
+                    endpoint: custom_endpoint
+                        .unwrap_or_else(|| format!("https://s3.{}.amazonaws.com", region)),
+                    region,
+                    access_key_id: std::env::var("S3_ACCESS_KEY_ID")?,
+                    secret_access_key: std::env::var("S3_SECRET_ACCESS_KEY")?,
+                    path_style,
+                }))
+            }
+            other => anyhow::bail!("Unknown STORAGE_BACKEND '{}', expected 'gcs' or 's3'", other),
+        }
+    }
+}
+
+impl WebhookEndpoint {
+    /// Parse comma-separated `event=url` pairs from `WEBHOOK_ENDPOINTS`
+    fn list_from_env() -> anyhow::Result<Vec<Self>> {
+        let Ok(value) = std::env::var("WEBHOOK_ENDPOINTS") else {
+            return Ok(Vec::new());
+        };
+
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| match entry.split_once('=') {
+                Some((event, url)) if !event.trim().is_empty() && !url.trim().is_empty() => Ok(Self {
+                    event: event.trim().to_string(),
+                    url: url.trim().to_string(),
+                }),
+                _ => anyhow::bail!("Invalid WEBHOOK_ENDPOINTS entry '{}', expected event=url", entry),
+            })
+            .collect()
+    }
+}
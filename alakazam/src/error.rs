@@ -1,5 +1,5 @@
 use axum::{
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -31,12 +31,18 @@ pub enum AppError {
     #[error("Release channel not found")]
     ChannelNotFound,
 
+    #[error("Release channel not allowed for this arcade")]
+    ChannelNotAllowed,
+
     #[error("Customer not found")]
     CustomerNotFound,
 
     #[error("Cannot delete customer with assigned arcades")]
     CustomerHasArcades,
 
+    #[error("Cannot delete game assigned to arcades")]
+    GameHasAssignments,
+
     #[error("Snorlax version not found")]
     SnorlaxVersionNotFound,
 
@@ -49,6 +55,9 @@ pub enum AppError {
     #[error("No current Gyros version set")]
     NoCurrentGyrosVersion,
 
+    #[error("Too many requests, retry after {retry_after_secs}s")]
+    TooManyRequests { retry_after_secs: u64 },
+
     #[error("Internal server error: {0}")]
     Internal(String),
 }
@@ -67,12 +76,25 @@ impl IntoResponse for AppError {
             AppError::GameNotFound => (StatusCode::NOT_FOUND, "Game not found".to_string()),
             AppError::GameVersionNotFound => (StatusCode::NOT_FOUND, "Game version not found".to_string()),
             AppError::ChannelNotFound => (StatusCode::NOT_FOUND, "Release channel not found".to_string()),
+            AppError::ChannelNotAllowed => (StatusCode::FORBIDDEN, "Release channel not allowed for this arcade".to_string()),
             AppError::CustomerNotFound => (StatusCode::NOT_FOUND, "Customer not found".to_string()),
             AppError::CustomerHasArcades => (StatusCode::CONFLICT, "Cannot delete customer with assigned arcades".to_string()),
+            AppError::GameHasAssignments => (StatusCode::CONFLICT, "Cannot delete game assigned to arcades; pass force=true to delete its assignments too".to_string()),
             AppError::SnorlaxVersionNotFound => (StatusCode::NOT_FOUND, "Snorlax version not found".to_string()),
             AppError::NoCurrentSnorlaxVersion => (StatusCode::NOT_FOUND, "No current Snorlax version set".to_string()),
             AppError::GyrosVersionNotFound => (StatusCode::NOT_FOUND, "Gyros version not found".to_string()),
             AppError::NoCurrentGyrosVersion => (StatusCode::NOT_FOUND, "No current Gyros version set".to_string()),
+            AppError::TooManyRequests { retry_after_secs } => {
+                let body = Json(json!({
+                    "error": "Too many requests"
+                }));
+                return (
+                    StatusCode::TOO_MANY_REQUESTS,
+                    [(header::RETRY_AFTER, retry_after_secs.to_string())],
+                    body,
+                )
+                    .into_response();
+            }
             AppError::Internal(msg) => {
                 tracing::error!("Internal error: {}", msg);
                 (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error".to_string())
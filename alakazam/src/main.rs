@@ -9,9 +9,11 @@ mod services;
 
 use axum::extract::DefaultBodyLimit;
 use axum::http::{HeaderValue, Method};
-use config::Config;
-use repositories::{ArcadeRepository, ChannelRepository, CustomerRepository, GameRepository, GyrosRepository, SensorRepository, SnorlaxRepository};
-use services::{AdminService, ArcadeService, GcsService, GyrosService, SensorService, SnorlaxService};
+use config::{Config, StorageBackend};
+use repositories::{ArcadeRepository, AuditRepository, ChannelRepository, CustomerRepository, GameRepository, GyrosRepository, SensorRepository, SnorlaxRepository, WebhookRepository};
+use api::{AuthLimiter, AuthRateLimit, InMemoryAuthLimiter};
+use services::{AdminService, ArcadeService, AuditService, GcsService, GyrosService, ObjectStorage, S3Service, SensorService, SnorlaxService, WebhookService};
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tower_http::{cors::CorsLayer, trace::TraceLayer};
 use tracing::info;
@@ -42,24 +44,37 @@ async fn main() -> anyhow::Result<()> {
     let snorlax_repo = Arc::new(SnorlaxRepository::new(pool.clone()));
     let gyros_repo = Arc::new(GyrosRepository::new(pool.clone()));
     let sensor_repo = Arc::new(SensorRepository::new(pool.clone()));
-
-    // Initialize GCS service with Application Default Credentials
-    let gcs_service = Arc::new(
-        GcsService::new(
-            config.gcs.bucket_name.clone(),
-            config.gcs.signed_url_duration_secs,
-        )
-        .await?,
-    );
-
-    info!("GCS service initialized for bucket: {}", config.gcs.bucket_name);
+    let audit_repo = Arc::new(AuditRepository::new(pool.clone()));
+    let webhook_repo = Arc::new(WebhookRepository::new(pool.clone()));
+
+    // Initialize object storage for the configured backend
+    let duration_secs = config.storage.signed_url_duration_secs;
+    let max_duration_secs = config.storage.max_signed_url_duration_secs;
+    let storage: Arc<dyn ObjectStorage> = match &config.storage.backend {
+        StorageBackend::Gcs(gcs) => {
+            // GCS uses Application Default Credentials
+            let service = GcsService::new(gcs.bucket_name.clone(), duration_secs, max_duration_secs).await?;
+            info!("GCS storage initialized for bucket: {}", gcs.bucket_name);
+            Arc::new(service)
+        }
+        StorageBackend::S3(s3) => {
+            let service = S3Service::new(s3.clone(), duration_secs, max_duration_secs)?;
+            info!("S3 storage initialized for bucket: {} at {}", s3.bucket_name, s3.endpoint);
+            Arc::new(service)
+        }
+    };
 
     // Initialize services
-    let arcade_service = Arc::new(ArcadeService::new(arcade_repo.clone(), game_repo.clone(), gcs_service.clone()));
-    let snorlax_service = Arc::new(SnorlaxService::new(snorlax_repo.clone(), gcs_service.clone()));
-    let gyros_service = Arc::new(GyrosService::new(gyros_repo.clone(), gcs_service.clone()));
+    let arcade_service = Arc::new(ArcadeService::new(arcade_repo.clone(), game_repo.clone(), storage.clone()));
+    let snorlax_service = Arc::new(SnorlaxService::new(snorlax_repo.clone(), storage.clone()));
+    let gyros_service = Arc::new(GyrosService::new(gyros_repo.clone(), storage.clone()));
     let admin_service = Arc::new(AdminService::new(arcade_repo.clone(), channel_repo.clone(), customer_repo.clone(), game_repo.clone()));
     let sensor_service = Arc::new(SensorService::new(sensor_repo.clone(), arcade_repo.clone()));
+    let audit_service = Arc::new(AuditService::new(audit_repo));
+    let webhook_service = Arc::new(WebhookService::new(&config.webhooks, webhook_repo)?);
+    info!("Webhooks configured for {} endpoint(s)", config.webhooks.endpoints.len());
+    let auth_limiter: Arc<dyn AuthLimiter> = Arc::new(InMemoryAuthLimiter::new(&config.rate_limit));
+    let auth_rate_limit = AuthRateLimit::new(auth_limiter, &config.rate_limit);
 
     // Configure CORS
     let allowed_origins: Vec<HeaderValue> = config.cors.allowed_origin
@@ -83,7 +98,7 @@ async fn main() -> anyhow::Result<()> {
     // Build application router
     let app = axum::Router::new()
         .merge(routes::create_router())
-        .nest("/api", api::create_api_router(arcade_service, gcs_service, snorlax_service, gyros_service, admin_service, sensor_service))
+        .nest("/api", api::create_api_router(arcade_service, storage, snorlax_service, gyros_service, admin_service, sensor_service, audit_service, webhook_service, auth_rate_limit))
         .layer(DefaultBodyLimit::max(20 * 1024 * 1024 * 1024)) // 20 GB limit for file uploads
         .layer(cors)
         .layer(TraceLayer::new_for_http());
@@ -94,7 +109,7 @@ async fn main() -> anyhow::Result<()> {
 
     info!("Alakazam server listening on {}", addr);
 
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
 
     Ok(())
 }
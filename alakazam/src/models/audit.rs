@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::chrono::{DateTime, Utc};
+use sqlx::FromRow;
+
+/// A recorded admin mutation
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct AuditLogEntry {
+    pub id: i64,
+    pub actor: String,
+    pub action: String,
+    pub entity_type: String,
+    pub entity_id: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Optional filters for reading the audit log
+#[derive(Debug, Default, Deserialize)]
+pub struct AuditLogFilter {
+    pub entity_type: Option<String>,
+    pub entity_id: Option<i32>,
+    pub actor: Option<String>,
+}
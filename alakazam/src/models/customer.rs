@@ -9,4 +9,6 @@ pub struct Customer {
     pub phone_number: Option<String>,
     pub email: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// Set while soft-deleted
+    pub deleted_at: Option<DateTime<Utc>>,
 }
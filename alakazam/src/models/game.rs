@@ -7,6 +7,8 @@ pub struct Game {
     pub id: i32,
     pub name: String,
     pub created_at: DateTime<Utc>,
+    /// Set while soft-deleted
+    pub deleted_at: Option<DateTime<Utc>>,
 }
 
 /// Game version entity from database
@@ -77,3 +79,9 @@ pub struct PublishVersionRequest {
 pub struct UpdateArcadeChannelRequest {
     pub channel_id: i32,
 }
+
+/// Request to set the extra channels an arcade may request
+#[derive(Debug, Deserialize)]
+pub struct UpdateArcadeAllowedChannelsRequest {
+    pub channel_ids: Vec<i32>,
+}
@@ -1,15 +1,21 @@
 mod arcade;
+mod audit;
 mod customer;
 mod game;
 mod gyros;
+mod pagination;
 mod release_channel;
+mod search;
 mod sensor;
 mod snorlax;
 
 pub use arcade::*;
+pub use audit::*;
 pub use customer::*;
 pub use game::*;
 pub use gyros::*;
+pub use pagination::*;
 pub use release_channel::*;
+pub use search::*;
 pub use sensor::*;
 pub use snorlax::*;
@@ -0,0 +1,105 @@
+use crate::error::{AppError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Page size used when a list request doesn't specify one
+pub const DEFAULT_PAGE_LIMIT: i64 = 100;
+/// Largest page a client may request
+pub const MAX_PAGE_LIMIT: i64 = 500;
+
+/// `?limit=&offset=` query parameters accepted by list endpoints
+#[derive(Debug, Default, Deserialize)]
+pub struct PageParams {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// Validated paging window
+#[derive(Debug, Clone, Copy)]
+pub struct Pagination {
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl PageParams {
+    pub fn validate(&self) -> Result<Pagination> {
+        let limit = self.limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+        let offset = self.offset.unwrap_or(0);
+
+        if !(1..=MAX_PAGE_LIMIT).contains(&limit) {
+            return Err(AppError::BadRequest(format!(
+                "limit must be between 1 and {}",
+                MAX_PAGE_LIMIT
+            )));
+        }
+
+        if offset < 0 {
+            return Err(AppError::BadRequest("offset must not be negative".to_string()));
+        }
+
+        Ok(Pagination { limit, offset })
+    }
+}
+
+/// Envelope returned by paginated list endpoints
+#[derive(Debug, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, total: i64, pagination: Pagination) -> Self {
+        Self {
+            items,
+            total,
+            limit: pagination.limit,
+            offset: pagination.offset,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(limit: Option<i64>, offset: Option<i64>) -> PageParams {
+        PageParams { limit, offset }
+    }
+
+    #[test]
+    fn missing_params_use_the_defaults() {
+        let pagination = PageParams::default().validate().unwrap();
+        assert_eq!(pagination.limit, DEFAULT_PAGE_LIMIT);
+        assert_eq!(pagination.offset, 0);
+    }
+
+    #[test]
+    fn limit_must_be_within_bounds() {
+        assert!(params(Some(1), None).validate().is_ok());
+        assert!(params(Some(MAX_PAGE_LIMIT), None).validate().is_ok());
+        assert!(matches!(params(Some(0), None).validate(), Err(AppError::BadRequest(_))));
+        assert!(matches!(
+            params(Some(MAX_PAGE_LIMIT + 1), None).validate(),
+            Err(AppError::BadRequest(_))
+        ));
+    }
+
+    #[test]
+    fn offset_must_not_be_negative() {
+        assert_eq!(params(None, Some(40)).validate().unwrap().offset, 40);
+        assert!(matches!(params(None, Some(-1)).validate(), Err(AppError::BadRequest(_))));
+    }
+
+    #[test]
+    fn page_echoes_the_window_it_was_built_from() {
+        let pagination = params(Some(2), Some(4)).validate().unwrap();
+        let page = Page::new(vec!["e", "f"], 7, pagination);
+
+        assert_eq!(
+            serde_json::to_value(&page).unwrap(),
+            serde_json::json!({ "items": ["e", "f"], "total": 7, "limit": 2, "offset": 4 })
+        );
+    }
+}
@@ -0,0 +1,17 @@
+use serde::Serialize;
+
+/// One match from the admin search
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SearchHit {
+    Arcade { id: i32, name: String, machine_id: String },
+    Game { id: i32, name: String },
+}
+
+/// Search matches, capped per entity type
+#[derive(Debug, Serialize)]
+pub struct SearchResults {
+    pub items: Vec<SearchHit>,
+    /// More matches exist than were returned; narrow the query to see them
+    pub truncated: bool,
+}
@@ -1,5 +1,6 @@
 use crate::{error::Result, models::Arcade};
 use sqlx::PgPool;
+use super::contains_pattern;
 
 pub struct ArcadeRepository {
     pool: PgPool,
@@ -68,6 +69,23 @@ impl ArcadeRepository {
         Ok(arcades)
     }
 
+    /// Arcades whose name or machine ID contains `query`, case-insensitively, at most `limit`
+    pub async fn search(&self, query: &str, limit: i64) -> Result<Vec<Arcade>> {
+        let arcades = sqlx::query_as::<_, Arcade>(
+            "SELECT id, name, machine_id, status, channel_id, customer_id, installed_games, last_seen_at, created_at
+             FROM arcades
+             WHERE name ILIKE $1 OR machine_id ILIKE $1
+             ORDER BY name ASC, id ASC
+             LIMIT $2"
+        )
+        .bind(contains_pattern(query))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(arcades)
+    }
+
     /// Get arcade by ID
     pub async fn get_by_id(&self, id: i32) -> Result<Option<Arcade>> {
         let arcade = sqlx::query_as::<_, Arcade>(
@@ -125,6 +143,63 @@ impl ArcadeRepository {
         Ok(arcade)
     }
 
+    /// Whether an arcade may request versions from the named channel
+    /// Its own channel is always allowed; others must be on its allowlist.
+    pub async fn is_channel_allowed(&self, arcade_id: i32, channel: &str) -> Result<bool> {
+        let allowed = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (
+                 SELECT 1 FROM arcades a
+                 JOIN release_channels rc ON rc.id = a.channel_id
+                 WHERE a.id = $1 AND rc.name = $2
+                 UNION ALL
+                 SELECT 1 FROM arcade_allowed_channels aac
+                 JOIN release_channels rc ON rc.id = aac.channel_id
+                 WHERE aac.arcade_id = $1 AND rc.name = $2
+             )"
+        )
+        .bind(arcade_id)
+        .bind(channel)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(allowed)
+    }
+
+    /// Get the extra channel IDs an arcade may request
+    pub async fn get_allowed_channel_ids(&self, arcade_id: i32) -> Result<Vec<i32>> {
+        let ids = sqlx::query_scalar::<_, i32>(
+            "SELECT channel_id FROM arcade_allowed_channels WHERE arcade_id = $1 ORDER BY channel_id"
+        )
+        .bind(arcade_id)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(ids)
+    }
+
+    /// Set the extra channels an arcade may request (replaces existing)
+    pub async fn set_allowed_channels(&self, arcade_id: i32, channel_ids: &[i32]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM arcade_allowed_channels WHERE arcade_id = $1")
+            .bind(arcade_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for channel_id in channel_ids {
+            sqlx::query(
+                "INSERT INTO arcade_allowed_channels (arcade_id, channel_id) VALUES ($1, $2)
+                 ON CONFLICT (arcade_id, channel_id) DO NOTHING"
+            )
+            .bind(arcade_id)
+            .bind(channel_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
     /// Update installed games JSON for an arcade
     pub async fn update_installed_games(&self, arcade_id: i32, games_json: serde_json::Value) -> Result<()> {
         sqlx::query(
@@ -143,7 +218,7 @@ impl ArcadeRepository {
     /// Get assigned game IDs for an arcade
     pub async fn get_assigned_game_ids(&self, arcade_id: i32) -> Result<Vec<i32>> {
         let ids = sqlx::query_scalar::<_, i32>(
-            "SELECT game_id FROM arcade_game_assignments WHERE arcade_id = $1 ORDER BY game_id"
+            "SELECT game_id FROM arcade_game_assignments WHERE arcade_id = $1 AND deleted_at IS NULL ORDER BY game_id"
         )
         .bind(arcade_id)
         .fetch_all(&self.pool)
@@ -169,4 +244,28 @@ impl ArcadeRepository {
         }
         Ok(())
     }
+
+    /// Add (arcade_id, game_id) assignments in one transaction, keeping existing ones
+    /// Returns per pair whether it was newly assigned; soft-deleted assignments are revived.
+    pub async fn add_game_assignments(&self, pairs: &[(i32, i32)]) -> Result<Vec<bool>> {
+        let mut tx = self.pool.begin().await?;
+        let mut assigned = Vec::with_capacity(pairs.len());
+
+        for &(arcade_id, game_id) in pairs {
+            let row = sqlx::query_scalar::<_, i32>(
+                "INSERT INTO arcade_game_assignments (arcade_id, game_id) VALUES ($1, $2)
+                 ON CONFLICT (arcade_id, game_id) DO UPDATE SET deleted_at = NULL, created_at = NOW()
+                 WHERE arcade_game_assignments.deleted_at IS NOT NULL
+                 RETURNING arcade_id"
+            )
+            .bind(arcade_id)
+            .bind(game_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+            assigned.push(row.is_some());
+        }
+
+        tx.commit().await?;
+        Ok(assigned)
+    }
 }
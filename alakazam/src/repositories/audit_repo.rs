@@ -0,0 +1,81 @@
+use crate::{
+    error::Result,
+    models::{AuditLogEntry, AuditLogFilter, Pagination},
+};
+use sqlx::PgPool;
+
+pub struct AuditRepository {
+    pool: PgPool,
+}
+
+impl AuditRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record an admin mutation
+    pub async fn insert(
+        &self,
+        actor: &str,
+        action: &str,
+        entity_type: &str,
+        entity_id: i32,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO audit_log (actor, action, entity_type, entity_id)
+             VALUES ($1, $2, $3, $4)"
+        )
+        .bind(actor)
+        .bind(action)
+        .bind(entity_type)
+        .bind(entity_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// List one page of matching entries, newest first
+    pub async fn list(
+        &self,
+        filter: &AuditLogFilter,
+        pagination: Pagination,
+    ) -> Result<Vec<AuditLogEntry>> {
+        let entries = sqlx::query_as::<_, AuditLogEntry>(
+            "SELECT id, actor, action, entity_type, entity_id, created_at
+             FROM audit_log
+             WHERE ($1::text IS NULL OR entity_type = $1)
+               AND ($2::int IS NULL OR entity_id = $2)
+               AND ($3::text IS NULL OR actor = $3)
+             ORDER BY created_at DESC, id DESC
+             LIMIT $4 OFFSET $5"
+        )
+        .bind(filter.entity_type.as_deref())
+        .bind(filter.entity_id)
+        .bind(filter.actor.as_deref())
+        .bind(pagination.limit)
+        .bind(pagination.offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(entries)
+    }
+
+    /// Count matching entries
+    pub async fn count(&self, filter: &AuditLogFilter) -> Result<i64> {
+        let count = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*)
+             FROM audit_log
+             WHERE ($1::text IS NULL OR entity_type = $1)
+               AND ($2::int IS NULL OR entity_id = $2)
+               AND ($3::text IS NULL OR actor = $3)"
+        )
+        .bind(filter.entity_type.as_deref())
+        .bind(filter.entity_id)
+        .bind(filter.actor.as_deref())
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+}
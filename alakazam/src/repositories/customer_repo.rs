@@ -1,4 +1,7 @@
-use crate::{error::Result, models::Customer};
+use crate::{
+    error::Result,
+    models::{Customer, Pagination},
+};
 use sqlx::PgPool;
 
 pub struct CustomerRepository {
@@ -20,7 +23,7 @@ impl CustomerRepository {
         let customer = sqlx::query_as::<_, Customer>(
             "INSERT INTO customers (name, phone_number, email)
              VALUES ($1, $2, $3)
-             RETURNING id, name, phone_number, email, created_at"
+             RETURNING id, name, phone_number, email, created_at, deleted_at"
         )
         .bind(name)
         .bind(phone_number)
@@ -31,27 +34,45 @@ impl CustomerRepository {
         Ok(customer)
     }
 
-    /// List all customers
-    pub async fn list_all(&self) -> Result<Vec<Customer>> {
+    /// List one page of customers, soft-deleted ones only if asked for
+    pub async fn list_page(&self, pagination: Pagination, include_deleted: bool) -> Result<Vec<Customer>> {
         let customers = sqlx::query_as::<_, Customer>(
-            "SELECT id, name, phone_number, email, created_at
+            "SELECT id, name, phone_number, email, created_at, deleted_at
              FROM customers
-             ORDER BY name ASC"
+             WHERE $3 OR deleted_at IS NULL
+             ORDER BY name ASC, id ASC
+             LIMIT $1 OFFSET $2"
         )
+        .bind(pagination.limit)
+        .bind(pagination.offset)
+        .bind(include_deleted)
         .fetch_all(&self.pool)
         .await?;
 
         Ok(customers)
     }
 
-    /// Get customer by ID
-    pub async fn get_by_id(&self, id: i32) -> Result<Option<Customer>> {
+    /// Count customers, soft-deleted ones only if asked for
+    pub async fn count(&self, include_deleted: bool) -> Result<i64> {
+        let count = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM customers WHERE $1 OR deleted_at IS NULL"
+        )
+        .bind(include_deleted)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Get customer by ID, soft-deleted customers only if asked for
+    pub async fn get_by_id(&self, id: i32, include_deleted: bool) -> Result<Option<Customer>> {
         let customer = sqlx::query_as::<_, Customer>(
-            "SELECT id, name, phone_number, email, created_at
+            "SELECT id, name, phone_number, email, created_at, deleted_at
              FROM customers
-             WHERE id = $1"
+             WHERE id = $1 AND ($2 OR deleted_at IS NULL)"
         )
         .bind(id)
+        .bind(include_deleted)
         .fetch_optional(&self.pool)
         .await?;
 
@@ -70,7 +91,7 @@ impl CustomerRepository {
             "UPDATE customers
              SET name = $2, phone_number = $3, email = $4
              WHERE id = $1
-             RETURNING id, name, phone_number, email, created_at"
+             RETURNING id, name, phone_number, email, created_at, deleted_at"
         )
         .bind(id)
         .bind(name)
@@ -82,9 +103,9 @@ impl CustomerRepository {
         Ok(customer)
     }
 
-    /// Delete customer (will fail if customer has arcades due to FK constraint)
+    /// Soft-delete customer
     pub async fn delete(&self, id: i32) -> Result<()> {
-        sqlx::query("DELETE FROM customers WHERE id = $1")
+        sqlx::query("UPDATE customers SET deleted_at = NOW() WHERE id = $1 AND deleted_at IS NULL")
             .bind(id)
             .execute(&self.pool)
             .await?;
@@ -92,6 +113,21 @@ impl CustomerRepository {
         Ok(())
     }
 
+    /// Undo a soft delete
+    pub async fn restore(&self, id: i32) -> Result<Customer> {
+        let customer = sqlx::query_as::<_, Customer>(
+            "UPDATE customers
+             SET deleted_at = NULL
+             WHERE id = $1
+             RETURNING id, name, phone_number, email, created_at, deleted_at"
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(customer)
+    }
+
     /// Get arcade IDs assigned to a customer
     pub async fn get_arcade_ids(&self, customer_id: i32) -> Result<Vec<i32>> {
         let ids = sqlx::query_scalar::<_, i32>(
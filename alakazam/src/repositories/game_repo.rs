@@ -1,8 +1,10 @@
 use crate::{
     error::Result,
-    models::{ChannelInfo, Game, GameVersion, GameVersionWithChannels},
+    models::{ChannelInfo, Game, GameVersion, GameVersionWithChannels, Pagination},
 };
+use sqlx::types::chrono::{DateTime, Utc};
 use sqlx::PgPool;
+use super::contains_pattern;
 
 pub struct GameRepository {
     pool: PgPool,
@@ -22,7 +24,7 @@ impl GameRepository {
         let game = sqlx::query_as::<_, Game>(
             "INSERT INTO games (name)
              VALUES ($1)
-             RETURNING id, name, created_at"
+             RETURNING id, name, created_at, deleted_at"
         )
         .bind(name)
         .fetch_one(&self.pool)
@@ -31,40 +33,95 @@ impl GameRepository {
         Ok(game)
     }
 
-    /// List all games
-    pub async fn list_all_games(&self) -> Result<Vec<Game>> {
+    /// List one page of games, soft-deleted ones only if asked for
+    pub async fn list_games_page(
+        &self,
+        pagination: Pagination,
+        include_deleted: bool,
+        name: Option<&str>,
+    ) -> Result<Vec<Game>> {
         let games = sqlx::query_as::<_, Game>(
-            "SELECT id, name, created_at
+            "SELECT id, name, created_at, deleted_at
              FROM games
-             ORDER BY name ASC"
+             WHERE ($3 OR deleted_at IS NULL) AND ($4::text IS NULL OR name ILIKE $4)
+             ORDER BY name ASC, id ASC
+             LIMIT $1 OFFSET $2"
         )
+        .bind(pagination.limit)
+        .bind(pagination.offset)
+        .bind(include_deleted)
+        .bind(name.map(contains_pattern))
         .fetch_all(&self.pool)
         .await?;
 
         Ok(games)
     }
 
-    /// Get game by ID
-    pub async fn get_game_by_id(&self, game_id: i32) -> Result<Option<Game>> {
+    /// Count games, soft-deleted ones only if asked for
+    pub async fn count_games(&self, include_deleted: bool, name: Option<&str>) -> Result<i64> {
+        let count = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM games WHERE ($1 OR deleted_at IS NULL) AND ($2::text IS NULL OR name ILIKE $2)"
+        )
+        .bind(include_deleted)
+        .bind(name.map(contains_pattern))
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Games whose name contains `query`, case-insensitively, at most `limit`
+    pub async fn search_by_name(&self, query: &str, limit: i64) -> Result<Vec<Game>> {
+        let games = sqlx::query_as::<_, Game>(
+            "SELECT id, name, created_at, deleted_at
+             FROM games
+             WHERE deleted_at IS NULL AND name ILIKE $1
+             ORDER BY name ASC, id ASC
+             LIMIT $2"
+        )
+        .bind(contains_pattern(query))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(games)
+    }
+
+    /// Get game by ID, soft-deleted games only if asked for
+    pub async fn get_game_by_id(&self, game_id: i32, include_deleted: bool) -> Result<Option<Game>> {
         let game = sqlx::query_as::<_, Game>(
-            "SELECT id, name, created_at
+            "SELECT id, name, created_at, deleted_at
              FROM games
-             WHERE id = $1"
+             WHERE id = $1 AND ($2 OR deleted_at IS NULL)"
         )
         .bind(game_id)
+        .bind(include_deleted)
         .fetch_optional(&self.pool)
         .await?;
 
         Ok(game)
     }
 
+    /// Whether an active game other than `excluding_id` already uses `name`
+    pub async fn active_name_taken(&self, name: &str, excluding_id: i32) -> Result<bool> {
+        let taken = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS(SELECT 1 FROM games WHERE name = $1 AND id <> $2 AND deleted_at IS NULL)"
+        )
+        .bind(name)
+        .bind(excluding_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(taken)
+    }
+
     /// Update game
     pub async fn update_game(&self, id: i32, name: &str) -> Result<Game> {
         let game = sqlx::query_as::<_, Game>(
             "UPDATE games
              SET name = $2
              WHERE id = $1
-             RETURNING id, name, created_at"
+             RETURNING id, name, created_at, deleted_at"
         )
         .bind(id)
         .bind(name)
@@ -74,16 +131,72 @@ impl GameRepository {
         Ok(game)
     }
 
-    /// Delete game
+    /// Count arcades a game is actively assigned to
+    pub async fn count_active_assignments(&self, game_id: i32) -> Result<i64> {
+        let count = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM arcade_game_assignments WHERE game_id = $1 AND deleted_at IS NULL"
+        )
+        .bind(game_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Soft-delete game along with its active arcade assignments
+    /// Versions are kept so a restored game is immediately usable again.
     pub async fn delete_game(&self, id: i32) -> Result<()> {
-        sqlx::query("DELETE FROM games WHERE id = $1")
+        let mut tx = self.pool.begin().await?;
+
+        let deleted_at = sqlx::query_scalar::<_, DateTime<Utc>>(
+            "UPDATE games SET deleted_at = NOW() WHERE id = $1 AND deleted_at IS NULL RETURNING deleted_at"
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        if let Some(deleted_at) = deleted_at {
+            sqlx::query(
+                "UPDATE arcade_game_assignments SET deleted_at = $2 WHERE game_id = $1 AND deleted_at IS NULL"
+            )
             .bind(id)
-            .execute(&self.pool)
+            .bind(deleted_at)
+            .execute(&mut *tx)
             .await?;
+        }
 
+        tx.commit().await?;
         Ok(())
     }
 
+    /// Undo a soft delete, restoring the assignments that were deleted with the game
+    pub async fn restore_game(&self, id: i32) -> Result<Game> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            "UPDATE arcade_game_assignments aga
+             SET deleted_at = NULL
+             FROM games g
+             WHERE g.id = $1 AND aga.game_id = g.id AND aga.deleted_at = g.deleted_at"
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        let game = sqlx::query_as::<_, Game>(
+            "UPDATE games
+             SET deleted_at = NULL
+             WHERE id = $1
+             RETURNING id, name, created_at, deleted_at"
+        )
+        .bind(id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(game)
+    }
+
     // ========================================================================
     // GAME VERSION CRUD
     // ========================================================================
@@ -118,21 +231,40 @@ impl GameRepository {
         Ok(version)
     }
 
-    /// List all versions for a game
-    pub async fn list_versions_by_game(&self, game_id: i32) -> Result<Vec<GameVersion>> {
+    /// List one page of versions for a game, newest first
+    pub async fn list_versions_by_game(
+        &self,
+        game_id: i32,
+        pagination: Pagination,
+    ) -> Result<Vec<GameVersion>> {
         let versions = sqlx::query_as::<_, GameVersion>(
             "SELECT id, game_id, version, gcs_path, release_date
              FROM game_versions
              WHERE game_id = $1
-             ORDER BY release_date DESC"
+             ORDER BY release_date DESC, id DESC
+             LIMIT $2 OFFSET $3"
         )
         .bind(game_id)
+        .bind(pagination.limit)
+        .bind(pagination.offset)
         .fetch_all(&self.pool)
         .await?;
 
         Ok(versions)
     }
 
+    /// Count all versions for a game
+    pub async fn count_versions_by_game(&self, game_id: i32) -> Result<i64> {
+        let count = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM game_versions WHERE game_id = $1"
+        )
+        .bind(game_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
     /// Get version with channels included
     pub async fn get_version_with_channels(&self, version_id: i32) -> Result<Option<GameVersionWithChannels>> {
         // First get the version
@@ -163,9 +295,13 @@ impl GameRepository {
         }))
     }
 
-    /// List all versions for a game with their channels
-    pub async fn list_versions_with_channels(&self, game_id: i32) -> Result<Vec<GameVersionWithChannels>> {
-        let versions = self.list_versions_by_game(game_id).await?;
+    /// List one page of versions for a game with their channels
+    pub async fn list_versions_with_channels(
+        &self,
+        game_id: i32,
+        pagination: Pagination,
+    ) -> Result<Vec<GameVersionWithChannels>> {
+        let versions = self.list_versions_by_game(game_id, pagination).await?;
 
         let mut versions_with_channels = Vec::new();
         for version in versions {
@@ -248,20 +384,24 @@ impl GameRepository {
         Ok(())
     }
 
-    /// Get all game versions available to an arcade
-    /// Only returns games explicitly assigned to this arcade AND published to its channel
-    pub async fn get_arcade_available_games(&self, arcade_id: i32) -> Result<Vec<GameVersion>> {
+    /// Latest version of each game assigned to the arcade, published to the
+    /// named channel or, without one, to the arcade's own channel
+    pub async fn get_arcade_available_games(&self, arcade_id: i32, channel: Option<&str>) -> Result<Vec<GameVersion>> {
         let results = sqlx::query_as::<_, GameVersion>(
             r#"SELECT DISTINCT ON (gv.game_id)
                 gv.id, gv.game_id, gv.version, gv.gcs_path, gv.release_date
                FROM game_versions gv
                JOIN game_version_channels gvc ON gv.id = gvc.version_id
-               JOIN arcades a ON a.channel_id = gvc.channel_id
-               JOIN arcade_game_assignments aga ON aga.arcade_id = a.id AND aga.game_id = gv.game_id
-               WHERE a.id = $1
+               JOIN release_channels rc ON rc.id = gvc.channel_id
+               JOIN arcade_game_assignments aga ON aga.game_id = gv.game_id
+               JOIN arcades a ON a.id = aga.arcade_id
+               JOIN games g ON g.id = gv.game_id
+               WHERE a.id = $1 AND aga.deleted_at IS NULL AND g.deleted_at IS NULL
+                 AND (($2::text IS NULL AND gvc.channel_id = a.channel_id) OR rc.name = $2)
                ORDER BY gv.game_id, gv.release_date DESC"#
         )
         .bind(arcade_id)
+        .bind(channel)
         .fetch_all(&self.pool)
         .await?;
 
@@ -1,15 +1,33 @@
 mod arcade_repo;
+mod audit_repo;
 mod channel_repo;
 mod customer_repo;
 mod game_repo;
 mod gyros_repo;
 mod sensor_repo;
 mod snorlax_repo;
+mod webhook_repo;
 
 pub use arcade_repo::ArcadeRepository;
+pub use audit_repo::AuditRepository;
 pub use channel_repo::ChannelRepository;
 pub use customer_repo::CustomerRepository;
 pub use game_repo::GameRepository;
 pub use gyros_repo::GyrosRepository;
 pub use sensor_repo::SensorRepository;
 pub use snorlax_repo::SnorlaxRepository;
+pub use webhook_repo::WebhookRepository;
+
+/// ILIKE pattern matching names that contain `needle`, with wildcards in it taken literally
+fn contains_pattern(needle: &str) -> String {
+    let mut pattern = String::with_capacity(needle.len() + 2);
+    pattern.push('%');
+    for c in needle.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
@@ -0,0 +1,36 @@
+use crate::error::Result;
+use sqlx::PgPool;
+
+pub struct WebhookRepository {
+    pool: PgPool,
+}
+
+impl WebhookRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Keep a delivery that exhausted its attempts, so it can be inspected and replayed
+    pub async fn insert_dead_letter(
+        &self,
+        event: &str,
+        url: &str,
+        payload: &serde_json::Value,
+        last_error: &str,
+        attempts: i32,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO webhook_dead_letters (event, url, payload, last_error, attempts)
+             VALUES ($1, $2, $3, $4, $5)"
+        )
+        .bind(event)
+        .bind(url)
+        .bind(payload)
+        .bind(last_error)
+        .bind(attempts)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
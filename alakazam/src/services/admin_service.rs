@@ -1,10 +1,16 @@
 use crate::{
     error::{AppError, Result},
-    models::{Arcade, Customer, Game, GameVersion, GameVersionWithChannels, ReleaseChannel},
+    models::{
+        Arcade, Customer, Game, GameVersion, GameVersionWithChannels, Pagination, ReleaseChannel,
+        SearchHit, SearchResults,
+    },
     repositories::{ArcadeRepository, ChannelRepository, CustomerRepository, GameRepository},
 };
 use std::sync::Arc;
 
+/// Matches returned per entity type by `search`
+const SEARCH_LIMIT: i64 = 25;
+
 pub struct AdminService {
     arcade_repo: Arc<ArcadeRepository>,
     channel_repo: Arc<ChannelRepository>,
@@ -73,6 +79,34 @@ impl AdminService {
         self.arcade_repo.set_game_assignments(arcade_id, game_ids).await
     }
 
+    /// Assign games to arcades in bulk, refusing the whole batch if any arcade
+    /// or game doesn't exist. Returns per pair whether it was newly assigned.
+    pub async fn add_game_assignments(&self, pairs: &[(i32, i32)]) -> Result<Vec<bool>> {
+        let mut arcade_ids: Vec<i32> = pairs.iter().map(|&(arcade_id, _)| arcade_id).collect();
+        let mut game_ids: Vec<i32> = pairs.iter().map(|&(_, game_id)| game_id).collect();
+        arcade_ids.sort_unstable();
+        arcade_ids.dedup();
+        game_ids.sort_unstable();
+        game_ids.dedup();
+
+        let mut missing = Vec::new();
+        for id in arcade_ids {
+            if self.arcade_repo.get_by_id(id).await?.is_none() {
+                missing.push(format!("arcade {}", id));
+            }
+        }
+        for id in game_ids {
+            if self.game_repo.get_game_by_id(id, false).await?.is_none() {
+                missing.push(format!("game {}", id));
+            }
+        }
+        if !missing.is_empty() {
+            return Err(AppError::BadRequest(format!("Unknown {}", missing.join(", "))));
+        }
+
+        self.arcade_repo.add_game_assignments(pairs).await
+    }
+
     pub async fn update_arcade_channel(&self, arcade_id: i32, channel_id: i32) -> Result<Arcade> {
         // Verify arcade exists
         self.get_arcade(arcade_id).await?;
@@ -84,6 +118,25 @@ impl AdminService {
         self.arcade_repo.update_channel(arcade_id, channel_id).await
     }
 
+    pub async fn get_arcade_allowed_channels(&self, arcade_id: i32) -> Result<Vec<i32>> {
+        self.get_arcade(arcade_id).await?;
+        self.arcade_repo.get_allowed_channel_ids(arcade_id).await
+    }
+
+    /// Replace the extra channels an arcade may request with `?channel=`
+    pub async fn set_arcade_allowed_channels(&self, arcade_id: i32, channel_ids: &[i32]) -> Result<Vec<i32>> {
+        // Verify arcade exists
+        self.get_arcade(arcade_id).await?;
+
+        // Verify all channels exist
+        for channel_id in channel_ids {
+            self.get_channel(*channel_id).await?;
+        }
+
+        self.arcade_repo.set_allowed_channels(arcade_id, channel_ids).await?;
+        self.arcade_repo.get_allowed_channel_ids(arcade_id).await
+    }
+
     // ========================================================================
     // CUSTOMER OPERATIONS
     // ========================================================================
@@ -97,13 +150,21 @@ impl AdminService {
         self.customer_repo.create(name, phone_number, email).await
     }
 
-    pub async fn list_customers(&self) -> Result<Vec<Customer>> {
-        self.customer_repo.list_all().await
+    /// One page of customers plus the total count
+    pub async fn list_customers(&self, pagination: Pagination, include_deleted: bool) -> Result<(Vec<Customer>, i64)> {
+        let customers = self.customer_repo.list_page(pagination, include_deleted).await?;
+        let total = self.customer_repo.count(include_deleted).await?;
+        Ok((customers, total))
     }
 
     pub async fn get_customer(&self, id: i32) -> Result<Customer> {
+        self.find_customer(id, false).await
+    }
+
+    /// Get a customer, including a soft-deleted one if asked for
+    pub async fn find_customer(&self, id: i32, include_deleted: bool) -> Result<Customer> {
         self.customer_repo
-            .get_by_id(id)
+            .get_by_id(id, include_deleted)
             .await?
             .ok_or(AppError::CustomerNotFound)
     }
@@ -130,12 +191,17 @@ impl AdminService {
         self.customer_repo.delete(id).await
     }
 
+    pub async fn restore_customer(&self, id: i32) -> Result<Customer> {
+        self.find_customer(id, true).await?;
+        self.customer_repo.restore(id).await
+    }
+
     pub async fn get_customer_arcade_ids(&self, customer_id: i32) -> Result<Vec<i32>> {
         self.customer_repo.get_arcade_ids(customer_id).await
     }
 
-    pub async fn get_customer_with_arcade_ids(&self, id: i32) -> Result<(Customer, Vec<i32>)> {
-        let customer = self.get_customer(id).await?;
+    pub async fn get_customer_with_arcade_ids(&self, id: i32, include_deleted: bool) -> Result<(Customer, Vec<i32>)> {
+        let customer = self.find_customer(id, include_deleted).await?;
         let arcade_ids = self.customer_repo.get_arcade_ids(id).await?;
         Ok((customer, arcade_ids))
     }
@@ -191,13 +257,27 @@ impl AdminService {
         self.game_repo.create_game(name).await
     }
 
-    pub async fn list_games(&self) -> Result<Vec<Game>> {
-        self.game_repo.list_all_games().await
+    /// One page of games plus the total count, optionally only those whose name contains `name`
+    pub async fn list_games(
+        &self,
+        pagination: Pagination,
+        include_deleted: bool,
+        name: Option<&str>,
+    ) -> Result<(Vec<Game>, i64)> {
+        let name = name.map(str::trim).filter(|n| !n.is_empty());
+        let games = self.game_repo.list_games_page(pagination, include_deleted, name).await?;
+        let total = self.game_repo.count_games(include_deleted, name).await?;
+        Ok((games, total))
     }
 
     pub async fn get_game(&self, id: i32) -> Result<Game> {
+        self.find_game(id, false).await
+    }
+
+    /// Get a game, including a soft-deleted one if asked for
+    pub async fn find_game(&self, id: i32, include_deleted: bool) -> Result<Game> {
         self.game_repo
-            .get_game_by_id(id)
+            .get_game_by_id(id, include_deleted)
             .await?
             .ok_or(AppError::GameNotFound)
     }
@@ -207,12 +287,30 @@ impl AdminService {
         self.game_repo.update_game(id, name).await
     }
 
-    pub async fn delete_game(&self, id: i32) -> Result<()> {
+    /// Soft-delete a game
+    /// A game still assigned to arcades is only deleted with `force`, which
+    /// soft-deletes those assignments too.
+    pub async fn delete_game(&self, id: i32, force: bool) -> Result<()> {
         self.get_game(id).await?;
-        // CASCADE delete will remove versions
+
+        if !force && self.game_repo.count_active_assignments(id).await? > 0 {
+            return Err(AppError::GameHasAssignments);
+        }
+
         self.game_repo.delete_game(id).await
     }
 
+    pub async fn restore_game(&self, id: i32) -> Result<Game> {
+        let game = self.find_game(id, true).await?;
+        if self.game_repo.active_name_taken(&game.name, id).await? {
+            return Err(AppError::BadRequest(format!(
+                "Another game is already named '{}'; rename it before restoring this one",
+                game.name
+            )));
+        }
+        self.game_repo.restore_game(id).await
+    }
+
     // ========================================================================
     // GAME VERSION OPERATIONS
     // ========================================================================
@@ -227,9 +325,19 @@ impl AdminService {
         self.game_repo.create_version(game_id, version, gcs_path).await
     }
 
-    pub async fn list_game_versions_with_channels(&self, game_id: i32) -> Result<Vec<GameVersionWithChannels>> {
+    /// One page of a game's versions plus the total count
+    pub async fn list_game_versions_with_channels(
+        &self,
+        game_id: i32,
+        pagination: Pagination,
+    ) -> Result<(Vec<GameVersionWithChannels>, i64)> {
         self.get_game(game_id).await?;
-        self.game_repo.list_versions_with_channels(game_id).await
+        let versions = self
+            .game_repo
+            .list_versions_with_channels(game_id, pagination)
+            .await?;
+        let total = self.game_repo.count_versions_by_game(game_id).await?;
+        Ok((versions, total))
     }
 
     pub async fn get_game_version(&self, version_id: i32) -> Result<GameVersion> {
@@ -291,4 +399,38 @@ impl AdminService {
         // Unpublish from all channels
         self.game_repo.unpublish_version_from_all_channels(version_id).await
     }
+
+    // ========================================================================
+    // SEARCH OPERATIONS
+    // ========================================================================
+
+    /// Arcades and games whose name contains `query`, arcades first
+    pub async fn search(&self, query: &str) -> Result<SearchResults> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Err(AppError::BadRequest("Search query must not be empty".to_string()));
+        }
+
+        // One extra row per type tells whether the results were cut off
+        let mut arcades = self.arcade_repo.search(query, SEARCH_LIMIT + 1).await?;
+        let mut games = self.game_repo.search_by_name(query, SEARCH_LIMIT + 1).await?;
+        let truncated = arcades.len() as i64 > SEARCH_LIMIT || games.len() as i64 > SEARCH_LIMIT;
+        arcades.truncate(SEARCH_LIMIT as usize);
+        games.truncate(SEARCH_LIMIT as usize);
+
+        let items = arcades
+            .into_iter()
+            .map(|arcade| SearchHit::Arcade {
+                id: arcade.id,
+                name: arcade.name,
+                machine_id: arcade.machine_id,
+            })
+            .chain(games.into_iter().map(|game| SearchHit::Game {
+                id: game.id,
+                name: game.name,
+            }))
+            .collect();
+
+        Ok(SearchResults { items, truncated })
+    }
 }
@@ -2,22 +2,22 @@ use crate::{
     error::{AppError, Result},
     models::{ArcadeConfigResponse, GameAssignmentResponse},
     repositories::{ArcadeRepository, GameRepository},
-    services::GcsService,
+    services::ObjectStorage,
 };
 use std::sync::Arc;
 
 pub struct ArcadeService {
     arcade_repo: Arc<ArcadeRepository>,
     game_repo: Arc<GameRepository>,
-    gcs_service: Arc<GcsService>,
+    storage: Arc<dyn ObjectStorage>,
 }
 
 impl ArcadeService {
-    pub fn new(arcade_repo: Arc<ArcadeRepository>, game_repo: Arc<GameRepository>, gcs_service: Arc<GcsService>) -> Self {
+    pub fn new(arcade_repo: Arc<ArcadeRepository>, game_repo: Arc<GameRepository>, storage: Arc<dyn ObjectStorage>) -> Self {
         Self {
             arcade_repo,
             game_repo,
-            gcs_service,
+            storage,
         }
     }
 
@@ -37,7 +37,9 @@ impl ArcadeService {
     }
 
     /// Get all game versions available to an arcade (based on its channel)
-    pub async fn get_arcade_games(&self, machine_id: &str) -> Result<Vec<GameAssignmentResponse>> {
+    /// `channel` picks versions from another release channel by name instead, if
+    /// the arcade is allowed to follow it
+    pub async fn get_arcade_games(&self, machine_id: &str, channel: Option<&str>) -> Result<Vec<GameAssignmentResponse>> {
         // Authenticate arcade
         let arcade = self
             .arcade_repo
@@ -48,8 +50,14 @@ impl ArcadeService {
         // Update last seen
         self.arcade_repo.update_last_seen(arcade.id).await?;
 
+        if let Some(channel) = channel
+            && !self.arcade_repo.is_channel_allowed(arcade.id, channel).await?
+        {
+            return Err(AppError::ChannelNotAllowed);
+        }
+
         // Get all versions available to this arcade based on its channel
-        let available_versions = self.game_repo.get_arcade_available_games(arcade.id).await?;
+        let available_versions = self.game_repo.get_arcade_available_games(arcade.id, channel).await?;
 
         // Build response with full game and version details
         let mut responses = Vec::new();
@@ -58,17 +66,18 @@ impl ArcadeService {
             // Get game info
             let game = self
                 .game_repo
-                .get_game_by_id(version.game_id)
+                .get_game_by_id(version.game_id, false)
                 .await?
                 .ok_or(AppError::GameNotFound)?;
 
             // Generate signed URL for background image
             let background_image_url = {
                 let bg_path = format!("{}/{}BG.jpg", game.name, game.name);
-                self.gcs_service
-                    .generate_signed_download_url(&bg_path)
+                self.storage
+                    .signed_download_url(&bg_path)
                     .await
                     .ok()
+                    .map(|signed| signed.url)
             };
 
             responses.push(GameAssignmentResponse {
@@ -0,0 +1,48 @@
+use crate::{
+    error::Result,
+    models::{AuditLogEntry, AuditLogFilter, Pagination},
+    repositories::AuditRepository,
+};
+use std::sync::Arc;
+
+pub struct AuditService {
+    audit_repo: Arc<AuditRepository>,
+}
+
+impl AuditService {
+    pub fn new(audit_repo: Arc<AuditRepository>) -> Self {
+        Self { audit_repo }
+    }
+
+    /// Record an admin mutation that has already succeeded
+    ///
+    /// A failed write is logged rather than returned: the mutation itself
+    /// went through and its result must still reach the caller.
+    pub async fn record(&self, actor: &str, action: &str, entity_type: &str, entity_id: i32) {
+        if let Err(e) = self
+            .audit_repo
+            .insert(actor, action, entity_type, entity_id)
+            .await
+        {
+            tracing::error!(
+                actor,
+                action,
+                entity_type,
+                entity_id,
+                error = %e,
+                "Failed to write audit log entry"
+            );
+        }
+    }
+
+    /// One page of matching entries plus the total count
+    pub async fn list(
+        &self,
+        filter: &AuditLogFilter,
+        pagination: Pagination,
+    ) -> Result<(Vec<AuditLogEntry>, i64)> {
+        let entries = self.audit_repo.list(filter, pagination).await?;
+        let total = self.audit_repo.count(filter).await?;
+        Ok((entries, total))
+    }
+}
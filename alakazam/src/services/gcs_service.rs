@@ -1,8 +1,13 @@
-use crate::error::{AppError, Result};
+use crate::{
+    error::{AppError, Result},
+    services::{signed_url_cache::SignedUrlCache, ObjectStorage, SignedUrl, StoredFile},
+};
+use async_trait::async_trait;
 use percent_encoding::{percent_encode, AsciiSet, CONTROLS};
 use serde::Deserialize;
 use sha2::Digest;
 use std::sync::Arc;
+use std::time::Instant;
 
 // Based on RFC 3986, encode everything except unreserved characters (A-Z, a-z, 0-9, -, ., _, ~)
 const QUERY_ENCODE_SET: &AsciiSet = &CONTROLS
@@ -25,6 +30,9 @@ const QUERY_ENCODE_SET: &AsciiSet = &CONTROLS
     .add(b'%')
     .add(b'+');
 
+/// Longest expiry GCS accepts for a V4 signed URL (7 days)
+const MAX_SIGNED_URL_EXPIRY_SECS: u32 = 604_800;
+
 #[derive(Debug, Deserialize)]
 struct GcsListResponse {
     items: Option<Vec<GcsObject>>,
@@ -33,16 +41,25 @@ struct GcsListResponse {
 #[derive(Debug, Deserialize)]
 struct GcsObject {
     name: String,
+    /// Object size in bytes (GCS reports this as a string)
+    size: Option<String>,
+    /// Base64-encoded MD5 digest of the object contents
+    #[serde(rename = "md5Hash")]
+    md5_hash: Option<String>,
 }
 
 pub struct GcsService {
     bucket_name: String,
     url_duration_secs: u32,
+    max_url_duration_secs: u32,
     token_provider: Arc<dyn gcp_auth::TokenProvider>,
+    /// Signing goes through the IAM signBlob API, so download URLs with the
+    /// default validity are reused while still fresh
+    download_urls: SignedUrlCache,
 }
 
 impl GcsService {
-    pub async fn new(bucket_name: String, duration_secs: u32) -> Result<Self> {
+    pub async fn new(bucket_name: String, duration_secs: u32, max_duration_secs: u32) -> Result<Self> {
         // Initialize Application Default Credentials
         let token_provider = gcp_auth::provider()
             .await
@@ -52,33 +69,21 @@ impl GcsService {
 
         Ok(Self {
             bucket_name,
-            url_duration_secs: duration_secs,
+            url_duration_secs: duration_secs.min(MAX_SIGNED_URL_EXPIRY_SECS),
+            max_url_duration_secs: max_duration_secs.max(duration_secs).min(MAX_SIGNED_URL_EXPIRY_SECS),
             token_provider,
+            download_urls: SignedUrlCache::new(duration_secs.min(MAX_SIGNED_URL_EXPIRY_SECS)),
         })
     }
 
-    /// Get the URL duration in seconds
-    pub fn get_url_duration_secs(&self) -> u32 {
-        self.url_duration_secs
-    }
-
-    /// Generate a signed URL for downloading an object (v4 signing)
-    pub async fn generate_signed_download_url(&self, object_path: &str) -> Result<String> {
-        self.generate_signed_url(object_path, "GET", self.url_duration_secs).await
-    }
-
-    /// Generate a signed URL for uploading an object (v4 signing)
-    pub async fn generate_signed_upload_url(&self, object_path: &str, duration_secs: u32) -> Result<String> {
-        self.generate_signed_url(object_path, "PUT", duration_secs).await
-    }
-
-    /// Internal method to generate signed URLs for both upload and download
+    /// Generate a v4 signed URL for both upload and download
     async fn generate_signed_url(&self, object_path: &str, method: &str, expiration: u32) -> Result<String> {
         use chrono::Utc;
 
         let now = Utc::now();
         let timestamp = now.format("%Y%m%dT%H%M%SZ").to_string();
         let datestamp = now.format("%Y%m%d").to_string();
+        let expiration = expiration.min(MAX_SIGNED_URL_EXPIRY_SECS);
 
         let encoded_path = object_path
             .split('/')
@@ -127,67 +132,6 @@ impl GcsService {
         Ok(url)
     }
 
-    /// List all files in a GCS folder and generate signed URLs for each
-    /// Returns a list of relative paths and their signed download URLs
-    pub async fn list_and_sign_folder(&self, folder_path: &str) -> Result<Vec<crate::api::handlers::GameFile>> {
-        use reqwest::Client;
-
-        // Get OAuth2 token for GCS API access
-        let token = self.get_access_token().await?;
-
-        // List objects with the folder prefix
-        let list_url = format!(
-            "https://storage.googleapis.com/storage/v1/b/{}/o?prefix={}",
-            self.bucket_name,
-            percent_encoding::utf8_percent_encode(folder_path, &percent_encoding::NON_ALPHANUMERIC)
-        );
-
-        let client = Client::new();
-        let response = client
-            .get(&list_url)
-            .header("Authorization", format!("Bearer {}", token))
-            .send()
-            .await
-            .map_err(|e| AppError::Internal(format!("Failed to list GCS objects: {}", e)))?;
-
-        if !response.status().is_success() {
-            return Err(AppError::Internal(format!(
-                "GCS list failed with status {}: {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            )));
-        }
-
-        let list_response: GcsListResponse = response
-            .json()
-            .await
-            .map_err(|e| AppError::Internal(format!("Failed to parse GCS list response: {}", e)))?;
-
-        // Generate signed URLs for each file
-        let mut files = Vec::new();
-        for item in list_response.items.unwrap_or_default() {
-            // Skip directories (objects ending with /)
-            if item.name.ends_with('/') {
-                continue;
-            }
-
-            let download_url = self.generate_signed_download_url(&item.name).await?;
-
-            // Get relative path (remove the folder prefix)
-            let relative_path = item.name
-                .strip_prefix(&format!("{}/", folder_path))
-                .unwrap_or(&item.name)
-                .to_string();
-
-            files.push(crate::api::handlers::GameFile {
-                path: relative_path,
-                download_url,
-            });
-        }
-
-        Ok(files)
-    }
-
     /// Get service account email from GCP metadata server or environment variable
     async fn get_service_account_email(&self) -> Result<String> {
         // Try environment variable first (for local development)
@@ -239,47 +183,114 @@ impl GcsService {
         Ok(token.as_str().to_string())
     }
 
-    /// Delete a file from GCS
-    pub async fn delete_file(&self, object_path: &str) -> Result<()> {
-        use reqwest::Client;
+    /// Sign a string using IAM signBlob API
+    async fn sign_string(&self, message: &str, service_account_email: &str) -> Result<String> {
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize)]
+        struct SignBlobRequest {
+            payload: String,
+        }
+
+        #[derive(Deserialize)]
+        #[allow(dead_code)]
+        struct SignBlobResponse {
+            #[serde(rename = "keyId")]
+            key_id: String,
+            #[serde(rename = "signedBlob")]
+            signed_blob: String,
+        }
 
         let token = self.get_access_token().await?;
-        let delete_url = format!(
-            "https://storage.googleapis.com/storage/v1/b/{}/o/{}",
-            self.bucket_name,
-            percent_encoding::utf8_percent_encode(object_path, &percent_encoding::NON_ALPHANUMERIC)
+        let sign_url = format!(
+            "https://iamcredentials.googleapis.com/v1/projects/-/serviceAccounts/{}:signBlob",
+            service_account_email
         );
 
-        let client = Client::new();
+        let payload_base64 = base64::Engine::encode(
+            &base64::engine::general_purpose::STANDARD,
+            message.as_bytes()
+        );
+
+        let request_body = SignBlobRequest {
+            payload: payload_base64,
+        };
+
+        let client = reqwest::Client::new();
         let response = client
-            .delete(&delete_url)
+            .post(&sign_url)
             .header("Authorization", format!("Bearer {}", token))
+            .json(&request_body)
             .send()
             .await
-            .map_err(|e| AppError::Internal(format!("Failed to delete from GCS: {}", e)))?;
+            .map_err(|e| AppError::Internal(format!("Failed to call signBlob API: {}", e)))?;
 
-        if !response.status().is_success() && response.status().as_u16() != 404 {
+        if !response.status().is_success() {
             let status = response.status();
             let error_text = response.text().await.unwrap_or_default();
             return Err(AppError::Internal(format!(
-                "GCS delete failed with status {}: {}",
+                "signBlob API failed with status {}: {}",
                 status, error_text
             )));
         }
 
-        Ok(())
+        let sign_response: SignBlobResponse = response
+            .json()
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to parse signBlob response: {}", e)))?;
+
+        // Decode the base64 signature and encode as hex
+        let signature_bytes = base64::Engine::decode(
+            &base64::engine::general_purpose::STANDARD,
+            sign_response.signed_blob
+        ).map_err(|e| AppError::Internal(format!("Failed to decode signature: {}", e)))?;
+
+        Ok(hex::encode(signature_bytes))
+    }
+}
+
+#[async_trait]
+impl ObjectStorage for GcsService {
+    fn url_duration_secs(&self) -> u32 {
+        self.url_duration_secs
+    }
+
+    fn max_url_duration_secs(&self) -> u32 {
+        self.max_url_duration_secs
+    }
+
+    async fn signed_download_url_valid_for(&self, object_path: &str, duration_secs: u32) -> Result<SignedUrl> {
+        let cacheable = duration_secs == self.url_duration_secs;
+        if let Some(url) = cacheable.then(|| self.download_urls.get(object_path)).flatten() {
+            return Ok(url);
+        }
+
+        let signed_at = Instant::now();
+        let signed_at_utc = chrono::Utc::now();
+        let url = self.generate_signed_url(object_path, "GET", duration_secs).await?;
+        let url = SignedUrl::new(url, signed_at_utc, duration_secs.min(MAX_SIGNED_URL_EXPIRY_SECS));
+
+        if cacheable {
+            self.download_urls.insert(object_path, url.clone(), signed_at);
+        }
+        Ok(url)
+    }
+
+    async fn signed_upload_url(&self, object_path: &str, duration_secs: u32) -> Result<String> {
+        self.generate_signed_url(object_path, "PUT", duration_secs).await
     }
 
-    /// Delete all files with a specific prefix (folder)
-    pub async fn delete_folder(&self, folder_path: &str) -> Result<()> {
+    async fn list_folder(&self, folder_path: &str) -> Result<Vec<StoredFile>> {
         use reqwest::Client;
 
-        // List all objects with the prefix
+        // Get OAuth2 token for GCS API access
         let token = self.get_access_token().await?;
+
+        // List objects with the folder prefix
         let list_url = format!(
             "https://storage.googleapis.com/storage/v1/b/{}/o?prefix={}",
             self.bucket_name,
-            percent_encoding::utf8_percent_encode(folder_path, &percent_encoding::NON_ALPHANUMERIC)
+            percent_encoding::utf8_percent_encode(folder_path, percent_encoding::NON_ALPHANUMERIC)
         );
 
         let client = Client::new();
@@ -303,76 +314,97 @@ impl GcsService {
             .await
             .map_err(|e| AppError::Internal(format!("Failed to parse GCS list response: {}", e)))?;
 
-        // Delete each file
+        let mut files = Vec::new();
         for item in list_response.items.unwrap_or_default() {
-            self.delete_file(&item.name).await?;
+            // Skip directories (objects ending with /)
+            if item.name.ends_with('/') {
+                continue;
+            }
+
+            // Get relative path (remove the folder prefix)
+            let relative_path = item.name
+                .strip_prefix(&format!("{}/", folder_path))
+                .unwrap_or(&item.name)
+                .to_string();
+
+            files.push(StoredFile {
+                path: relative_path,
+                size: item.size.as_deref().and_then(|s| s.parse().ok()),
+                md5_hash: item.md5_hash,
+                key: item.name,
+            });
         }
 
-        Ok(())
+        Ok(files)
     }
 
-    /// Sign a string using IAM signBlob API
-    async fn sign_string(&self, message: &str, service_account_email: &str) -> Result<String> {
-        use serde::{Deserialize, Serialize};
+    async fn delete_file(&self, object_path: &str) -> Result<()> {
+        use reqwest::Client;
 
-        #[derive(Serialize)]
-        struct SignBlobRequest {
-            payload: String,
-        }
+        let token = self.get_access_token().await?;
+        let delete_url = format!(
+            "https://storage.googleapis.com/storage/v1/b/{}/o/{}",
+            self.bucket_name,
+            percent_encoding::utf8_percent_encode(object_path, percent_encoding::NON_ALPHANUMERIC)
+        );
 
-        #[derive(Deserialize)]
-        #[allow(dead_code)]
-        struct SignBlobResponse {
-            #[serde(rename = "keyId")]
-            key_id: String,
-            #[serde(rename = "signedBlob")]
-            signed_blob: String,
+        let client = Client::new();
+        let response = client
+            .delete(&delete_url)
+            .header("Authorization", format!("Bearer {}", token))
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to delete from GCS: {}", e)))?;
+
+        if !response.status().is_success() && response.status().as_u16() != 404 {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            return Err(AppError::Internal(format!(
+                "GCS delete failed with status {}: {}",
+                status, error_text
+            )));
         }
 
-        let token = self.get_access_token().await?;
-        let sign_url = format!(
-            "https://iamcredentials.googleapis.com/v1/projects/-/serviceAccounts/{}:signBlob",
-            service_account_email
-        );
+        Ok(())
+    }
 
-        let payload_base64 = base64::Engine::encode(
-            &base64::engine::general_purpose::STANDARD,
-            message.as_bytes()
-        );
+    async fn delete_folder(&self, folder_path: &str) -> Result<()> {
+        use reqwest::Client;
 
-        let request_body = SignBlobRequest {
-            payload: payload_base64,
-        };
+        // List all objects with the prefix
+        let token = self.get_access_token().await?;
+        let list_url = format!(
+            "https://storage.googleapis.com/storage/v1/b/{}/o?prefix={}",
+            self.bucket_name,
+            percent_encoding::utf8_percent_encode(folder_path, percent_encoding::NON_ALPHANUMERIC)
+        );
 
-        let client = reqwest::Client::new();
+        let client = Client::new();
         let response = client
-            .post(&sign_url)
+            .get(&list_url)
             .header("Authorization", format!("Bearer {}", token))
-            .json(&request_body)
             .send()
             .await
-            .map_err(|e| AppError::Internal(format!("Failed to call signBlob API: {}", e)))?;
+            .map_err(|e| AppError::Internal(format!("Failed to list GCS objects: {}", e)))?;
 
         if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
             return Err(AppError::Internal(format!(
-                "signBlob API failed with status {}: {}",
-                status, error_text
+                "GCS list failed with status {}: {}",
+                response.status(),
+                response.text().await.unwrap_or_default()
             )));
         }
 
-        let sign_response: SignBlobResponse = response
+        let list_response: GcsListResponse = response
             .json()
             .await
-            .map_err(|e| AppError::Internal(format!("Failed to parse signBlob response: {}", e)))?;
+            .map_err(|e| AppError::Internal(format!("Failed to parse GCS list response: {}", e)))?;
 
-        // Decode the base64 signature and encode as hex
-        let signature_bytes = base64::Engine::decode(
-            &base64::engine::general_purpose::STANDARD,
-            sign_response.signed_blob
-        ).map_err(|e| AppError::Internal(format!("Failed to decode signature: {}", e)))?;
+        // Delete each file
+        for item in list_response.items.unwrap_or_default() {
+            self.delete_file(&item.name).await?;
+        }
 
-        Ok(hex::encode(signature_bytes))
+        Ok(())
     }
 }
@@ -2,20 +2,20 @@ use crate::{
     error::{AppError, Result},
     models::GyrosVersion,
     repositories::GyrosRepository,
-    services::GcsService,
+    services::ObjectStorage,
 };
 use std::sync::Arc;
 
 pub struct GyrosService {
     repository: Arc<GyrosRepository>,
-    gcs_service: Arc<GcsService>,
+    storage: Arc<dyn ObjectStorage>,
 }
 
 impl GyrosService {
-    pub fn new(repository: Arc<GyrosRepository>, gcs_service: Arc<GcsService>) -> Self {
+    pub fn new(repository: Arc<GyrosRepository>, storage: Arc<dyn ObjectStorage>) -> Self {
         Self {
             repository,
-            gcs_service,
+            storage,
         }
     }
 
@@ -59,7 +59,7 @@ impl GyrosService {
         }
 
         let firmware_path = format!("{}/Gyros.bin", version.gcs_path);
-        self.gcs_service.delete_file(&firmware_path).await?;
+        self.storage.delete_file(&firmware_path).await?;
 
         self.repository.delete_version(id).await
     }
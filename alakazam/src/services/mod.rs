@@ -1,13 +1,22 @@
 mod admin_service;
 mod arcade_service;
+mod audit_service;
 mod gcs_service;
 mod gyros_service;
+mod object_storage;
+mod s3_service;
 mod sensor_service;
+mod signed_url_cache;
 mod snorlax_service;
+mod webhook_service;
 
 pub use admin_service::AdminService;
 pub use arcade_service::ArcadeService;
+pub use audit_service::AuditService;
 pub use gcs_service::GcsService;
 pub use gyros_service::GyrosService;
+pub use object_storage::{ObjectStorage, SignedUrl, StoredFile};
+pub use s3_service::S3Service;
 pub use sensor_service::SensorService;
 pub use snorlax_service::SnorlaxService;
+pub use webhook_service::WebhookService;
@@ -0,0 +1,140 @@
+use crate::{
+    api::handlers::GameFile,
+    error::{AppError, Result},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::{StreamExt, TryStreamExt};
+
+/// Signed URLs generated at once when signing many objects
+const SIGN_CONCURRENCY: usize = 16;
+
+/// A signed download URL and when it stops working
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignedUrl {
+    pub url: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl SignedUrl {
+    /// URL signed at `signed_at` to stay valid for `duration_secs`
+    pub fn new(url: String, signed_at: DateTime<Utc>, duration_secs: u32) -> Self {
+        Self {
+            url,
+            expires_at: signed_at + chrono::Duration::seconds(duration_secs as i64),
+        }
+    }
+}
+
+/// Files of a folder with signed download URLs
+#[derive(Debug)]
+pub struct SignedFolder {
+    pub files: Vec<GameFile>,
+    /// When the first of the URLs expires, `None` for an empty folder
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// A file found under a folder
+#[derive(Debug, Clone)]
+pub struct StoredFile {
+    /// Full object path
+    pub key: String,
+    /// Path relative to the listed folder
+    pub path: String,
+    pub size: Option<u64>,
+    /// Base64-encoded MD5 digest, when the backend knows it
+    pub md5_hash: Option<String>,
+}
+
+/// Object storage holding game builds, backgrounds, Snorlax APKs and Gyros firmware
+///
+/// Clients never talk to the API for file contents: they download and upload
+/// through short-lived signed URLs issued by the backend selected in `Config`.
+#[async_trait]
+pub trait ObjectStorage: Send + Sync {
+    /// How long signed download URLs stay valid, in seconds
+    fn url_duration_secs(&self) -> u32;
+
+    /// Longest validity a client may ask for instead of `url_duration_secs`
+    fn max_url_duration_secs(&self) -> u32;
+
+    /// Validity for download URLs: `requested` seconds when given, the default otherwise
+    fn download_url_duration(&self, requested: Option<u32>) -> Result<u32> {
+        match requested {
+            None => Ok(self.url_duration_secs()),
+            Some(0) => Err(AppError::BadRequest("expires_in must be at least 1 second".to_string())),
+            Some(secs) if secs > self.max_url_duration_secs() => Err(AppError::BadRequest(format!(
+                "expires_in may be at most {} seconds",
+                self.max_url_duration_secs()
+            ))),
+            Some(secs) => Ok(secs),
+        }
+    }
+
+    /// Signed URL for downloading an object, valid for up to `duration_secs`
+    /// A backend may hand out a URL it signed earlier, so check `expires_at`.
+    async fn signed_download_url_valid_for(&self, object_path: &str, duration_secs: u32) -> Result<SignedUrl>;
+
+    /// Signed URL for downloading an object, valid for up to `url_duration_secs`
+    async fn signed_download_url(&self, object_path: &str) -> Result<SignedUrl> {
+        self.signed_download_url_valid_for(object_path, self.url_duration_secs())
+            .await
+    }
+
+    /// Generate a signed URL the caller can PUT an object to
+    async fn signed_upload_url(&self, object_path: &str, duration_secs: u32) -> Result<String>;
+
+    /// List all files in a folder, skipping directory placeholders
+    async fn list_folder(&self, folder_path: &str) -> Result<Vec<StoredFile>>;
+
+    /// Signed download URLs for many objects, in order, generated concurrently
+    /// Each object succeeds or fails on its own.
+    async fn signed_download_urls(&self, object_paths: &[String], duration_secs: u32) -> Vec<Result<SignedUrl>> {
+        // Owned paths: a closure over borrowed ones fails async_trait's Send check
+        futures::stream::iter(object_paths.to_vec())
+            .map(|path| async move { self.signed_download_url_valid_for(&path, duration_secs).await })
+            .buffered(SIGN_CONCURRENCY)
+            .collect()
+            .await
+    }
+
+    /// Metadata of a single object, `None` if it doesn't exist
+    async fn file_info(&self, object_path: &str) -> Result<Option<StoredFile>> {
+        // Listing with the full path as the prefix finds the object on any backend
+        Ok(self
+            .list_folder(object_path)
+            .await?
+            .into_iter()
+            .find(|file| file.key == object_path))
+    }
+
+    /// Generate signed download URLs for listed files; any file failing to sign fails the whole set
+    async fn sign_files(&self, files: Vec<StoredFile>, duration_secs: u32) -> Result<SignedFolder> {
+        let signed: Vec<(GameFile, DateTime<Utc>)> = futures::stream::iter(files)
+            .map(|file| async move {
+                let signed = self.signed_download_url_valid_for(&file.key, duration_secs).await?;
+                let game_file = GameFile {
+                    path: file.path,
+                    download_url: signed.url,
+                    size: file.size,
+                    md5_hash: file.md5_hash,
+                };
+                Ok::<_, AppError>((game_file, signed.expires_at))
+            })
+            .buffered(SIGN_CONCURRENCY)
+            .try_collect()
+            .await?;
+
+        let expires_at = signed.iter().map(|(_, expires_at)| *expires_at).min();
+        Ok(SignedFolder {
+            files: signed.into_iter().map(|(file, _)| file).collect(),
+            expires_at,
+        })
+    }
+
+    /// Delete an object; deleting a missing object succeeds
+    async fn delete_file(&self, object_path: &str) -> Result<()>;
+
+    /// Delete all objects under a folder prefix
+    async fn delete_folder(&self, folder_path: &str) -> Result<()>;
+}
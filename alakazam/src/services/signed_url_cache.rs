@@ -0,0 +1,107 @@
+use super::SignedUrl;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Most URLs kept; past this, expired entries are pruned and then the soonest to expire evicted
+const MAX_ENTRIES: usize = 10_000;
+
+/// Share of a URL's validity during which the cached URL is handed out
+/// The last 10% is left as a safety margin so clients never receive a URL about to expire.
+const REUSE_FRACTION: f64 = 0.9;
+
+struct CachedUrl {
+    url: SignedUrl,
+    refresh_at: Instant,
+}
+
+/// Signed download URLs by object path, reused until close to expiry
+pub struct SignedUrlCache {
+    reuse_for: Duration,
+    entries: Mutex<HashMap<String, CachedUrl>>,
+}
+
+impl SignedUrlCache {
+    pub fn new(url_duration_secs: u32) -> Self {
+        Self {
+            reuse_for: Duration::from_secs(url_duration_secs as u64).mul_f64(REUSE_FRACTION),
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Cached URL for `object_path`, unless it is within the safety margin of expiring
+    /// Its `expires_at` is the one it was signed with, not a fresh validity.
+    pub fn get(&self, object_path: &str) -> Option<SignedUrl> {
+        let entries = self.entries.lock().unwrap();
+        let entry = entries.get(object_path)?;
+        (Instant::now() < entry.refresh_at).then(|| entry.url.clone())
+    }
+
+    /// Remember a URL minted at `signed_at`
+    pub fn insert(&self, object_path: &str, url: SignedUrl, signed_at: Instant) {
+        if self.reuse_for.is_zero() {
+            return;
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= MAX_ENTRIES && !entries.contains_key(object_path) {
+            let now = Instant::now();
+            entries.retain(|_, e| now < e.refresh_at);
+
+            if entries.len() >= MAX_ENTRIES {
+                let soonest = entries
+                    .iter()
+                    .min_by_key(|(_, e)| e.refresh_at)
+                    .map(|(path, _)| path.clone());
+                if let Some(path) = soonest {
+                    entries.remove(&path);
+                }
+            }
+        }
+
+        entries.insert(
+            object_path.to_string(),
+            CachedUrl {
+                url,
+                refresh_at: signed_at + self.reuse_for,
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn signed(url: &str) -> SignedUrl {
+        SignedUrl::new(url.to_string(), Utc::now(), 3600)
+    }
+
+    #[test]
+    fn second_request_inside_the_window_gets_the_cached_url() {
+        let cache = SignedUrlCache::new(3600);
+        let first = signed("https://storage.example/game/file.pak?sig=1");
+        cache.insert("game/file.pak", first.clone(), Instant::now());
+
+        assert_eq!(cache.get("game/file.pak"), Some(first));
+        assert_eq!(cache.get("game/other.pak"), None);
+    }
+
+    #[test]
+    fn urls_near_expiry_are_not_handed_out() {
+        let cache = SignedUrlCache::new(100);
+        let signed_at = Instant::now() - Duration::from_secs(95);
+        cache.insert("game/file.pak", signed("https://storage.example/old"), signed_at);
+
+        assert_eq!(cache.get("game/file.pak"), None);
+    }
+
+    #[test]
+    fn zero_duration_disables_caching() {
+        let cache = SignedUrlCache::new(0);
+        cache.insert("game/file.pak", signed("https://storage.example/x"), Instant::now());
+
+        assert_eq!(cache.get("game/file.pak"), None);
+    }
+}
@@ -2,21 +2,20 @@ use crate::{
     error::{AppError, Result},
     models::{SnorlaxApkResponse, SnorlaxVersion},
     repositories::SnorlaxRepository,
-    services::GcsService,
+    services::ObjectStorage,
 };
-use chrono::Utc;
 use std::sync::Arc;
 
 pub struct SnorlaxService {
     repository: Arc<SnorlaxRepository>,
-    gcs_service: Arc<GcsService>,
+    storage: Arc<dyn ObjectStorage>,
 }
 
 impl SnorlaxService {
-    pub fn new(repository: Arc<SnorlaxRepository>, gcs_service: Arc<GcsService>) -> Self {
+    pub fn new(repository: Arc<SnorlaxRepository>, storage: Arc<dyn ObjectStorage>) -> Self {
         Self {
             repository,
-            gcs_service,
+            storage,
         }
     }
 
@@ -32,22 +31,18 @@ impl SnorlaxService {
     pub async fn get_latest_apk_response(&self) -> Result<SnorlaxApkResponse> {
         let current_version = self.get_current_version().await?;
 
-        // Construct full GCS path: {folder}/Snorlax.apk
+        // Construct full object path: {folder}/Snorlax.apk
         let full_gcs_path = format!("{}/Snorlax.apk", current_version.gcs_path);
 
         // Generate signed download URL
-        let download_url = self
-            .gcs_service
-            .generate_signed_download_url(&full_gcs_path)
+        let signed = self
+            .storage
+            .signed_download_url(&full_gcs_path)
             .await?;
 
-        // Calculate expiration time
-        let duration_secs = self.gcs_service.get_url_duration_secs();
-        let expires_at = Utc::now() + chrono::Duration::seconds(duration_secs as i64);
-
         Ok(SnorlaxApkResponse {
-            download_url,
-            expires_at,
+            download_url: signed.url,
+            expires_at: signed.expires_at,
             version: current_version.version,
         })
     }
@@ -92,7 +87,7 @@ impl SnorlaxService {
         }
 
         let apk_path = format!("{}/Snorlax.apk", version.gcs_path);
-        self.gcs_service.delete_file(&apk_path).await?;
+        self.storage.delete_file(&apk_path).await?;
 
         self.repository.delete_version(id).await
     }
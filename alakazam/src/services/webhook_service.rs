@@ -0,0 +1,232 @@
+use crate::{
+    config::{WebhookConfig, WebhookEndpoint},
+    error::{AppError, Result},
+    repositories::WebhookRepository,
+};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Wait before the second delivery attempt, doubled for each one after
+const FIRST_RETRY_DELAY: Duration = Duration::from_secs(2);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(300);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// JSON body POSTed to webhook endpoints
+#[derive(Debug, Serialize)]
+struct WebhookPayload<'a, T: Serialize> {
+    event: &'a str,
+    entity_type: &'a str,
+    entity_id: i32,
+    actor: &'a str,
+    occurred_at: DateTime<Utc>,
+    /// The entity after the change, `null` when it was deleted
+    data: T,
+}
+
+/// Notifies integrators of catalog changes (games, versions, assignments)
+///
+/// Events are named `<entity type>.<action>` after the audit log, e.g.
+/// `game_version.publish`. Delivery runs in a spawned task so a slow or
+/// unreachable endpoint never holds up the admin request; deliveries that
+/// fail every attempt are kept in `webhook_dead_letters`.
+pub struct WebhookService {
+    client: reqwest::Client,
+    endpoints: Vec<WebhookEndpoint>,
+    secret: Option<Arc<str>>,
+    max_attempts: u32,
+    webhook_repo: Arc<WebhookRepository>,
+}
+
+impl WebhookService {
+    pub fn new(config: &WebhookConfig, webhook_repo: Arc<WebhookRepository>) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| AppError::Internal(format!("Failed to create webhook client: {}", e)))?;
+
+        Ok(Self {
+            client,
+            endpoints: config.endpoints.clone(),
+            secret: config.secret.as_deref().map(Arc::from),
+            max_attempts: config.max_attempts.max(1),
+            webhook_repo,
+        })
+    }
+
+    /// Send an event to every endpoint subscribed to it, without waiting for delivery
+    pub fn notify<T: Serialize>(&self, actor: &str, action: &str, entity_type: &str, entity_id: i32, data: T) {
+        let event = format!("{}.{}", entity_type, action);
+        let urls: Vec<String> = self
+            .endpoints
+            .iter()
+            .filter(|endpoint| subscribes(&endpoint.event, &event))
+            .map(|endpoint| endpoint.url.clone())
+            .collect();
+        if urls.is_empty() {
+            return;
+        }
+
+        let payload = WebhookPayload {
+            event: &event,
+            entity_type,
+            entity_id,
+            actor,
+            occurred_at: Utc::now(),
+            data,
+        };
+        let payload = match serde_json::to_value(&payload) {
+            Ok(payload) => payload,
+            Err(e) => {
+                tracing::error!(event, error = %e, "Failed to serialize webhook payload");
+                return;
+            }
+        };
+
+        for url in urls {
+            let delivery = Delivery {
+                client: self.client.clone(),
+                secret: self.secret.clone(),
+                max_attempts: self.max_attempts,
+                webhook_repo: self.webhook_repo.clone(),
+                event: event.clone(),
+                url,
+                payload: payload.clone(),
+            };
+            tokio::spawn(delivery.run());
+        }
+    }
+}
+
+/// Whether an endpoint subscribed with `pattern` receives `event`
+fn subscribes(pattern: &str, event: &str) -> bool {
+    if pattern == "*" || pattern == event {
+        return true;
+    }
+
+    match (pattern.strip_suffix(".*"), event.split_once('.')) {
+        (Some(entity_type), Some((event_entity_type, _))) => entity_type == event_entity_type,
+        _ => false,
+    }
+}
+
+/// One event on its way to one endpoint
+struct Delivery {
+    client: reqwest::Client,
+    secret: Option<Arc<str>>,
+    max_attempts: u32,
+    webhook_repo: Arc<WebhookRepository>,
+    event: String,
+    url: String,
+    payload: serde_json::Value,
+}
+
+impl Delivery {
+    async fn run(self) {
+        let body = self.payload.to_string();
+        let mut delay = FIRST_RETRY_DELAY;
+        let mut last_error = String::new();
+
+        for attempt in 1..=self.max_attempts {
+            match self.send(&body).await {
+                Ok(()) => {
+                    tracing::debug!(event = %self.event, url = %self.url, attempt, "Webhook delivered");
+                    return;
+                }
+                Err(e) => {
+                    tracing::warn!(event = %self.event, url = %self.url, attempt, error = %e, "Webhook delivery failed");
+                    last_error = e;
+                }
+            }
+
+            if attempt < self.max_attempts {
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(MAX_RETRY_DELAY);
+            }
+        }
+
+        tracing::error!(
+            event = %self.event,
+            url = %self.url,
+            attempts = self.max_attempts,
+            error = %last_error,
+            "Giving up on webhook delivery"
+        );
+
+        if let Err(e) = self
+            .webhook_repo
+            .insert_dead_letter(&self.event, &self.url, &self.payload, &last_error, self.max_attempts as i32)
+            .await
+        {
+            tracing::error!(
+                event = %self.event,
+                url = %self.url,
+                payload = %body,
+                error = %e,
+                "Failed to write webhook dead letter"
+            );
+        }
+    }
+
+    async fn send(&self, body: &str) -> std::result::Result<(), String> {
+        let timestamp = Utc::now().timestamp().to_string();
+
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header("X-Alakazam-Event", &self.event)
+            .header("X-Alakazam-Timestamp", &timestamp);
+
+        if let Some(secret) = &self.secret {
+            request = request.header("X-Alakazam-Signature", sign(secret, &timestamp, body));
+        }
+
+        let response = request
+            .body(body.to_string())
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("endpoint responded with status {}", response.status()))
+        }
+    }
+}
+
+/// `sha256=<hex>` HMAC over `<timestamp>.<body>`, so receivers can reject replays
+fn sign(secret: &str, timestamp: &str, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoints_receive_only_subscribed_events() {
+        assert!(subscribes("*", "game.created"));
+        assert!(subscribes("game.*", "game.created"));
+        assert!(subscribes("game.created", "game.created"));
+        assert!(!subscribes("game.created", "game.deleted"));
+        assert!(!subscribes("arcade.*", "game.created"));
+        assert!(!subscribes("game.*", "gameversion.created"));
+    }
+
+    #[test]
+    fn signature_is_hmac_sha256_of_timestamp_and_body() {
+        assert_eq!(
+            sign("whsec_test", "1700000000", r#"{"event":"game.created"}"#),
+            "sha256=fd41a402ef3acbadfaf0f2bc63dcbee788cc7158803aad5af5f692b2f8f61dc6"
+        );
+    }
+}
@@ -2,7 +2,62 @@ use arboard::Clipboard;
 use colored::*;
 use std::process;
 
+const USAGE: &str = "Usage: calyrex [--json] [--no-wait]
+
+  --json     Print {\"machine_id\":\"...\"} (or {\"error\":\"...\"}) and exit, without
+             touching the clipboard or waiting for Enter
+  --no-wait  Exit right away instead of waiting for Enter";
+
+struct Options {
+    json: bool,
+    wait: bool,
+}
+
+impl Options {
+    fn parse() -> Self {
+        let mut options = Options { json: false, wait: true };
+
+        for arg in std::env::args().skip(1) {
+            match arg.as_str() {
+                "--json" => options.json = true,
+                "--no-wait" => options.wait = false,
+                "-h" | "--help" => {
+                    println!("{}", USAGE);
+                    process::exit(0);
+                }
+                other => {
+                    eprintln!("Unknown argument: {}\n\n{}", other, USAGE);
+                    process::exit(2);
+                }
+            }
+        }
+
+        options
+    }
+}
+
 fn main() {
+    let options = Options::parse();
+
+    if options.json {
+        print_json();
+    } else {
+        print_interactive(options.wait);
+    }
+}
+
+/// Single JSON line for provisioning scripts; exit code is nonzero on failure
+fn print_json() {
+    match machine_uid::get() {
+        Ok(id) => println!("{{\"machine_id\":{}}}", json_string(&id)),
+        Err(e) => {
+            println!("{{\"error\":{}}}", json_string(&format!("Error getting machine ID: {}", e)));
+            process::exit(1);
+        }
+    }
+}
+
+fn print_interactive(wait: bool) {
     println!("{}", "=".repeat(50).bright_cyan());
     println!("{}", "           Calyrex - B3n00n - CombaticaLTD".bright_cyan().italic());
     println!("{}", "=".repeat(50).bright_cyan());
@@ -12,8 +67,10 @@ fn main() {
         Ok(id) => id,
         Err(e) => {
             eprintln!("{} {}", "Error getting machine ID:".bright_red().bold(), e);
-            eprintln!("Press any key to exit...");
-            let _ = std::io::stdin().read_line(&mut String::new());
+            if wait {
+                eprintln!("Press any key to exit...");
+                let _ = std::io::stdin().read_line(&mut String::new());
+            }
             process::exit(1);
         }
     };
@@ -37,7 +94,57 @@ fn main() {
         }
     }
 
-    println!();
-    println!("{}", "Press Enter to exit...".bright_black());
-    let _ = std::io::stdin().read_line(&mut String::new());
+    if wait {
+        println!();
+        println!("{}", "Press Enter to exit...".bright_black());
+        let _ = std::io::stdin().read_line(&mut String::new());
+    }
+}
+
+/// Quote `value` as a JSON string
+fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_text_is_only_quoted() {
+        assert_eq!(json_string("4c4c4544-0042"), r#""4c4c4544-0042""#);
+        assert_eq!(json_string(""), r#""""#);
+    }
+
+    #[test]
+    fn quotes_and_backslashes_are_escaped() {
+        assert_eq!(json_string(r#"say "hi""#), r#""say \"hi\"""#);
+        assert_eq!(json_string(r"C:\Windows\System32"), r#""C:\\Windows\\System32""#);
+    }
+
+    #[test]
+    fn control_characters_are_escaped() {
+        assert_eq!(json_string("a\nb\r\tc"), r#""a\nb\r\tc""#);
+        assert_eq!(json_string("\u{0}\u{1b}"), r#""\u0000\u001b""#);
+    }
+
+    #[test]
+    fn non_ascii_is_kept_as_is() {
+        assert_eq!(json_string("機械 ID"), "\"機械 ID\"");
+        assert_eq!(json_string("\u{7f}"), "\"\u{7f}\"");
+    }
 }
@@ -22,8 +22,14 @@ import type {
   CreateCustomerRequest,
   UpdateCustomerRequest,
   TrackedSensor,
+  Page,
+  AuditLogEntry,
+  AuditLogFilter,
 } from '../types';
 
+/** Largest page Alakazam serves for list endpoints */
+const MAX_PAGE_LIMIT = 500;
+
 class AlakazamAPI {
   private client: AxiosInstance;
 
@@ -45,6 +51,21 @@ class AlakazamAPI {
     });
   }
 
+  /** Fetch every item of a paginated list endpoint */
+  private async getAllPages<T>(url: string): Promise<T[]> {
+    const items: T[] = [];
+    let total = Infinity;
+    while (items.length < total) {
+      const response = await this.client.get<Page<T>>(url, {
+        params: { limit: MAX_PAGE_LIMIT, offset: items.length },
+      });
+      items.push(...response.data.items);
+      total = response.data.total;
+      if (response.data.items.length === 0) break;
+    }
+    return items;
+  }
+
   // Arcade endpoints
   async getArcades(): Promise<Arcade[]> {
     const response = await this.client.get('/api/admin/arcades');
@@ -72,8 +93,7 @@ class AlakazamAPI {
 
   // Customer endpoints
   async getCustomers(): Promise<Customer[]> {
-    const response = await this.client.get('/api/admin/customers');
-    return response.data;
+    return this.getAllPages<Customer>('/api/admin/customers');
   }
 
   async getCustomer(id: number): Promise<Customer> {
@@ -97,8 +117,7 @@ class AlakazamAPI {
 
   // Game endpoints
   async getGames(): Promise<Game[]> {
-    const response = await this.client.get('/api/admin/games');
-    return response.data;
+    return this.getAllPages<Game>('/api/admin/games');
   }
 
   async getGame(id: number): Promise<Game> {
@@ -145,8 +164,7 @@ class AlakazamAPI {
 
   // Game Version endpoints
   async getGameVersions(gameId: number): Promise<GameVersionWithChannels[]> {
-    const response = await this.client.get(`/api/admin/games/${gameId}/versions`);
-    return response.data;
+    return this.getAllPages<GameVersionWithChannels>(`/api/admin/games/${gameId}/versions`);
   }
 
   async getGameVersion(gameId: number, versionId: number): Promise<GameVersionWithChannels> {
@@ -402,6 +420,18 @@ class AlakazamAPI {
     const response = await this.client.get('/api/admin/sensors');
     return response.data;
   }
+
+  // Audit log endpoints
+  async getAuditLog(
+    filter: AuditLogFilter = {},
+    limit?: number,
+    offset?: number
+  ): Promise<Page<AuditLogEntry>> {
+    const response = await this.client.get('/api/admin/audit-log', {
+      params: { ...filter, limit, offset },
+    });
+    return response.data;
+  }
 }
 
 export const api = new AlakazamAPI();
@@ -164,7 +164,29 @@ export interface TrackedSensor {
   created_at: string;
 }
 
+export interface AuditLogEntry {
+  id: number;
+  actor: string;
+  action: string;
+  entity_type: string;
+  entity_id: number;
+  created_at: string;
+}
+
+export interface AuditLogFilter {
+  entity_type?: string;
+  entity_id?: number;
+  actor?: string;
+}
+
 // API Response types
+export interface Page<T> {
+  items: T[];
+  total: number;
+  limit: number;
+  offset: number;
+}
+
 export interface ApiError {
   error: string;
   details?: string;
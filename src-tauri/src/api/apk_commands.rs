@@ -1,38 +1,87 @@
+use crate::application::dto::CommandResultDto;
 use crate::application::services::ApkApplicationService;
-use crate::app::ApkFile;
+use crate::app::{ApkDuplicateGroup, ApkFile, ApkFilePage};
+use crate::domain::repositories::{ApkInfo, ApkListQuery, ApkSortField, SortOrder};
 use std::sync::Arc;
 use tauri::State;
 
-/// List all APK files
+/// List stored APK files, sorted and paginated
+/// Defaults to every APK sorted by name; `total` counts all pages.
 #[tauri::command]
 pub async fn list_apks(
+    sort_by: Option<ApkSortField>,
+    order: Option<SortOrder>,
+    offset: Option<usize>,
+    limit: Option<usize>,
     apk_service: State<'_, Arc<ApkApplicationService>>,
-) -> Result<Vec<ApkFile>, String> {
-    let apk_infos = apk_service
-        .list_apks()
+) -> Result<ApkFilePage, String> {
+    let query = ApkListQuery {
+        sort_by: sort_by.unwrap_or_default(),
+        order: order.unwrap_or_default(),
+        offset: offset.unwrap_or(0),
+        limit,
+    };
+
+    let page = apk_service
+        .list_apks_page(query)
         .await
         .map_err(|e| format!("Failed to list APKs: {}", e))?;
 
-    // Convert ApkInfo to ApkFile
-    let apk_files = apk_infos
+    Ok(ApkFilePage {
+        items: page.items.into_iter().map(apk_file).collect(),
+        total: page.total,
+    })
+}
+
+/// Stored APKs with identical contents, grouped by SHA-256
+/// Groups wasting the most disk space come first; files within a group oldest first.
+#[tauri::command]
+pub async fn find_apk_duplicates(
+    apk_service: State<'_, Arc<ApkApplicationService>>,
+) -> Result<Vec<ApkDuplicateGroup>, String> {
+    let groups = apk_service
+        .find_apk_duplicates()
+        .await
+        .map_err(|e| format!("Failed to find duplicate APKs: {}", e))?;
+
+    Ok(groups
         .into_iter()
-        .map(|info| ApkFile::new(info.filename, info.size_bytes, info.url))
-        .collect();
+        .map(|group| ApkDuplicateGroup {
+            wasted_bytes: group.wasted_bytes(),
+            sha256: group.sha256,
+            files: group.apks.into_iter().map(apk_file).collect(),
+        })
+        .collect())
+}
 
-    Ok(apk_files)
+fn apk_file(info: ApkInfo) -> ApkFile {
+    ApkFile::new(info.filename, info.size_bytes, info.url, info.sha256)
+        .with_metadata(info.metadata)
+        .with_added_at(info.added_at)
 }
 
 /// Add an APK file from a source path
+/// Returns the filename it was stored under. Set `dedupe` to refuse an APK that
+/// is already in the repository under another name.
 #[tauri::command]
 pub async fn add_apk(
     source_path: String,
+    dedupe: Option<bool>,
     apk_service: State<'_, Arc<ApkApplicationService>>,
-) -> Result<(), String> {
-    let _filename = apk_service
-        .add_apk(source_path.into())
+) -> Result<String, String> {
+    apk_service
+        .add_apk(source_path.into(), dedupe.unwrap_or(false))
         .await
-        .map_err(|e| format!("Failed to add APK: {}", e))?;
+        .map_err(|e| format!("Failed to add APK: {}", e))
+}
 
+/// Cancel an APK that is being added, identified by its source filename
+#[tauri::command]
+pub fn cancel_add_apk(
+    filename: String,
+    apk_service: State<'_, Arc<ApkApplicationService>>,
+) -> Result<(), String> {
+    apk_service.cancel_add_apk(&filename);
     Ok(())
 }
 
@@ -54,6 +103,36 @@ pub async fn remove_apk(
     Ok(())
 }
 
+/// Verify a stored APK against the SHA-256 recorded when it was added
+#[tauri::command]
+pub async fn verify_apk(
+    filename: String,
+    apk_service: State<'_, Arc<ApkApplicationService>>,
+) -> Result<CommandResultDto, String> {
+    if filename.contains("..") || filename.contains('/') || filename.contains('\\') {
+        return Err("Invalid filename: path traversal not allowed".to_string());
+    }
+
+    let verification = apk_service
+        .verify_apk(&filename)
+        .await
+        .map_err(|e| format!("Failed to verify APK: {}", e))?;
+
+    let result = if verification.is_valid() {
+        CommandResultDto::success("verify_apk", format!("{} checksum OK", filename))
+    } else {
+        CommandResultDto::failure(
+            "verify_apk",
+            format!(
+                "{} is corrupted on disk: expected SHA-256 {} but found {}",
+                filename, verification.expected_sha256, verification.actual_sha256
+            ),
+        )
+    };
+
+    Ok(result)
+}
+
 /// Open the APK folder in the system file explorer
 #[tauri::command]
 pub fn open_apk_folder(apk_service: State<'_, Arc<ApkApplicationService>>) -> Result<(), String> {
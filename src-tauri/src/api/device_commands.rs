@@ -1,14 +1,26 @@
-use crate::api::helpers::execute_batch_command;
-use crate::application::dto::{BatchResultDto, DeviceStateDto};
-use crate::application::services::{ClientApkService, DeviceApplicationService};
+use crate::api::helpers::{execute_batch_command, parse_device_ids};
+use crate::application::dto::{
+    ApkBatchInstallDto, ApkDryRunDto, BatchResultDto, CommandResultDto, DeviceInstalledAppsDto, DeviceStateDto,
+    ExportFormat, InstalledAppDto, InstalledAppsResultDto, RemoteApkInstallDto, StorageInfoDto,
+    DeviceWifiStatusDto, WifiStatusDto, WifiStatusResultDto,
+};
+use crate::application::services::{
+    ClientApkService, DeviceApplicationService, PacketTraceService,
+};
 use crate::domain::commands::{
-    ClearWifiCredentialsCommand, CloseAllAppsCommand, ConfigureDeviceCommand,
-    DisplayMessageCommand, ExecuteShellCommand, GetInstalledAppsCommand, GetVolumeCommand,
-    InstallApkCommand, LaunchAppCommand, PingCommand, RequestBatteryCommand,
-    RestartDeviceCommand, SetVolumeCommand, UninstallAppCommand,
+    ClearWifiCredentialsCommand, CloseAllAppsCommand, Command, ConfigureDeviceCommand,
+    DisplayMessageCommand, ExecuteShellCommand, GetBrightnessCommand, GetInstalledAppsCommand,
+    GetVolumeCommand, GetWifiStatusCommand,
+    InstallApkCommand, LaunchAppCommand, LocateDeviceCommand, MessageSeverity, PingCommand,
+    RequestBatteryCommand,
+    RequestStorageCommand, RestartDeviceCommand, SetBrightnessCommand, SetTimeCommand,
+    SetVolumeCommand, UninstallAppCommand,
 };
 use crate::domain::models::{DeviceId, PackageName, Serial};
+use crate::domain::repositories::CommandHistoryRepository;
+use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 use tauri::State;
 use uuid::Uuid;
 
@@ -16,13 +28,20 @@ use uuid::Uuid;
 #[tauri::command]
 pub async fn get_devices(
     device_service: State<'_, Arc<DeviceApplicationService>>,
+    command_history: State<'_, Arc<dyn CommandHistoryRepository>>,
 ) -> Result<Vec<DeviceStateDto>, String> {
     let devices = device_service
         .get_all_devices()
         .await
         .map_err(|e| format!("Failed to get devices: {}", e))?;
 
-    Ok(devices.iter().map(DeviceStateDto::from).collect())
+    Ok(devices
+        .iter()
+        .map(|device| {
+            DeviceStateDto::from(device)
+                .with_command_history(&command_history.recent_commands(&device.id(), None))
+        })
+        .collect())
 }
 
 /// Get a specific device by ID
@@ -30,6 +49,7 @@ pub async fn get_devices(
 pub async fn get_device(
     device_id: String,
     device_service: State<'_, Arc<DeviceApplicationService>>,
+    command_history: State<'_, Arc<dyn CommandHistoryRepository>>,
 ) -> Result<Option<DeviceStateDto>, String> {
     let uuid = Uuid::parse_str(&device_id)
         .map_err(|e| format!("Invalid device ID: {}", e))?;
@@ -40,7 +60,26 @@ pub async fn get_device(
         .await
         .map_err(|e| format!("Failed to get device: {}", e))?;
 
-    Ok(device.as_ref().map(DeviceStateDto::from))
+    Ok(device.as_ref().map(|device| {
+        DeviceStateDto::from(device)
+            .with_command_history(&command_history.recent_commands(&device.id(), None))
+    }))
+}
+
+/// Export the device roster, including disconnected devices, as CSV or JSON
+/// Returns the rendered document. With `path` it is also written to that file;
+/// otherwise the frontend handles saving it.
+#[tauri::command]
+pub async fn export_devices(
+    format: ExportFormat,
+    path: Option<String>,
+    device_service: State<'_, Arc<DeviceApplicationService>>,
+) -> Result<String, String> {
+    match path {
+        Some(path) => device_service.export_devices_to_file(format, Path::new(&path)).await,
+        None => device_service.export_devices(format).await,
+    }
+    .map_err(|e| format!("Failed to export devices: {}", e))
 }
 
 /// Set a custom name for a device
@@ -59,6 +98,80 @@ pub async fn set_device_name(
         .map_err(|e| format!("Failed to set device name: {}", e))
 }
 
+/// Replace the tags of a device, returning them normalized
+#[tauri::command]
+pub async fn set_device_tags(
+    serial: String,
+    tags: Vec<String>,
+    device_service: State<'_, Arc<DeviceApplicationService>>,
+) -> Result<Vec<String>, String> {
+    let serial = Serial::new(serial)
+        .map_err(|e| format!("Invalid serial number: {}", e))?;
+
+    device_service
+        .set_device_tags(serial, tags)
+        .await
+        .map_err(|e| format!("Failed to set device tags: {}", e))
+}
+
+/// Set or clear the free-text note for a device
+#[tauri::command]
+pub async fn set_device_note(
+    serial: String,
+    note: Option<String>,
+    device_service: State<'_, Arc<DeviceApplicationService>>,
+) -> Result<(), String> {
+    let serial = Serial::new(serial)
+        .map_err(|e| format!("Invalid serial number: {}", e))?;
+
+    device_service
+        .set_device_note(serial, note)
+        .await
+        .map_err(|e| format!("Failed to set device note: {}", e))
+}
+
+/// Set or clear the app a group of devices should stay in
+#[tauri::command]
+pub async fn set_expected_app(
+    serials: Vec<String>,
+    package_name: Option<String>,
+    device_service: State<'_, Arc<DeviceApplicationService>>,
+) -> Result<(), String> {
+    let serials = serials
+        .into_iter()
+        .map(|s| Serial::new(s).map_err(|e| format!("Invalid serial number: {}", e)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let package_name = package_name
+        .map(|p| PackageName::new(p).map_err(|e| format!("Invalid package name: {}", e)))
+        .transpose()?;
+
+    device_service
+        .set_expected_package(&serials, package_name)
+        .await
+        .map_err(|e| format!("Failed to set expected app: {}", e))
+}
+
+/// Get connected devices carrying a tag
+#[tauri::command]
+pub async fn list_devices_by_tag(
+    tag: String,
+    device_service: State<'_, Arc<DeviceApplicationService>>,
+    command_history: State<'_, Arc<dyn CommandHistoryRepository>>,
+) -> Result<Vec<DeviceStateDto>, String> {
+    let devices = device_service
+        .list_devices_by_tag(&tag)
+        .await
+        .map_err(|e| format!("Failed to list devices: {}", e))?;
+
+    Ok(devices
+        .iter()
+        .map(|device| {
+            DeviceStateDto::from(device)
+                .with_command_history(&command_history.recent_commands(&device.id(), None))
+        })
+        .collect())
+}
+
 /// Launch an app on multiple devices
 #[tauri::command]
 pub async fn launch_app(
@@ -77,19 +190,55 @@ pub async fn launch_app(
     .await
 }
 
+/// Launch an app on every connected device
+/// `stagger_ms` spaces the launches out so devices don't download assets all at once.
+#[tauri::command]
+pub async fn launch_app_all(
+    package_name: String,
+    stagger_ms: Option<u64>,
+    device_service: State<'_, Arc<DeviceApplicationService>>,
+) -> Result<BatchResultDto, String> {
+    let package_name = PackageName::new(package_name)
+        .map_err(|e| format!("Invalid package name: {}", e))?;
+
+    device_service
+        .launch_app_all(package_name, Duration::from_millis(stagger_ms.unwrap_or(0)))
+        .await
+        .map(Into::into)
+        .map_err(|e| format!("Failed to launch app: {}", e))
+}
+
+/// Launch an app on every connected device carrying a tag
+#[tauri::command]
+pub async fn launch_app_by_tag(
+    tag: String,
+    package_name: String,
+    stagger_ms: Option<u64>,
+    device_service: State<'_, Arc<DeviceApplicationService>>,
+) -> Result<BatchResultDto, String> {
+    let package_name = PackageName::new(package_name)
+        .map_err(|e| format!("Invalid package name: {}", e))?;
+
+    device_service
+        .launch_app_by_tag(&tag, package_name, Duration::from_millis(stagger_ms.unwrap_or(0)))
+        .await
+        .map(Into::into)
+        .map_err(|e| format!("Failed to launch app: {}", e))
+}
+
 /// Execute a shell command on multiple devices
+/// In safe mode, commands not on the allowlist fail on every device without being sent.
 #[tauri::command]
 pub async fn execute_shell(
     device_ids: Vec<String>,
     command: String,
     device_service: State<'_, Arc<DeviceApplicationService>>,
 ) -> Result<BatchResultDto, String> {
-    execute_batch_command(
-        device_ids,
-        &device_service,
-        ExecuteShellCommand::new(command),
-    )
-    .await
+    let ids = parse_device_ids(device_ids)?;
+    let result = device_service
+        .execute_shell(ids, ExecuteShellCommand::new(command))
+        .await;
+    Ok(result.into())
 }
 
 /// Uninstall an app from multiple devices
@@ -119,6 +268,80 @@ pub async fn request_battery(
     execute_batch_command(device_ids, &device_service, RequestBatteryCommand).await
 }
 
+/// Request storage status from multiple devices
+#[tauri::command]
+pub async fn request_storage(
+    device_ids: Vec<String>,
+    device_service: State<'_, Arc<DeviceApplicationService>>,
+) -> Result<BatchResultDto, String> {
+    execute_batch_command(device_ids, &device_service, RequestStorageCommand).await
+}
+
+/// Get the last storage status reported by a device
+/// Returns None until the device has answered a storage request.
+#[tauri::command]
+pub async fn get_storage(
+    device_id: String,
+    device_service: State<'_, Arc<DeviceApplicationService>>,
+) -> Result<Option<StorageInfoDto>, String> {
+    let uuid = Uuid::parse_str(&device_id)
+        .map_err(|e| format!("Invalid device ID: {}", e))?;
+
+    let device = device_service
+        .get_device(DeviceId::from_uuid(uuid))
+        .await
+        .map_err(|e| format!("Failed to get device: {}", e))?;
+
+    Ok(device.and_then(|d| d.storage().map(StorageInfoDto::from)))
+}
+
+/// Capture a screenshot from a device
+/// Returns the PNG as a `data:` URL ready to use as an image source.
+#[tauri::command]
+pub async fn request_screenshot(
+    device_id: String,
+    device_service: State<'_, Arc<DeviceApplicationService>>,
+) -> Result<String, String> {
+    let uuid = Uuid::parse_str(&device_id)
+        .map_err(|e| format!("Invalid device ID: {}", e))?;
+
+    let png = device_service
+        .request_screenshot(DeviceId::from_uuid(uuid))
+        .await
+        .map_err(|e| format!("Failed to capture screenshot: {}", e))?;
+
+    let encoded = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, png);
+    Ok(format!("data:image/png;base64,{}", encoded))
+}
+
+/// Start streaming a device's raw packets on the `arceus://packet-trace` channel
+#[tauri::command]
+pub fn start_device_trace(
+    device_id: String,
+    trace_service: State<'_, Arc<PacketTraceService>>,
+) -> Result<(), String> {
+    let uuid = Uuid::parse_str(&device_id)
+        .map_err(|e| format!("Invalid device ID: {}", e))?;
+
+    trace_service
+        .start_trace(DeviceId::from_uuid(uuid))
+        .map_err(|e| format!("Failed to start device trace: {}", e))
+}
+
+/// Stop streaming a device's raw packets
+#[tauri::command]
+pub fn stop_device_trace(
+    device_id: String,
+    trace_service: State<'_, Arc<PacketTraceService>>,
+) -> Result<(), String> {
+    let uuid = Uuid::parse_str(&device_id)
+        .map_err(|e| format!("Invalid device ID: {}", e))?;
+
+    trace_service
+        .stop_trace(DeviceId::from_uuid(uuid))
+        .map_err(|e| format!("Failed to stop device trace: {}", e))
+}
+
 /// Ping multiple devices
 #[tauri::command]
 pub async fn ping_devices(
@@ -128,6 +351,15 @@ pub async fn ping_devices(
     execute_batch_command(device_ids, &device_service, PingCommand).await
 }
 
+/// Make devices flash their LED and play a sound so they can be found physically
+#[tauri::command]
+pub async fn locate_device(
+    device_ids: Vec<String>,
+    device_service: State<'_, Arc<DeviceApplicationService>>,
+) -> Result<BatchResultDto, String> {
+    execute_batch_command(device_ids, &device_service, LocateDeviceCommand).await
+}
+
 /// Set volume on multiple devices
 #[tauri::command]
 pub async fn set_volume(
@@ -141,6 +373,23 @@ pub async fn set_volume(
     execute_batch_command(device_ids, &device_service, command).await
 }
 
+/// Apply a named volume preset (e.g. "quiet", "show") to multiple devices
+#[tauri::command]
+pub async fn set_volume_preset(
+    device_ids: Vec<String>,
+    preset: String,
+    device_service: State<'_, Arc<DeviceApplicationService>>,
+) -> Result<BatchResultDto, String> {
+    let ids = parse_device_ids(device_ids)?;
+
+    let result = device_service
+        .set_volume_preset(ids, &preset)
+        .await
+        .map_err(|e| format!("Failed to set volume preset: {}", e))?;
+
+    Ok(result.into())
+}
+
 /// Get volume from multiple devices
 #[tauri::command]
 pub async fn get_volume(
@@ -150,13 +399,86 @@ pub async fn get_volume(
     execute_batch_command(device_ids, &device_service, GetVolumeCommand).await
 }
 
+/// Set screen brightness on multiple devices
+/// Levels below the minimum floor are raised so the screen stays visible
+#[tauri::command]
+pub async fn set_brightness(
+    device_ids: Vec<String>,
+    level: u8,
+    device_service: State<'_, Arc<DeviceApplicationService>>,
+) -> Result<BatchResultDto, String> {
+    let command = SetBrightnessCommand::new(level)
+        .map_err(|e| format!("Invalid brightness level: {}", e))?;
+
+    execute_batch_command(device_ids, &device_service, command).await
+}
+
+/// Get screen brightness from multiple devices
+#[tauri::command]
+pub async fn get_brightness(
+    device_ids: Vec<String>,
+    device_service: State<'_, Arc<DeviceApplicationService>>,
+) -> Result<BatchResultDto, String> {
+    execute_batch_command(device_ids, &device_service, GetBrightnessCommand).await
+}
+
 /// Get installed apps from multiple devices
+/// Apps can be filtered by package name prefix; system apps are excluded unless requested
 #[tauri::command]
 pub async fn get_installed_apps(
     device_ids: Vec<String>,
+    package_prefix: Option<String>,
+    include_system: Option<bool>,
     device_service: State<'_, Arc<DeviceApplicationService>>,
-) -> Result<BatchResultDto, String> {
-    execute_batch_command(device_ids, &device_service, GetInstalledAppsCommand).await
+) -> Result<InstalledAppsResultDto, String> {
+    let ids = parse_device_ids(device_ids.clone())?;
+    let batch = execute_batch_command(device_ids, &device_service, GetInstalledAppsCommand).await?;
+
+    let include_system = include_system.unwrap_or(false);
+    let mut devices = Vec::with_capacity(ids.len());
+    for device_id in ids {
+        let apps = device_service
+            .get_installed_apps(device_id, package_prefix.as_deref(), include_system)
+            .await
+            .map_err(|e| format!("Failed to get installed apps: {}", e))?;
+
+        if let Some(apps) = apps {
+            devices.push(DeviceInstalledAppsDto {
+                device_id: device_id.as_uuid().to_string(),
+                apps: apps.iter().map(InstalledAppDto::from).collect(),
+            });
+        }
+    }
+
+    Ok(InstalledAppsResultDto { batch, devices })
+}
+
+/// Query WiFi status (SSID, signal strength, link speed) from multiple devices
+/// Devices on a captive portal or without a connection report `disconnected`.
+#[tauri::command]
+pub async fn get_wifi_status(
+    device_ids: Vec<String>,
+    device_service: State<'_, Arc<DeviceApplicationService>>,
+) -> Result<WifiStatusResultDto, String> {
+    let ids = parse_device_ids(device_ids.clone())?;
+    let batch = execute_batch_command(device_ids, &device_service, GetWifiStatusCommand).await?;
+
+    let mut devices = Vec::with_capacity(ids.len());
+    for device_id in ids {
+        let status = device_service
+            .get_wifi_status(device_id)
+            .await
+            .map_err(|e| format!("Failed to get WiFi status: {}", e))?;
+
+        if let Some(status) = status {
+            devices.push(DeviceWifiStatusDto {
+                device_id: device_id.as_uuid().to_string(),
+                status: WifiStatusDto::from(&status),
+            });
+        }
+    }
+
+    Ok(WifiStatusResultDto { batch, devices })
 }
 
 /// Restart multiple devices
@@ -169,23 +491,135 @@ pub async fn restart_devices(
 }
 
 /// Install APK from remote URL on multiple devices
+/// With `check_storage` set, refuses to start if the APK won't fit on a device.
+/// With `dry_run` set, downloads the APK once to report its size and SHA-256
+/// instead of installing. `expected_sha256` makes devices verify their download.
 #[tauri::command]
 pub async fn install_remote_apk(
     device_ids: Vec<String>,
     url: String,
+    check_storage: Option<bool>,
+    dry_run: Option<bool>,
+    expected_sha256: Option<String>,
     device_service: State<'_, Arc<DeviceApplicationService>>,
-) -> Result<BatchResultDto, String> {
-    execute_batch_command(device_ids, &device_service, InstallApkCommand::new(url)).await
+) -> Result<RemoteApkInstallDto, String> {
+    install_remote_apk_on(
+        &device_service,
+        device_ids,
+        url,
+        check_storage.unwrap_or(false),
+        dry_run.unwrap_or(false),
+        expected_sha256,
+    )
+    .await
 }
 
-/// Install APK from local file on multiple devices
+/// Shared by `install_remote_apk` and the remote API
+pub(super) async fn install_remote_apk_on(
+    device_service: &Arc<DeviceApplicationService>,
+    device_ids: Vec<String>,
+    url: String,
+    check_storage: bool,
+    dry_run: bool,
+    expected_sha256: Option<String>,
+) -> Result<RemoteApkInstallDto, String> {
+    if check_storage {
+        let ids = parse_device_ids(device_ids.clone())?;
+        device_service
+            .ensure_apk_fits(&ids, &url)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+
+    if dry_run {
+        let device_count = parse_device_ids(device_ids)?.len();
+        let report = match DeviceApplicationService::check_remote_apk(&url).await {
+            Ok((size_bytes, sha256)) => ApkDryRunDto {
+                result: CommandResultDto::success(
+                    "install_apk",
+                    format!(
+                        "Would install {} ({:.1} MB, SHA-256 {}) on {} device(s)",
+                        url,
+                        size_bytes as f64 / (1024.0 * 1024.0),
+                        sha256,
+                        device_count
+                    ),
+                ),
+                url,
+                size_bytes: Some(size_bytes),
+                sha256: Some(sha256),
+                device_count,
+            },
+            Err(e) => ApkDryRunDto {
+                result: CommandResultDto::failure("install_apk", e.to_string()),
+                url,
+                size_bytes: None,
+                sha256: None,
+                device_count,
+            },
+        };
+        return Ok(RemoteApkInstallDto::DryRun(report));
+    }
+
+    let command = InstallApkCommand::new(url)
+        .with_sha256(expected_sha256.map(|sha256| sha256.to_ascii_lowercase()));
+    execute_batch_command(device_ids, device_service, command)
+        .await
+        .map(RemoteApkInstallDto::Installed)
+}
+
+/// Install APK from remote URL on multiple devices, waiting for each result
+/// Devices install a few at a time; progress arrives as `apkBatchInstallProgress`
+/// events and one device failing doesn't stop the rest.
 #[tauri::command]
-pub async fn install_local_apk(
+pub async fn install_remote_apk_batch(
     device_ids: Vec<String>,
-    filename: String,
+    url: String,
+    expected_sha256: Option<String>,
+    device_service: State<'_, Arc<DeviceApplicationService>>,
+) -> Result<ApkBatchInstallDto, String> {
+    let ids = parse_device_ids(device_ids)?;
+    let command = InstallApkCommand::new(url)
+        .with_sha256(expected_sha256.map(|sha256| sha256.to_ascii_lowercase()));
+    command.validate()?;
+
+    Ok(device_service.install_apk_batch(ids, command).await)
+}
+
+/// Install an APK on the headsets with the given serials, waiting for each result
+/// Takes either a remote `url` or the `filename` of a local APK. Serials that
+/// aren't connected are reported as skipped; progress arrives as
+/// `apkBatchInstallProgress` events carrying each device's serial.
+#[tauri::command]
+pub async fn install_apk_on_serials(
+    serials: Vec<String>,
+    url: Option<String>,
+    filename: Option<String>,
+    expected_sha256: Option<String>,
     apk_service: State<'_, Arc<crate::application::services::ApkApplicationService>>,
     device_service: State<'_, Arc<DeviceApplicationService>>,
-) -> Result<BatchResultDto, String> {
+) -> Result<ApkBatchInstallDto, String> {
+    let serials = serials
+        .into_iter()
+        .map(|s| Serial::new(s).map_err(|e| format!("Invalid serial number: {}", e)))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let command = match (url, filename) {
+        (Some(url), None) => InstallApkCommand::new(url)
+            .with_sha256(expected_sha256.map(|sha256| sha256.to_ascii_lowercase())),
+        (None, Some(filename)) => local_apk_command(&apk_service, &filename).await?,
+        _ => return Err("Specify either an APK url or a local filename".to_string()),
+    };
+    command.validate()?;
+
+    Ok(device_service.install_apk_on_serials(serials, command).await)
+}
+
+/// Install command for a local APK, verified against its known SHA-256
+async fn local_apk_command(
+    apk_service: &crate::application::services::ApkApplicationService,
+    filename: &str,
+) -> Result<InstallApkCommand, String> {
     let apks = apk_service
         .list_apks()
         .await
@@ -203,12 +637,19 @@ pub async fn install_local_apk(
         "Installing local APK"
     );
 
-    let result = execute_batch_command(
-        device_ids,
-        &device_service,
-        InstallApkCommand::new(apk.url.clone()),
-    )
-    .await?;
+    Ok(InstallApkCommand::new(apk.url.clone()).with_sha256(apk.sha256.clone()))
+}
+
+/// Install APK from local file on multiple devices
+#[tauri::command]
+pub async fn install_local_apk(
+    device_ids: Vec<String>,
+    filename: String,
+    apk_service: State<'_, Arc<crate::application::services::ApkApplicationService>>,
+    device_service: State<'_, Arc<DeviceApplicationService>>,
+) -> Result<BatchResultDto, String> {
+    let command = local_apk_command(&apk_service, &filename).await?;
+    let result = execute_batch_command(device_ids, &device_service, command).await?;
 
     tracing::info!(
         succeeded = result.success_count,
@@ -258,14 +699,68 @@ pub async fn clear_wifi_credentials(
 pub async fn display_message(
     device_ids: Vec<String>,
     message: String,
+    severity: Option<MessageSeverity>,
+    duration_secs: Option<u32>,
     device_service: State<'_, Arc<DeviceApplicationService>>,
 ) -> Result<BatchResultDto, String> {
-    execute_batch_command(
-        device_ids,
-        &device_service,
-        DisplayMessageCommand::new(message),
-    )
-    .await
+    let command = DisplayMessageCommand::new(message)
+        .with_severity(severity.unwrap_or_default())
+        .with_duration(duration_secs);
+    command.validate()?;
+
+    execute_batch_command(device_ids, &device_service, command).await
+}
+
+/// Display a message on every connected device
+#[tauri::command]
+pub async fn display_message_all(
+    message: String,
+    severity: Option<MessageSeverity>,
+    duration_secs: Option<u32>,
+    device_service: State<'_, Arc<DeviceApplicationService>>,
+) -> Result<BatchResultDto, String> {
+    let command = DisplayMessageCommand::new(message)
+        .with_severity(severity.unwrap_or_default())
+        .with_duration(duration_secs);
+    command.validate()?;
+
+    device_service
+        .execute_command_all(Arc::new(command))
+        .await
+        .map(Into::into)
+        .map_err(|e| format!("Failed to display message: {}", e))
+}
+
+/// Set the clock of multiple devices to the server's UTC time
+/// `timezone` is an IANA name such as "Europe/Amsterdam"; without it the
+/// devices keep their timezone.
+#[tauri::command]
+pub async fn sync_time(
+    device_ids: Vec<String>,
+    timezone: Option<String>,
+    device_service: State<'_, Arc<DeviceApplicationService>>,
+) -> Result<BatchResultDto, String> {
+    let command = SetTimeCommand::new(timezone);
+    command.validate()?;
+
+    let ids = parse_device_ids(device_ids)?;
+    Ok(device_service.sync_time(ids, command).await.into())
+}
+
+/// Set the clock of every connected device to the server's UTC time
+#[tauri::command]
+pub async fn sync_time_all(
+    timezone: Option<String>,
+    device_service: State<'_, Arc<DeviceApplicationService>>,
+) -> Result<BatchResultDto, String> {
+    let command = SetTimeCommand::new(timezone);
+    command.validate()?;
+
+    device_service
+        .sync_time_all(command)
+        .await
+        .map(Into::into)
+        .map_err(|e| format!("Failed to sync time: {}", e))
 }
 
 /// Check for client APK updates and download if available
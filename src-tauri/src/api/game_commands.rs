@@ -1,4 +1,7 @@
-use crate::application::services::{GameApplicationService, GameVersionService, GameStatus};
+use crate::application::services::{
+    DownloadQueueEntry, GameApplicationService, GameVerification, GameVersionService, GameStatus,
+    UpdatePreview,
+};
 use crate::domain::models::{GameConfig, PackageName};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -34,6 +37,7 @@ impl From<crate::domain::models::GameState> for GameStateDto {
 pub async fn start_game(
     config_dto: GameConfigDto,
     game_service: State<'_, Arc<GameApplicationService>>,
+    game_version_service: State<'_, Arc<GameVersionService>>,
 ) -> Result<GameStateDto, String> {
     tracing::info!(
         game = %config_dto.name,
@@ -55,6 +59,10 @@ pub async fn start_game(
         .await
         .map_err(|e| format!("Failed to start game: {}", e))?;
 
+    if let Err(e) = game_version_service.record_game_played(&game_state.config.name).await {
+        tracing::warn!(game = %game_state.config.name, error = %e, "Failed to record last played time");
+    }
+
     Ok(game_state.into())
 }
 
@@ -88,14 +96,17 @@ pub async fn get_game_list(
 }
 
 /// Download and install a game (or update it)
+/// Resumes a previously interrupted download unless `resume` is false
 #[tauri::command]
 pub async fn download_game(
     game_id: i32,
+    resume: Option<bool>,
     game_version_service: State<'_, Arc<GameVersionService>>,
 ) -> Result<(), String> {
-    tracing::info!("Starting download for game {}", game_id);
+    let resume = resume.unwrap_or(true);
+    tracing::info!("Starting download for game {} (resume: {})", game_id, resume);
     game_version_service
-        .download_and_install_game(game_id)
+        .download_and_install_game(game_id, resume)
         .await
         .map_err(|e| format!("Failed to download game: {}", e))
 }
@@ -111,6 +122,105 @@ pub async fn cancel_download(
     Ok(())
 }
 
+/// Get active, queued and recently completed downloads
+#[tauri::command]
+pub async fn get_download_queue(
+    game_version_service: State<'_, Arc<GameVersionService>>,
+) -> Result<Vec<DownloadQueueEntry>, String> {
+    Ok(game_version_service.get_download_queue().await)
+}
+
+/// Pause an ongoing or queued download, keeping its partial files
+#[tauri::command]
+pub async fn pause_download(
+    game_id: i32,
+    game_version_service: State<'_, Arc<GameVersionService>>,
+) -> Result<(), String> {
+    tracing::info!("Pausing download for game {}", game_id);
+    game_version_service
+        .pause_download(game_id)
+        .await
+        .map_err(|e| format!("Failed to pause download: {}", e))
+}
+
+/// Resume a paused download where it left off
+#[tauri::command]
+pub async fn resume_download(
+    game_id: i32,
+    game_version_service: State<'_, Arc<GameVersionService>>,
+) -> Result<(), String> {
+    game_version_service
+        .resume_download(game_id)
+        .await
+        .map_err(|e| format!("Failed to resume download: {}", e))
+}
+
+/// Delete an installed game's files, returning the number of bytes freed
+/// Refused while the game is running or downloading.
+#[tauri::command]
+pub async fn delete_game(
+    game_id: i32,
+    game_service: State<'_, Arc<GameApplicationService>>,
+    game_version_service: State<'_, Arc<GameVersionService>>,
+) -> Result<u64, String> {
+    tracing::info!("Deleting game {}", game_id);
+    let running_game = game_service.get_current_game().map(|state| state.config.name);
+    game_version_service
+        .delete_game(game_id, running_game.as_deref())
+        .await
+        .map_err(|e| format!("Failed to delete game: {}", e))
+}
+
+/// Show which files updating a game would download and remove, and how many bytes
+#[tauri::command]
+pub async fn preview_update(
+    game_id: i32,
+    game_version_service: State<'_, Arc<GameVersionService>>,
+) -> Result<UpdatePreview, String> {
+    game_version_service
+        .preview_update(game_id)
+        .await
+        .map_err(|e| format!("Failed to preview update: {}", e))
+}
+
+/// Re-check an installed game's files against the server's sizes and checksums
+#[tauri::command]
+pub async fn verify_game(
+    game_id: i32,
+    game_version_service: State<'_, Arc<GameVersionService>>,
+) -> Result<GameVerification, String> {
+    tracing::info!("Verifying game {}", game_id);
+    game_version_service
+        .verify_game(game_id)
+        .await
+        .map_err(|e| format!("Failed to verify game: {}", e))
+}
+
+/// Make a game follow a release channel such as `beta`, or with `null` the arcade's own
+#[tauri::command]
+pub async fn set_game_channel(
+    game_id: i32,
+    channel: Option<String>,
+    game_version_service: State<'_, Arc<GameVersionService>>,
+) -> Result<(), String> {
+    game_version_service
+        .set_game_channel(game_id, channel.as_deref())
+        .await
+        .map_err(|e| format!("Failed to set game channel: {}", e))
+}
+
+/// Get the release channel a game follows, `null` if it follows the arcade's own
+#[tauri::command]
+pub async fn get_game_channel(
+    game_id: i32,
+    game_version_service: State<'_, Arc<GameVersionService>>,
+) -> Result<Option<String>, String> {
+    game_version_service
+        .get_game_channel(game_id)
+        .await
+        .map_err(|e| format!("Failed to get game channel: {}", e))
+}
+
 /// Force refresh games from server (requires internet connection)
 #[tauri::command]
 pub async fn force_refresh_games(
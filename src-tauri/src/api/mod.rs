@@ -4,11 +4,15 @@ mod apk_commands;
 mod device_commands;
 mod game_commands;
 mod helpers;
+mod remote_api;
 mod sensor_commands;
+mod server_commands;
 mod update_commands;
 
 pub use apk_commands::*;
 pub use device_commands::*;
 pub use game_commands::*;
+pub use remote_api::{serve_remote_api, RemoteApiState};
 pub use sensor_commands::*;
+pub use server_commands::*;
 pub use update_commands::*;
@@ -0,0 +1,392 @@
+/// Remote API
+/// A small HTTP API for driving Arceus without the UI, so a venue's central
+/// controller can orchestrate several instances. It is off unless
+/// `remote_api.enabled` is set, and every request must carry
+/// `Authorization: Bearer <remote_api.token>`. It speaks plain HTTP and binds
+/// to loopback by default; put it behind a TLS-terminating proxy before
+/// exposing it to the network.
+/// Bodies and responses use the same camelCase DTOs as the Tauri commands;
+/// failures come back as `{ "error": "..." }`.
+
+use super::device_commands::install_remote_apk_on;
+use super::helpers::{execute_batch_command, parse_device_ids};
+use crate::app::models::RemoteApiConfig;
+use crate::application::dto::{BatchResultDto, DeviceStateDto, RemoteApkInstallDto};
+use crate::application::services::device_app_service::MAX_LAUNCH_STAGGER;
+use crate::application::services::DeviceApplicationService;
+use crate::domain::commands::{
+    CloseAllAppsCommand, Command, DisplayMessageCommand, ExecuteShellCommand, LaunchAppCommand,
+    MessageSeverity, RestartDeviceCommand, UninstallAppCommand,
+};
+use crate::domain::models::{Device, PackageName};
+use crate::domain::repositories::CommandHistoryRepository;
+use axum::extract::{Path, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::Deserialize;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Services the remote API drives, shared with the Tauri commands
+pub struct RemoteApiState {
+    device_service: Arc<DeviceApplicationService>,
+    command_history: Arc<dyn CommandHistoryRepository>,
+    token: Arc<str>,
+}
+
+impl RemoteApiState {
+    pub fn new(
+        device_service: Arc<DeviceApplicationService>,
+        command_history: Arc<dyn CommandHistoryRepository>,
+        token: String,
+    ) -> Self {
+        Self {
+            device_service,
+            command_history,
+            token: token.into(),
+        }
+    }
+
+    fn device_state(&self, device: &Arc<Device>) -> DeviceStateDto {
+        DeviceStateDto::from(device)
+            .with_command_history(&self.command_history.recent_commands(&device.id(), None))
+    }
+}
+
+/// Serve the remote API until the listener fails
+pub async fn serve_remote_api(config: RemoteApiConfig, state: RemoteApiState) -> std::io::Result<()> {
+    if state.token.trim().is_empty() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "remote_api.token must be set when the remote API is enabled",
+        ));
+    }
+    if !is_loopback(&config.bind_address) {
+        tracing::warn!(
+            address = %config.bind_address,
+            "Remote API is bound beyond loopback over plain HTTP; its bearer token is sent in the clear"
+        );
+    }
+
+    let listener = tokio::net::TcpListener::bind((config.bind_address.as_str(), config.port)).await?;
+    tracing::info!(address = %listener.local_addr()?, "Remote API listening");
+
+    axum::serve(listener, router(Arc::new(state))).await
+}
+
+fn router(state: Arc<RemoteApiState>) -> Router {
+    let token = state.token.clone();
+    let routes = Router::new()
+        .route("/api/v1/devices", get(get_devices))
+        .route("/api/v1/devices/{device_id}", get(get_device))
+        .route("/api/v1/commands/launch-app", post(launch_app))
+        .route("/api/v1/commands/launch-app-all", post(launch_app_all))
+        .route("/api/v1/commands/install-remote-apk", post(install_remote_apk))
+        .route("/api/v1/commands/uninstall-app", post(uninstall_app))
+        .route("/api/v1/commands/restart-devices", post(restart_devices))
+        .route("/api/v1/commands/close-all-apps", post(close_all_apps))
+        .route("/api/v1/commands/display-message", post(display_message))
+        .route("/api/v1/commands/execute-shell", post(execute_shell))
+        .with_state(state);
+    require_bearer_token(routes, token)
+}
+
+/// Reject every request to `routes` that doesn't carry `token`
+fn require_bearer_token(routes: Router, token: Arc<str>) -> Router {
+    routes.layer(middleware::from_fn_with_state(token, require_token))
+}
+
+/// Whether `bind_address` only accepts local connections
+fn is_loopback(bind_address: &str) -> bool {
+    bind_address == "localhost"
+        || bind_address
+            .parse::<IpAddr>()
+            .map(|ip| ip.is_loopback())
+            .unwrap_or(false)
+}
+
+/// Error response with a status code and a `{ "error": ... }` body
+struct ApiError(StatusCode, String);
+
+impl From<String> for ApiError {
+    /// Command helpers only fail on bad input
+    fn from(message: String) -> Self {
+        Self(StatusCode::BAD_REQUEST, message)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+async fn require_token(State(token): State<Arc<str>>, request: Request, next: Next) -> Response {
+    let presented = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match presented {
+        Some(presented) if tokens_match(presented.trim(), &token) => next.run(request).await,
+        _ => ApiError(StatusCode::UNAUTHORIZED, "Missing or invalid bearer token".to_string()).into_response(),
+    }
+}
+
+/// Compare tokens without bailing out at the first differing byte
+fn tokens_match(presented: &str, expected: &str) -> bool {
+    presented.len() == expected.len()
+        && presented
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn parse_package_name(package_name: String) -> Result<PackageName, ApiError> {
+    PackageName::new(package_name).map_err(|e| format!("Invalid package name: {}", e).into())
+}
+
+fn parse_stagger(stagger_ms: Option<u64>) -> Result<Duration, ApiError> {
+    let stagger = Duration::from_millis(stagger_ms.unwrap_or(0));
+    if stagger > MAX_LAUNCH_STAGGER {
+        return Err(format!(
+            "staggerMs must be at most {}",
+            MAX_LAUNCH_STAGGER.as_millis()
+        )
+        .into());
+    }
+    Ok(stagger)
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DeviceIdsRequest {
+    device_ids: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LaunchAppRequest {
+    device_ids: Vec<String>,
+    package_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LaunchAppAllRequest {
+    package_name: String,
+    stagger_ms: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InstallRemoteApkRequest {
+    device_ids: Vec<String>,
+    url: String,
+    check_storage: Option<bool>,
+    dry_run: Option<bool>,
+    expected_sha256: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DisplayMessageRequest {
+    device_ids: Vec<String>,
+    message: String,
+    severity: Option<MessageSeverity>,
+    duration_secs: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExecuteShellRequest {
+    device_ids: Vec<String>,
+    command: String,
+}
+
+async fn get_devices(State(state): State<Arc<RemoteApiState>>) -> ApiResult<Vec<DeviceStateDto>> {
+    let devices = state.device_service.get_all_devices().await.map_err(|e| {
+        ApiError(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get devices: {}", e))
+    })?;
+
+    Ok(Json(devices.iter().map(|device| state.device_state(device)).collect()))
+}
+
+async fn get_device(
+    State(state): State<Arc<RemoteApiState>>,
+    Path(device_id): Path<String>,
+) -> ApiResult<DeviceStateDto> {
+    let device_id = parse_device_ids(vec![device_id])?.remove(0);
+
+    let device = state.device_service.get_device(device_id).await.map_err(|e| {
+        ApiError(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to get device: {}", e))
+    })?;
+
+    device
+        .map(|device| Json(state.device_state(&device)))
+        .ok_or_else(|| ApiError(StatusCode::NOT_FOUND, "Device not found".to_string()))
+}
+
+async fn launch_app(
+    State(state): State<Arc<RemoteApiState>>,
+    Json(request): Json<LaunchAppRequest>,
+) -> ApiResult<BatchResultDto> {
+    let package_name = parse_package_name(request.package_name)?;
+    let result =
+        execute_batch_command(request.device_ids, &state.device_service, LaunchAppCommand::new(package_name)).await?;
+    Ok(Json(result))
+}
+
+async fn launch_app_all(
+    State(state): State<Arc<RemoteApiState>>,
+    Json(request): Json<LaunchAppAllRequest>,
+) -> ApiResult<BatchResultDto> {
+    let package_name = parse_package_name(request.package_name)?;
+    let stagger = parse_stagger(request.stagger_ms)?;
+
+    state
+        .device_service
+        .launch_app_all(package_name, stagger)
+        .await
+        .map(|result| Json(result.into()))
+        .map_err(|e| ApiError(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to launch app: {}", e)))
+}
+
+async fn install_remote_apk(
+    State(state): State<Arc<RemoteApiState>>,
+    Json(request): Json<InstallRemoteApkRequest>,
+) -> ApiResult<RemoteApkInstallDto> {
+    let result = install_remote_apk_on(
+        &state.device_service,
+        request.device_ids,
+        request.url,
+        request.check_storage.unwrap_or(false),
+        request.dry_run.unwrap_or(false),
+        request.expected_sha256,
+    )
+    .await?;
+    Ok(Json(result))
+}
+
+async fn uninstall_app(
+    State(state): State<Arc<RemoteApiState>>,
+    Json(request): Json<LaunchAppRequest>,
+) -> ApiResult<BatchResultDto> {
+    let package_name = parse_package_name(request.package_name)?;
+    let result =
+        execute_batch_command(request.device_ids, &state.device_service, UninstallAppCommand::new(package_name)).await?;
+    Ok(Json(result))
+}
+
+async fn restart_devices(
+    State(state): State<Arc<RemoteApiState>>,
+    Json(request): Json<DeviceIdsRequest>,
+) -> ApiResult<BatchResultDto> {
+    let result = execute_batch_command(request.device_ids, &state.device_service, RestartDeviceCommand).await?;
+    Ok(Json(result))
+}
+
+async fn close_all_apps(
+    State(state): State<Arc<RemoteApiState>>,
+    Json(request): Json<DeviceIdsRequest>,
+) -> ApiResult<BatchResultDto> {
+    let result = execute_batch_command(request.device_ids, &state.device_service, CloseAllAppsCommand).await?;
+    Ok(Json(result))
+}
+
+async fn display_message(
+    State(state): State<Arc<RemoteApiState>>,
+    Json(request): Json<DisplayMessageRequest>,
+) -> ApiResult<BatchResultDto> {
+    let command = DisplayMessageCommand::new(request.message)
+        .with_severity(request.severity.unwrap_or_default())
+        .with_duration(request.duration_secs);
+    command.validate()?;
+
+    let result = execute_batch_command(request.device_ids, &state.device_service, command).await?;
+    Ok(Json(result))
+}
+
+/// Goes through the same shell safe-mode policy as the UI
+async fn execute_shell(
+    State(state): State<Arc<RemoteApiState>>,
+    Json(request): Json<ExecuteShellRequest>,
+) -> ApiResult<BatchResultDto> {
+    let ids = parse_device_ids(request.device_ids)?;
+    let result = state
+        .device_service
+        .execute_shell(ids, ExecuteShellCommand::new(request.command))
+        .await;
+    Ok(Json(result.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_the_exact_token_matches() {
+        let expected = "0123456789abcdef";
+
+        assert!(tokens_match("0123456789abcdef", expected));
+        assert!(!tokens_match("0123456789abcdeF", expected));
+        assert!(!tokens_match("0123456789abcde", expected));
+        assert!(!tokens_match("", expected));
+    }
+
+    #[test]
+    fn stagger_is_capped() {
+        assert_eq!(parse_stagger(None).ok(), Some(Duration::ZERO));
+        assert_eq!(parse_stagger(Some(250)).ok(), Some(Duration::from_millis(250)));
+        assert!(parse_stagger(Some(MAX_LAUNCH_STAGGER.as_millis() as u64)).is_ok());
+
+        let rejected = parse_stagger(Some(u64::MAX)).err().unwrap();
+        assert_eq!(rejected.0, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn only_loopback_addresses_count_as_local() {
+        assert!(is_loopback("127.0.0.1"));
+        assert!(is_loopback("::1"));
+        assert!(is_loopback("localhost"));
+        assert!(!is_loopback("0.0.0.0"));
+        assert!(!is_loopback("192.168.1.20"));
+    }
+
+    #[tokio::test]
+    async fn routes_require_the_bearer_token() {
+        let routes = Router::new().route("/api/v1/devices", get(|| async { "ok" }));
+        let app = require_bearer_token(routes, Arc::from("s3cret-token"));
+
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0)).await.unwrap();
+        let url = format!("http://{}/api/v1/devices", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let client = reqwest::Client::new();
+        let status = |auth: Option<&'static str>| {
+            let mut request = client.get(&url);
+            if let Some(auth) = auth {
+                request = request.header("Authorization", auth);
+            }
+            async move { request.send().await.unwrap().status().as_u16() }
+        };
+
+        assert_eq!(status(None).await, 401);
+        assert_eq!(status(Some("Bearer wrong-token")).await, 401);
+        assert_eq!(status(Some("Basic s3cret-token")).await, 401);
+        assert_eq!(status(Some("Bearer ")).await, 401);
+        assert_eq!(status(Some("Bearer s3cret-token")).await, 200);
+
+        let response = client.get(&url).send().await.unwrap();
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["error"], "Missing or invalid bearer token");
+    }
+}
@@ -1,5 +1,7 @@
 use crate::application::services::SensorService;
-use crate::domain::models::Sensor;
+use crate::domain::models::{Sensor, SensorFlashResult};
+use crate::infrastructure::sensor::BoardTarget;
+use serde::Deserialize;
 use std::sync::Arc;
 use tauri::State;
 
@@ -14,7 +16,8 @@ pub async fn list_sensors(
         .map_err(|e| format!("Failed to list sensors: {}", e))
 }
 
-/// Get detailed info for a specific sensor
+/// Get detailed info (firmware version, device name, ...) for a specific sensor
+/// A board in bootloader mode comes back with status `bootloader` and no details.
 #[tauri::command]
 pub async fn get_sensor_info(
     port: String,
@@ -27,23 +30,108 @@ pub async fn get_sensor_info(
 }
 
 /// Upload firmware to a sensor with a custom device name
+/// With `skip_if_version`, a board already running that version and name is not
+/// re-flashed. Returns whether the board was flashed.
 #[tauri::command]
 pub async fn upload_sensor_firmware(
     port: Option<String>,
     firmware_path: String,
     device_name: String,
+    skip_if_version: Option<String>,
     sensor_service: State<'_, Arc<SensorService>>,
-) -> Result<(), String> {
+) -> Result<bool, String> {
     sensor_service
         .upload_firmware(
             port.as_deref(),
             firmware_path.into(),
             &device_name,
+            skip_if_version.as_deref(),
         )
         .await
         .map_err(|e| format!("Failed to upload firmware: {}", e))
 }
 
+/// Update a sensor to a known-good firmware image, keeping its device name
+/// Without `firmware_path` the configured or bundled image is used.
+/// Returns whether the board was flashed; boards already on the image's version are skipped unless `force`.
+#[tauri::command]
+pub async fn update_sensor_firmware(
+    port: Option<String>,
+    firmware_path: Option<String>,
+    force: Option<bool>,
+    sensor_service: State<'_, Arc<SensorService>>,
+) -> Result<bool, String> {
+    sensor_service
+        .update_firmware(port.as_deref(), firmware_path.map(Into::into), force.unwrap_or(false))
+        .await
+        .map_err(|e| format!("Failed to update firmware: {}", e))
+}
+
+/// A sensor to flash in a batch, as sent by the frontend
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SensorFlashTarget {
+    pub port: String,
+    pub device_name: String,
+}
+
+/// Upload firmware to several sensors in sequence, each with its own device name
+/// Without `targets`, every connected sensor is flashed and named `<namePrefix><n>`.
+/// Returns per-port success or failure.
+#[tauri::command]
+pub async fn upload_sensor_firmware_batch(
+    firmware_path: String,
+    targets: Option<Vec<SensorFlashTarget>>,
+    name_prefix: Option<String>,
+    sensor_service: State<'_, Arc<SensorService>>,
+) -> Result<Vec<SensorFlashResult>, String> {
+    let boards = match targets {
+        Some(targets) => targets
+            .into_iter()
+            .map(|t| BoardTarget {
+                port: t.port,
+                device_name: t.device_name,
+            })
+            .collect(),
+        None => sensor_service
+            .connected_targets(name_prefix.as_deref().unwrap_or_default().trim())
+            .await
+            .map_err(|e| format!("Failed to upload firmware: {}", e))?,
+    };
+
+    sensor_service
+        .upload_firmware_batch(firmware_path.into(), boards)
+        .await
+        .map_err(|e| format!("Failed to upload firmware: {}", e))
+}
+
+/// Stop the firmware upload in progress; the board is left in bootloader mode, ready to retry
+/// Returns whether an upload was running.
+#[tauri::command]
+pub fn cancel_firmware_upload(sensor_service: State<'_, Arc<SensorService>>) -> bool {
+    sensor_service.cancel_upload()
+}
+
+/// Back up the firmware currently on a sensor to a user-chosen file
+/// Returns the number of bytes written.
+#[tauri::command]
+pub async fn backup_sensor_firmware(
+    port: String,
+    output_path: String,
+    sensor_service: State<'_, Arc<SensorService>>,
+) -> Result<u64, String> {
+    sensor_service
+        .backup_firmware(&port, output_path.into())
+        .await
+        .map_err(|e| format!("Failed to back up firmware: {}", e))
+}
+
+/// Check a sensor device name, so the UI can refuse invalid names before flashing
+#[tauri::command]
+pub fn validate_sensor_name(name: String) -> Result<(), String> {
+    SensorService::validate_device_name(&name).map_err(|e| format!("Invalid sensor name: {}", e))
+}
+
 /// Get the maximum allowed device name length
 #[tauri::command]
 pub fn get_max_sensor_name_length(
@@ -0,0 +1,75 @@
+use crate::app::models::ServerStats;
+use crate::app::ServerManager;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, State};
+
+/// Directory inside the app data dir that packet captures are written to
+const CAPTURE_DIRECTORY: &str = "packet-captures";
+
+/// Connected devices, uptime and command count for the dashboard header
+#[tauri::command]
+pub async fn get_server_stats(
+    server_manager: State<'_, Arc<ServerManager>>,
+) -> Result<ServerStats, String> {
+    Ok(server_manager.stats())
+}
+
+/// Turn raw packet logging on or off without restarting the server
+/// With `capture_path`, traced packets are also appended to that file. It must be
+/// a plain file name; the file lives in the `packet-captures` folder of the app data dir.
+#[tauri::command]
+pub fn set_packet_trace(
+    enabled: bool,
+    capture_path: Option<String>,
+    app: AppHandle,
+    server_manager: State<'_, Arc<ServerManager>>,
+) -> Result<(), String> {
+    let capture_path = match capture_path.filter(|_| enabled) {
+        Some(name) => {
+            let data_dir = app
+                .path()
+                .app_data_dir()
+                .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+            let path = capture_file(&data_dir, &name)?;
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)
+                    .map_err(|e| format!("Failed to create capture directory: {}", e))?;
+            }
+            Some(path)
+        }
+        None => None,
+    };
+
+    server_manager
+        .set_packet_trace(enabled, capture_path.as_deref())
+        .map_err(|e| format!("Failed to set packet trace: {}", e))
+}
+
+/// Resolve a capture file name inside the capture directory, refusing anything
+/// that would point elsewhere (separators, `..`, absolute paths)
+fn capture_file(data_dir: &Path, name: &str) -> Result<PathBuf, String> {
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(file)), None) => Ok(data_dir.join(CAPTURE_DIRECTORY).join(file)),
+        _ => Err(format!("Capture file must be a plain file name, got {:?}", name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capture_files_stay_in_the_capture_directory() {
+        let data_dir = Path::new("/data/arceus");
+        assert_eq!(
+            capture_file(data_dir, "session.log").unwrap(),
+            data_dir.join(CAPTURE_DIRECTORY).join("session.log")
+        );
+
+        for name in ["", "..", "../escape.log", "nested/session.log", "/etc/passwd"] {
+            assert!(capture_file(data_dir, name).is_err(), "{:?}", name);
+        }
+    }
+}
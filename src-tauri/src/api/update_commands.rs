@@ -1,5 +1,5 @@
 use crate::application::services::update_service::UpdateService;
-use crate::app::models::update::UpdateStatus;
+use crate::app::models::update::{UpdateChannel, UpdateStatus};
 use crate::app::{AppState, ServerManager};
 use std::sync::Arc;
 use tauri::{AppHandle, Manager, State};
@@ -12,11 +12,34 @@ pub async fn check_for_updates(
     update_service.lock().await.check_for_updates().await
 }
 
+/// Install the update found by `check_for_updates`
+/// `allow_downgrade` confirms installing an older stable release over a beta.
 #[tauri::command]
 pub async fn download_and_install_update(
+    allow_downgrade: Option<bool>,
     update_service: State<'_, Arc<Mutex<UpdateService>>>,
 ) -> Result<(), String> {
-    update_service.lock().await.download_and_install().await
+    update_service
+        .lock()
+        .await
+        .download_and_install(allow_downgrade.unwrap_or(false))
+        .await
+}
+
+#[tauri::command]
+pub async fn get_update_channel(
+    update_service: State<'_, Arc<Mutex<UpdateService>>>,
+) -> Result<UpdateChannel, String> {
+    Ok(update_service.lock().await.channel())
+}
+
+/// Pick the channel future update checks use; persisted across restarts
+#[tauri::command]
+pub async fn set_update_channel(
+    channel: UpdateChannel,
+    update_service: State<'_, Arc<Mutex<UpdateService>>>,
+) -> Result<(), String> {
+    update_service.lock().await.set_channel(channel).await
 }
 
 #[tauri::command]
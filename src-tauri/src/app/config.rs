@@ -1,14 +1,59 @@
-use crate::app::{error::Result, models::{ServerConfig, AlakazamConfig}};
+use crate::app::{error::Result, models::{ServerConfig, AlakazamConfig, DfuConfig, RemoteApiConfig, SensorFirmwareConfig, WatchdogConfig, update::UpdateChannel}};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_MAX_CONCURRENT_DOWNLOADS: usize = 2;
+const DEFAULT_FILE_DOWNLOAD_CONCURRENCY: usize = 4;
+const DEFAULT_MAX_SCREENSHOT_BYTES: usize = 8 * 1024 * 1024;
+const DEFAULT_MAX_APK_BYTES: u64 = 4 * 1024 * 1024 * 1024;
+const DEFAULT_EVENT_COALESCE_WINDOW_MS: u64 = 250;
+const DEFAULT_COMMAND_HISTORY_SIZE: usize = 50;
+const MAX_COMMAND_HISTORY_SIZE: usize = 1000;
+const MIN_REMOTE_API_TOKEN_LEN: usize = 16;
+
+/// File in the app data directory whose settings override the defaults
+pub const CONFIG_FILENAME: &str = "arceus.json";
+
+/// Built-in named volume levels
+fn default_volume_presets() -> BTreeMap<String, u8> {
+    BTreeMap::from([("quiet".to_string(), 30), ("show".to_string(), 80)])
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     pub server: ServerConfig,
     pub alakazam: AlakazamConfig,
+    pub remote_api: RemoteApiConfig,
+    pub dfu: DfuConfig,
+    pub sensor_firmware: SensorFirmwareConfig,
+    pub watchdog: WatchdogConfig,
+    /// Channel used until one is picked with `set_update_channel`
+    pub update_channel: UpdateChannel,
     pub apk_directory: PathBuf,
     pub database_path: PathBuf,
     pub games_directory: PathBuf,
+    /// Number of game downloads allowed to run at once, the rest are queued
+    pub max_concurrent_downloads: usize,
+    /// Number of files of a single game fetched in parallel
+    pub file_download_concurrency: usize,
+    /// Aggregate cap for game and APK downloads in bytes per second (0 = unthrottled)
+    pub max_download_bytes_per_sec: u64,
+    /// Largest screenshot a device may stream back before it is discarded
+    pub max_screenshot_bytes: usize,
+    /// Largest APK `add_apk` accepts into the APK directory (0 = no limit)
+    pub max_apk_bytes: u64,
+    /// Named volume levels (0-100) that can be applied to a group of devices
+    pub volume_presets: BTreeMap<String, u8>,
+    /// Minimum milliseconds between frontend events of the same high-frequency
+    /// kind (battery, progress) per device (0 = emit every event)
+    pub event_coalesce_window_ms: u64,
+    /// Number of command results kept per device (1-1000)
+    pub command_history_size: usize,
+    /// Only allow shell commands matching `shell_allowlist`, for kiosk deployments
+    pub shell_safe_mode: bool,
+    /// Shell commands allowed in safe mode; `*` and `?` are wildcards
+    pub shell_allowlist: Vec<String>,
 }
 
 impl AppConfig {
@@ -16,12 +61,58 @@ impl AppConfig {
         Self {
             server: ServerConfig::default(),
             alakazam: AlakazamConfig::default(),
+            remote_api: RemoteApiConfig::default(),
+            dfu: DfuConfig::default(),
+            sensor_firmware: SensorFirmwareConfig::default(),
+            watchdog: WatchdogConfig::default(),
+            update_channel: UpdateChannel::default(),
             apk_directory,
             database_path,
             games_directory,
+            max_concurrent_downloads: DEFAULT_MAX_CONCURRENT_DOWNLOADS,
+            file_download_concurrency: DEFAULT_FILE_DOWNLOAD_CONCURRENCY,
+            max_download_bytes_per_sec: 0,
+            max_screenshot_bytes: DEFAULT_MAX_SCREENSHOT_BYTES,
+            max_apk_bytes: DEFAULT_MAX_APK_BYTES,
+            volume_presets: default_volume_presets(),
+            event_coalesce_window_ms: DEFAULT_EVENT_COALESCE_WINDOW_MS,
+            command_history_size: DEFAULT_COMMAND_HISTORY_SIZE,
+            shell_safe_mode: false,
+            shell_allowlist: Vec::new(),
         }
     }
 
+    /// Apply the settings in a JSON file on top of this configuration
+    /// Only keys present in the file change, at any depth, so a file holding
+    /// `{ "watchdog": { "enabled": true } }` keeps every other default.
+    /// A missing file leaves the configuration as it is.
+    pub fn with_overrides_from(self, path: &Path) -> Result<Self> {
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(self),
+            Err(e) => {
+                return Err(crate::app::error::ArceusError::Config(format!(
+                    "Failed to read {}: {}",
+                    path.display(),
+                    e
+                )))
+            }
+        };
+
+        let overrides: serde_json::Value = serde_json::from_str(&contents).map_err(|e| {
+            crate::app::error::ArceusError::Config(format!("Invalid JSON in {}: {}", path.display(), e))
+        })?;
+        self.with_overrides(overrides).map_err(|e| {
+            crate::app::error::ArceusError::Config(format!("Invalid setting in {}: {}", path.display(), e))
+        })
+    }
+
+    fn with_overrides(self, overrides: serde_json::Value) -> serde_json::Result<Self> {
+        let mut merged = serde_json::to_value(&self)?;
+        merge_json(&mut merged, overrides);
+        serde_json::from_value(merged)
+    }
+
     pub fn validate(&self) -> Result<()> {
         if self.server.tcp_port == 0 {
             return Err(crate::app::error::ArceusError::Config(
@@ -41,12 +132,112 @@ impl AppConfig {
             ));
         }
 
+        if self.remote_api.enabled {
+            if self.remote_api.port == 0 {
+                return Err(crate::app::error::ArceusError::Config(
+                    "Remote API port must be greater than 0".to_string(),
+                ));
+            }
+
+            if self.remote_api.port == self.server.tcp_port || self.remote_api.port == self.server.http_port {
+                return Err(crate::app::error::ArceusError::Config(
+                    "Remote API port must differ from the TCP and HTTP ports".to_string(),
+                ));
+            }
+
+            if self.remote_api.token.trim().len() < MIN_REMOTE_API_TOKEN_LEN {
+                return Err(crate::app::error::ArceusError::Config(format!(
+                    "Remote API token must be at least {} characters",
+                    MIN_REMOTE_API_TOKEN_LEN
+                )));
+            }
+        }
+
+        if self.server.command_timeout == 0 {
+            return Err(crate::app::error::ArceusError::Config(
+                "Command timeout must be greater than 0".to_string(),
+            ));
+        }
+
         if self.server.max_connections == 0 {
             return Err(crate::app::error::ArceusError::Config(
                 "Max connections must be greater than 0".to_string(),
             ));
         }
 
+        if self.server.max_packet_payload == 0
+            || self.server.max_packet_payload > crate::infrastructure::protocol::MAX_PAYLOAD_LEN
+        {
+            return Err(crate::app::error::ArceusError::Config(format!(
+                "Max packet payload must be 1-{}, got {}",
+                crate::infrastructure::protocol::MAX_PAYLOAD_LEN,
+                self.server.max_packet_payload
+            )));
+        }
+
+        if self.server.malformed_packet_limit == 0 || self.server.malformed_packet_window == 0 {
+            return Err(crate::app::error::ArceusError::Config(
+                "Malformed packet limit and window must be greater than 0".to_string(),
+            ));
+        }
+
+        if let Some((name, level)) = self.volume_presets.iter().find(|(_, level)| **level > 100) {
+            return Err(crate::app::error::ArceusError::Config(format!(
+                "Volume preset '{}' must be 0-100, got {}",
+                name, level
+            )));
+        }
+
+        if self.command_history_size == 0 || self.command_history_size > MAX_COMMAND_HISTORY_SIZE {
+            return Err(crate::app::error::ArceusError::Config(format!(
+                "Command history size must be 1-{}, got {}",
+                MAX_COMMAND_HISTORY_SIZE, self.command_history_size
+            )));
+        }
+
+        if self.dfu.max_attempts == 0 {
+            return Err(crate::app::error::ArceusError::Config(
+                "DFU max attempts must be greater than 0".to_string(),
+            ));
+        }
+
+        if !self.dfu.backoff_factor.is_finite() || self.dfu.backoff_factor < 1.0 {
+            return Err(crate::app::error::ArceusError::Config(format!(
+                "DFU backoff factor must be at least 1.0, got {}",
+                self.dfu.backoff_factor
+            )));
+        }
+
+        if self.dfu.upload_timeout_secs == 0 {
+            return Err(crate::app::error::ArceusError::Config(
+                "DFU upload timeout must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.watchdog.home_threshold_secs == 0 {
+            return Err(crate::app::error::ArceusError::Config(
+                "Watchdog home threshold must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.watchdog.max_relaunch_attempts == 0 {
+            return Err(crate::app::error::ArceusError::Config(
+                "Watchdog max relaunch attempts must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.max_concurrent_downloads == 0 {
+            return Err(crate::app::error::ArceusError::Config(
+                "Max concurrent downloads must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.file_download_concurrency == 0 {
+            return Err(crate::app::error::ArceusError::Config(
+                "File download concurrency must be greater than 0".to_string(),
+            ));
+        }
+
         Ok(())
     }
 }
@@ -56,10 +247,42 @@ impl Default for AppConfig {
         Self {
             server: ServerConfig::default(),
             alakazam: AlakazamConfig::default(),
+            remote_api: RemoteApiConfig::default(),
+            dfu: DfuConfig::default(),
+            sensor_firmware: SensorFirmwareConfig::default(),
+            watchdog: WatchdogConfig::default(),
+            update_channel: UpdateChannel::default(),
             apk_directory: PathBuf::from("apks"),
             database_path: PathBuf::from("arceus.db"),
             games_directory: PathBuf::from("C:/Combatica"),
+            max_concurrent_downloads: DEFAULT_MAX_CONCURRENT_DOWNLOADS,
+            file_download_concurrency: DEFAULT_FILE_DOWNLOAD_CONCURRENCY,
+            max_download_bytes_per_sec: 0,
+            max_screenshot_bytes: DEFAULT_MAX_SCREENSHOT_BYTES,
+            max_apk_bytes: DEFAULT_MAX_APK_BYTES,
+            volume_presets: default_volume_presets(),
+            event_coalesce_window_ms: DEFAULT_EVENT_COALESCE_WINDOW_MS,
+            command_history_size: DEFAULT_COMMAND_HISTORY_SIZE,
+            shell_safe_mode: false,
+            shell_allowlist: Vec::new(),
+        }
+    }
+}
+
+/// Overwrite `base` with `overrides`, merging objects key by key
+fn merge_json(base: &mut serde_json::Value, overrides: serde_json::Value) {
+    match (base, overrides) {
+        (serde_json::Value::Object(base), serde_json::Value::Object(overrides)) => {
+            for (key, value) in overrides {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_json(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
         }
+        (base, overrides) => *base = overrides,
     }
 }
 
@@ -73,3 +296,59 @@ pub fn get_machine_id() -> Result<String> {
 pub const CLIENT_APK_FILENAME: &str = "Snorlax.apk";
 pub const CLIENT_METADATA_FILENAME: &str = "client_metadata.json";
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_replace_only_the_keys_they_name() {
+        let config = AppConfig::default()
+            .with_overrides(serde_json::json!({
+                "watchdog": { "enabled": true },
+                "remote_api": { "enabled": true, "token": "0123456789abcdef" },
+                "max_concurrent_downloads": 4,
+            }))
+            .unwrap();
+
+        assert!(config.watchdog.enabled);
+        assert_eq!(config.watchdog.home_threshold_secs, WatchdogConfig::default().home_threshold_secs);
+        assert!(config.remote_api.enabled);
+        assert_eq!(config.remote_api.bind_address, "127.0.0.1");
+        assert_eq!(config.max_concurrent_downloads, 4);
+        assert_eq!(config.server.tcp_port, ServerConfig::default().tcp_port);
+    }
+
+    #[test]
+    fn mistyped_overrides_are_rejected() {
+        assert!(AppConfig::default()
+            .with_overrides(serde_json::json!({ "watchdog": { "enabled": "yes" } }))
+            .is_err());
+    }
+
+    #[test]
+    fn missing_config_file_keeps_the_defaults() {
+        let path = std::env::temp_dir().join(format!("arceus-config-{}.json", uuid::Uuid::new_v4()));
+        let config = AppConfig::default().with_overrides_from(&path).unwrap();
+        assert_eq!(config.server.tcp_port, ServerConfig::default().tcp_port);
+    }
+
+    #[test]
+    fn sensor_firmware_falls_back_to_the_bundled_image() {
+        let resources = std::env::temp_dir().join(format!("arceus-resources-{}", uuid::Uuid::new_v4()));
+        let bundled = resources.join(crate::app::models::BUNDLED_SENSOR_FIRMWARE);
+
+        let unconfigured = SensorFirmwareConfig::default();
+        assert_eq!(unconfigured.resolve_path(Some(&resources)), None);
+        assert_eq!(unconfigured.resolve_path(None), None);
+
+        std::fs::create_dir_all(bundled.parent().unwrap()).unwrap();
+        std::fs::write(&bundled, b"image").unwrap();
+        assert_eq!(unconfigured.resolve_path(Some(&resources)), Some(bundled.clone()));
+
+        let configured = SensorFirmwareConfig { path: Some(PathBuf::from("custom.bin")) };
+        assert_eq!(configured.resolve_path(Some(&resources)), Some(PathBuf::from("custom.bin")));
+
+        std::fs::remove_dir_all(&resources).unwrap();
+    }
+}
@@ -1,8 +1,20 @@
-use crate::application::dto::{BatteryInfoDto, CommandResultDto, DeviceStateDto, OperationProgressDto, VolumeInfoDto};
+use crate::application::services::DownloadState;
+use crate::application::dto::{ApkInstallStatus, BatteryInfoDto, CommandResultDto, DeviceStateDto, InstalledAppDto, OperationProgressDto, OperationStage, PacketTraceDto, StorageInfoDto, VolumeInfoDto, WifiStatusDto};
+use crate::domain::models::{CommandRecord, DeviceId};
+use crate::domain::repositories::CommandHistoryRepository;
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter};
 use uuid::Uuid;
 
+/// Channel carrying every `ArceusEvent`
+const EVENT_CHANNEL: &str = "arceus://event";
+/// Channel carrying packets of devices being traced
+const PACKET_TRACE_CHANNEL: &str = "arceus://packet-trace";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "camelCase")]
 pub enum ArceusEvent {
@@ -17,6 +29,22 @@ pub enum ArceusEvent {
         serial: String,
     },
 
+    #[serde(rename_all = "camelCase")]
+    DeviceFlagged {
+        device_id: Uuid,
+        serial: String,
+        reason: String,
+    },
+
+    /// The device switched away from the package staff expect it to stay in
+    #[serde(rename_all = "camelCase")]
+    DeviceLeftApp {
+        device_id: Uuid,
+        serial: String,
+        expected: String,
+        actual: String,
+    },
+
     #[serde(rename_all = "camelCase")]
     DeviceUpdated {
         device: DeviceStateDto,
@@ -34,6 +62,24 @@ pub enum ArceusEvent {
         volume_info: VolumeInfoDto,
     },
 
+    #[serde(rename_all = "camelCase")]
+    BrightnessUpdated {
+        device_id: Uuid,
+        brightness: u8,
+    },
+
+    #[serde(rename_all = "camelCase")]
+    StorageUpdated {
+        device_id: Uuid,
+        storage_info: StorageInfoDto,
+    },
+
+    #[serde(rename_all = "camelCase")]
+    WifiStatusUpdated {
+        device_id: Uuid,
+        wifi_status: WifiStatusDto,
+    },
+
     #[serde(rename_all = "camelCase")]
     CommandExecuted {
         device_id: Uuid,
@@ -43,7 +89,7 @@ pub enum ArceusEvent {
     #[serde(rename_all = "camelCase")]
     InstalledAppsReceived {
         device_id: Uuid,
-        apps: Vec<String>,
+        apps: Vec<InstalledAppDto>,
     },
 
     #[serde(rename_all = "camelCase")]
@@ -61,6 +107,12 @@ pub enum ArceusEvent {
 
     ServerStopped,
 
+    #[serde(rename_all = "camelCase")]
+    ConnectionRejected {
+        ip_address: String,
+        reason: String,
+    },
+
     #[serde(rename_all = "camelCase")]
     HttpServerStarted {
         port: u16,
@@ -102,6 +154,61 @@ pub enum ArceusEvent {
         game_id: i32,
         game_name: String,
         percentage: f32,
+        /// 1-based position while waiting for a download slot, `None` once started
+        queue_position: Option<usize>,
+    },
+
+    #[serde(rename_all = "camelCase")]
+    GameVerificationProgress {
+        game_id: i32,
+        game_name: String,
+        verified_files: usize,
+        total_files: usize,
+        current_file: String,
+    },
+
+    /// A downloaded file didn't match the file list and was deleted
+    #[serde(rename_all = "camelCase")]
+    GameVerificationFailed {
+        game_id: i32,
+        game_name: String,
+        file: String,
+        reason: String,
+    },
+
+    #[serde(rename_all = "camelCase")]
+    GameDownloadStateChanged {
+        game_id: i32,
+        game_name: String,
+        state: DownloadState,
+    },
+
+    #[serde(rename_all = "camelCase")]
+    GameDeleted {
+        game_id: i32,
+        game_name: String,
+        freed_bytes: u64,
+    },
+
+    #[serde(rename_all = "camelCase")]
+    ApkAddProgress {
+        /// Name of the source file being added
+        filename: String,
+        bytes_copied: u64,
+        total_bytes: u64,
+    },
+
+    #[serde(rename_all = "camelCase")]
+    ApkBatchInstallProgress {
+        batch_id: String,
+        device_id: Option<Uuid>,
+        serial: Option<String>,
+        status: ApkInstallStatus,
+        /// Failure or skip reason
+        message: Option<String>,
+        /// Devices finished so far, whatever their outcome
+        completed: usize,
+        total: usize,
     },
 
     #[serde(rename_all = "camelCase")]
@@ -109,27 +216,207 @@ pub enum ArceusEvent {
         port: String,
         stage: String,
         percentage: f32,
+        /// Firmware bytes flashed so far in the current attempt
+        bytes_sent: Option<u64>,
+        total_bytes: Option<u64>,
+        /// 1-based upload attempt, present while flashing
+        attempt: Option<u32>,
     },
 }
 
+/// How an event takes part in coalescing
+#[derive(Debug, PartialEq)]
+enum Coalescing {
+    /// Emitted immediately
+    None,
+    /// High-frequency update that may be held back for its key; a terminal one
+    /// (e.g. the final stage of a progress stream) is always delivered at once
+    Update { key: String, terminal: bool },
+    /// Emitted immediately, after discarding updates held back for these streams
+    /// so a stale progress value can't arrive after it
+    Ends(Vec<String>),
+}
+
+impl ArceusEvent {
+    fn coalescing(&self) -> Coalescing {
+        let update = |key: String, terminal: bool| Coalescing::Update { key, terminal };
+
+        match self {
+            ArceusEvent::BatteryUpdated { device_id, .. } => update(format!("battery:{}", device_id), false),
+            ArceusEvent::VolumeUpdated { device_id, .. } => update(format!("volume:{}", device_id), false),
+            ArceusEvent::OperationProgress {
+                device_id, progress, ..
+            } => update(
+                format!("operation:{}:{}", device_id, progress.operation_id),
+                progress.stage != OperationStage::InProgress,
+            ),
+            ArceusEvent::GameDownloadProgress { game_id, percentage, .. } => {
+                update(format!("game_download:{}", game_id), *percentage >= 100.0)
+            }
+            ArceusEvent::GameVerificationProgress {
+                game_id,
+                verified_files,
+                total_files,
+                ..
+            } => update(format!("game_verify:{}", game_id), verified_files == total_files),
+            ArceusEvent::ApkAddProgress {
+                filename,
+                bytes_copied,
+                total_bytes,
+            } => update(format!("apk_add:{}", filename), bytes_copied == total_bytes),
+            ArceusEvent::SensorUploadProgress { port, stage, .. } => update(
+                format!("sensor_upload:{}", port),
+                matches!(stage.as_str(), "completed" | "failed" | "skipped"),
+            ),
+            ArceusEvent::GameDownloadStateChanged { game_id, .. } => {
+                Coalescing::Ends(vec![format!("game_download:{}", game_id)])
+            }
+            ArceusEvent::GameDeleted { game_id, .. } => Coalescing::Ends(vec![
+                format!("game_download:{}", game_id),
+                format!("game_verify:{}", game_id),
+            ]),
+            ArceusEvent::DeviceDisconnected { device_id, .. } => Coalescing::Ends(vec![
+                format!("battery:{}", device_id),
+                format!("volume:{}", device_id),
+                format!("operation:{}", device_id),
+            ]),
+            _ => Coalescing::None,
+        }
+    }
+}
+
+/// Whether coalescing key `key` belongs to `stream`: equal to it, or nested under it
+/// (`operation:<device>` covers `operation:<device>:<operation>`)
+fn in_stream(key: &str, stream: &str) -> bool {
+    key.strip_prefix(stream)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with(':'))
+}
+
+/// Per-key coalescing state
+struct CoalesceSlot {
+    last_emitted: Option<Instant>,
+    /// Latest event held back until the window elapses
+    pending: Option<ArceusEvent>,
+}
+
 #[derive(Clone)]
 pub struct EventBus {
     app_handle: AppHandle,
+    /// Minimum time between two emissions of the same high-frequency event
+    coalesce_window: Duration,
+    coalesced: Arc<Mutex<HashMap<String, CoalesceSlot>>>,
+    command_history: Arc<dyn CommandHistoryRepository>,
 }
 
 impl EventBus {
-    pub fn new(app_handle: AppHandle) -> Self {
-        Self { app_handle }
+    pub fn new(
+        app_handle: AppHandle,
+        coalesce_window: Duration,
+        command_history: Arc<dyn CommandHistoryRepository>,
+    ) -> Self {
+        Self {
+            app_handle,
+            coalesce_window,
+            coalesced: Arc::new(Mutex::new(HashMap::new())),
+            command_history,
+        }
     }
 
+    /// Emit an event to the frontend
+    /// High-frequency events (battery, volume, progress) are emitted at most once per
+    /// coalesce window per device/operation; intermediate values are dropped and the
+    /// latest one is delivered when the window ends.
     pub fn emit(&self, event: ArceusEvent) {
-        let event_name = "arceus://event";
+        match event.coalescing() {
+            Coalescing::Update { key, terminal } if !self.coalesce_window.is_zero() => {
+                self.emit_coalesced(key, terminal, event)
+            }
+            Coalescing::Ends(streams) => {
+                // Removing the slot also turns any scheduled flush into a no-op
+                self.coalesced
+                    .lock()
+                    .retain(|key, _| !streams.iter().any(|stream| in_stream(key, stream)));
+                self.emit_now(&event);
+            }
+            _ => self.emit_now(&event),
+        }
+    }
 
-        if let Err(e) = self.app_handle.emit(event_name, &event) {
+    fn emit_now(&self, event: &ArceusEvent) {
+        if let Err(e) = self.app_handle.emit(EVENT_CHANNEL, event) {
             tracing::error!("Failed to emit event {:?}: {}", event, e);
         }
     }
 
+    fn emit_coalesced(&self, key: String, terminal: bool, event: ArceusEvent) {
+        let now = Instant::now();
+        let window = self.coalesce_window;
+        let mut slots = self.coalesced.lock();
+
+        if !slots.contains_key(&key) {
+            // Forget keys that have been quiet for a full window
+            slots.retain(|_, slot| {
+                slot.pending.is_some()
+                    || slot.last_emitted.is_some_and(|t| now.duration_since(t) < window)
+            });
+        }
+
+        let slot = slots.entry(key.clone()).or_insert(CoalesceSlot {
+            last_emitted: None,
+            pending: None,
+        });
+
+        if terminal {
+            // Supersedes anything still waiting so the final state is never overwritten
+            slot.pending = None;
+            slot.last_emitted = Some(now);
+            drop(slots);
+            self.emit_now(&event);
+            return;
+        }
+
+        if slot.pending.is_some() {
+            // A flush is already scheduled, just keep the latest value
+            slot.pending = Some(event);
+            return;
+        }
+
+        match slot.last_emitted {
+            Some(last) if now.duration_since(last) < window => {
+                slot.pending = Some(event);
+                let delay = window - now.duration_since(last);
+                let bus = self.clone();
+                tauri::async_runtime::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    bus.flush_coalesced(&key);
+                });
+            }
+            _ => {
+                slot.last_emitted = Some(now);
+                drop(slots);
+                self.emit_now(&event);
+            }
+        }
+    }
+
+    fn flush_coalesced(&self, key: &str) {
+        let event = {
+            let mut slots = self.coalesced.lock();
+            let Some(slot) = slots.get_mut(key) else {
+                return;
+            };
+            let event = slot.pending.take();
+            if event.is_some() {
+                slot.last_emitted = Some(Instant::now());
+            }
+            event
+        };
+
+        if let Some(event) = event {
+            self.emit_now(&event);
+        }
+    }
+
     pub fn device_connected(&self, device: DeviceStateDto) {
         self.emit(ArceusEvent::DeviceConnected { device });
     }
@@ -138,6 +425,23 @@ impl EventBus {
         self.emit(ArceusEvent::DeviceUpdated { device });
     }
 
+    pub fn device_flagged(&self, device_id: Uuid, serial: String, reason: String) {
+        self.emit(ArceusEvent::DeviceFlagged {
+            device_id,
+            serial,
+            reason,
+        });
+    }
+
+    pub fn device_left_app(&self, device_id: Uuid, serial: String, expected: String, actual: String) {
+        self.emit(ArceusEvent::DeviceLeftApp {
+            device_id,
+            serial,
+            expected,
+            actual,
+        });
+    }
+
     pub fn battery_updated(&self, device_id: Uuid, battery_info: BatteryInfoDto) {
         self.emit(ArceusEvent::BatteryUpdated {
             device_id,
@@ -152,11 +456,41 @@ impl EventBus {
         });
     }
 
+    pub fn brightness_updated(&self, device_id: Uuid, brightness: u8) {
+        self.emit(ArceusEvent::BrightnessUpdated {
+            device_id,
+            brightness,
+        });
+    }
+
+    pub fn storage_updated(&self, device_id: Uuid, storage_info: StorageInfoDto) {
+        self.emit(ArceusEvent::StorageUpdated {
+            device_id,
+            storage_info,
+        });
+    }
+
+    pub fn wifi_status_updated(&self, device_id: Uuid, wifi_status: WifiStatusDto) {
+        self.emit(ArceusEvent::WifiStatusUpdated {
+            device_id,
+            wifi_status,
+        });
+    }
+
     pub fn command_executed(&self, device_id: Uuid, result: CommandResultDto) {
+        self.command_history
+            .record(DeviceId::from_uuid(device_id), CommandRecord::from(&result));
         self.emit(ArceusEvent::CommandExecuted { device_id, result });
     }
 
-    pub fn installed_apps_received(&self, device_id: Uuid, apps: Vec<String>) {
+    /// Emit a traced packet on its own channel so traces never mix with app events
+    pub fn packet_traced(&self, trace: PacketTraceDto) {
+        if let Err(e) = self.app_handle.emit(PACKET_TRACE_CHANNEL, &trace) {
+            tracing::error!("Failed to emit packet trace: {}", e);
+        }
+    }
+
+    pub fn installed_apps_received(&self, device_id: Uuid, apps: Vec<InstalledAppDto>) {
         self.emit(ArceusEvent::InstalledAppsReceived { device_id, apps });
     }
 
@@ -171,6 +505,10 @@ impl EventBus {
         self.emit(ArceusEvent::ServerStopped);
     }
 
+    pub fn connection_rejected(&self, ip_address: String, reason: String) {
+        self.emit(ArceusEvent::ConnectionRejected { ip_address, reason });
+    }
+
     pub fn http_server_started(&self, port: u16, url: String) {
         self.emit(ArceusEvent::HttpServerStarted { port, url });
     }
@@ -200,11 +538,85 @@ impl EventBus {
         game_id: i32,
         game_name: String,
         percentage: f32,
+        queue_position: Option<usize>,
     ) {
         self.emit(ArceusEvent::GameDownloadProgress {
             game_id,
             game_name,
             percentage,
+            queue_position,
+        });
+    }
+
+    pub fn game_verification_progress(
+        &self,
+        game_id: i32,
+        game_name: String,
+        verified_files: usize,
+        total_files: usize,
+        current_file: String,
+    ) {
+        self.emit(ArceusEvent::GameVerificationProgress {
+            game_id,
+            game_name,
+            verified_files,
+            total_files,
+            current_file,
+        });
+    }
+
+    pub fn game_verification_failed(&self, game_id: i32, game_name: String, file: String, reason: String) {
+        self.emit(ArceusEvent::GameVerificationFailed {
+            game_id,
+            game_name,
+            file,
+            reason,
+        });
+    }
+
+    pub fn game_download_state_changed(&self, game_id: i32, game_name: String, state: DownloadState) {
+        self.emit(ArceusEvent::GameDownloadStateChanged {
+            game_id,
+            game_name,
+            state,
+        });
+    }
+
+    pub fn game_deleted(&self, game_id: i32, game_name: String, freed_bytes: u64) {
+        self.emit(ArceusEvent::GameDeleted {
+            game_id,
+            game_name,
+            freed_bytes,
+        });
+    }
+
+    pub fn apk_add_progress(&self, filename: String, bytes_copied: u64, total_bytes: u64) {
+        self.emit(ArceusEvent::ApkAddProgress {
+            filename,
+            bytes_copied,
+            total_bytes,
+        });
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn apk_batch_install_progress(
+        &self,
+        batch_id: String,
+        device_id: Option<Uuid>,
+        serial: Option<String>,
+        status: ApkInstallStatus,
+        message: Option<String>,
+        completed: usize,
+        total: usize,
+    ) {
+        self.emit(ArceusEvent::ApkBatchInstallProgress {
+            batch_id,
+            device_id,
+            serial,
+            status,
+            message,
+            completed,
+            total,
         });
     }
 
@@ -213,7 +625,88 @@ impl EventBus {
             port,
             stage,
             percentage,
+            bytes_sent: None,
+            total_bytes: None,
+            attempt: None,
+        });
+    }
+
+    pub fn sensor_flash_progress(
+        &self,
+        port: String,
+        stage: String,
+        bytes_sent: u64,
+        total_bytes: u64,
+        attempt: u32,
+    ) {
+        let percentage = if total_bytes == 0 {
+            0.0
+        } else {
+            bytes_sent as f32 / total_bytes as f32 * 100.0
+        };
+
+        self.emit(ArceusEvent::SensorUploadProgress {
+            port,
+            stage,
+            percentage,
+            bytes_sent: Some(bytes_sent),
+            total_bytes: Some(total_bytes),
+            attempt: Some(attempt),
         });
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn download_progress(percentage: f32) -> ArceusEvent {
+        ArceusEvent::GameDownloadProgress {
+            game_id: 7,
+            game_name: "Game".to_string(),
+            percentage,
+            queue_position: None,
+        }
+    }
+
+    #[test]
+    fn final_download_progress_is_terminal() {
+        let key = "game_download:7".to_string();
+        assert_eq!(
+            download_progress(40.0).coalescing(),
+            Coalescing::Update { key: key.clone(), terminal: false }
+        );
+        assert_eq!(download_progress(100.0).coalescing(), Coalescing::Update { key, terminal: true });
+    }
+
+    #[test]
+    fn state_changes_end_the_progress_stream() {
+        let paused = ArceusEvent::GameDownloadStateChanged {
+            game_id: 7,
+            game_name: "Game".to_string(),
+            state: DownloadState::Paused,
+        };
+        assert_eq!(paused.coalescing(), Coalescing::Ends(vec!["game_download:7".to_string()]));
+    }
+
+    #[test]
+    fn deleting_a_game_ends_its_progress_streams() {
+        let deleted = ArceusEvent::GameDeleted {
+            game_id: 7,
+            game_name: "Game".to_string(),
+            freed_bytes: 0,
+        };
+        assert_eq!(
+            deleted.coalescing(),
+            Coalescing::Ends(vec!["game_download:7".to_string(), "game_verify:7".to_string()])
+        );
+    }
+
+    #[test]
+    fn streams_cover_their_own_and_nested_keys_only() {
+        assert!(in_stream("game_download:7", "game_download:7"));
+        assert!(!in_stream("game_download:71", "game_download:7"));
+        assert!(in_stream("operation:dev:op-1", "operation:dev"));
+        assert!(!in_stream("operation:device2:op-1", "operation:dev"));
+    }
+}
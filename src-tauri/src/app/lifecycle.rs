@@ -1,13 +1,14 @@
 use crate::infrastructure::network::TcpServer;
 use parking_lot::RwLock;
 use std::sync::Arc;
-use tokio::process::Child;
 
 pub struct AppState {
     tcp_server: Arc<TcpServer>,
     tcp_server_handle: RwLock<Option<tauri::async_runtime::JoinHandle<()>>>,
-    http_server: RwLock<Option<Child>>,
+    http_server_handle: RwLock<Option<tauri::async_runtime::JoinHandle<()>>>,
     battery_monitor_handle: RwLock<Option<tauri::async_runtime::JoinHandle<()>>>,
+    app_watchdog_handle: RwLock<Option<tauri::async_runtime::JoinHandle<()>>>,
+    heartbeat_sweeper_handle: RwLock<Option<tauri::async_runtime::JoinHandle<()>>>,
 }
 
 impl AppState {
@@ -15,8 +16,10 @@ impl AppState {
         Self {
             tcp_server,
             tcp_server_handle: RwLock::new(None),
-            http_server: RwLock::new(None),
+            http_server_handle: RwLock::new(None),
             battery_monitor_handle: RwLock::new(None),
+            app_watchdog_handle: RwLock::new(None),
+            heartbeat_sweeper_handle: RwLock::new(None),
         }
     }
 
@@ -24,29 +27,46 @@ impl AppState {
         *self.tcp_server_handle.write() = Some(handle);
     }
 
-    pub fn set_http_server(&self, child: Child) {
-        *self.http_server.write() = Some(child);
+    pub fn set_http_server_handle(&self, handle: tauri::async_runtime::JoinHandle<()>) {
+        *self.http_server_handle.write() = Some(handle);
     }
 
     pub fn set_battery_monitor(&self, handle: tauri::async_runtime::JoinHandle<()>) {
         *self.battery_monitor_handle.write() = Some(handle);
     }
 
+    pub fn set_app_watchdog(&self, handle: tauri::async_runtime::JoinHandle<()>) {
+        *self.app_watchdog_handle.write() = Some(handle);
+    }
+
+    pub fn set_heartbeat_sweeper(&self, handle: tauri::async_runtime::JoinHandle<()>) {
+        *self.heartbeat_sweeper_handle.write() = Some(handle);
+    }
+
     pub fn shutdown(&self) {
         tracing::info!("Shutting down services");
 
-        self.tcp_server.shutdown();
+        // Blocks, so this must not be called from an async task
+        tauri::async_runtime::block_on(self.tcp_server.drain());
 
         if let Some(handle) = self.battery_monitor_handle.write().take() {
             handle.abort();
         }
 
+        if let Some(handle) = self.app_watchdog_handle.write().take() {
+            handle.abort();
+        }
+
+        if let Some(handle) = self.heartbeat_sweeper_handle.write().take() {
+            handle.abort();
+        }
+
         if let Some(handle) = self.tcp_server_handle.write().take() {
             let _ = tauri::async_runtime::block_on(handle);
         }
 
-        if let Some(mut child) = self.http_server.write().take() {
-            let _ = tauri::async_runtime::block_on(child.kill());
+        if let Some(handle) = self.http_server_handle.write().take() {
+            handle.abort();
         }
 
         tracing::info!("Shutdown complete");
@@ -8,10 +8,10 @@ pub mod models;
 pub mod server_manager;
 pub mod signal_handler;
 
-pub use config::AppConfig;
+pub use config::{AppConfig, CONFIG_FILENAME};
 pub use error::Result;
 pub use events::EventBus;
 pub use lifecycle::AppState;
-pub use models::{ApkFile, ServerConfig};
+pub use models::{ApkDuplicateGroup, ApkFile, ApkFilePage, ServerConfig};
 pub use server_manager::ServerManager;
 pub use signal_handler::setup_signal_handlers;
@@ -1,3 +1,5 @@
+use crate::domain::repositories::ApkMetadata;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -5,14 +7,64 @@ pub struct ApkFile {
     pub filename: String,
     pub size_bytes: u64,
     pub url: String,
+    pub sha256: Option<String>,
+    pub package_name: Option<String>,
+    pub version_name: Option<String>,
+    pub version_code: Option<u32>,
+    pub label: Option<String>,
+    /// Base64-encoded PNG launcher icon
+    pub icon: Option<String>,
+    pub added_at: Option<DateTime<Utc>>,
+}
+
+/// One page of stored APKs
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ApkFilePage {
+    pub items: Vec<ApkFile>,
+    /// Number of APKs across all pages
+    pub total: usize,
+}
+
+/// Stored APKs with identical contents
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ApkDuplicateGroup {
+    pub sha256: String,
+    /// Disk space freed by keeping only one copy
+    pub wasted_bytes: u64,
+    /// Oldest first
+    pub files: Vec<ApkFile>,
 }
 
 impl ApkFile {
-    pub fn new(filename: String, size_bytes: u64, url: String) -> Self {
+    pub fn new(filename: String, size_bytes: u64, url: String, sha256: Option<String>) -> Self {
         Self {
             filename,
             size_bytes,
             url,
+            sha256,
+            package_name: None,
+            version_name: None,
+            version_code: None,
+            label: None,
+            icon: None,
+            added_at: None,
+        }
+    }
+
+    pub fn with_added_at(mut self, added_at: Option<DateTime<Utc>>) -> Self {
+        self.added_at = added_at;
+        self
+    }
+
+    /// Fill in package details parsed from the APK, if any
+    pub fn with_metadata(mut self, metadata: Option<ApkMetadata>) -> Self {
+        if let Some(metadata) = metadata {
+            self.package_name = Some(metadata.package_name);
+            self.version_name = metadata.version_name;
+            self.version_code = metadata.version_code;
+            self.label = metadata.label;
+            self.icon = metadata.icon_png_base64;
         }
+        self
     }
 }
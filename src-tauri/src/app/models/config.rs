@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerConfig {
@@ -8,6 +9,26 @@ pub struct ServerConfig {
     pub max_connections: usize,
     pub battery_update_interval: u64,
     pub heartbeat_timeout: u64,
+    /// Seconds to wait for a device to answer a command
+    pub command_timeout: u64,
+    /// Extra attempts for idempotent commands (battery, ping, ...) that time out
+    pub command_retries: u32,
+    /// Seconds a graceful shutdown waits for in-flight commands before closing connections
+    pub shutdown_grace_period: u64,
+    /// Log every raw packet (opcode and hex payload) at debug level
+    pub packet_trace: bool,
+    /// File traced packets are appended to, one line each, for offline analysis
+    pub packet_capture_path: Option<PathBuf>,
+    /// Largest packet payload a device may send; longer length prefixes are protocol violations
+    /// Defaults to the 64 KiB the length prefix allows, so only a lower setting rejects anything.
+    pub max_packet_payload: usize,
+    /// Errors a connection may hit within `malformed_packet_window` before it is dropped and flagged
+    pub malformed_packet_limit: u32,
+    /// Seconds over which `malformed_packet_limit` is counted
+    pub malformed_packet_window: u64,
+    /// Seconds a device may stay out of its expected app before the app watchdog
+    /// relaunches it; 0 only alerts
+    pub expected_app_relaunch_secs: u64,
 }
 
 impl Default for ServerConfig {
@@ -19,10 +40,115 @@ impl Default for ServerConfig {
             max_connections: 100,
             battery_update_interval: 60,
             heartbeat_timeout: 30,
+            command_timeout: 10,
+            command_retries: 2,
+            shutdown_grace_period: 5,
+            packet_trace: false,
+            packet_capture_path: None,
+            max_packet_payload: crate::infrastructure::protocol::MAX_PAYLOAD_LEN,
+            malformed_packet_limit: 5,
+            malformed_packet_window: 60,
+            expected_app_relaunch_secs: 0,
         }
     }
 }
 
+/// HTTP API for driving Arceus without the UI, e.g. from a venue controller
+/// The API speaks plain HTTP, so it listens on loopback unless `bind_address`
+/// is changed; put a TLS-terminating proxy in front before exposing it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteApiConfig {
+    pub enabled: bool,
+    pub bind_address: String,
+    pub port: u16,
+    /// Bearer token every request must present
+    pub token: String,
+}
+
+impl Default for RemoteApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: "127.0.0.1".to_string(),
+            port: 43574,
+            token: String::new(),
+        }
+    }
+}
+
+/// Retry policy for sensor firmware uploads whose serial port is busy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DfuConfig {
+    /// Upload attempts per board, including the first
+    pub max_attempts: u32,
+    /// Milliseconds to wait before the first retry
+    pub retry_delay_ms: u64,
+    /// Multiplier applied to the delay for each further retry (1.0 = fixed delay)
+    pub backoff_factor: f64,
+    /// Seconds a single upload attempt may take before it is aborted
+    pub upload_timeout_secs: u64,
+}
+
+impl Default for DfuConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            retry_delay_ms: 3000,
+            backoff_factor: 1.0,
+            upload_timeout_secs: 180,
+        }
+    }
+}
+
+/// Kiosk watchdog that relaunches a device's expected app after it drops to the home shell
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchdogConfig {
+    pub enabled: bool,
+    /// Seconds a device may sit in the home shell before its app is relaunched
+    pub home_threshold_secs: u64,
+    /// Relaunches tried before giving up on a device until it is back in its app
+    pub max_relaunch_attempts: u32,
+    /// Packages that count as the home shell
+    pub home_packages: Vec<String>,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            home_threshold_secs: 20,
+            max_relaunch_attempts: 3,
+            home_packages: vec![
+                "com.oculus.vrshell".to_string(),
+                "com.oculus.shellenv".to_string(),
+                "com.oculus.systemux".to_string(),
+            ],
+        }
+    }
+}
+
+/// Sensor firmware image shipped with the app, relative to its resource directory
+pub const BUNDLED_SENSOR_FIRMWARE: &str = "resources/firmware/sensor.bin";
+
+/// Known-good sensor firmware image used by `update_sensor_firmware`
+/// The version it installs is read from the image itself.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SensorFirmwareConfig {
+    /// Overrides the bundled image
+    pub path: Option<PathBuf>,
+}
+
+impl SensorFirmwareConfig {
+    /// The configured image, or the bundled one if this build ships it
+    pub fn resolve_path(&self, resource_dir: Option<&Path>) -> Option<PathBuf> {
+        self.path.clone().or_else(|| {
+            resource_dir
+                .map(|dir| dir.join(BUNDLED_SENSOR_FIRMWARE))
+                .filter(|path| path.is_file())
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AlakazamConfig {
     pub base_url: String,
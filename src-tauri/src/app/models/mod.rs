@@ -1,6 +1,8 @@
 pub mod apk;
 pub mod config;
+pub mod server;
 pub mod update;
 
 pub use apk::*;
 pub use config::*;
+pub use server::*;
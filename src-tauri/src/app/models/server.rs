@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// Snapshot of the device server's counters
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ServerStats {
+    /// Devices with an active session
+    pub connected_devices: usize,
+    pub max_connections: usize,
+    /// Seconds since the TCP server started listening, `None` while it is stopped
+    pub uptime_secs: Option<u64>,
+    /// Commands sent to devices since the server started
+    pub commands_executed: u64,
+}
@@ -1,6 +1,50 @@
 use serde::{Deserialize, Serialize};
 
+/// Release line the updater follows
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+impl UpdateChannel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => "stable",
+            UpdateChannel::Beta => "beta",
+        }
+    }
+
+    /// Updater manifest published for this channel
+    pub fn manifest_url(&self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => {
+                "https://github.com/B3n00n/Arceus/releases/latest/download/update-manifest.json"
+            }
+            // Rolling pre-release, `latest` never points at it
+            UpdateChannel::Beta => {
+                "https://github.com/B3n00n/Arceus/releases/download/beta/update-manifest.json"
+            }
+        }
+    }
+}
+
+impl std::str::FromStr for UpdateChannel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "stable" => Ok(UpdateChannel::Stable),
+            "beta" => Ok(UpdateChannel::Beta),
+            other => Err(format!("Unknown update channel '{}'", other)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct UpdateInfo {
     pub version: String,
     pub current_version: String,
@@ -9,6 +53,9 @@ pub struct UpdateInfo {
     pub date: Option<String>,
     pub pub_date: Option<String>,
     pub is_available: bool,
+    pub channel: UpdateChannel,
+    /// The offered version is older than the installed one, e.g. stable after a beta
+    pub is_downgrade: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
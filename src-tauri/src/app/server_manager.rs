@@ -1,7 +1,9 @@
-use crate::application::services::{BatteryMonitor, HttpServerService};
+use crate::application::services::{AppWatchdog, BatteryMonitor};
+use crate::app::models::ServerStats;
 use crate::app::{AppConfig, AppState, EventBus};
-use crate::infrastructure::network::TcpServer;
+use crate::infrastructure::network::{FileServer, TcpServer};
 use parking_lot::RwLock;
+use std::path::Path;
 use std::sync::Arc;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -16,6 +18,7 @@ pub struct ServerManager {
     config: AppConfig,
     event_bus: Arc<EventBus>,
     battery_monitor: Arc<BatteryMonitor>,
+    app_watchdog: Arc<AppWatchdog>,
 }
 
 impl ServerManager {
@@ -24,6 +27,7 @@ impl ServerManager {
         config: AppConfig,
         event_bus: Arc<EventBus>,
         battery_monitor: Arc<BatteryMonitor>,
+        app_watchdog: Arc<AppWatchdog>,
     ) -> Self {
         Self {
             state: RwLock::new(ServerState::NotStarted),
@@ -31,6 +35,7 @@ impl ServerManager {
             config,
             event_bus,
             battery_monitor,
+            app_watchdog,
         }
     }
 
@@ -55,21 +60,23 @@ impl ServerManager {
         let apk_port = self.config.server.http_port;
         let apk_dir = self.config.apk_directory.clone();
         let event_bus = self.event_bus.clone();
-        let app_state_clone = app_state.clone();
 
-        tauri::async_runtime::spawn(async move {
-            match HttpServerService::start_server(apk_port, apk_dir, "APK Server").await {
-                Ok(child) => {
+        let http_handle = tauri::async_runtime::spawn(async move {
+            match FileServer::bind(apk_port, apk_dir).await {
+                Ok(server) => {
                     let url = format!("http://127.0.0.1:{}", apk_port);
                     event_bus.http_server_started(apk_port, url);
 
-                    app_state_clone.set_http_server(child);
+                    if let Err(e) = server.serve().await {
+                        tracing::error!("APK HTTP server error: {}", e);
+                    }
                 }
                 Err(e) => {
                     tracing::error!("Failed to start APK HTTP server: {}", e);
                 }
             }
         });
+        app_state.set_http_server_handle(http_handle);
 
         let battery_monitor = self.battery_monitor.clone();
         let app_state_for_monitor = app_state.clone();
@@ -80,7 +87,26 @@ impl ServerManager {
 
         app_state_for_monitor.set_battery_monitor(handle);
 
+        if self.app_watchdog.is_enabled() {
+            let app_watchdog = self.app_watchdog.clone();
+            let handle = tauri::async_runtime::spawn(async move {
+                app_watchdog.start().await;
+            });
+            app_state.set_app_watchdog(handle);
+        }
+
+        let sweeper_handle = self.tcp_server.spawn_heartbeat_sweeper();
+        app_state.set_heartbeat_sweeper(sweeper_handle);
+
         *state = ServerState::Running;
         tracing::info!("All background servers started");
     }
+
+    pub fn stats(&self) -> ServerStats {
+        self.tcp_server.stats()
+    }
+
+    pub fn set_packet_trace(&self, enabled: bool, capture_path: Option<&Path>) -> std::io::Result<()> {
+        self.tcp_server.set_packet_trace(enabled, capture_path)
+    }
 }
@@ -5,7 +5,8 @@ pub fn setup_signal_handlers(app_state: Arc<AppState>) {
     tauri::async_runtime::spawn(async move {
         wait_for_shutdown_signal().await;
         tracing::info!("Shutdown signal received, initiating graceful shutdown");
-        app_state.shutdown();
+        // Shutdown blocks while devices drain, so keep it off the async workers
+        let _ = tauri::async_runtime::spawn_blocking(move || app_state.shutdown()).await;
     });
 }
 
@@ -2,6 +2,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::domain::commands::BatchResult;
+use crate::domain::models::CommandRecord;
 
 /// Command execution result DTO for frontend
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -33,6 +34,28 @@ impl CommandResultDto {
     }
 }
 
+impl From<&CommandRecord> for CommandResultDto {
+    fn from(record: &CommandRecord) -> Self {
+        Self {
+            timestamp: record.timestamp(),
+            command_type: record.command_type().to_string(),
+            success: record.success(),
+            message: record.message().to_string(),
+        }
+    }
+}
+
+impl From<&CommandResultDto> for CommandRecord {
+    fn from(result: &CommandResultDto) -> Self {
+        CommandRecord::new(
+            result.timestamp,
+            result.command_type.clone(),
+            result.success,
+            result.message.clone(),
+        )
+    }
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BatchResultDto {
@@ -44,6 +67,26 @@ pub struct BatchResultDto {
     pub failed: Vec<FailedDeviceDto>,
 }
 
+/// What a remote APK install would do, without contacting any device
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApkDryRunDto {
+    pub result: CommandResultDto,
+    pub url: String,
+    pub size_bytes: Option<u64>,
+    /// Hex-encoded SHA-256 of the APK as served; pass it back to verify the real install
+    pub sha256: Option<String>,
+    pub device_count: usize,
+}
+
+/// Result of `install_remote_apk`: a dry-run report or the install batch result
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum RemoteApkInstallDto {
+    DryRun(ApkDryRunDto),
+    Installed(BatchResultDto),
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FailedDeviceDto {
@@ -53,6 +96,56 @@ pub struct FailedDeviceDto {
     pub is_retriable: bool,
 }
 
+/// Where a device stands in a batch APK install
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ApkInstallStatus {
+    Queued,
+    Installing,
+    Installed,
+    Failed,
+    /// Not attempted, e.g. the device is unknown or offline
+    Skipped,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApkBatchDeviceResultDto {
+    /// Unset for a serial with no connected device
+    pub device_id: Option<String>,
+    /// Unset for a device ID that isn't connected
+    pub serial: Option<String>,
+    pub status: ApkInstallStatus,
+    /// Failure or skip reason
+    pub message: Option<String>,
+}
+
+/// Outcome of installing one APK on a group of devices
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApkBatchInstallDto {
+    pub batch_id: String,
+    pub total_count: usize,
+    pub installed_count: usize,
+    pub failed_count: usize,
+    pub skipped_count: usize,
+    pub devices: Vec<ApkBatchDeviceResultDto>,
+}
+
+impl ApkBatchInstallDto {
+    pub fn new(batch_id: String, devices: Vec<ApkBatchDeviceResultDto>) -> Self {
+        let count = |status| devices.iter().filter(|d| d.status == status).count();
+        Self {
+            batch_id,
+            total_count: devices.len(),
+            installed_count: count(ApkInstallStatus::Installed),
+            failed_count: count(ApkInstallStatus::Failed),
+            skipped_count: count(ApkInstallStatus::Skipped),
+            devices,
+        }
+    }
+}
+
 impl<T> From<BatchResult<T>> for BatchResultDto {
     fn from(result: BatchResult<T>) -> Self {
         BatchResultDto {
@@ -4,8 +4,8 @@ use std::collections::VecDeque;
 use std::sync::Arc;
 use uuid::Uuid;
 
-use super::{BatteryInfoDto, CommandResultDto, VolumeInfoDto};
-use crate::domain::models::Device;
+use super::{BatteryInfoDto, CommandResultDto, StorageInfoDto, VolumeInfoDto, WifiStatusDto};
+use crate::domain::models::{CommandRecord, Device, LinkQuality};
 
 /// Device information DTO for frontend
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -17,7 +17,33 @@ pub struct DeviceInfoDto {
     pub version: String,
     pub connected_at: DateTime<Utc>,
     pub custom_name: Option<String>,
+    pub tags: Vec<String>,
+    pub note: Option<String>,
     pub running_app: Option<String>,
+    /// Package the device should stay in, if staff set one
+    pub expected_package: Option<String>,
+}
+
+/// Connection quality of a device, for spotting headsets on bad WiFi
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkQualityDto {
+    /// Round-trip time of the last answered ping
+    pub last_rtt_ms: Option<u32>,
+    /// Average round-trip time over the last few pings
+    pub avg_rtt_ms: Option<u32>,
+    /// Smoothed variation between heartbeat intervals
+    pub heartbeat_jitter_ms: Option<u32>,
+}
+
+impl From<&LinkQuality> for LinkQualityDto {
+    fn from(quality: &LinkQuality) -> Self {
+        Self {
+            last_rtt_ms: quality.last_rtt_ms(),
+            avg_rtt_ms: quality.avg_rtt_ms(),
+            heartbeat_jitter_ms: quality.heartbeat_jitter_ms(),
+        }
+    }
 }
 
 /// Complete device state DTO for frontend
@@ -27,6 +53,10 @@ pub struct DeviceStateDto {
     pub info: DeviceInfoDto,
     pub battery: Option<BatteryInfoDto>,
     pub volume: Option<VolumeInfoDto>,
+    pub brightness: Option<u8>,
+    pub storage: Option<StorageInfoDto>,
+    pub wifi_status: Option<WifiStatusDto>,
+    pub link_quality: LinkQualityDto,
     pub command_history: VecDeque<CommandResultDto>,
 }
 
@@ -39,7 +69,10 @@ impl From<&Arc<Device>> for DeviceStateDto {
             version: device.version().to_string(),
             connected_at: device.connected_at(),
             custom_name: device.custom_name().map(|s| s.to_string()),
+            tags: device.tags().to_vec(),
+            note: device.note().map(|s| s.to_string()),
             running_app: device.running_app().map(|s| s.to_string()),
+            expected_package: device.expected_package().map(|s| s.to_string()),
         };
 
         let battery = device.battery().map(|b| BatteryInfoDto {
@@ -55,15 +88,29 @@ impl From<&Arc<Device>> for DeviceStateDto {
             )
         });
 
+        let storage = device.storage().map(StorageInfoDto::from);
+        let wifi_status = device.wifi_status().map(WifiStatusDto::from);
+
         DeviceStateDto {
             info,
             battery,
             volume,
+            brightness: device.brightness(),
+            storage,
+            wifi_status,
+            link_quality: LinkQualityDto::from(device.link_quality()),
             command_history: VecDeque::new(),
         }
     }
 }
 
+impl DeviceStateDto {
+    pub fn with_command_history(mut self, command_history: &[CommandRecord]) -> Self {
+        self.command_history = command_history.iter().map(CommandResultDto::from).collect();
+        self
+    }
+}
+
 impl From<Arc<Device>> for DeviceStateDto {
     fn from(device: Arc<Device>) -> Self {
         Self::from(&device)
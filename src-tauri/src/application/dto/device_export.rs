@@ -0,0 +1,124 @@
+use chrono::SecondsFormat;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::domain::models::Device;
+
+/// Output format for a device roster export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+/// One device in a roster export
+/// Disconnected devices are listed with their last known state.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceExportRow {
+    pub serial: String,
+    pub model: String,
+    pub custom_name: Option<String>,
+    pub tags: Vec<String>,
+    pub connected: bool,
+    pub ip_address: Option<String>,
+    pub battery_level: Option<u8>,
+    pub running_app: Option<String>,
+    pub connected_at: String,
+    pub last_seen: String,
+}
+
+impl DeviceExportRow {
+    const CSV_HEADER: [&'static str; 10] = [
+        "serial",
+        "model",
+        "custom_name",
+        "tags",
+        "connected",
+        "ip_address",
+        "battery_level",
+        "running_app",
+        "connected_at",
+        "last_seen",
+    ];
+
+    pub fn new(device: &Arc<Device>, connected: bool) -> Self {
+        Self {
+            serial: device.serial().as_str().to_string(),
+            model: device.model().to_string(),
+            custom_name: device.custom_name().map(|s| s.to_string()),
+            tags: device.tags().to_vec(),
+            connected,
+            ip_address: device.ip_address().map(|ip| ip.to_string()),
+            battery_level: device.battery().map(|b| b.level()),
+            running_app: device.running_app().map(|s| s.to_string()),
+            connected_at: device
+                .connected_at()
+                .to_rfc3339_opts(SecondsFormat::Secs, true),
+            last_seen: device.last_seen().to_rfc3339_opts(SecondsFormat::Secs, true),
+        }
+    }
+
+    /// Tags are `;`-separated in CSV, so the roster stays one row per device
+    fn csv_fields(&self) -> [String; 10] {
+        [
+            self.serial.clone(),
+            self.model.clone(),
+            self.custom_name.clone().unwrap_or_default(),
+            self.tags.join(";"),
+            self.connected.to_string(),
+            self.ip_address.clone().unwrap_or_default(),
+            self.battery_level.map(|l| l.to_string()).unwrap_or_default(),
+            self.running_app.clone().unwrap_or_default(),
+            self.connected_at.clone(),
+            self.last_seen.clone(),
+        ]
+    }
+}
+
+/// Render a device roster in the requested format
+pub fn render_device_export(
+    rows: &[DeviceExportRow],
+    format: ExportFormat,
+) -> Result<String, serde_json::Error> {
+    match format {
+        ExportFormat::Json => serde_json::to_string_pretty(rows),
+        ExportFormat::Csv => Ok(render_csv(rows)),
+    }
+}
+
+/// RFC 4180 CSV: CRLF line endings, fields quoted when they contain
+/// commas, quotes or line breaks, embedded quotes doubled
+fn render_csv(rows: &[DeviceExportRow]) -> String {
+    let mut output = String::new();
+
+    let header: Vec<String> = DeviceExportRow::CSV_HEADER
+        .iter()
+        .map(|h| h.to_string())
+        .collect();
+    push_csv_record(&mut output, &header);
+
+    for row in rows {
+        push_csv_record(&mut output, &row.csv_fields());
+    }
+
+    output
+}
+
+fn push_csv_record(output: &mut String, fields: &[String]) {
+    for (index, field) in fields.iter().enumerate() {
+        if index > 0 {
+            output.push(',');
+        }
+
+        if field.contains([',', '"', '\r', '\n']) {
+            output.push('"');
+            output.push_str(&field.replace('"', "\"\""));
+            output.push('"');
+        } else {
+            output.push_str(field);
+        }
+    }
+    output.push_str("\r\n");
+}
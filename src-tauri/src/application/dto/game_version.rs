@@ -45,6 +45,12 @@ pub struct GameFile {
     pub path: String,
     /// Signed download URL
     pub download_url: String,
+    /// Expected size in bytes, if the server reports it
+    #[serde(default)]
+    pub size: Option<u64>,
+    /// Expected base64-encoded MD5 digest, if the server reports it
+    #[serde(default)]
+    pub md5_hash: Option<String>,
 }
 
 /// Local metadata about installed game versions
@@ -56,6 +62,10 @@ pub struct LocalGameMetadata {
     pub installed_version: String,
     pub installed_version_id: i32,
     pub installed_at: DateTime<Utc>,
+    /// Relative paths of the files this version installed, used to delete it
+    /// Empty for games installed before the manifest was recorded.
+    #[serde(default)]
+    pub files: Vec<String>,
 }
 
 impl LocalGameMetadata {
@@ -66,8 +76,14 @@ impl LocalGameMetadata {
             installed_version: version,
             installed_version_id: version_id,
             installed_at: Utc::now(),
+            files: Vec::new(),
         }
     }
+
+    pub fn with_files(mut self, files: Vec<String>) -> Self {
+        self.files = files;
+        self
+    }
 }
 
 /// Cached game entry - minimal structure for offline access
@@ -87,6 +103,15 @@ pub struct CachedGameEntry {
     pub installed_at: Option<DateTime<Utc>>,
 }
 
+/// Locally tracked disk usage and play history of a game
+/// Kept apart from `CachedGameEntry` so server syncs never overwrite it.
+#[derive(Debug, Clone, Default)]
+pub struct GameStats {
+    /// Sum of the game's on-disk files, `None` until first measured
+    pub installed_size_bytes: Option<u64>,
+    pub last_played: Option<DateTime<Utc>>,
+}
+
 impl CachedGameEntry {
     /// Create entry from Alakazam assignment and optional local metadata
     pub fn from_assignment_and_metadata(
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+use super::BatchResultDto;
+use crate::domain::models::InstalledApp;
+
+/// Installed application DTO for frontend
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledAppDto {
+    pub package_name: String,
+    pub label: Option<String>,
+    pub version_name: Option<String>,
+    pub version_code: Option<u32>,
+    pub is_system: bool,
+}
+
+impl From<&InstalledApp> for InstalledAppDto {
+    fn from(app: &InstalledApp) -> Self {
+        Self {
+            package_name: app.package_name().to_string(),
+            label: app.label().map(|s| s.to_string()),
+            version_name: app.version_name().map(|s| s.to_string()),
+            version_code: app.version_code(),
+            is_system: app.is_system(),
+        }
+    }
+}
+
+/// Filtered installed apps for a single device
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceInstalledAppsDto {
+    pub device_id: String,
+    pub apps: Vec<InstalledAppDto>,
+}
+
+/// Result of a `get_installed_apps` batch
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledAppsResultDto {
+    pub batch: BatchResultDto,
+    pub devices: Vec<DeviceInstalledAppsDto>,
+}
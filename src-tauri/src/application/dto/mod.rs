@@ -3,14 +3,24 @@ mod battery;
 mod client_apk_metadata;
 mod command;
 mod device;
+mod device_export;
+mod installed_app;
 pub mod game_version;
 mod operation_progress;
+mod packet_trace;
+mod storage;
 mod volume;
+mod wifi;
 
 pub use battery::*;
 pub use client_apk_metadata::*;
 pub use command::*;
 pub use device::*;
+pub use device_export::*;
+pub use installed_app::*;
 pub use game_version::*;
 pub use operation_progress::*;
+pub use packet_trace::*;
+pub use storage::*;
 pub use volume::*;
+pub use wifi::*;
@@ -0,0 +1,29 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::domain::services::PacketDirection;
+use crate::infrastructure::protocol::RawPacket;
+
+/// A single traced packet, emitted on the packet trace event channel
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PacketTraceDto {
+    pub device_id: Uuid,
+    pub direction: PacketDirection,
+    pub opcode: u8,
+    pub payload_hex: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl PacketTraceDto {
+    pub fn new(device_id: Uuid, direction: PacketDirection, packet: &RawPacket) -> Self {
+        Self {
+            device_id,
+            direction,
+            opcode: packet.opcode,
+            payload_hex: packet.payload.iter().map(|b| format!("{:02x}", b)).collect(),
+            timestamp: Utc::now(),
+        }
+    }
+}
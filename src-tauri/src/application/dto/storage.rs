@@ -0,0 +1,23 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::models::StorageInfo;
+
+/// Storage information DTO for frontend
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageInfoDto {
+    pub total_bytes: u64,
+    pub free_bytes: u64,
+    pub last_updated: DateTime<Utc>,
+}
+
+impl From<&StorageInfo> for StorageInfoDto {
+    fn from(storage: &StorageInfo) -> Self {
+        Self {
+            total_bytes: storage.total_bytes(),
+            free_bytes: storage.free_bytes(),
+            last_updated: storage.last_updated(),
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+use super::BatchResultDto;
+use crate::domain::models::WifiStatus;
+
+/// WiFi status DTO for frontend
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "state", rename_all = "camelCase")]
+pub enum WifiStatusDto {
+    #[serde(rename_all = "camelCase")]
+    Connected {
+        ssid: String,
+        rssi: i32,
+        link_speed_mbps: u32,
+    },
+    Disconnected,
+}
+
+impl From<&WifiStatus> for WifiStatusDto {
+    fn from(status: &WifiStatus) -> Self {
+        match status {
+            WifiStatus::Connected {
+                ssid,
+                rssi,
+                link_speed_mbps,
+            } => WifiStatusDto::Connected {
+                ssid: ssid.clone(),
+                rssi: *rssi,
+                link_speed_mbps: *link_speed_mbps,
+            },
+            WifiStatus::Disconnected => WifiStatusDto::Disconnected,
+        }
+    }
+}
+
+/// WiFi status for a single device
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceWifiStatusDto {
+    pub device_id: String,
+    pub status: WifiStatusDto,
+}
+
+/// Result of a `get_wifi_status` batch
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WifiStatusResultDto {
+    pub batch: BatchResultDto,
+    pub devices: Vec<DeviceWifiStatusDto>,
+}
@@ -1,6 +1,13 @@
-use crate::domain::repositories::{ApkInfo, ApkRepository, RepositoryError};
+use crate::app::EventBus;
+use crate::domain::repositories::{
+    find_duplicates, ApkInfo, ApkListQuery, ApkPage, ApkRepository, ApkVerification, DuplicateApks,
+    RepositoryError,
+};
+use parking_lot::Mutex;
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
 
 /// Result type for APK service operations
 pub type Result<T> = std::result::Result<T, ApkServiceError>;
@@ -14,20 +21,43 @@ pub enum ApkServiceError {
     #[error("Invalid file path: {0}")]
     InvalidPath(String),
 
+    #[error("{0} is already being added")]
+    AlreadyAdding(String),
+
+    #[error(
+        "APK is too large: {:.1} MB, the limit is {:.1} MB",
+        *.size_bytes as f64 / BYTES_PER_MB,
+        *.max_bytes as f64 / BYTES_PER_MB
+    )]
+    TooLarge { size_bytes: u64, max_bytes: u64 },
+
     #[error("Operation failed: {0}")]
     OperationFailed(String),
 }
 
+const BYTES_PER_MB: f64 = 1024.0 * 1024.0;
+
 /// Application service for APK management
 /// This service orchestrates APK related use cases.
 pub struct ApkApplicationService {
     apk_repo: Arc<dyn ApkRepository>,
+    event_bus: Arc<EventBus>,
+    /// Largest APK accepted by `add_apk` (0 = no limit)
+    max_apk_bytes: u64,
+    /// Cancellation tokens of APKs being copied in, keyed by source filename
+    /// Shared so an add only clears its own entry, never that of a newer add of the same file.
+    active_adds: Mutex<HashMap<String, Arc<CancellationToken>>>,
 }
 
 impl ApkApplicationService {
     /// Create a new ApkApplicationService
-    pub fn new(apk_repo: Arc<dyn ApkRepository>) -> Self {
-        Self { apk_repo }
+    pub fn new(apk_repo: Arc<dyn ApkRepository>, event_bus: Arc<EventBus>, max_apk_bytes: u64) -> Self {
+        Self {
+            apk_repo,
+            event_bus,
+            max_apk_bytes,
+            active_adds: Mutex::new(HashMap::new()),
+        }
     }
 
     /// List all available APK files
@@ -35,9 +65,20 @@ impl ApkApplicationService {
         Ok(self.apk_repo.list_apks().await?)
     }
 
+    /// List one sorted page of APK files, with the total count
+    pub async fn list_apks_page(&self, query: ApkListQuery) -> Result<ApkPage> {
+        Ok(self.apk_repo.list_apks_page(query).await?)
+    }
+
+    /// Stored APKs with identical contents, grouped by SHA-256, for cleaning up copies
+    pub async fn find_apk_duplicates(&self) -> Result<Vec<DuplicateApks>> {
+        Ok(find_duplicates(self.apk_repo.list_apks().await?))
+    }
+
     /// Add a new APK file from a source path
-    /// Copies the file into the APK repository.
-    pub async fn add_apk(&self, source_path: PathBuf) -> Result<String> {
+    /// Copies the file into the APK repository, emitting `ApkAddProgress` events
+    /// keyed by the source filename. Identical APKs are rejected when `dedupe` is set.
+    pub async fn add_apk(&self, source_path: PathBuf, dedupe: bool) -> Result<String> {
         if !source_path.exists() {
             return Err(ApkServiceError::InvalidPath(format!(
                 "Source file does not exist: {}",
@@ -59,7 +100,63 @@ impl ApkApplicationService {
             )));
         }
 
-        let filename = self.apk_repo.add_apk(source_path.clone()).await?;
+        // Checked before anything is read, so an oversized file isn't even hashed
+        if self.max_apk_bytes > 0 {
+            let size_bytes = tokio::fs::metadata(&source_path)
+                .await
+                .map_err(|e| ApkServiceError::InvalidPath(format!("{}: {}", source_path.display(), e)))?
+                .len();
+            if size_bytes > self.max_apk_bytes {
+                return Err(ApkServiceError::TooLarge {
+                    size_bytes,
+                    max_bytes: self.max_apk_bytes,
+                });
+            }
+        }
+
+        let source_filename = source_path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let cancel_token = Arc::new(CancellationToken::new());
+        {
+            let mut active = self.active_adds.lock();
+            if active.contains_key(&source_filename) {
+                return Err(ApkServiceError::AlreadyAdding(source_filename));
+            }
+            active.insert(source_filename.clone(), cancel_token.clone());
+        }
+
+        let event_bus = self.event_bus.clone();
+        let progress_filename = source_filename.clone();
+        let result = self
+            .apk_repo
+            .add_apk(
+                source_path.clone(),
+                dedupe,
+                cancel_token.as_ref().clone(),
+                Box::new(move |progress| {
+                    event_bus.apk_add_progress(
+                        progress_filename.clone(),
+                        progress.bytes_copied,
+                        progress.total_bytes,
+                    );
+                }),
+            )
+            .await;
+
+        {
+            // A cancelled add may already have been replaced by a new one
+            let mut active = self.active_adds.lock();
+            if active
+                .get(&source_filename)
+                .is_some_and(|token| Arc::ptr_eq(token, &cancel_token))
+            {
+                active.remove(&source_filename);
+            }
+        }
+        let filename = result?;
 
         tracing::info!(
             filename = %filename,
@@ -70,6 +167,15 @@ impl ApkApplicationService {
         Ok(filename)
     }
 
+    /// Cancel an APK that is being copied in, identified by its source filename
+    /// The partially copied file is removed by the repository.
+    pub fn cancel_add_apk(&self, filename: &str) {
+        if let Some(token) = self.active_adds.lock().remove(filename) {
+            token.cancel();
+            tracing::info!(filename = %filename, "APK add cancelled");
+        }
+    }
+
     pub async fn remove_apk(&self, filename: &str) -> Result<()> {
         self.apk_repo.remove_apk(filename).await?;
 
@@ -81,6 +187,24 @@ impl ApkApplicationService {
         Ok(())
     }
 
+    /// Re-hash a stored APK to detect corruption on disk
+    pub async fn verify_apk(&self, filename: &str) -> Result<ApkVerification> {
+        let verification = self.apk_repo.verify_apk(filename).await?;
+
+        if verification.is_valid() {
+            tracing::info!(filename = %filename, "APK checksum verified");
+        } else {
+            tracing::warn!(
+                filename = %filename,
+                expected = %verification.expected_sha256,
+                actual = %verification.actual_sha256,
+                "APK checksum mismatch"
+            );
+        }
+
+        Ok(verification)
+    }
+
     pub fn open_apk_folder(&self) -> Result<()> {
         let path = self.apk_repo.get_storage_directory();
 
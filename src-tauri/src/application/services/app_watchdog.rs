@@ -0,0 +1,429 @@
+/// App Watchdog
+/// Single place that relaunches a device's expected app once it has been out
+/// of it for too long. Two rules feed it:
+/// - `server.expected_app_relaunch_secs`: out of the app, in anything else
+/// - `watchdog.home_threshold_secs`: sitting in the home shell, which is what
+///   a crashed or quit game looks like on a Quest (only with `watchdog.enabled`)
+///
+/// Only devices pinned to an app with `set_expected_app` are watched. The wait
+/// restarts whenever the device switches to another app, so a relaunch is only
+/// ever due for the app it is in now. Each device gets
+/// `watchdog.max_relaunch_attempts` relaunches; the count resets once the app
+/// has stayed up for `STABLE_RUN`, so a game that crashes on start isn't
+/// relaunched forever. Every relaunch is recorded in the device's command
+/// history as `auto_relaunch`.
+
+use crate::app::models::WatchdogConfig;
+use crate::app::EventBus;
+use crate::application::dto::CommandResultDto;
+use crate::domain::commands::LaunchAppCommand;
+use crate::domain::models::{Device, DeviceId, PackageName};
+use crate::domain::repositories::DeviceRepository;
+use crate::domain::services::{CommandExecutor, SessionManager};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Longest gap between two looks at the connected devices
+const MAX_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How long the expected app must stay up before a device's relaunch attempts are reset
+const STABLE_RUN: Duration = Duration::from_secs(300);
+
+/// What the watchdog should do about a device after looking at it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Nothing,
+    Relaunch { attempt: u32 },
+    GiveUp,
+}
+
+/// How long a device may stay out of its expected app, by where it went
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RelaunchPolicy {
+    /// In any other app, `None` to only alert
+    grace: Option<Duration>,
+    /// In the home shell, `None` while the watchdog is off
+    home_threshold: Option<Duration>,
+    max_attempts: u32,
+}
+
+impl RelaunchPolicy {
+    fn new(config: &WatchdogConfig, grace: Option<Duration>) -> Self {
+        Self {
+            grace,
+            home_threshold: config
+                .enabled
+                .then(|| Duration::from_secs(config.home_threshold_secs)),
+            max_attempts: config.max_relaunch_attempts,
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        self.grace.is_some() || self.home_threshold.is_some()
+    }
+
+    /// Time out of the expected app after which it is relaunched, `None` to leave it be
+    fn threshold(&self, at_home: bool) -> Option<Duration> {
+        let home_threshold = self.home_threshold.filter(|_| at_home);
+        match (self.grace, home_threshold) {
+            (Some(grace), Some(home)) => Some(grace.min(home)),
+            (grace, home) => grace.or(home),
+        }
+    }
+
+    /// How often devices need looking at to honor the shortest threshold
+    fn check_interval(&self) -> Duration {
+        [self.grace, self.home_threshold]
+            .into_iter()
+            .flatten()
+            .fold(MAX_CHECK_INTERVAL, Duration::min)
+            .max(Duration::from_secs(1))
+    }
+}
+
+/// What the watchdog has seen of one device
+#[derive(Debug, Default)]
+struct DeviceWatch {
+    /// App the device was last seen in while out of its expected one, and since when
+    away: Option<(String, Instant)>,
+    /// When the device was first seen back in its expected app
+    in_app_since: Option<Instant>,
+    attempts: u32,
+    gave_up: bool,
+}
+
+impl DeviceWatch {
+    fn observe(
+        &mut self,
+        now: Instant,
+        running: Option<&str>,
+        in_app: bool,
+        at_home: bool,
+        policy: &RelaunchPolicy,
+    ) -> Action {
+        if in_app {
+            self.away = None;
+            let in_app_since = *self.in_app_since.get_or_insert(now);
+            if now.duration_since(in_app_since) >= STABLE_RUN {
+                self.attempts = 0;
+                self.gave_up = false;
+            }
+            return Action::Nothing;
+        }
+
+        self.in_app_since = None;
+        let Some(running) = running else {
+            self.away = None;
+            return Action::Nothing;
+        };
+
+        // Switching to another app drops the relaunch that was pending for the last one
+        let away_since = match &self.away {
+            Some((package, since)) if package == running => *since,
+            _ => {
+                self.away = Some((running.to_string(), now));
+                now
+            }
+        };
+
+        let Some(threshold) = policy.threshold(at_home) else {
+            return Action::Nothing;
+        };
+        if now.duration_since(away_since) < threshold {
+            return Action::Nothing;
+        }
+
+        if self.attempts >= policy.max_attempts {
+            if self.gave_up {
+                return Action::Nothing;
+            }
+            self.gave_up = true;
+            return Action::GiveUp;
+        }
+
+        // Give the relaunched app a full threshold to come up before trying again
+        self.attempts += 1;
+        self.away = Some((running.to_string(), now));
+        Action::Relaunch { attempt: self.attempts }
+    }
+}
+
+/// Background service that relaunches expected apps on devices stuck in the home shell
+pub struct AppWatchdog {
+    device_repo: Arc<dyn DeviceRepository>,
+    session_manager: Arc<dyn SessionManager>,
+    command_executor: Arc<CommandExecutor>,
+    event_bus: Arc<EventBus>,
+    config: WatchdogConfig,
+    policy: RelaunchPolicy,
+    watches: Mutex<HashMap<DeviceId, DeviceWatch>>,
+}
+
+impl AppWatchdog {
+    pub fn new(
+        device_repo: Arc<dyn DeviceRepository>,
+        session_manager: Arc<dyn SessionManager>,
+        command_executor: Arc<CommandExecutor>,
+        event_bus: Arc<EventBus>,
+        config: WatchdogConfig,
+        relaunch_grace: Option<Duration>,
+    ) -> Self {
+        Self {
+            device_repo,
+            session_manager,
+            command_executor,
+            event_bus,
+            policy: RelaunchPolicy::new(&config, relaunch_grace),
+            config,
+            watches: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether either relaunch rule is configured
+    pub fn is_enabled(&self) -> bool {
+        self.policy.is_active()
+    }
+
+    pub async fn start(self: Arc<Self>) {
+        let check_interval = self.policy.check_interval();
+        tracing::info!(
+            grace = ?self.policy.grace,
+            home_threshold = ?self.policy.home_threshold,
+            max_attempts = self.policy.max_attempts,
+            "App watchdog started"
+        );
+
+        let mut interval_timer = tokio::time::interval(check_interval);
+
+        loop {
+            interval_timer.tick().await;
+
+            if let Err(e) = self.check_devices().await {
+                tracing::error!(error = %e, "App watchdog check failed");
+            }
+        }
+    }
+
+    async fn check_devices(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let devices: Vec<_> = self
+            .device_repo
+            .find_all()
+            .await?
+            .into_iter()
+            .filter(|d| d.expected_package().is_some() && self.session_manager.has_session(&d.id()))
+            .collect();
+
+        let now = Instant::now();
+        let mut relaunches = Vec::new();
+        {
+            let mut watches = self.watches.lock();
+            // Forget devices that disconnected or were unpinned
+            watches.retain(|id, _| devices.iter().any(|d| d.id() == *id));
+
+            for device in &devices {
+                let in_app = device.running_package() == device.expected_package();
+                let at_home = device
+                    .running_package()
+                    .is_some_and(|p| self.config.home_packages.iter().any(|home| home == p));
+
+                let watch = watches.entry(device.id()).or_default();
+                match watch.observe(now, device.running_package(), in_app, at_home, &self.policy) {
+                    Action::Nothing => {}
+                    Action::Relaunch { attempt } => relaunches.push((device.clone(), attempt)),
+                    Action::GiveUp => {
+                        tracing::warn!(
+                            device_id = %device.id(),
+                            serial = %device.serial(),
+                            attempts = self.policy.max_attempts,
+                            "Device keeps leaving its expected app, no longer relaunching it"
+                        );
+                        self.event_bus.command_executed(
+                            device.id().as_uuid(),
+                            CommandResultDto::failure(
+                                "auto_relaunch",
+                                format!(
+                                    "Gave up relaunching {} after {} attempts",
+                                    device.expected_package().unwrap_or_default(),
+                                    self.policy.max_attempts
+                                ),
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+
+        for (device, attempt) in relaunches {
+            self.relaunch(&device, attempt).await;
+        }
+
+        Ok(())
+    }
+
+    async fn relaunch(&self, device: &Device, attempt: u32) {
+        let Some(expected) = device.expected_package() else {
+            return;
+        };
+        let max_attempts = self.policy.max_attempts;
+
+        let result = match PackageName::new(expected.to_string()) {
+            Ok(package_name) => self
+                .command_executor
+                .execute_single(device.id(), Arc::new(LaunchAppCommand::new(package_name)))
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+
+        let entry = match result {
+            Ok(_) => {
+                tracing::info!(
+                    device_id = %device.id(),
+                    package_name = %expected,
+                    attempt,
+                    "Relaunched expected app from the home shell"
+                );
+                CommandResultDto::success(
+                    "auto_relaunch",
+                    format!("Relaunched {} (attempt {}/{})", expected, attempt, max_attempts),
+                )
+            }
+            Err(e) => {
+                tracing::warn!(
+                    device_id = %device.id(),
+                    package_name = %expected,
+                    attempt,
+                    error = %e,
+                    "Failed to relaunch expected app"
+                );
+                CommandResultDto::failure(
+                    "auto_relaunch",
+                    format!("Failed to relaunch {} (attempt {}/{}): {}", expected, attempt, max_attempts, e),
+                )
+            }
+        };
+        self.event_bus.command_executed(device.id().as_uuid(), entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOME: &str = "com.oculus.vrshell";
+    const OTHER: &str = "com.example.browser";
+
+    fn policy(grace: Option<u64>, home_threshold: Option<u64>) -> RelaunchPolicy {
+        RelaunchPolicy {
+            grace: grace.map(Duration::from_secs),
+            home_threshold: home_threshold.map(Duration::from_secs),
+            max_attempts: 2,
+        }
+    }
+
+    /// Observe a device in its expected app
+    fn in_app(watch: &mut DeviceWatch, now: Instant, policy: &RelaunchPolicy) -> Action {
+        watch.observe(now, Some("com.example.game"), true, false, policy)
+    }
+
+    fn at_home(watch: &mut DeviceWatch, now: Instant, policy: &RelaunchPolicy) -> Action {
+        watch.observe(now, Some(HOME), false, true, policy)
+    }
+
+    fn elsewhere(watch: &mut DeviceWatch, package: &str, now: Instant, policy: &RelaunchPolicy) -> Action {
+        watch.observe(now, Some(package), false, false, policy)
+    }
+
+    #[test]
+    fn relaunches_after_threshold_then_gives_up() {
+        let policy = policy(None, Some(10));
+        let mut watch = DeviceWatch::default();
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        assert_eq!(at_home(&mut watch, at(0), &policy), Action::Nothing);
+        assert_eq!(at_home(&mut watch, at(9), &policy), Action::Nothing);
+        assert_eq!(at_home(&mut watch, at(10), &policy), Action::Relaunch { attempt: 1 });
+
+        // Comes up briefly, then crashes straight back home
+        assert_eq!(in_app(&mut watch, at(12), &policy), Action::Nothing);
+        assert_eq!(at_home(&mut watch, at(14), &policy), Action::Nothing);
+        assert_eq!(at_home(&mut watch, at(24), &policy), Action::Relaunch { attempt: 2 });
+        assert_eq!(at_home(&mut watch, at(34), &policy), Action::GiveUp);
+        assert_eq!(at_home(&mut watch, at(44), &policy), Action::Nothing);
+    }
+
+    #[test]
+    fn attempts_reset_once_the_app_stays_up() {
+        let policy = policy(None, Some(10));
+        let mut watch = DeviceWatch::default();
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        at_home(&mut watch, at(0), &policy);
+        assert_eq!(at_home(&mut watch, at(10), &policy), Action::Relaunch { attempt: 1 });
+
+        in_app(&mut watch, at(15), &policy);
+        in_app(&mut watch, at(15 + STABLE_RUN.as_secs()), &policy);
+        assert_eq!(watch.attempts, 0);
+
+        // Another app isn't the home shell, so without a grace period it isn't relaunched over
+        let later = 20 + STABLE_RUN.as_secs();
+        assert_eq!(elsewhere(&mut watch, OTHER, at(later), &policy), Action::Nothing);
+        assert_eq!(elsewhere(&mut watch, OTHER, at(later + 60), &policy), Action::Nothing);
+    }
+
+    #[test]
+    fn grace_period_relaunches_out_of_any_app() {
+        let policy = policy(Some(30), None);
+        let mut watch = DeviceWatch::default();
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        assert_eq!(elsewhere(&mut watch, OTHER, at(0), &policy), Action::Nothing);
+        assert_eq!(elsewhere(&mut watch, OTHER, at(29), &policy), Action::Nothing);
+        assert_eq!(elsewhere(&mut watch, OTHER, at(30), &policy), Action::Relaunch { attempt: 1 });
+
+        // The home shell counts as leaving too when only the grace period is set
+        let mut watch = DeviceWatch::default();
+        at_home(&mut watch, at(0), &policy);
+        assert_eq!(at_home(&mut watch, at(30), &policy), Action::Relaunch { attempt: 1 });
+    }
+
+    #[test]
+    fn switching_apps_drops_the_pending_relaunch() {
+        let policy = policy(Some(30), None);
+        let mut watch = DeviceWatch::default();
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        elsewhere(&mut watch, OTHER, at(0), &policy);
+        assert_eq!(elsewhere(&mut watch, "com.example.store", at(20), &policy), Action::Nothing);
+        // 30 s after the first switch, but only 10 s into the current app
+        assert_eq!(elsewhere(&mut watch, "com.example.store", at(30), &policy), Action::Nothing);
+        assert_eq!(
+            elsewhere(&mut watch, "com.example.store", at(50), &policy),
+            Action::Relaunch { attempt: 1 }
+        );
+
+        // Returning to the expected app cancels it outright
+        let mut watch = DeviceWatch::default();
+        elsewhere(&mut watch, OTHER, at(0), &policy);
+        in_app(&mut watch, at(10), &policy);
+        assert_eq!(elsewhere(&mut watch, OTHER, at(31), &policy), Action::Nothing);
+    }
+
+    #[test]
+    fn the_shorter_threshold_wins_in_the_home_shell() {
+        let policy = policy(Some(60), Some(10));
+        assert_eq!(policy.threshold(true), Some(Duration::from_secs(10)));
+        assert_eq!(policy.threshold(false), Some(Duration::from_secs(60)));
+        assert_eq!(policy.check_interval(), MAX_CHECK_INTERVAL);
+
+        let off = RelaunchPolicy::new(&WatchdogConfig::default(), None);
+        assert!(!off.is_active());
+        assert_eq!(off.threshold(true), None);
+    }
+}
@@ -2,11 +2,27 @@
 ///
 /// Orchestrates device operations using domain services and repositories.
 
-use crate::domain::commands::{BatchResult, Command, CommandResponse};
-use crate::domain::models::{Device, DeviceId, Serial};
-use crate::domain::repositories::{DeviceNameRepository, DeviceRepository, RepositoryError};
-use crate::domain::services::{CommandError, CommandExecutor};
+use crate::app::EventBus;
+use crate::application::dto::{
+    render_device_export, ApkBatchDeviceResultDto, ApkBatchInstallDto, ApkInstallStatus,
+    CommandResultDto, DeviceExportRow, ExportFormat,
+};
+use crate::domain::commands::{
+    BatchResult, Command, CommandResponse, ExecuteShellCommand, InstallApkCommand, LaunchAppCommand,
+    RequestScreenshotCommand, SetTimeCommand, SetVolumeCommand,
+};
+use crate::domain::models::{ApkInstallOutcome, Device, DeviceId, InstalledApp, PackageName, Serial, WifiStatus};
+use crate::domain::repositories::{normalize_tags, DeviceNameRepository, DeviceRepository, RepositoryError};
+use crate::domain::services::{
+    CommandError, CommandExecutor, ScreenshotAssembler, ScreenshotError, ShellPolicy,
+};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Semaphore;
 
 /// Result type for application service operations
 pub type Result<T> = std::result::Result<T, ApplicationError>;
@@ -22,8 +38,55 @@ pub enum ApplicationError {
     #[error("Device with serial {serial} not found")]
     DeviceNotFoundBySerial { serial: String },
 
+    #[error("Screenshot failed: {0}")]
+    Screenshot(#[from] ScreenshotError),
+
     #[error("Operation failed: {0}")]
     OperationFailed(String),
+
+    #[error(
+        "Not enough storage on {}: needs {:.1} MB but only {:.1} MB is free",
+        .device,
+        *.required_bytes as f64 / BYTES_PER_MB,
+        *.free_bytes as f64 / BYTES_PER_MB
+    )]
+    InsufficientStorage {
+        device: String,
+        required_bytes: u64,
+        free_bytes: u64,
+    },
+}
+
+const BYTES_PER_MB: f64 = 1024.0 * 1024.0;
+
+/// How long to wait for a device to finish streaming a screenshot
+const SCREENSHOT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How many devices download and install an APK at once in a batch install,
+/// so the HTTP server serving the APK isn't saturated
+const MAX_CONCURRENT_APK_INSTALLS: usize = 5;
+
+/// How long to wait for a device to download and install an APK
+const APK_INSTALL_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Longest gap between two staggered app launches
+pub const MAX_LAUNCH_STAGGER: Duration = Duration::from_secs(60);
+
+/// How long to wait for a remote APK server to accept a connection
+const REMOTE_APK_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a HEAD request for a remote APK's size may take
+const REMOTE_APK_HEAD_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How long a dry-run download of a remote APK may take, matching what devices get
+const REMOTE_APK_DOWNLOAD_TIMEOUT: Duration = APK_INSTALL_TIMEOUT;
+
+/// A device in a batch APK install, as far as it could be resolved
+/// `device_id` is unset for a serial with no connected device, `serial` for
+/// a device ID that isn't connected.
+struct ApkInstallTarget {
+    device_id: Option<DeviceId>,
+    serial: Option<String>,
 }
 
 /// Application service for device operations
@@ -33,6 +96,12 @@ pub struct DeviceApplicationService {
     device_repo: Arc<dyn DeviceRepository>,
     device_name_repo: Arc<dyn DeviceNameRepository>,
     command_executor: Arc<CommandExecutor>,
+    screenshot_assembler: Arc<ScreenshotAssembler>,
+    event_bus: Arc<EventBus>,
+    volume_presets: BTreeMap<String, u8>,
+    shell_policy: ShellPolicy,
+    /// Shared by all batch installs so concurrent batches don't add up
+    apk_install_slots: Arc<Semaphore>,
 }
 
 impl DeviceApplicationService {
@@ -40,11 +109,20 @@ impl DeviceApplicationService {
         device_repo: Arc<dyn DeviceRepository>,
         device_name_repo: Arc<dyn DeviceNameRepository>,
         command_executor: Arc<CommandExecutor>,
+        screenshot_assembler: Arc<ScreenshotAssembler>,
+        event_bus: Arc<EventBus>,
+        volume_presets: BTreeMap<String, u8>,
+        shell_policy: ShellPolicy,
     ) -> Self {
         Self {
             device_repo,
             device_name_repo,
             command_executor,
+            screenshot_assembler,
+            event_bus,
+            volume_presets,
+            shell_policy,
+            apk_install_slots: Arc::new(Semaphore::new(MAX_CONCURRENT_APK_INSTALLS)),
         }
     }
 
@@ -58,6 +136,62 @@ impl DeviceApplicationService {
         Ok(self.device_repo.find_by_id(id).await?)
     }
 
+    /// Get the last reported installed apps of a device, filtered
+    /// Returns None if the device is unknown or hasn't reported its apps yet.
+    pub async fn get_installed_apps(
+        &self,
+        id: DeviceId,
+        package_prefix: Option<&str>,
+        include_system: bool,
+    ) -> Result<Option<Vec<InstalledApp>>> {
+        let Some(device) = self.device_repo.find_by_id(id).await? else {
+            return Ok(None);
+        };
+
+        Ok(device.installed_apps().map(|apps| {
+            apps.iter()
+                .filter(|app| app.matches(package_prefix, include_system))
+                .cloned()
+                .collect()
+        }))
+    }
+
+    /// Get the last reported WiFi status of a device
+    /// Returns None if the device is unknown or hasn't reported its WiFi status yet.
+    pub async fn get_wifi_status(&self, id: DeviceId) -> Result<Option<WifiStatus>> {
+        let device = self.device_repo.find_by_id(id).await?;
+        Ok(device.and_then(|d| d.wifi_status().cloned()))
+    }
+
+    /// Render the device roster as CSV or JSON
+    /// Taken from one repository snapshot; disconnected devices keep their last known state.
+    pub async fn export_devices(&self, format: ExportFormat) -> Result<String> {
+        let snapshot = self.device_repo.snapshot().await?;
+
+        let mut rows: Vec<DeviceExportRow> = snapshot
+            .connected
+            .iter()
+            .map(|device| DeviceExportRow::new(device, true))
+            .chain(snapshot.disconnected.iter().map(|device| DeviceExportRow::new(device, false)))
+            .collect();
+        rows.sort_by(|a, b| a.serial.cmp(&b.serial));
+
+        render_device_export(&rows, format)
+            .map_err(|e| ApplicationError::OperationFailed(format!("Failed to serialize devices: {}", e)))
+    }
+
+    /// Write the device roster to `path`, returning the document written
+    pub async fn export_devices_to_file(&self, format: ExportFormat, path: &Path) -> Result<String> {
+        let document = self.export_devices(format).await?;
+
+        tokio::fs::write(path, &document).await.map_err(|e| {
+            ApplicationError::OperationFailed(format!("Failed to write {}: {}", path.display(), e))
+        })?;
+
+        tracing::info!(path = %path.display(), format = ?format, "Device inventory exported");
+        Ok(document)
+    }
+
     /// Set a custom name for a device
     pub async fn set_device_name(&self, serial: Serial, name: Option<String>) -> Result<()> {
         if let Some(device) = self.device_repo.find_by_serial(&serial).await? {
@@ -76,6 +210,514 @@ impl DeviceApplicationService {
         Ok(())
     }
 
+    /// Replace the tags of a device, returning them normalized
+    /// Tags are trimmed, lowercased and de-duplicated before they are stored.
+    pub async fn set_device_tags(&self, serial: Serial, tags: Vec<String>) -> Result<Vec<String>> {
+        let tags = normalize_tags(tags);
+
+        if let Some(device) = self.device_repo.find_by_serial(&serial).await? {
+            let updated_device = device.as_ref().clone().with_tags(tags.clone());
+            self.device_repo.save(updated_device).await?;
+        }
+
+        self.device_name_repo.set_tags(&serial, &tags).await?;
+
+        tracing::info!(serial = %serial, tags = ?tags, "Device tags updated");
+
+        Ok(tags)
+    }
+
+    /// Set or, with `None` or blank text, clear the note for a device
+    pub async fn set_device_note(&self, serial: Serial, note: Option<String>) -> Result<()> {
+        let note = note
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty());
+
+        if let Some(device) = self.device_repo.find_by_serial(&serial).await? {
+            let updated_device = device.as_ref().clone().with_note(note.clone());
+            self.device_repo.save(updated_device).await?;
+        }
+
+        self.device_name_repo.set_note(&serial, note).await?;
+
+        tracing::info!(serial = %serial, "Device note updated");
+
+        Ok(())
+    }
+
+    /// Set or, with `None`, clear the app each device should stay in
+    /// Devices that switch away from it raise a `DeviceLeftApp` alert.
+    pub async fn set_expected_package(&self, serials: &[Serial], package_name: Option<PackageName>) -> Result<()> {
+        let package_name = package_name.map(|p| p.as_str().to_string());
+
+        for serial in serials {
+            if let Some(device) = self.device_repo.find_by_serial(serial).await? {
+                let updated_device = device.as_ref().clone().with_expected_package(package_name.clone());
+                self.device_repo.save(updated_device).await?;
+            }
+
+            self.device_name_repo
+                .set_expected_package(serial, package_name.as_deref())
+                .await?;
+        }
+
+        tracing::info!(devices = serials.len(), package_name = ?package_name, "Expected app updated");
+
+        Ok(())
+    }
+
+    /// Connected devices carrying a tag, matched after normalizing it
+    pub async fn list_devices_by_tag(&self, tag: &str) -> Result<Vec<Arc<Device>>> {
+        let tag = tag.trim().to_lowercase();
+        let devices = self.device_repo.find_all().await?;
+
+        Ok(devices.into_iter().filter(|d| d.has_tag(&tag)).collect())
+    }
+
+    /// Check that an APK at `url` fits on every device before installing it
+    /// Devices that haven't reported storage yet, or APKs whose size can't be
+    /// determined, are not blocked.
+    pub async fn ensure_apk_fits(&self, device_ids: &[DeviceId], url: &str) -> Result<()> {
+        let Some(apk_size) = Self::fetch_remote_size(url).await else {
+            tracing::warn!(url = %url, "Could not determine APK size, skipping storage check");
+            return Ok(());
+        };
+
+        for device_id in device_ids {
+            let Some(device) = self.device_repo.find_by_id(*device_id).await? else {
+                continue;
+            };
+
+            if let Some(storage) = device.storage() {
+                if !storage.can_fit(apk_size) {
+                    return Err(ApplicationError::InsufficientStorage {
+                        device: device
+                            .custom_name()
+                            .unwrap_or(device.serial().as_str())
+                            .to_string(),
+                        required_bytes: apk_size,
+                        free_bytes: storage.free_bytes(),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// HTTP client for requests to a remote APK server, bounded by `timeout`
+    fn remote_apk_client(timeout: Duration) -> reqwest::Result<reqwest::Client> {
+        reqwest::Client::builder()
+            .connect_timeout(REMOTE_APK_CONNECT_TIMEOUT)
+            .timeout(timeout)
+            .build()
+    }
+
+    /// Read the size of a remote file from a HEAD request
+    async fn fetch_remote_size(url: &str) -> Option<u64> {
+        let response = Self::remote_apk_client(REMOTE_APK_HEAD_TIMEOUT)
+            .ok()?
+            .head(url)
+            .send()
+            .await
+            .ok()?;
+        if !response.status().is_success() {
+            return None;
+        }
+
+        response
+            .headers()
+            .get(reqwest::header::CONTENT_LENGTH)?
+            .to_str()
+            .ok()?
+            .parse()
+            .ok()
+    }
+
+    /// Download an APK once to confirm it is served, returning its size and hex SHA-256
+    /// Nothing is sent to devices.
+    pub async fn check_remote_apk(url: &str) -> Result<(u64, String)> {
+        let mut response = Self::remote_apk_client(REMOTE_APK_DOWNLOAD_TIMEOUT)
+            .map_err(|e| ApplicationError::OperationFailed(format!("Failed to create HTTP client: {}", e)))?
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| ApplicationError::OperationFailed(format!("{} is unreachable: {}", url, e)))?;
+
+        if !response.status().is_success() {
+            return Err(ApplicationError::OperationFailed(format!(
+                "{} returned {}",
+                url,
+                response.status()
+            )));
+        }
+
+        let mut hasher = Sha256::new();
+        let mut size_bytes = 0u64;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| ApplicationError::OperationFailed(format!("Download of {} failed: {}", url, e)))?
+        {
+            size_bytes += chunk.len() as u64;
+            hasher.update(&chunk);
+        }
+
+        Ok((size_bytes, format!("{:x}", hasher.finalize())))
+    }
+
+    /// Install an APK on many devices, a few at a time, waiting for each result
+    /// A failure on one device doesn't stop the others. Progress is reported
+    /// per device through `ApkBatchInstallProgress` events.
+    pub async fn install_apk_batch(
+        &self,
+        device_ids: Vec<DeviceId>,
+        command: InstallApkCommand,
+    ) -> ApkBatchInstallDto {
+        let mut targets = Vec::with_capacity(device_ids.len());
+        for device_id in device_ids {
+            let serial = match self.device_repo.find_by_id(device_id).await {
+                Ok(device) => device.map(|d| d.serial().to_string()),
+                Err(_) => None,
+            };
+            targets.push(ApkInstallTarget {
+                device_id: Some(device_id),
+                serial,
+            });
+        }
+
+        self.run_apk_batch(targets, command).await
+    }
+
+    /// Install an APK on the devices with the given serials, as `install_apk_batch`
+    /// For pushing a build to a handful of headsets without making a group for
+    /// them. Serials with no connected device are reported as skipped.
+    pub async fn install_apk_on_serials(
+        &self,
+        serials: Vec<Serial>,
+        command: InstallApkCommand,
+    ) -> ApkBatchInstallDto {
+        let mut targets: Vec<ApkInstallTarget> = Vec::with_capacity(serials.len());
+        for serial in serials {
+            if targets.iter().any(|t| t.serial.as_deref() == Some(serial.as_str())) {
+                continue;
+            }
+
+            let device_id = match self.device_repo.find_by_serial(&serial).await {
+                Ok(device) => device.map(|d| d.id()),
+                Err(e) => {
+                    tracing::warn!(serial = %serial, error = %e, "Failed to look up device for APK install");
+                    None
+                }
+            };
+            targets.push(ApkInstallTarget {
+                device_id,
+                serial: Some(serial.to_string()),
+            });
+        }
+
+        self.run_apk_batch(targets, command).await
+    }
+
+    async fn run_apk_batch(&self, targets: Vec<ApkInstallTarget>, command: InstallApkCommand) -> ApkBatchInstallDto {
+        let batch_id = uuid::Uuid::new_v4().to_string();
+        let total = targets.len();
+        let command: Arc<dyn Command> = Arc::new(command.awaiting_result());
+        let completed = AtomicUsize::new(0);
+
+        tracing::info!(batch_id = %batch_id, devices = total, "Starting batch APK install");
+
+        for target in &targets {
+            self.emit_install_progress(&batch_id, target, ApkInstallStatus::Queued, None, 0, total);
+        }
+
+        let installs = targets.into_iter().map(|target| {
+            let command = Arc::clone(&command);
+            let batch_id = batch_id.as_str();
+            let completed = &completed;
+            async move {
+                let (status, message) = match target.device_id {
+                    Some(device_id) => {
+                        self.install_on_device(device_id, &target, command, batch_id, completed, total)
+                            .await
+                    }
+                    None => (ApkInstallStatus::Skipped, Some("Device is not connected".to_string())),
+                };
+                let done = completed.fetch_add(1, Ordering::SeqCst) + 1;
+                self.emit_install_progress(batch_id, &target, status, message.clone(), done, total);
+
+                ApkBatchDeviceResultDto {
+                    device_id: target.device_id.map(|id| id.as_uuid().to_string()),
+                    serial: target.serial,
+                    status,
+                    message,
+                }
+            }
+        });
+        let result = ApkBatchInstallDto::new(batch_id, futures::future::join_all(installs).await);
+
+        tracing::info!(
+            batch_id = %result.batch_id,
+            installed = result.installed_count,
+            failed = result.failed_count,
+            skipped = result.skipped_count,
+            "Batch APK install completed"
+        );
+
+        result
+    }
+
+    /// Install on one device once an install slot is free
+    async fn install_on_device(
+        &self,
+        device_id: DeviceId,
+        target: &ApkInstallTarget,
+        command: Arc<dyn Command>,
+        batch_id: &str,
+        completed: &AtomicUsize,
+        total: usize,
+    ) -> (ApkInstallStatus, Option<String>) {
+        match self.device_repo.find_by_id(device_id).await {
+            Ok(Some(_)) => {}
+            Ok(None) => return (ApkInstallStatus::Skipped, Some("Device is not connected".to_string())),
+            Err(e) => return (ApkInstallStatus::Failed, Some(e.to_string())),
+        }
+
+        let _slot = self
+            .apk_install_slots
+            .acquire()
+            .await
+            .expect("APK install semaphore is never closed");
+
+        self.emit_install_progress(
+            batch_id,
+            target,
+            ApkInstallStatus::Installing,
+            None,
+            completed.load(Ordering::SeqCst),
+            total,
+        );
+
+        match self
+            .command_executor
+            .execute_with_timeout(device_id, command, APK_INSTALL_TIMEOUT)
+            .await
+        {
+            Ok(CommandResponse::SuccessWithData(payload)) => match ApkInstallOutcome::decode(&payload) {
+                Some(outcome) if outcome.is_success() => (ApkInstallStatus::Installed, None),
+                Some(outcome) => (ApkInstallStatus::Failed, Some(outcome.message())),
+                None => (
+                    ApkInstallStatus::Failed,
+                    Some("Device reported the install failed".to_string()),
+                ),
+            },
+            Ok(_) => (
+                ApkInstallStatus::Failed,
+                Some("Device reported the install failed".to_string()),
+            ),
+            // Disconnected while waiting for a slot
+            Err(e @ (CommandError::DeviceNotFound { .. } | CommandError::SessionNotFound { .. })) => {
+                (ApkInstallStatus::Skipped, Some(e.to_string()))
+            }
+            Err(e) => (ApkInstallStatus::Failed, Some(e.to_string())),
+        }
+    }
+
+    fn emit_install_progress(
+        &self,
+        batch_id: &str,
+        target: &ApkInstallTarget,
+        status: ApkInstallStatus,
+        message: Option<String>,
+        completed: usize,
+        total: usize,
+    ) {
+        self.event_bus.apk_batch_install_progress(
+            batch_id.to_string(),
+            target.device_id.map(|id| id.as_uuid()),
+            target.serial.clone(),
+            status,
+            message,
+            completed,
+            total,
+        );
+    }
+
+    /// Capture a screenshot from a device
+    /// Returns the reassembled PNG bytes once the device has streamed every chunk.
+    pub async fn request_screenshot(&self, device_id: DeviceId) -> Result<Vec<u8>> {
+        let receiver = self.screenshot_assembler.expect(device_id);
+
+        if let Err(e) = self
+            .command_executor
+            .execute_single(device_id, Arc::new(RequestScreenshotCommand))
+            .await
+        {
+            self.screenshot_assembler.cancel(&device_id);
+            return Err(e.into());
+        }
+
+        match tokio::time::timeout(SCREENSHOT_TIMEOUT, receiver).await {
+            Ok(Ok(result)) => Ok(result?),
+            Ok(Err(_)) => Err(ApplicationError::OperationFailed(
+                "Screenshot request was superseded or the device disconnected".to_string(),
+            )),
+            Err(_) => {
+                self.screenshot_assembler.cancel(&device_id);
+                Err(ApplicationError::OperationFailed(format!(
+                    "Timed out after {}s waiting for screenshot",
+                    SCREENSHOT_TIMEOUT.as_secs()
+                )))
+            }
+        }
+    }
+
+    /// Apply a named volume preset to a group of devices
+    pub async fn set_volume_preset(
+        &self,
+        device_ids: Vec<DeviceId>,
+        preset: &str,
+    ) -> Result<BatchResult<CommandResponse>> {
+        let level = self.volume_presets.get(preset).copied().ok_or_else(|| {
+            let available: Vec<&str> = self.volume_presets.keys().map(String::as_str).collect();
+            CommandError::ValidationFailed(format!(
+                "Unknown volume preset '{}' (available: {})",
+                preset,
+                available.join(", ")
+            ))
+        })?;
+
+        let command = SetVolumeCommand::new(level).map_err(CommandError::ValidationFailed)?;
+
+        tracing::info!(preset = %preset, level, devices = device_ids.len(), "Applying volume preset");
+
+        Ok(self.execute_command_batch(device_ids, Arc::new(command)).await)
+    }
+
+    /// Run a shell command on a group of devices, subject to the shell policy
+    /// A refused command is never sent; every device gets a failed result
+    /// explaining why.
+    pub async fn execute_shell(
+        &self,
+        device_ids: Vec<DeviceId>,
+        command: ExecuteShellCommand,
+    ) -> BatchResult<CommandResponse> {
+        let Err(reason) = self.shell_policy.check(&command.command) else {
+            return self.execute_command_batch(device_ids, Arc::new(command)).await;
+        };
+
+        tracing::warn!(command = %command.command, reason = %reason, "Shell command blocked");
+
+        let mut result = BatchResult::new();
+        for device_id in device_ids {
+            self.event_bus.command_executed(
+                device_id.as_uuid(),
+                CommandResultDto::failure(command.name(), reason.clone()),
+            );
+            result.add_failure(device_id, reason.clone());
+        }
+        result
+    }
+
+    /// Launch an app on every connected device
+    pub async fn launch_app_all(
+        &self,
+        package_name: PackageName,
+        stagger: Duration,
+    ) -> Result<BatchResult<CommandResponse>> {
+        let devices = self.device_repo.find_all().await?;
+        Ok(self.launch_app_on_devices(devices, package_name, stagger).await)
+    }
+
+    /// Launch an app on every connected device carrying a tag
+    pub async fn launch_app_by_tag(
+        &self,
+        tag: &str,
+        package_name: PackageName,
+        stagger: Duration,
+    ) -> Result<BatchResult<CommandResponse>> {
+        let devices = self.list_devices_by_tag(tag).await?;
+        Ok(self.launch_app_on_devices(devices, package_name, stagger).await)
+    }
+
+    /// Launch an app on several devices, sending the n-th launch `n * stagger` after
+    /// the first so apps that pull assets on start don't all hit the WiFi at once
+    /// `stagger` is capped at `MAX_LAUNCH_STAGGER`.
+    /// Devices whose last reported app list lacks the package fail without being sent
+    /// the command; devices that haven't reported their apps yet are tried anyway.
+    async fn launch_app_on_devices(
+        &self,
+        devices: Vec<Arc<Device>>,
+        package_name: PackageName,
+        stagger: Duration,
+    ) -> BatchResult<CommandResponse> {
+        let mut result = BatchResult::new();
+        let mut targets = Vec::new();
+
+        for device in devices {
+            let missing = device.installed_apps().is_some_and(|apps| {
+                !apps.iter().any(|app| app.package_name() == package_name.as_str())
+            });
+
+            if missing {
+                result.add_failure(device.id(), format!("{} is not installed", package_name));
+            } else {
+                targets.push(device.id());
+            }
+        }
+
+        let command: Arc<dyn Command> = Arc::new(LaunchAppCommand::new(package_name));
+        let launches = targets.into_iter().enumerate().map(|(index, device_id)| {
+            let command = Arc::clone(&command);
+            async move {
+                tokio::time::sleep(launch_delay(stagger, index)).await;
+                let outcome = self.command_executor.execute_single(device_id, command).await;
+                (device_id, outcome)
+            }
+        });
+
+        for (device_id, outcome) in futures::future::join_all(launches).await {
+            match outcome {
+                Ok(response) => result.add_success(device_id, response),
+                Err(e) => result.add_failure(device_id, e.to_string()),
+            }
+        }
+
+        result
+    }
+
+    /// Push the server's UTC time, and optionally a timezone, to devices
+    /// Devices that answer but couldn't apply it are reported as failed.
+    pub async fn sync_time(&self, device_ids: Vec<DeviceId>, command: SetTimeCommand) -> BatchResult<CommandResponse> {
+        let sent = self.execute_command_batch(device_ids, Arc::new(command)).await;
+
+        let mut result = BatchResult::new();
+        for (device_id, response) in sent.succeeded {
+            match &response {
+                CommandResponse::SuccessWithData(payload) if payload.first() == Some(&0) => {
+                    result.add_failure(device_id, "Device could not set its clock".to_string())
+                }
+                _ => result.add_success(device_id, response),
+            }
+        }
+        for (device_id, error) in sent.failed {
+            result.add_failure(device_id, error);
+        }
+        result
+    }
+
+    /// Push the server's time to every connected device
+    pub async fn sync_time_all(&self, command: SetTimeCommand) -> Result<BatchResult<CommandResponse>> {
+        let device_ids = self
+            .device_repo
+            .find_all()
+            .await?
+            .iter()
+            .map(|device| device.id())
+            .collect();
+        Ok(self.sync_time(device_ids, command).await)
+    }
+
     /// Execute a command on multiple devices (batch operation)
     pub async fn execute_command_batch(
         &self,
@@ -84,4 +726,35 @@ impl DeviceApplicationService {
     ) -> BatchResult<CommandResponse> {
         self.command_executor.execute_batch(device_ids, command).await
     }
+
+    /// Send a command to every connected device
+    pub async fn execute_command_all(&self, command: Arc<dyn Command>) -> Result<BatchResult<CommandResponse>> {
+        let device_ids = self
+            .device_repo
+            .find_all()
+            .await?
+            .iter()
+            .map(|device| device.id())
+            .collect();
+        Ok(self.execute_command_batch(device_ids, command).await)
+    }
+}
+
+/// Delay before the `index`-th staggered launch, with the stagger capped at `MAX_LAUNCH_STAGGER`
+fn launch_delay(stagger: Duration, index: usize) -> Duration {
+    let index = u32::try_from(index).unwrap_or(u32::MAX);
+    stagger.min(MAX_LAUNCH_STAGGER).saturating_mul(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn launch_delay_is_capped_and_never_overflows() {
+        assert_eq!(launch_delay(Duration::from_millis(500), 0), Duration::ZERO);
+        assert_eq!(launch_delay(Duration::from_millis(500), 3), Duration::from_millis(1500));
+        assert_eq!(launch_delay(Duration::from_secs(3600), 2), MAX_LAUNCH_STAGGER * 2);
+        assert_eq!(launch_delay(Duration::MAX, usize::MAX), MAX_LAUNCH_STAGGER.saturating_mul(u32::MAX));
+    }
 }
@@ -1,10 +1,16 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, Semaphore};
+use tokio_util::sync::CancellationToken;
 
 use crate::app::EventBus;
-use crate::application::dto::{CachedGameEntry, LocalGameMetadata};
-use crate::domain::repositories::{GameVersionError, GameVersionRepository};
+use crate::application::dto::{CachedGameEntry, GameAssignment, GameDownloadResponse, LocalGameMetadata};
+use crate::domain::repositories::{
+    DownloadPhase, FileDownloadProgress, FileVerificationFailure, GameVersionError, GameVersionRepository,
+    UpdatePlan,
+};
 use crate::infrastructure::repositories::SqliteGameCacheRepository;
 
 /// Game status information for the dashboard
@@ -20,6 +26,26 @@ pub struct GameStatus {
     pub download_progress: Option<DownloadProgress>,
     pub online: bool,
     pub background_image_path: Option<String>,
+    /// On-disk size of the installed files, `None` if not installed
+    pub installed_size_bytes: Option<u64>,
+    pub last_played: Option<DateTime<Utc>>,
+    /// Release channel the game follows, `None` for the arcade's own
+    pub channel: Option<String>,
+    /// The offered version is older than the installed one, e.g. after leaving a beta channel
+    pub is_downgrade: bool,
+}
+
+/// Longest release channel name accepted by `set_game_channel`
+const MAX_CHANNEL_NAME_LEN: usize = 64;
+
+/// Lifecycle of a game download
+/// Paused downloads keep their partial files and progress until resumed or cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DownloadState {
+    Running,
+    Paused,
+    Cancelled,
 }
 
 /// Download progress information
@@ -29,7 +55,77 @@ pub struct DownloadProgress {
     pub total_files: usize,
     pub downloaded_files: usize,
     pub current_file: String,
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
+    pub percentage: f32,
+    /// 1-based position in the download queue, `None` once the download has started
+    pub queue_position: Option<usize>,
+    pub state: DownloadState,
+}
+
+impl DownloadProgress {
+    fn starting(queue_position: Option<usize>) -> Self {
+        Self {
+            total_files: 0,
+            downloaded_files: 0,
+            current_file: String::new(),
+            downloaded_bytes: 0,
+            total_bytes: None,
+            percentage: 0.0,
+            queue_position,
+            state: DownloadState::Running,
+        }
+    }
+}
+
+/// Finished downloads remembered for `get_download_queue`
+const MAX_COMPLETED_DOWNLOADS: usize = 20;
+
+/// Where a download stands in the queue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DownloadQueueStatus {
+    Queued,
+    Active,
+    Paused,
+    Completed,
+}
+
+/// One download in the queue, as shown by `get_download_queue`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadQueueEntry {
+    pub game_id: i32,
+    pub game_name: String,
+    pub status: DownloadQueueStatus,
+    /// 1-based position while queued
+    pub queue_position: Option<usize>,
     pub percentage: f32,
+    /// When a completed download finished
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// Result of checking an installed game against the server's file list
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameVerification {
+    pub game_id: i32,
+    pub game_name: String,
+    pub version: String,
+    pub checked_files: usize,
+    /// Files that are missing or whose size or checksum doesn't match
+    pub failures: Vec<FileVerificationFailure>,
+}
+
+/// What updating a game to its assigned version would download and remove
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePreview {
+    pub game_id: i32,
+    pub game_name: String,
+    pub installed_version: Option<String>,
+    pub version: String,
+    pub plan: UpdatePlan,
 }
 
 /// Service for managing game versions
@@ -39,6 +135,19 @@ pub struct GameVersionService {
     event_bus: Arc<EventBus>,
     /// Track download progress for each game
     download_progress: Arc<RwLock<std::collections::HashMap<i32, DownloadProgress>>>,
+    /// Cancellation tokens for downloads that are queued or in progress
+    active_downloads: Arc<RwLock<std::collections::HashMap<i32, CancellationToken>>>,
+    /// Games whose files are being deleted; guarded by the `active_downloads` lock
+    deleting_games: Arc<RwLock<std::collections::HashSet<i32>>>,
+    /// Limits how many downloads run at once
+    download_slots: Arc<Semaphore>,
+    /// Downloads waiting for a slot, in order, as (game_id, game_name)
+    download_queue: Arc<RwLock<VecDeque<(i32, String)>>>,
+    /// Most recently finished downloads first
+    completed_downloads: Arc<RwLock<VecDeque<DownloadQueueEntry>>>,
+    /// Channel each game's offered version was taken from by the last sync, so
+    /// downloads fetch the same version; `None` until the first sync
+    served_channels: Arc<RwLock<Option<std::collections::HashMap<i32, String>>>>,
     /// Base directory for game installations (C:/Combatica)
     games_directory: std::path::PathBuf,
 }
@@ -49,12 +158,19 @@ impl GameVersionService {
         cache_repository: Arc<SqliteGameCacheRepository>,
         event_bus: Arc<EventBus>,
         games_directory: std::path::PathBuf,
+        max_concurrent_downloads: usize,
     ) -> Self {
         Self {
             repository,
             cache_repository,
             event_bus,
             download_progress: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            active_downloads: Arc::new(RwLock::new(std::collections::HashMap::new())),
+            deleting_games: Arc::new(RwLock::new(std::collections::HashSet::new())),
+            download_slots: Arc::new(Semaphore::new(max_concurrent_downloads)),
+            download_queue: Arc::new(RwLock::new(VecDeque::new())),
+            completed_downloads: Arc::new(RwLock::new(VecDeque::new())),
+            served_channels: Arc::new(RwLock::new(None)),
             games_directory,
         }
     }
@@ -166,7 +282,7 @@ impl GameVersionService {
     pub async fn sync_cache_with_server(&self) -> Result<bool, GameVersionError> {
         tracing::info!("Attempting to sync cache with Alakazam server...");
 
-        match self.repository.fetch_game_assignments().await {
+        match self.fetch_assignments().await {
             Ok(assignments) => {
                 // Build a lookup for local metadata
                 let mut local_metadata_map = std::collections::HashMap::new();
@@ -200,6 +316,105 @@ impl GameVersionService {
         }
     }
 
+    /// Fetch assignments for the arcade's channel, swapping in the version from
+    /// each game's own channel where one is set
+    /// A game with nothing published on its channel, or whose channel can't be
+    /// fetched, keeps the arcade channel's version. The channel each version came
+    /// from is remembered for `fetch_download_urls`.
+    async fn fetch_assignments(&self) -> Result<Vec<GameAssignment>, GameVersionError> {
+        let mut assignments = self.repository.fetch_game_assignments(None).await?;
+
+        let game_channels = self.cache_repository.get_game_channels().await.unwrap_or_else(|e| {
+            tracing::warn!("Failed to load game channels: {}", e);
+            Default::default()
+        });
+        let channels: std::collections::BTreeSet<&String> = game_channels.values().collect();
+        let mut served_channels = std::collections::HashMap::new();
+
+        for channel in channels {
+            let from_channel = match self.repository.fetch_game_assignments(Some(channel)).await {
+                Ok(from_channel) => from_channel,
+                Err(e) => {
+                    tracing::warn!("Failed to fetch games on the {} channel: {}", channel, e);
+                    continue;
+                }
+            };
+
+            for assignment in assignments
+                .iter_mut()
+                .filter(|a| game_channels.get(&a.game_id) == Some(channel))
+            {
+                match from_channel.iter().find(|a| a.game_id == assignment.game_id) {
+                    Some(on_channel) => {
+                        *assignment = on_channel.clone();
+                        served_channels.insert(assignment.game_id, channel.clone());
+                    }
+                    None => tracing::warn!(
+                        "{} has no version on the {} channel, offering the arcade's",
+                        assignment.game_name,
+                        channel
+                    ),
+                }
+            }
+        }
+
+        *self.served_channels.write().await = Some(served_channels);
+        Ok(assignments)
+    }
+
+    /// Fetch download URLs from the channel the game's offered version came from
+    /// That is the arcade's channel when the game's own channel had nothing for it.
+    async fn fetch_download_urls(&self, game_id: i32) -> Result<GameDownloadResponse, GameVersionError> {
+        if self.served_channels.read().await.is_none() {
+            self.fetch_assignments().await?;
+        }
+        let channel = self
+            .served_channels
+            .read()
+            .await
+            .as_ref()
+            .and_then(|served| served.get(&game_id).cloned());
+        self.repository.fetch_download_urls(game_id, channel.as_deref()).await
+    }
+
+    /// Release channel a game follows, `None` if it follows the arcade's own
+    pub async fn get_game_channel(&self, game_id: i32) -> Result<Option<String>, GameVersionError> {
+        self.cache_repository
+            .get_game_channel(game_id)
+            .await
+            .map_err(|e| GameVersionError::InvalidMetadata(format!("Cache error: {}", e)))
+    }
+
+    /// Make a game follow a release channel (e.g. `beta`), or with `None` the arcade's own
+    /// Updates are then only offered from that channel. Moving to a channel whose
+    /// latest version is older than the installed one offers it as a downgrade.
+    pub async fn set_game_channel(&self, game_id: i32, channel: Option<&str>) -> Result<(), GameVersionError> {
+        let channel = channel.map(str::trim).filter(|c| !c.is_empty()).map(str::to_ascii_lowercase);
+
+        if let Some(channel) = &channel {
+            let valid = channel.len() <= MAX_CHANNEL_NAME_LEN
+                && channel.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if !valid {
+                return Err(GameVersionError::InvalidChannel(channel.clone()));
+            }
+        }
+
+        self.cache_repository
+            .set_game_channel(game_id, channel.as_deref())
+            .await
+            .map_err(|e| GameVersionError::InvalidMetadata(format!("Cache error: {}", e)))?;
+
+        tracing::info!(
+            "Game {} now follows the {} channel",
+            game_id,
+            channel.as_deref().unwrap_or("arcade's")
+        );
+
+        // Pick up the channel's version now rather than on the next listing
+        self.sync_cache_with_server().await?;
+        Ok(())
+    }
+
     /// Get all games with their status (cache-first with background sync)
     pub async fn get_game_statuses(&self) -> Result<Vec<GameStatus>, GameVersionError> {
         // Try to sync with server first (best effort)
@@ -212,6 +427,16 @@ impl GameVersionService {
             .await
             .map_err(|e| GameVersionError::InvalidMetadata(format!("Cache error: {}", e)))?;
 
+        let mut game_stats = self.cache_repository.get_game_stats().await.unwrap_or_else(|e| {
+            tracing::warn!("Failed to load game stats: {}", e);
+            Default::default()
+        });
+
+        let mut game_channels = self.cache_repository.get_game_channels().await.unwrap_or_else(|e| {
+            tracing::warn!("Failed to load game channels: {}", e);
+            Default::default()
+        });
+
         let mut statuses = Vec::new();
 
         for entry in entries {
@@ -226,6 +451,11 @@ impl GameVersionService {
                 true
             };
 
+            // Version IDs are handed out in upload order, so a lower assigned ID is an older build
+            let is_downgrade = entry
+                .installed_version_id
+                .is_some_and(|installed_id| installed_id > entry.assigned_version_id);
+
             // Get download progress if downloading
             let download_progress = {
                 let progress_map = self.download_progress.read().await;
@@ -235,6 +465,14 @@ impl GameVersionService {
             // Check for local background image
             let background_image_path = self.get_background_image_path(&entry.game_name);
 
+            let stats = game_stats.remove(&entry.game_id).unwrap_or_default();
+            // Sizes are measured once and refreshed after downloads, not on every listing
+            let installed_size_bytes = match (&installed_version, stats.installed_size_bytes) {
+                (None, _) => None,
+                (Some(_), Some(bytes)) => Some(bytes),
+                (Some(_), None) => self.refresh_installed_size(entry.game_id, &entry.game_name).await,
+            };
+
             statuses.push(GameStatus {
                 game_id: entry.game_id,
                 game_name: entry.game_name.clone(),
@@ -245,6 +483,10 @@ impl GameVersionService {
                 download_progress,
                 online,
                 background_image_path,
+                installed_size_bytes,
+                last_played: stats.last_played,
+                channel: game_channels.remove(&entry.game_id),
+                is_downgrade,
             });
         }
 
@@ -256,12 +498,161 @@ impl GameVersionService {
     }
 
     /// Download and install a game (or update it)
+    /// Downloads beyond the concurrency limit wait in a queue until a slot frees up.
+    /// With `resume` set, files left partially downloaded by a cancelled or
+    /// interrupted attempt are continued rather than downloaded again.
     pub async fn download_and_install_game(
         &self,
         game_id: i32,
+        resume: bool,
+    ) -> Result<(), GameVersionError> {
+        let cancel_token = CancellationToken::new();
+        {
+            let mut active = self.active_downloads.write().await;
+            if active.contains_key(&game_id) {
+                return Err(GameVersionError::AlreadyDownloading(game_id));
+            }
+            if self.deleting_games.read().await.contains(&game_id) {
+                return Err(GameVersionError::Deleting(game_id));
+            }
+            active.insert(game_id, cancel_token.clone());
+        }
+
+        let result = match self.wait_for_download_slot(game_id, &cancel_token).await {
+            Ok(_permit) => self.install_game(game_id, resume, cancel_token).await,
+            Err(e) => Err(e),
+        };
+
+        self.active_downloads.write().await.remove(&game_id);
+        if result.is_ok() {
+            self.record_completed_download(game_id).await;
+        } else {
+            let mut progress_map = self.download_progress.write().await;
+            // A paused download keeps its progress so the UI can show where it stopped
+            if progress_map.get(&game_id).map(|p| p.state) == Some(DownloadState::Paused) {
+                return Err(GameVersionError::Paused);
+            }
+            progress_map.remove(&game_id);
+        }
+        result
+    }
+
+    async fn record_completed_download(&self, game_id: i32) {
+        let entry = DownloadQueueEntry {
+            game_id,
+            game_name: self.cached_game_name(game_id).await,
+            status: DownloadQueueStatus::Completed,
+            queue_position: None,
+            percentage: 100.0,
+            finished_at: Some(Utc::now()),
+        };
+
+        let mut completed = self.completed_downloads.write().await;
+        completed.retain(|e| e.game_id != game_id);
+        completed.push_front(entry);
+        completed.truncate(MAX_COMPLETED_DOWNLOADS);
+    }
+
+    /// Active downloads, then paused ones, then queued ones in order, then recently completed ones
+    pub async fn get_download_queue(&self) -> Vec<DownloadQueueEntry> {
+        let queue: Vec<(i32, String)> = self.download_queue.read().await.iter().cloned().collect();
+        let active_ids: Vec<i32> = self.active_downloads.read().await.keys().copied().collect();
+        let progress_map = self.download_progress.read().await.clone();
+
+        // A paused download has left both the queue and the active set; only
+        // its progress entry remembers it
+        let paused_ids: Vec<i32> = progress_map
+            .iter()
+            .filter(|(_, p)| p.state == DownloadState::Paused)
+            .map(|(id, _)| *id)
+            .collect();
+
+        let mut names = std::collections::HashMap::new();
+        for game_id in active_ids.iter().chain(&paused_ids) {
+            if !names.contains_key(game_id) {
+                names.insert(*game_id, self.cached_game_name(*game_id).await);
+            }
+        }
+
+        let completed: Vec<DownloadQueueEntry> =
+            self.completed_downloads.read().await.iter().cloned().collect();
+        assemble_download_queue(&queue, &active_ids, &progress_map, &names, completed)
+    }
+
+    /// Queue a download and wait until it may start
+    /// Returns `Cancelled` if the download is cancelled while still queued.
+    async fn wait_for_download_slot(
+        &self,
+        game_id: i32,
+        cancel_token: &CancellationToken,
+    ) -> Result<tokio::sync::OwnedSemaphorePermit, GameVersionError> {
+        if let Ok(permit) = Arc::clone(&self.download_slots).try_acquire_owned() {
+            return Ok(permit);
+        }
+
+        let game_name = self.cached_game_name(game_id).await;
+        let position = {
+            let mut queue = self.download_queue.write().await;
+            queue.push_back((game_id, game_name.clone()));
+            queue.len()
+        };
+
+        tracing::info!("Queued download for {} (position {})", game_name, position);
+        self.download_progress
+            .write()
+            .await
+            .insert(game_id, DownloadProgress::starting(Some(position)));
+        self.event_bus
+            .game_download_progress(game_id, game_name, 0.0, Some(position));
+
+        let result = tokio::select! {
+            _ = cancel_token.cancelled() => Err(GameVersionError::Cancelled),
+            permit = Arc::clone(&self.download_slots).acquire_owned() => {
+                permit.map_err(|_| GameVersionError::Cancelled)
+            }
+        };
+
+        self.leave_download_queue(game_id).await;
+        result
+    }
+
+    /// Remove a game from the download queue and notify the games behind it
+    async fn leave_download_queue(&self, game_id: i32) {
+        let remaining: Vec<(i32, String)> = {
+            let mut queue = self.download_queue.write().await;
+            queue.retain(|(id, _)| *id != game_id);
+            queue.iter().cloned().collect()
+        };
+
+        let mut progress_map = self.download_progress.write().await;
+        for (index, (queued_id, queued_name)) in remaining.into_iter().enumerate() {
+            let position = index + 1;
+            if let Some(progress) = progress_map.get_mut(&queued_id) {
+                progress.queue_position = Some(position);
+            }
+            self.event_bus
+                .game_download_progress(queued_id, queued_name, 0.0, Some(position));
+        }
+    }
+
+    async fn cached_game_name(&self, game_id: i32) -> String {
+        self.cache_repository
+            .get_all_entries()
+            .await
+            .ok()
+            .and_then(|entries| entries.into_iter().find(|e| e.game_id == game_id))
+            .map(|e| e.game_name)
+            .unwrap_or_else(|| format!("Game {}", game_id))
+    }
+
+    async fn install_game(
+        &self,
+        game_id: i32,
+        resume: bool,
+        cancel_token: CancellationToken,
     ) -> Result<(), GameVersionError> {
         // Fetch download URLs from Alakazam
-        let download_response = self.repository.fetch_download_urls(game_id).await?;
+        let download_response = self.fetch_download_urls(game_id).await?;
 
         let game_name = download_response.game_name.clone();
         let version = download_response.version.clone();
@@ -275,16 +666,23 @@ impl GameVersionService {
             total_files
         );
 
-        // Initialize progress tracking
+        // Initialize progress tracking, unless paused or cancelled while fetching URLs
         {
             let mut progress_map = self.download_progress.write().await;
+            if cancel_token.is_cancelled() {
+                return Err(GameVersionError::Cancelled);
+            }
             progress_map.insert(
                 game_id,
                 DownloadProgress {
                     total_files,
                     downloaded_files: 0,
                     current_file: String::new(),
+                    downloaded_bytes: 0,
+                    total_bytes: None,
                     percentage: 0.0,
+                    queue_position: None,
+                    state: DownloadState::Running,
                 },
             );
         }
@@ -294,37 +692,75 @@ impl GameVersionService {
         let event_bus = Arc::clone(&self.event_bus);
         let game_name_for_callback = game_name.clone();
 
-        let progress_callback = Box::new(move |downloaded: usize, total: usize, current_file: String| {
+        let progress_callback = Box::new(move |update: FileDownloadProgress| {
             let progress_map = Arc::clone(&progress_map);
             let event_bus = Arc::clone(&event_bus);
             let game_name = game_name_for_callback.clone();
 
             tauri::async_runtime::spawn(async move {
-                let percentage = if total > 0 {
-                    (downloaded as f32 / total as f32) * 100.0
-                } else {
-                    0.0
+                if update.phase == DownloadPhase::Verifying {
+                    if let Some(progress) = progress_map.write().await.get_mut(&game_id) {
+                        progress.current_file = format!("Verifying: {}", update.current_file);
+                    }
+                    event_bus.game_verification_progress(
+                        game_id,
+                        game_name,
+                        update.downloaded_files,
+                        update.total_files,
+                        update.current_file,
+                    );
+                    return;
+                }
+
+                // Prefer byte-based progress so resumed downloads start where they left off
+                let percentage = match update.total_bytes {
+                    Some(total_bytes) if total_bytes > 0 => {
+                        (update.downloaded_bytes as f32 / total_bytes as f32) * 100.0
+                    }
+                    _ if update.total_files > 0 => {
+                        (update.downloaded_files as f32 / update.total_files as f32) * 100.0
+                    }
+                    _ => 0.0,
                 };
 
                 // Update internal progress tracking
                 {
                     let mut map = progress_map.write().await;
                     if let Some(progress) = map.get_mut(&game_id) {
-                        progress.downloaded_files = downloaded;
-                        progress.total_files = total;
-                        progress.current_file = current_file;
+                        progress.downloaded_files = update.downloaded_files;
+                        progress.total_files = update.total_files;
+                        progress.current_file = update.current_file;
+                        progress.downloaded_bytes = update.downloaded_bytes;
+                        progress.total_bytes = update.total_bytes;
                         progress.percentage = percentage;
                     }
                 }
 
                 // Emit progress event to frontend
-                event_bus.game_download_progress(game_id, game_name, percentage);
+                event_bus.game_download_progress(game_id, game_name, percentage, None);
             });
         });
 
-        self.repository
-            .download_game_files(&game_name, &download_response.files, progress_callback)
-            .await?;
+        // Recorded with the file list so the game can be deleted later
+        let manifest = download_response.files.iter().map(|f| f.path.clone()).collect();
+        let metadata = LocalGameMetadata::new(game_id, game_name.clone(), version, version_id)
+            .with_files(manifest);
+
+        let downloaded = self
+            .repository
+            .download_game_files(
+                &metadata,
+                &download_response.files,
+                resume,
+                cancel_token,
+                progress_callback,
+            )
+            .await;
+        if let Err(GameVersionError::VerificationFailed { file, reason }) = &downloaded {
+            self.event_bus
+                .game_verification_failed(game_id, game_name.clone(), file.clone(), reason.clone());
+        }
+        downloaded?;
 
         // Download background image if provided
         if let Some(ref bg_url) = download_response.background_image_url {
@@ -335,12 +771,6 @@ impl GameVersionService {
             }
         }
 
-        // Save local metadata
-        let metadata = LocalGameMetadata::new(game_id, game_name.clone(), version, version_id);
-        self.repository
-            .save_local_metadata(&game_name, &metadata)
-            .await?;
-
         // Update cache with new metadata
         self.cache_repository
             .update_local_metadata(game_id, metadata.clone())
@@ -352,8 +782,10 @@ impl GameVersionService {
             .report_version_status(game_id, Some(version_id))
             .await?;
 
+        self.refresh_installed_size(game_id, &game_name).await;
+
         // Emit completion event (100%)
-        self.event_bus.game_download_progress(game_id, game_name.clone(), 100.0);
+        self.event_bus.game_download_progress(game_id, game_name.clone(), 100.0, None);
 
         // Clear progress tracking after delay to allow UI to show completion
         let progress_map = Arc::clone(&self.download_progress);
@@ -366,6 +798,122 @@ impl GameVersionService {
         Ok(())
     }
 
+    /// Delete an installed game's files to reclaim disk space
+    /// Only files recorded for the installed version are removed. Refuses while the
+    /// game is `running_game` or has a download queued, running or paused.
+    /// Returns the number of bytes freed.
+    pub async fn delete_game(&self, game_id: i32, running_game: Option<&str>) -> Result<u64, GameVersionError> {
+        let entry = self
+            .cache_repository
+            .get_all_entries()
+            .await
+            .map_err(|e| GameVersionError::InvalidMetadata(format!("Cache error: {}", e)))?
+            .into_iter()
+            .find(|e| e.game_id == game_id)
+            .ok_or_else(|| GameVersionError::InvalidMetadata(format!("Unknown game {}", game_id)))?;
+        let game_name = entry.game_name;
+
+        if running_game == Some(game_name.as_str()) {
+            return Err(GameVersionError::GameRunning(game_name));
+        }
+
+        // Marked under the downloads lock, so a download can't start until the
+        // files are gone; the lock itself isn't held across the manifest fetch
+        {
+            let active_downloads = self.active_downloads.write().await;
+            if active_downloads.contains_key(&game_id)
+                || self.download_progress.read().await.contains_key(&game_id)
+            {
+                return Err(GameVersionError::AlreadyDownloading(game_id));
+            }
+            if !self.deleting_games.write().await.insert(game_id) {
+                return Err(GameVersionError::Deleting(game_id));
+            }
+        }
+
+        let result = self.remove_game_files(game_id, &game_name).await;
+        self.deleting_games.write().await.remove(&game_id);
+        let (freed, installed_version) = result?;
+
+        if let Err(e) = self.repository.report_version_status(game_id, None).await {
+            tracing::warn!("Failed to report deletion of {} to Alakazam: {}", game_name, e);
+        }
+
+        self.event_bus.game_deleted(game_id, game_name.clone(), freed);
+        tracing::info!("Deleted {} v{}, freed {} bytes", game_name, installed_version, freed);
+        Ok(freed)
+    }
+
+    /// Remove the installed version's files and forget its local metadata
+    /// Returns the bytes freed and the version that was installed.
+    async fn remove_game_files(&self, game_id: i32, game_name: &str) -> Result<(u64, String), GameVersionError> {
+        let installed = self
+            .repository
+            .get_local_metadata(game_name)
+            .await?
+            .ok_or_else(|| GameVersionError::InvalidMetadata(format!("{} is not installed", game_name)))?;
+
+        // Games installed before manifests were recorded fall back to the server's
+        // file list, which only describes the installed files if it is still assigned
+        let files = if !installed.files.is_empty() {
+            installed.files
+        } else {
+            let download_response = self.fetch_download_urls(game_id).await?;
+            if download_response.version_id != installed.installed_version_id {
+                return Err(GameVersionError::InvalidMetadata(format!(
+                    "No file list is recorded for {} v{}; update the game before deleting it",
+                    game_name, installed.installed_version
+                )));
+            }
+            download_response.files.into_iter().map(|f| f.path).collect()
+        };
+
+        let freed = self.repository.delete_game_files(game_name, &files).await?;
+
+        self.cache_repository
+            .clear_local_metadata(game_id)
+            .await
+            .map_err(|e| GameVersionError::InvalidMetadata(format!("Cache update error: {}", e)))?;
+
+        Ok((freed, installed.installed_version))
+    }
+
+    /// Measure a game's on-disk size and store it in the cache
+    /// Returns `None` if the directory couldn't be read.
+    async fn refresh_installed_size(&self, game_id: i32, game_name: &str) -> Option<u64> {
+        let bytes = match self.repository.installed_size(game_name).await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                tracing::warn!("Failed to measure installed size of {}: {}", game_name, e);
+                return None;
+            }
+        };
+
+        if let Err(e) = self.cache_repository.set_installed_size(game_id, bytes).await {
+            tracing::warn!("Failed to cache installed size of {}: {}", game_name, e);
+        }
+        Some(bytes)
+    }
+
+    /// Record that a game was just launched, matched by its installed name
+    pub async fn record_game_played(&self, game_name: &str) -> Result<(), GameVersionError> {
+        let entries = self
+            .cache_repository
+            .get_all_entries()
+            .await
+            .map_err(|e| GameVersionError::InvalidMetadata(format!("Cache error: {}", e)))?;
+
+        let Some(entry) = entries.into_iter().find(|e| e.game_name == game_name) else {
+            tracing::debug!("Launched game {} is not in the game cache", game_name);
+            return Ok(());
+        };
+
+        self.cache_repository
+            .record_played(entry.game_id, Utc::now())
+            .await
+            .map_err(|e| GameVersionError::InvalidMetadata(format!("Cache error: {}", e)))
+    }
+
     /// Check for updates on startup (don't auto-download)
     pub async fn check_for_updates(&self) -> Result<Vec<GameStatus>, GameVersionError> {
         tracing::info!("Checking for game updates...");
@@ -393,12 +941,149 @@ impl GameVersionService {
         Ok(statuses)
     }
 
-    /// Cancel an ongoing download
+    /// Cancel an ongoing or queued download
+    /// A queued download is simply dropped from the queue. An active download keeps
+    /// its partial files so it can be resumed, and frees its slot for the next game.
     pub async fn cancel_download(&self, game_id: i32) {
+        if let Some(token) = self.active_downloads.write().await.remove(&game_id) {
+            token.cancel();
+        }
         self.download_progress.write().await.remove(&game_id);
+        self.event_bus.game_download_state_changed(
+            game_id,
+            self.cached_game_name(game_id).await,
+            DownloadState::Cancelled,
+        );
         tracing::info!("Cancelled download for game {}", game_id);
     }
 
+    /// Pause an ongoing or queued download, freeing its slot and bandwidth
+    /// Partial files and progress are kept; `resume_download` continues from them.
+    pub async fn pause_download(&self, game_id: i32) -> Result<(), GameVersionError> {
+        let token = self
+            .active_downloads
+            .write()
+            .await
+            .remove(&game_id)
+            .ok_or(GameVersionError::NotDownloading(game_id))?;
+
+        {
+            // Mark the download paused before cancelling it, so the download task
+            // sees a pause rather than a cancellation when it winds down. Both happen
+            // under the progress lock so `install_game` can't replace the entry.
+            let mut progress_map = self.download_progress.write().await;
+            let progress = progress_map
+                .entry(game_id)
+                .or_insert_with(|| DownloadProgress::starting(None));
+            progress.state = DownloadState::Paused;
+            progress.queue_position = None;
+            token.cancel();
+        }
+
+        self.event_bus.game_download_state_changed(
+            game_id,
+            self.cached_game_name(game_id).await,
+            DownloadState::Paused,
+        );
+        tracing::info!("Paused download for game {}", game_id);
+        Ok(())
+    }
+
+    /// Resume a paused download, continuing from its partial files
+    /// Like `download_and_install_game`, this returns once the download finishes.
+    pub async fn resume_download(&self, game_id: i32) -> Result<(), GameVersionError> {
+        {
+            let mut progress_map = self.download_progress.write().await;
+            match progress_map.get_mut(&game_id) {
+                Some(progress) if progress.state == DownloadState::Paused => {
+                    progress.state = DownloadState::Running;
+                }
+                _ => return Err(GameVersionError::NotPaused(game_id)),
+            }
+        }
+
+        self.event_bus.game_download_state_changed(
+            game_id,
+            self.cached_game_name(game_id).await,
+            DownloadState::Running,
+        );
+        tracing::info!("Resuming download for game {}", game_id);
+
+        self.download_and_install_game(game_id, true).await
+    }
+
+    /// Compute which files updating a game would add, replace and remove
+    /// Nothing is downloaded; use this to show the download size before updating.
+    pub async fn preview_update(&self, game_id: i32) -> Result<UpdatePreview, GameVersionError> {
+        let download_response = self.fetch_download_urls(game_id).await?;
+        let game_name = download_response.game_name.clone();
+
+        let installed_version = self
+            .repository
+            .get_local_metadata(&game_name)
+            .await?
+            .map(|metadata| metadata.installed_version);
+
+        let plan = self
+            .repository
+            .plan_update(&game_name, &download_response.files)
+            .await?;
+
+        Ok(UpdatePreview {
+            game_id,
+            game_name,
+            installed_version,
+            version: download_response.version,
+            plan,
+        })
+    }
+
+    /// Re-check an installed game's files against the sizes and checksums on the server
+    /// Only the assigned version's file list is available, so a game with an older
+    /// version installed can't be verified until it is updated.
+    pub async fn verify_game(&self, game_id: i32) -> Result<GameVerification, GameVersionError> {
+        let download_response = self.fetch_download_urls(game_id).await?;
+        let game_name = download_response.game_name.clone();
+
+        let installed = self
+            .repository
+            .get_local_metadata(&game_name)
+            .await?
+            .ok_or_else(|| GameVersionError::InvalidMetadata(format!("{} is not installed", game_name)))?;
+
+        if installed.installed_version_id != download_response.version_id {
+            return Err(GameVersionError::InvalidMetadata(format!(
+                "{} v{} is installed but v{} is assigned; update the game instead",
+                game_name, installed.installed_version, download_response.version
+            )));
+        }
+
+        let failures = self
+            .repository
+            .verify_game_files(&game_name, &download_response.files)
+            .await?;
+
+        if failures.is_empty() {
+            tracing::info!("Verified {} v{}", game_name, download_response.version);
+        } else {
+            tracing::warn!(
+                "{} of {} files failed verification for {} v{}",
+                failures.len(),
+                download_response.files.len(),
+                game_name,
+                download_response.version
+            );
+        }
+
+        Ok(GameVerification {
+            game_id,
+            game_name,
+            version: download_response.version,
+            checked_files: download_response.files.len(),
+            failures,
+        })
+    }
+
     /// Force refresh games from server (requires internet connection)
     pub async fn force_refresh(&self) -> Result<Vec<GameStatus>, GameVersionError> {
         let online = self.sync_cache_with_server().await.unwrap_or(false);
@@ -412,3 +1097,132 @@ impl GameVersionService {
         self.get_game_statuses().await
     }
 }
+
+/// Order the download queue: active, paused, queued (in queue order), then completed
+/// A game that is both queued and in the active set is still waiting for a
+/// slot, so it is listed as queued.
+fn assemble_download_queue(
+    queue: &[(i32, String)],
+    active_ids: &[i32],
+    progress: &std::collections::HashMap<i32, DownloadProgress>,
+    names: &std::collections::HashMap<i32, String>,
+    completed: Vec<DownloadQueueEntry>,
+) -> Vec<DownloadQueueEntry> {
+    let is_queued = |id: &i32| queue.iter().any(|(queued_id, _)| queued_id == id);
+    let percentage = |id: &i32| progress.get(id).map_or(0.0, |p| p.percentage);
+    let name = |id: &i32| names.get(id).cloned().unwrap_or_else(|| format!("Game {}", id));
+    let running_entry = |game_id: i32, status| DownloadQueueEntry {
+        percentage: percentage(&game_id),
+        game_name: name(&game_id),
+        game_id,
+        status,
+        queue_position: None,
+        finished_at: None,
+    };
+
+    let mut active: Vec<i32> = active_ids.iter().copied().filter(|id| !is_queued(id)).collect();
+    active.sort_unstable();
+
+    let mut paused: Vec<i32> = progress
+        .iter()
+        .filter(|(id, p)| p.state == DownloadState::Paused && !is_queued(id) && !active.contains(id))
+        .map(|(id, _)| *id)
+        .collect();
+    paused.sort_unstable();
+
+    let mut entries = Vec::with_capacity(active.len() + paused.len() + queue.len() + completed.len());
+    entries.extend(active.into_iter().map(|id| running_entry(id, DownloadQueueStatus::Active)));
+    entries.extend(paused.into_iter().map(|id| running_entry(id, DownloadQueueStatus::Paused)));
+    for (index, (game_id, game_name)) in queue.iter().enumerate() {
+        entries.push(DownloadQueueEntry {
+            percentage: percentage(game_id),
+            game_id: *game_id,
+            game_name: game_name.clone(),
+            status: DownloadQueueStatus::Queued,
+            queue_position: Some(index + 1),
+            finished_at: None,
+        });
+    }
+    entries.extend(completed);
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn progress(percentage: f32, state: DownloadState) -> DownloadProgress {
+        DownloadProgress {
+            total_files: 10,
+            downloaded_files: 0,
+            current_file: String::new(),
+            downloaded_bytes: 0,
+            total_bytes: None,
+            percentage,
+            queue_position: None,
+            state,
+        }
+    }
+
+    fn completed(game_id: i32) -> DownloadQueueEntry {
+        DownloadQueueEntry {
+            game_id,
+            game_name: format!("Done {}", game_id),
+            status: DownloadQueueStatus::Completed,
+            queue_position: None,
+            percentage: 100.0,
+            finished_at: Some(Utc::now()),
+        }
+    }
+
+    #[test]
+    fn queue_lists_active_paused_queued_then_completed() {
+        let queue = vec![(4, "Fourth".to_string()), (3, "Third".to_string())];
+        let active_ids = vec![2, 4];
+        let progress_map = HashMap::from([
+            (1, progress(40.0, DownloadState::Paused)),
+            (2, progress(75.0, DownloadState::Running)),
+            (5, progress(10.0, DownloadState::Cancelled)),
+        ]);
+        let names = HashMap::from([(1, "First".to_string()), (2, "Second".to_string())]);
+
+        let entries = assemble_download_queue(&queue, &active_ids, &progress_map, &names, vec![completed(9)]);
+
+        let summary: Vec<(i32, DownloadQueueStatus, Option<usize>)> = entries
+            .iter()
+            .map(|e| (e.game_id, e.status, e.queue_position))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (2, DownloadQueueStatus::Active, None),
+                (1, DownloadQueueStatus::Paused, None),
+                (4, DownloadQueueStatus::Queued, Some(1)),
+                (3, DownloadQueueStatus::Queued, Some(2)),
+                (9, DownloadQueueStatus::Completed, None),
+            ]
+        );
+        assert_eq!(entries[1].game_name, "First");
+        assert_eq!(entries[1].percentage, 40.0);
+    }
+
+    #[test]
+    fn queued_game_is_never_also_listed_as_paused() {
+        let queue = vec![(1, "First".to_string())];
+        let progress_map = HashMap::from([(1, progress(40.0, DownloadState::Paused))]);
+
+        let entries = assemble_download_queue(&queue, &[], &progress_map, &HashMap::new(), Vec::new());
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].status, DownloadQueueStatus::Queued);
+    }
+
+    #[test]
+    fn unknown_names_fall_back_to_the_game_id() {
+        let entries = assemble_download_queue(&[], &[7], &HashMap::new(), &HashMap::new(), Vec::new());
+
+        assert_eq!(entries[0].game_name, "Game 7");
+        assert_eq!(entries[0].percentage, 0.0);
+    }
+}
@@ -1,4 +1,5 @@
 pub mod apk_app_service;
+pub mod app_watchdog;
 pub mod battery_monitor;
 pub mod client_apk_service;
 pub mod device_app_service;
@@ -6,13 +7,16 @@ pub mod update_service;
 pub mod game_app_service;
 pub mod game_version_service;
 pub mod http_server_service;
+pub mod packet_trace_service;
 pub mod sensor_service;
 
 pub use apk_app_service::ApkApplicationService;
+pub use app_watchdog::AppWatchdog;
 pub use battery_monitor::BatteryMonitor;
 pub use client_apk_service::ClientApkService;
 pub use device_app_service::{ApplicationError, DeviceApplicationService};
 pub use game_app_service::GameApplicationService;
-pub use game_version_service::{GameVersionService, GameStatus};
+pub use game_version_service::{DownloadQueueEntry, DownloadQueueStatus, DownloadState, GameVerification, GameVersionService, GameStatus, UpdatePreview};
 pub use http_server_service::HttpServerService;
+pub use packet_trace_service::PacketTraceService;
 pub use sensor_service::SensorService;
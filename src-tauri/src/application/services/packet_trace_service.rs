@@ -0,0 +1,54 @@
+use crate::app::EventBus;
+use crate::application::dto::PacketTraceDto;
+use crate::domain::models::DeviceId;
+use crate::domain::services::{PacketTraceHook, SessionManager};
+use std::sync::Arc;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PacketTraceError {
+    #[error("Device {0} is not connected")]
+    NotConnected(DeviceId),
+}
+
+/// Streams a device's raw packets to the frontend while a trace is active
+///
+/// Packets are picked up by the server's packet log, which only calls hooks of
+/// traced devices; the same log does server-wide debug logging and captures.
+/// A trace ends when stopped or when the device disconnects.
+pub struct PacketTraceService {
+    session_manager: Arc<dyn SessionManager>,
+    event_bus: Arc<EventBus>,
+}
+
+impl PacketTraceService {
+    pub fn new(session_manager: Arc<dyn SessionManager>, event_bus: Arc<EventBus>) -> Self {
+        Self {
+            session_manager,
+            event_bus,
+        }
+    }
+
+    pub fn start_trace(&self, device_id: DeviceId) -> Result<(), PacketTraceError> {
+        let event_bus = self.event_bus.clone();
+        let uuid = device_id.as_uuid();
+        let hook: PacketTraceHook = Arc::new(move |direction, packet| {
+            event_bus.packet_traced(PacketTraceDto::new(uuid, direction, packet));
+        });
+
+        if !self.session_manager.set_packet_trace(&device_id, Some(hook)) {
+            return Err(PacketTraceError::NotConnected(device_id));
+        }
+
+        tracing::info!(device_id = %device_id, "Packet trace started");
+        Ok(())
+    }
+
+    pub fn stop_trace(&self, device_id: DeviceId) -> Result<(), PacketTraceError> {
+        if !self.session_manager.set_packet_trace(&device_id, None) {
+            return Err(PacketTraceError::NotConnected(device_id));
+        }
+
+        tracing::info!(device_id = %device_id, "Packet trace stopped");
+        Ok(())
+    }
+}
@@ -1,13 +1,15 @@
 use crate::app::events::EventBus;
-use crate::app::models::config::AlakazamConfig;
+use crate::app::models::config::{AlakazamConfig, DfuConfig, SensorFirmwareConfig};
 use crate::app::config::get_machine_id;
-use crate::domain::models::{Sensor, SensorConnectionStatus};
+use crate::domain::models::{Sensor, SensorConnectionStatus, SensorFlashResult};
 use crate::infrastructure::sensor::{
-    DfuUploader, FirmwarePatcher, SensorError, SerialComm, XiaoDetector, XiaoMode,
+    BoardTarget, DfuProgress, DfuUploader, FirmwarePatcher, SensorError, SerialComm, UploadOutcome,
+    XiaoDetector, XiaoMode,
 };
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
 
 /// Result type for sensor service operations
 pub type Result<T> = std::result::Result<T, SensorServiceError>;
@@ -36,41 +38,71 @@ pub struct SensorService {
     serial_lock: Mutex<()>,
     event_bus: Arc<EventBus>,
     alakazam_config: AlakazamConfig,
+    dfu_config: DfuConfig,
+    firmware_config: SensorFirmwareConfig,
+    /// Cancels the upload in progress, if any
+    upload_cancel: parking_lot::Mutex<Option<CancellationToken>>,
 }
 
 impl SensorService {
-    pub fn new(event_bus: Arc<EventBus>, alakazam_config: AlakazamConfig) -> Self {
+    pub fn new(
+        event_bus: Arc<EventBus>,
+        alakazam_config: AlakazamConfig,
+        dfu_config: DfuConfig,
+        firmware_config: SensorFirmwareConfig,
+    ) -> Self {
         Self {
             serial_lock: Mutex::new(()),
             event_bus,
             alakazam_config,
+            dfu_config,
+            firmware_config,
+            upload_cancel: parking_lot::Mutex::new(None),
         }
     }
 
     /// List all connected sensors (fast - doesn't open serial ports)
+    /// Boards in bootloader mode are reported as such, ready to flash without a 1200-baud touch.
     pub async fn list_sensors(&self) -> Result<Vec<Sensor>> {
         let ports = XiaoDetector::find_all();
 
         let sensors: Vec<Sensor> = ports
             .into_iter()
-            .map(|port| Sensor::from_port(&port.port, port.mode == XiaoMode::Bootloader))
+            .map(|port| {
+                Sensor::from_port(&port.port, port.mode == XiaoMode::Bootloader)
+                    .with_usb_serial(port.serial_number)
+            })
             .collect();
 
         Ok(sensors)
     }
 
     /// Get detailed info for a specific sensor by port (opens serial port)
+    /// A board in bootloader mode has no serial console; it is returned with
+    /// the `Bootloader` status and no firmware details instead of timing out.
     pub async fn get_sensor_info(&self, port: &str) -> Result<Sensor> {
         let _guard = self.serial_lock.lock().await;
 
         let port_name = port.to_string();
 
+        // Enumerating USB ports blocks just like the serial query does
         let info = tokio::task::spawn_blocking(move || {
-            SerialComm::open_and_get_info(&port_name, 3)
+            let in_bootloader = XiaoDetector::find_all()
+                .iter()
+                .any(|d| d.port == port_name && d.mode == XiaoMode::Bootloader);
+            if in_bootloader {
+                return Ok(None);
+            }
+            SerialComm::open_and_get_info(&port_name, 3).map(Some)
         })
         .await
         .map_err(|e| SensorServiceError::OperationFailed(e.to_string()))??;
 
+        let Some(info) = info else {
+            tracing::debug!(port = %port, "Sensor is in bootloader mode, skipping info query");
+            return Ok(Sensor::from_port(port, true));
+        };
+
         let sensor = Sensor::new(port.to_string(), SensorConnectionStatus::Connected)
             .with_info(
                 info.serial_number,
@@ -84,36 +116,16 @@ impl SensorService {
     }
 
     /// Upload firmware to a sensor with a custom device name
+    /// Returns `false` if the board already ran `skip_if_version` and was not flashed.
     pub async fn upload_firmware(
         &self,
         port: Option<&str>,
         firmware_path: PathBuf,
         device_name: &str,
-    ) -> Result<()> {
-        if !firmware_path.exists() {
-            return Err(SensorServiceError::FirmwareNotFound(
-                firmware_path.display().to_string(),
-            ));
-        }
-
-        if firmware_path.extension().and_then(|s| s.to_str()) != Some("bin") {
-            return Err(SensorServiceError::InvalidFirmware(
-                "Firmware must have .bin extension".to_string(),
-            ));
-        }
-
-        if device_name.trim().is_empty() {
-            return Err(SensorServiceError::InvalidFirmware(
-                "Device name cannot be empty".to_string(),
-            ));
-        }
-
-        if device_name.len() > FirmwarePatcher::max_name_length() {
-            return Err(SensorServiceError::InvalidFirmware(format!(
-                "Device name too long (max {} characters)",
-                FirmwarePatcher::max_name_length()
-            )));
-        }
+        skip_if_version: Option<&str>,
+    ) -> Result<bool> {
+        Self::validate_firmware_path(&firmware_path)?;
+        Self::validate_device_name(device_name)?;
 
         // Acquire serial lock to prevent concurrent port access
         let _guard = self.serial_lock.lock().await;
@@ -135,18 +147,43 @@ impl SensorService {
 
         let event_bus = self.event_bus.clone();
         let progress_port = port_str.clone();
-        let on_progress: Arc<dyn Fn(f32) + Send + Sync> = Arc::new(move |pct| {
-            event_bus.sensor_upload_progress(
+        let on_progress = Arc::new(move |progress: DfuProgress| {
+            event_bus.sensor_flash_progress(
                 progress_port.clone(),
-                "uploading".to_string(),
-                pct,
+                progress.phase.as_str().to_string(),
+                progress.bytes_sent as u64,
+                progress.total_bytes as u64,
+                progress.attempt,
             );
         });
 
-        let result = DfuUploader::upload_with_name(port, &firmware_path, device_name, on_progress).await;
+        let cancel = self.begin_upload();
+        let result = DfuUploader::upload_with_name(
+            port,
+            &firmware_path,
+            device_name,
+            skip_if_version,
+            &self.dfu_config,
+            &cancel,
+            on_progress,
+        )
+        .await;
+        self.end_upload();
 
         match &result {
-            Ok(()) => {
+            Ok(UploadOutcome::AlreadyCurrent { version }) => {
+                self.event_bus.sensor_upload_progress(
+                    port_str,
+                    "skipped".to_string(),
+                    100.0,
+                );
+                tracing::info!(
+                    device_name = %device_name,
+                    version = %version,
+                    "Sensor already up to date, firmware upload skipped"
+                );
+            }
+            Ok(UploadOutcome::Flashed) => {
                 self.event_bus.sensor_upload_progress(
                     port_str,
                     "completed".to_string(),
@@ -174,13 +211,268 @@ impl SensorService {
             }
         }
 
-        result?;
+        Ok(result? == UploadOutcome::Flashed)
+    }
+
+    /// Bring a sensor up to a known-good firmware image, keeping its device name
+    ///
+    /// The image defaults to the configured or bundled one, and the version it
+    /// installs is read from the image. The board's current version and name
+    /// are read over serial; it is only flashed if the version differs, or
+    /// with `force`. Returns whether the board was flashed.
+    pub async fn update_firmware(
+        &self,
+        port: Option<&str>,
+        firmware_path: Option<PathBuf>,
+        force: bool,
+    ) -> Result<bool> {
+        let firmware_path = firmware_path
+            .or_else(|| self.firmware_config.path.clone())
+            .ok_or_else(|| {
+                SensorServiceError::OperationFailed(
+                    "No firmware image given, configured or bundled".to_string(),
+                )
+            })?;
+        Self::validate_firmware_path(&firmware_path)?;
+
+        let image = tokio::fs::read(&firmware_path).await.map_err(|e| {
+            SensorServiceError::OperationFailed(format!(
+                "Failed to read {}: {}",
+                firmware_path.display(),
+                e
+            ))
+        })?;
+        let version = FirmwarePatcher::embedded_version(&image);
+        if version.is_none() {
+            tracing::warn!(
+                firmware = %firmware_path.display(),
+                "Firmware image doesn't carry its version, boards will be flashed regardless"
+            );
+        }
+
+        let port = match port {
+            Some(port) => port.to_string(),
+            None => XiaoDetector::find_first()?.port,
+        };
+
+        let identity = {
+            let _guard = self.serial_lock.lock().await;
+
+            let port_name = port.clone();
+            tokio::task::spawn_blocking(move || {
+                let in_bootloader = XiaoDetector::find_all()
+                    .iter()
+                    .any(|d| d.port == port_name && d.mode == XiaoMode::Bootloader);
+                if in_bootloader {
+                    return Ok(None);
+                }
+                SerialComm::read_firmware_identity(&port_name).map(Some)
+            })
+            .await
+            .map_err(|e| SensorServiceError::OperationFailed(e.to_string()))??
+        };
+
+        let Some(identity) = identity else {
+            return Err(SensorServiceError::OperationFailed(format!(
+                "{} is in bootloader mode, so its device name can't be read; \
+                 flash it with an explicit name instead",
+                port
+            )));
+        };
+
+        let device_name = identity.device_name.ok_or_else(|| {
+            SensorServiceError::OperationFailed(format!(
+                "{} did not report its device name, refusing to flash without one",
+                port
+            ))
+        })?;
+
+        tracing::info!(
+            port = %port,
+            device_name = %device_name,
+            current = ?identity.version,
+            target = ?version,
+            force,
+            "Checking sensor firmware"
+        );
+
+        if !force && firmware_is_current(identity.version.as_deref(), version.as_deref()) {
+            self.event_bus
+                .sensor_upload_progress(port.clone(), "skipped".to_string(), 100.0);
+            tracing::info!(
+                port = %port,
+                device_name = %device_name,
+                "Sensor already runs the target firmware, update skipped"
+            );
+            return Ok(false);
+        }
+
+        self.upload_firmware(Some(&port), firmware_path, &device_name, None)
+            .await
+    }
+
+    /// Flash firmware to several sensors in sequence, one device name per port
+    /// A failing board doesn't stop the batch; each port's outcome is returned.
+    pub async fn upload_firmware_batch(
+        &self,
+        firmware_path: PathBuf,
+        boards: Vec<BoardTarget>,
+    ) -> Result<Vec<SensorFlashResult>> {
+        Self::validate_firmware_path(&firmware_path)?;
+
+        if boards.is_empty() {
+            return Err(SensorServiceError::OperationFailed(
+                "No sensors selected for upload".to_string(),
+            ));
+        }
+
+        for board in &boards {
+            Self::validate_device_name(&board.device_name)?;
+        }
+
+        let _guard = self.serial_lock.lock().await;
+
+        tracing::info!(
+            firmware = %firmware_path.display(),
+            boards = boards.len(),
+            "Starting batch firmware upload"
+        );
+
+        for board in &boards {
+            self.event_bus
+                .sensor_upload_progress(board.port.clone(), "queued".to_string(), 0.0);
+        }
+
+        let event_bus = self.event_bus.clone();
+        let on_progress = Arc::new(move |port: &str, progress: DfuProgress| {
+            event_bus.sensor_flash_progress(
+                port.to_string(),
+                progress.phase.as_str().to_string(),
+                progress.bytes_sent as u64,
+                progress.total_bytes as u64,
+                progress.attempt,
+            );
+        });
+
+        let cancel = self.begin_upload();
+        let results =
+            DfuUploader::upload_batch(&firmware_path, &boards, &self.dfu_config, &cancel, on_progress)
+                .await;
+        self.end_upload();
+        let results = results?;
+
+        let results: Vec<SensorFlashResult> = results
+            .into_iter()
+            .map(|board| {
+                let (stage, percentage) = match &board.result {
+                    Ok(()) => ("completed", 100.0),
+                    Err(_) => ("failed", 0.0),
+                };
+                self.event_bus
+                    .sensor_upload_progress(board.port.clone(), stage.to_string(), percentage);
+
+                SensorFlashResult {
+                    port: board.port,
+                    device_name: board.device_name,
+                    success: board.result.is_ok(),
+                    error: board.result.err().map(|e| e.to_string()),
+                }
+            })
+            .collect();
+
+        let failed = results.iter().filter(|r| !r.success).count();
+        tracing::info!(
+            succeeded = results.len() - failed,
+            failed,
+            "Batch firmware upload finished"
+        );
+
+        Ok(results)
+    }
+
+    /// Batch targets for every connected sensor, named `<prefix>1`, `<prefix>2`, ...
+    /// Boards are numbered in port order.
+    pub async fn connected_targets(&self, name_prefix: &str) -> Result<Vec<BoardTarget>> {
+        let _guard = self.serial_lock.lock().await;
+        Ok(DfuUploader::targets_for_all(|index, _| {
+            format!("{}{}", name_prefix, index + 1)
+        })?)
+    }
+
+    fn validate_firmware_path(firmware_path: &Path) -> Result<()> {
+        if !firmware_path.exists() {
+            return Err(SensorServiceError::FirmwareNotFound(
+                firmware_path.display().to_string(),
+            ));
+        }
+
+        if firmware_path.extension().and_then(|s| s.to_str()) != Some("bin") {
+            return Err(SensorServiceError::InvalidFirmware(
+                "Firmware must have .bin extension".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Stop the firmware upload in progress
+    /// Returns `false` if nothing was being flashed.
+    pub fn cancel_upload(&self) -> bool {
+        match self.upload_cancel.lock().as_ref() {
+            Some(cancel) => {
+                tracing::info!("Cancelling firmware upload");
+                cancel.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Register a new upload; callers hold `serial_lock`, so there is only ever one
+    fn begin_upload(&self) -> CancellationToken {
+        let cancel = CancellationToken::new();
+        *self.upload_cancel.lock() = Some(cancel.clone());
+        cancel
+    }
+
+    fn end_upload(&self) {
+        *self.upload_cancel.lock() = None;
+    }
+
+    /// Check a device name before anything is flashed
+    pub fn validate_device_name(device_name: &str) -> Result<()> {
+        FirmwarePatcher::validate_name(device_name)?;
         Ok(())
     }
 
+    /// Copy the firmware currently on a sensor to `output_path`
+    /// The file is only created once the full image has been read.
+    pub async fn backup_firmware(&self, port: &str, output_path: PathBuf) -> Result<u64> {
+        let _guard = self.serial_lock.lock().await;
+
+        let image = DfuUploader::read_firmware(port).await?;
+
+        let partial_path = output_path.with_extension("part");
+        tokio::fs::write(&partial_path, &image)
+            .await
+            .map_err(SensorError::Io)?;
+        tokio::fs::rename(&partial_path, &output_path)
+            .await
+            .map_err(SensorError::Io)?;
+
+        tracing::info!(
+            port = %port,
+            path = %output_path.display(),
+            bytes = image.len(),
+            "Sensor firmware backed up"
+        );
+
+        Ok(image.len() as u64)
+    }
+
     /// Get the maximum allowed device name length
     pub fn max_device_name_length(&self) -> usize {
-        FirmwarePatcher::max_name_length()
+        FirmwarePatcher::MAX_NAME_LEN
     }
 
     /// Check if a firmware file contains the name placeholder
@@ -277,3 +569,26 @@ impl Default for SensorService {
         panic!("SensorService requires EventBus and AlakazamConfig — use SensorService::new()")
     }
 }
+
+/// Whether a board reporting `current` already runs the image versioned `target`
+/// An unknown version on either side counts as out of date.
+fn firmware_is_current(current: Option<&str>, target: Option<&str>) -> bool {
+    match (current, target) {
+        (Some(current), Some(target)) => current.trim() == target.trim(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_a_matching_known_version_is_current() {
+        assert!(firmware_is_current(Some("1.4.2"), Some("1.4.2")));
+        assert!(firmware_is_current(Some(" 1.4.2 "), Some("1.4.2")));
+        assert!(!firmware_is_current(Some("1.4.1"), Some("1.4.2")));
+        assert!(!firmware_is_current(None, Some("1.4.2")));
+        assert!(!firmware_is_current(Some("1.4.2"), None));
+    }
+}
@@ -1,6 +1,7 @@
-use crate::app::models::update::{UpdateInfo, UpdateProgress, UpdateStatus};
+use crate::app::models::update::{UpdateChannel, UpdateInfo, UpdateProgress, UpdateStatus};
+use std::path::PathBuf;
 use std::sync::Arc;
-use tauri::{AppHandle, Emitter};
+use tauri::{AppHandle, Emitter, Url};
 use tauri_plugin_updater::{Update, UpdaterExt};
 use tokio::sync::Mutex;
 
@@ -10,22 +11,69 @@ const GITHUB_ACCEPT_HEADER: &str = "application/vnd.github.v3+json";
 pub struct UpdateService {
     app_handle: AppHandle,
     current_update: Arc<Mutex<Option<Update>>>,
+    channel: UpdateChannel,
+    /// Where the picked channel is kept across restarts
+    channel_file: PathBuf,
 }
 
 impl UpdateService {
-    pub fn new(app_handle: AppHandle) -> Self {
+    /// Uses the channel saved in `channel_file`, or `default_channel` if none was picked yet
+    pub fn new(app_handle: AppHandle, channel_file: PathBuf, default_channel: UpdateChannel) -> Self {
+        let channel = match std::fs::read_to_string(&channel_file) {
+            Ok(saved) => saved.parse().unwrap_or_else(|e| {
+                tracing::warn!("Ignoring saved update channel: {}", e);
+                default_channel
+            }),
+            Err(_) => default_channel,
+        };
+
         Self {
             app_handle,
             current_update: Arc::new(Mutex::new(None)),
+            channel,
+            channel_file,
+        }
+    }
+
+    pub fn channel(&self) -> UpdateChannel {
+        self.channel
+    }
+
+    /// Switch channels; any update found on the old channel is forgotten
+    pub async fn set_channel(&mut self, channel: UpdateChannel) -> Result<(), String> {
+        std::fs::write(&self.channel_file, channel.as_str())
+            .map_err(|e| format!("Failed to save update channel: {}", e))?;
+
+        if channel != self.channel {
+            tracing::info!(from = self.channel.as_str(), to = channel.as_str(), "Update channel changed");
+            *self.current_update.lock().await = None;
         }
+        self.channel = channel;
+        Ok(())
     }
 
     pub async fn check_for_updates(&self) -> Result<UpdateStatus, String> {
         self.emit_status(UpdateStatus::Checking);
 
+        let channel = self.channel;
+        let manifest_url: Url = channel
+            .manifest_url()
+            .parse()
+            .map_err(|e| format!("Invalid update manifest URL: {}", e))?;
+
         let updater = self
             .app_handle
             .updater_builder()
+            .endpoints(vec![manifest_url])
+            .map_err(|e| format!("Failed to set update endpoint: {}", e))?
+            // Stable offers its latest release to a beta install even if it's
+            // older, so leaving the beta is possible; installing it needs confirmation
+            .version_comparator(move |current, remote| {
+                remote.version > current
+                    || (channel == UpdateChannel::Stable
+                        && !current.pre.is_empty()
+                        && remote.version != current)
+            })
             .header("User-Agent", USER_AGENT)
             .map_err(|e| format!("Failed to set User-Agent header: {}", e))?
             .header("Accept", GITHUB_ACCEPT_HEADER)
@@ -43,6 +91,8 @@ impl UpdateService {
                     body: update.body.clone(),
                     date: update.date.map(|d| d.to_string()),
                     is_available: true,
+                    channel,
+                    is_downgrade: is_downgrade(&update),
                 };
 
                 *self.current_update.lock().await = Some(update);
@@ -63,13 +113,25 @@ impl UpdateService {
         }
     }
 
-    pub async fn download_and_install(&self) -> Result<(), String> {
-        let update = self
-            .current_update
-            .lock()
-            .await
-            .take()
-            .ok_or_else(|| "No update available to download".to_string())?;
+    /// Install the update found by the last check
+    /// An older release (leaving the beta channel) is only installed with `allow_downgrade`.
+    pub async fn download_and_install(&self, allow_downgrade: bool) -> Result<(), String> {
+        let update = {
+            let mut current_update = self.current_update.lock().await;
+            let update = current_update
+                .take()
+                .ok_or_else(|| "No update available to download".to_string())?;
+
+            if is_downgrade(&update) && !allow_downgrade {
+                let message = format!(
+                    "Version {} is older than the installed {}; confirm the downgrade to install it",
+                    update.version, update.current_version
+                );
+                *current_update = Some(update);
+                return Err(message);
+            }
+            update
+        };
 
         let app_handle = self.app_handle.clone();
         let bytes_downloaded = Arc::new(Mutex::new(0u64));
@@ -119,6 +181,21 @@ impl UpdateService {
     }
 }
 
-pub fn create_update_service(app_handle: AppHandle) -> Arc<Mutex<UpdateService>> {
-    Arc::new(Mutex::new(UpdateService::new(app_handle)))
+/// Whether `update` would replace the installed version with an older one
+fn is_downgrade(update: &Update) -> bool {
+    match (
+        semver::Version::parse(&update.version),
+        semver::Version::parse(&update.current_version),
+    ) {
+        (Ok(offered), Ok(installed)) => offered < installed,
+        _ => false,
+    }
+}
+
+pub fn create_update_service(
+    app_handle: AppHandle,
+    channel_file: PathBuf,
+    default_channel: UpdateChannel,
+) -> Arc<Mutex<UpdateService>> {
+    Arc::new(Mutex::new(UpdateService::new(app_handle, channel_file, default_channel)))
 }
\ No newline at end of file
@@ -79,4 +79,15 @@ pub trait Command: Send + Sync + Debug {
     fn validate(&self) -> Result<(), String> {
         Ok(())
     }
+
+    /// Opcode of the packet the device sends back in response
+    /// `None` for fire-and-forget commands that don't wait for a reply.
+    fn response_opcode(&self) -> Option<u8> {
+        None
+    }
+
+    /// Whether the command can be safely re-sent after a timeout
+    fn is_idempotent(&self) -> bool {
+        false
+    }
 }
@@ -1,7 +1,7 @@
 /// Concrete device command implementations
 /// These commands correspond to the protocol opcodes for device operations.
 use crate::domain::commands::Command;
-use crate::domain::models::PackageName;
+use crate::domain::models::{IpAddress, PackageName};
 use crate::net::io::ProtocolWriteExt;
 use crate::infrastructure::protocol::opcodes::*;
 use byteorder::WriteBytesExt;
@@ -27,6 +27,10 @@ impl Command for LaunchAppCommand {
         "launch_app"
     }
 
+    fn response_opcode(&self) -> Option<u8> {
+        Some(LAUNCH_APP_RESPONSE)
+    }
+
     fn serialize(&self) -> Result<Vec<u8>, std::io::Error> {
         let mut buffer = Vec::new();
         buffer.write_string(self.package_name.as_str())?;
@@ -60,6 +64,10 @@ impl Command for ExecuteShellCommand {
         "execute_shell"
     }
 
+    fn response_opcode(&self) -> Option<u8> {
+        Some(SHELL_EXECUTION_RESPONSE)
+    }
+
     fn serialize(&self) -> Result<Vec<u8>, std::io::Error> {
         let mut buffer = Vec::new();
         buffer.write_string(&self.command)?;
@@ -87,12 +95,47 @@ impl Command for RequestBatteryCommand {
         "request_battery"
     }
 
+    fn response_opcode(&self) -> Option<u8> {
+        Some(BATTERY_STATUS)
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
     fn serialize(&self) -> Result<Vec<u8>, std::io::Error> {
         // No payload for battery request
         Ok(Vec::new())
     }
 }
 
+/// Request storage status from a device
+#[derive(Debug, Clone)]
+pub struct RequestStorageCommand;
+
+impl Command for RequestStorageCommand {
+    fn opcode(&self) -> u8 {
+        REQUEST_STORAGE
+    }
+
+    fn name(&self) -> &'static str {
+        "request_storage"
+    }
+
+    fn response_opcode(&self) -> Option<u8> {
+        Some(STORAGE_STATUS)
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, std::io::Error> {
+        // No payload for storage request
+        Ok(Vec::new())
+    }
+}
+
 /// Request installed applications list from a device
 #[derive(Debug, Clone)]
 pub struct GetInstalledAppsCommand;
@@ -106,6 +149,14 @@ impl Command for GetInstalledAppsCommand {
         "get_installed_apps"
     }
 
+    fn response_opcode(&self) -> Option<u8> {
+        Some(INSTALLED_APPS_RESPONSE)
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
     fn serialize(&self) -> Result<Vec<u8>, std::io::Error> {
         // No payload
         Ok(Vec::new())
@@ -125,6 +176,14 @@ impl Command for PingCommand {
         "ping"
     }
 
+    fn response_opcode(&self) -> Option<u8> {
+        Some(PING_RESPONSE)
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
     fn serialize(&self) -> Result<Vec<u8>, std::io::Error> {
         use byteorder::{BigEndian, WriteBytesExt};
         
@@ -145,11 +204,31 @@ impl Command for PingCommand {
 #[derive(Debug, Clone)]
 pub struct InstallApkCommand {
     pub url: String,
+    /// Hex-encoded SHA-256 the device should check the download against
+    pub expected_sha256: Option<String>,
+    /// Wait for the device's install result instead of returning once sent
+    pub await_result: bool,
 }
 
 impl InstallApkCommand {
     pub fn new(url: String) -> Self {
-        Self { url }
+        Self {
+            url,
+            expected_sha256: None,
+            await_result: false,
+        }
+    }
+
+    pub fn with_sha256(mut self, sha256: Option<String>) -> Self {
+        self.expected_sha256 = sha256;
+        self
+    }
+
+    /// Complete only when the device reports the install result
+    /// The response payload is `[success: u8]`.
+    pub fn awaiting_result(mut self) -> Self {
+        self.await_result = true;
+        self
     }
 }
 
@@ -162,9 +241,15 @@ impl Command for InstallApkCommand {
         "install_apk"
     }
 
+    fn response_opcode(&self) -> Option<u8> {
+        self.await_result.then_some(APK_INSTALL_RESPONSE)
+    }
+
+    /// Payload: [url: string][expected_sha256: string] (empty hash = not verified)
     fn serialize(&self) -> Result<Vec<u8>, std::io::Error> {
         let mut buffer = Vec::new();
         buffer.write_string(&self.url)?;
+        buffer.write_string(self.expected_sha256.as_deref().unwrap_or(""))?;
         Ok(buffer)
     }
 
@@ -172,6 +257,11 @@ impl Command for InstallApkCommand {
         if self.url.is_empty() {
             return Err("APK URL cannot be empty".to_string());
         }
+        if let Some(sha256) = &self.expected_sha256 {
+            if sha256.len() != 64 || !sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err("Expected SHA-256 must be 64 hex characters".to_string());
+            }
+        }
         if !self.url.starts_with("http://") && !self.url.starts_with("https://") {
             return Err("APK URL must be a valid HTTP/HTTPS URL".to_string());
         }
@@ -200,6 +290,10 @@ impl Command for UninstallAppCommand {
         "uninstall_app"
     }
 
+    fn response_opcode(&self) -> Option<u8> {
+        Some(UNINSTALL_APP_RESPONSE)
+    }
+
     fn serialize(&self) -> Result<Vec<u8>, std::io::Error> {
         let mut buffer = Vec::new();
         buffer.write_string(self.package_name.as_str())?;
@@ -231,6 +325,10 @@ impl Command for SetVolumeCommand {
         "set_volume"
     }
 
+    fn response_opcode(&self) -> Option<u8> {
+        Some(VOLUME_SET_RESPONSE)
+    }
+
     fn serialize(&self) -> Result<Vec<u8>, std::io::Error> {
         let mut buffer = Vec::new();
         buffer.write_u8(self.level)?;
@@ -258,6 +356,92 @@ impl Command for GetVolumeCommand {
         "get_volume"
     }
 
+    fn response_opcode(&self) -> Option<u8> {
+        Some(VOLUME_STATUS)
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, std::io::Error> {
+        // No payload
+        Ok(Vec::new())
+    }
+}
+
+/// Lowest brightness a device can be set to, so the screen never goes fully black
+pub const MIN_BRIGHTNESS: u8 = 5;
+
+/// Set screen brightness on a device
+#[derive(Debug, Clone)]
+pub struct SetBrightnessCommand {
+    pub level: u8,
+}
+
+impl SetBrightnessCommand {
+    /// Levels below `MIN_BRIGHTNESS` are raised to it
+    pub fn new(level: u8) -> Result<Self, String> {
+        if level > 100 {
+            return Err(format!("Brightness level must be 0-100, got {}", level));
+        }
+        Ok(Self {
+            level: level.max(MIN_BRIGHTNESS),
+        })
+    }
+}
+
+impl Command for SetBrightnessCommand {
+    fn opcode(&self) -> u8 {
+        SET_BRIGHTNESS
+    }
+
+    fn name(&self) -> &'static str {
+        "set_brightness"
+    }
+
+    fn response_opcode(&self) -> Option<u8> {
+        Some(BRIGHTNESS_RESPONSE)
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, std::io::Error> {
+        let mut buffer = Vec::new();
+        buffer.write_u8(self.level)?;
+        Ok(buffer)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if !(MIN_BRIGHTNESS..=100).contains(&self.level) {
+            return Err(format!(
+                "Brightness level must be {}-100, got {}",
+                MIN_BRIGHTNESS, self.level
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Request current screen brightness from a device
+#[derive(Debug, Clone)]
+pub struct GetBrightnessCommand;
+
+impl Command for GetBrightnessCommand {
+    fn opcode(&self) -> u8 {
+        GET_BRIGHTNESS
+    }
+
+    fn name(&self) -> &'static str {
+        "get_brightness"
+    }
+
+    fn response_opcode(&self) -> Option<u8> {
+        Some(BRIGHTNESS_RESPONSE)
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
     fn serialize(&self) -> Result<Vec<u8>, std::io::Error> {
         // No payload
         Ok(Vec::new())
@@ -296,6 +480,10 @@ impl Command for CloseAllAppsCommand {
         "close_all_apps"
     }
 
+    fn response_opcode(&self) -> Option<u8> {
+        Some(CLOSE_ALL_APPS_RESPONSE)
+    }
+
     fn serialize(&self) -> Result<Vec<u8>, std::io::Error> {
         // No payload
         Ok(Vec::new())
@@ -337,8 +525,7 @@ impl ConfigureDeviceCommand {
     }
 
     fn is_valid_ip_address(ip: &str) -> bool {
-        use std::net::Ipv4Addr;
-        ip.parse::<Ipv4Addr>().is_ok()
+        ip.parse::<IpAddress>().is_ok()
     }
 }
 
@@ -422,15 +609,185 @@ impl Command for ClearWifiCredentialsCommand {
     }
 }
 
+/// Request current WiFi status from a device
+#[derive(Debug, Clone)]
+pub struct GetWifiStatusCommand;
+
+impl Command for GetWifiStatusCommand {
+    fn opcode(&self) -> u8 {
+        GET_WIFI_STATUS
+    }
+
+    fn name(&self) -> &'static str {
+        "get_wifi_status"
+    }
+
+    fn response_opcode(&self) -> Option<u8> {
+        Some(WIFI_STATUS_RESPONSE)
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, std::io::Error> {
+        Ok(Vec::new())
+    }
+}
+
+/// Request a screenshot from a device
+/// The device streams the PNG back as SCREENSHOT_CHUNK packets.
+#[derive(Debug, Clone)]
+pub struct RequestScreenshotCommand;
+
+impl Command for RequestScreenshotCommand {
+    fn opcode(&self) -> u8 {
+        REQUEST_SCREENSHOT
+    }
+
+    fn name(&self) -> &'static str {
+        "request_screenshot"
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, std::io::Error> {
+        Ok(Vec::new())
+    }
+}
+
+/// Make a device identify itself by flashing its LED and playing a short sound
+/// Fire-and-forget: the device's ack is recorded by the LOCATE_DEVICE_RESPONSE handler.
+#[derive(Debug, Clone)]
+pub struct LocateDeviceCommand;
+
+impl Command for LocateDeviceCommand {
+    fn opcode(&self) -> u8 {
+        LOCATE_DEVICE
+    }
+
+    fn name(&self) -> &'static str {
+        "locate"
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, std::io::Error> {
+        Ok(Vec::new())
+    }
+}
+
+/// Longest accepted IANA timezone name
+const MAX_TIMEZONE_LEN: usize = 64;
+
+/// Milliseconds on the server's monotonic clock, counted from its first use
+fn monotonic_millis() -> u64 {
+    static EPOCH: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+    let epoch = *EPOCH.get_or_init(std::time::Instant::now);
+    epoch.elapsed().as_millis() as u64
+}
+
+/// Set a device's clock to the server's current UTC time
+/// The time is read when the command is serialized, right before it is sent,
+/// so retries carry a fresh time.
+#[derive(Debug, Clone, Default)]
+pub struct SetTimeCommand {
+    /// IANA timezone such as "Europe/Amsterdam", `None` to leave the device's as is
+    pub timezone: Option<String>,
+}
+
+impl SetTimeCommand {
+    pub fn new(timezone: Option<String>) -> Self {
+        Self { timezone }
+    }
+}
+
+impl Command for SetTimeCommand {
+    fn opcode(&self) -> u8 {
+        SET_TIME
+    }
+
+    fn name(&self) -> &'static str {
+        "set_time"
+    }
+
+    fn response_opcode(&self) -> Option<u8> {
+        Some(SET_TIME_RESPONSE)
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
+    /// Payload: [utc_millis: i64 BE][monotonic_millis: u64 BE][timezone: string] (empty = unchanged)
+    /// `monotonic_millis` is the server's monotonic clock at send time; comparing it
+    /// across syncs and pings lets a device estimate and correct for latency.
+    fn serialize(&self) -> Result<Vec<u8>, std::io::Error> {
+        use byteorder::BigEndian;
+
+        let mut buffer = Vec::new();
+        buffer.write_i64::<BigEndian>(chrono::Utc::now().timestamp_millis())?;
+        buffer.write_u64::<BigEndian>(monotonic_millis())?;
+        buffer.write_string(self.timezone.as_deref().unwrap_or(""))?;
+        Ok(buffer)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if let Some(timezone) = &self.timezone {
+            let valid_chars = timezone
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '+' | '-'));
+            if timezone.is_empty() || timezone.len() > MAX_TIMEZONE_LEN || !valid_chars {
+                return Err(format!("Invalid timezone: {:?}", timezone));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Longest time a message may stay on screen
+const MAX_MESSAGE_DURATION_SECS: u32 = 60 * 60;
+
+/// How the on-device overlay styles a message
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageSeverity {
+    #[default]
+    Info,
+    Warning,
+}
+
+impl MessageSeverity {
+    fn wire_value(self) -> u8 {
+        match self {
+            Self::Info => 0,
+            Self::Warning => 1,
+        }
+    }
+}
+
 /// Display a message notification on a device
 #[derive(Debug, Clone)]
 pub struct DisplayMessageCommand {
     pub message: String,
+    pub severity: MessageSeverity,
+    /// Seconds to show the message for, `None` to leave it to the device
+    pub duration_secs: Option<u32>,
 }
 
 impl DisplayMessageCommand {
     pub fn new(message: String) -> Self {
-        Self { message }
+        Self {
+            message,
+            severity: MessageSeverity::Info,
+            duration_secs: None,
+        }
+    }
+
+    pub fn with_severity(mut self, severity: MessageSeverity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    pub fn with_duration(mut self, duration_secs: Option<u32>) -> Self {
+        self.duration_secs = duration_secs;
+        self
     }
 }
 
@@ -443,9 +800,15 @@ impl Command for DisplayMessageCommand {
         "display_message"
     }
 
+    /// Payload: [message: string][severity: u8][duration_secs: u32 BE] (0 = device default)
+    /// Clients that predate severity and duration read the message and ignore the rest.
     fn serialize(&self) -> Result<Vec<u8>, std::io::Error> {
+        use byteorder::BigEndian;
+
         let mut buffer = Vec::new();
         buffer.write_string(&self.message)?;
+        buffer.write_u8(self.severity.wire_value())?;
+        buffer.write_u32::<BigEndian>(self.duration_secs.unwrap_or(0))?;
         Ok(buffer)
     }
 
@@ -453,6 +816,96 @@ impl Command for DisplayMessageCommand {
         if self.message.is_empty() {
             return Err("Message cannot be empty".to_string());
         }
+        if let Some(duration) = self.duration_secs {
+            if duration == 0 || duration > MAX_MESSAGE_DURATION_SECS {
+                return Err(format!(
+                    "Message duration must be 1-{} seconds, got {}",
+                    MAX_MESSAGE_DURATION_SECS, duration
+                ));
+            }
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::io::ProtocolReadExt;
+    use byteorder::{BigEndian, ReadBytesExt};
+    use std::io::Cursor;
+
+    #[test]
+    fn set_time_payload_carries_utc_and_monotonic_time() {
+        let before = chrono::Utc::now().timestamp_millis();
+        let first = SetTimeCommand::new(Some("Europe/Amsterdam".to_string())).serialize().unwrap();
+        let second = SetTimeCommand::default().serialize().unwrap();
+
+        let mut cursor = Cursor::new(first);
+        let utc_millis = cursor.read_i64::<BigEndian>().unwrap();
+        let first_monotonic = cursor.read_u64::<BigEndian>().unwrap();
+        assert!(utc_millis >= before);
+        assert_eq!(cursor.read_string().unwrap(), "Europe/Amsterdam");
+
+        let mut cursor = Cursor::new(second);
+        cursor.read_i64::<BigEndian>().unwrap();
+        assert!(cursor.read_u64::<BigEndian>().unwrap() >= first_monotonic);
+        assert_eq!(cursor.read_string().unwrap(), "");
+    }
+
+    #[test]
+    fn set_time_rejects_malformed_timezones() {
+        assert!(SetTimeCommand::new(None).validate().is_ok());
+        assert!(SetTimeCommand::new(Some("America/Argentina/Buenos_Aires".to_string())).validate().is_ok());
+        assert!(SetTimeCommand::new(Some("Etc/GMT+2".to_string())).validate().is_ok());
+        assert!(SetTimeCommand::new(Some(String::new())).validate().is_err());
+        assert!(SetTimeCommand::new(Some("Europe/Amsterdam; reboot".to_string())).validate().is_err());
+    }
+
+    #[test]
+    fn display_message_payload_extends_the_old_form() {
+        // Before severity and duration, the payload was only the message string
+        let mut old_form = Vec::new();
+        old_form.write_string("Game starts in 5 minutes").unwrap();
+
+        let payload = DisplayMessageCommand::new("Game starts in 5 minutes".to_string())
+            .serialize()
+            .unwrap();
+        assert!(payload.starts_with(&old_form));
+
+        // An old client reads the message and never looks at the rest
+        let mut cursor = Cursor::new(&payload);
+        assert_eq!(cursor.read_string().unwrap(), "Game starts in 5 minutes");
+
+        // Defaults: info severity, device-chosen duration
+        assert_eq!(cursor.read_u8().unwrap(), 0);
+        assert_eq!(cursor.read_u32::<BigEndian>().unwrap(), 0);
+        assert_eq!(cursor.position() as usize, payload.len());
+    }
+
+    #[test]
+    fn display_message_payload_carries_severity_and_duration() {
+        let payload = DisplayMessageCommand::new("Battery low".to_string())
+            .with_severity(MessageSeverity::Warning)
+            .with_duration(Some(90))
+            .serialize()
+            .unwrap();
+
+        let mut cursor = Cursor::new(&payload);
+        assert_eq!(cursor.read_string().unwrap(), "Battery low");
+        assert_eq!(cursor.read_u8().unwrap(), 1);
+        assert_eq!(cursor.read_u32::<BigEndian>().unwrap(), 90);
+        assert_eq!(cursor.position() as usize, payload.len());
+    }
+
+    #[test]
+    fn display_message_rejects_empty_text_and_bad_durations() {
+        let message = || DisplayMessageCommand::new("Hello".to_string());
+        assert!(message().validate().is_ok());
+        assert!(message().with_duration(Some(1)).validate().is_ok());
+        assert!(message().with_duration(Some(MAX_MESSAGE_DURATION_SECS)).validate().is_ok());
+        assert!(message().with_duration(Some(0)).validate().is_err());
+        assert!(message().with_duration(Some(MAX_MESSAGE_DURATION_SECS + 1)).validate().is_err());
+        assert!(DisplayMessageCommand::new(String::new()).validate().is_err());
+    }
+}
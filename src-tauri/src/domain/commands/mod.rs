@@ -5,7 +5,10 @@ pub use command::{Command, CommandResponse, BatchResult};
 
 pub use device_commands::{
     ClearWifiCredentialsCommand, CloseAllAppsCommand, ConfigureDeviceCommand,
-    DisplayMessageCommand, ExecuteShellCommand, GetInstalledAppsCommand, GetVolumeCommand,
-    InstallApkCommand, LaunchAppCommand, PingCommand, RequestBatteryCommand,
-    RestartDeviceCommand, SetVolumeCommand, UninstallAppCommand,
+    DisplayMessageCommand, ExecuteShellCommand, GetBrightnessCommand, GetInstalledAppsCommand,
+    GetVolumeCommand, GetWifiStatusCommand,
+    InstallApkCommand, LaunchAppCommand, LocateDeviceCommand, MessageSeverity, PingCommand,
+    RequestBatteryCommand,
+    RequestScreenshotCommand, RequestStorageCommand, RestartDeviceCommand, SetBrightnessCommand,
+    SetTimeCommand, SetVolumeCommand, UninstallAppCommand,
 };
@@ -0,0 +1,180 @@
+/// APK install outcome value object
+/// Result of an APK install as reported by a device's package manager.
+
+use crate::net::io::ProtocolReadExt;
+use byteorder::{BigEndian, ReadBytesExt};
+use std::io::Cursor;
+
+/// Why the package manager refused an install, for the failures staff can act on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstallFailureKind {
+    InsufficientStorage,
+    /// Installed copy was signed with a different key
+    SignatureMismatch,
+    /// Installed copy has a higher version code
+    VersionDowngrade,
+    /// APK doesn't support the device's SDK level or ABI
+    Incompatible,
+    InvalidApk,
+    Other,
+}
+
+impl InstallFailureKind {
+    /// Classify a failure by its `INSTALL_FAILED_*` name, falling back to the legacy status code
+    fn classify(code: Option<i32>, reason: Option<&str>) -> Self {
+        const BY_NAME: &[(&str, InstallFailureKind)] = &[
+            ("INSUFFICIENT_STORAGE", InstallFailureKind::InsufficientStorage),
+            ("UPDATE_INCOMPATIBLE", InstallFailureKind::SignatureMismatch),
+            ("SHARED_USER_INCOMPATIBLE", InstallFailureKind::SignatureMismatch),
+            ("NO_CERTIFICATES", InstallFailureKind::SignatureMismatch),
+            ("INCONSISTENT_CERTIFICATES", InstallFailureKind::SignatureMismatch),
+            ("VERSION_DOWNGRADE", InstallFailureKind::VersionDowngrade),
+            ("OLDER_SDK", InstallFailureKind::Incompatible),
+            ("NO_MATCHING_ABIS", InstallFailureKind::Incompatible),
+            ("INVALID_APK", InstallFailureKind::InvalidApk),
+        ];
+
+        if let Some(reason) = reason.map(str::to_ascii_uppercase) {
+            if let Some((_, kind)) = BY_NAME.iter().find(|(name, _)| reason.contains(name)) {
+                return *kind;
+            }
+        }
+
+        // PackageManager.INSTALL_FAILED_* / INSTALL_PARSE_FAILED_* codes
+        match code {
+            Some(-4) => Self::InsufficientStorage,
+            Some(-7) | Some(-8) | Some(-103) | Some(-104) => Self::SignatureMismatch,
+            Some(-25) => Self::VersionDowngrade,
+            Some(-12) | Some(-113) => Self::Incompatible,
+            Some(-2) => Self::InvalidApk,
+            _ => Self::Other,
+        }
+    }
+
+    /// What an operator can do about it, if anything obvious
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            Self::InsufficientStorage => Some("Free up storage on the device and try again"),
+            Self::SignatureMismatch | Self::VersionDowngrade => Some("Uninstall the existing version first"),
+            Self::Incompatible => Some("This APK doesn't support this device"),
+            Self::InvalidApk | Self::Other => None,
+        }
+    }
+}
+
+/// Outcome of an install reported in an APK_INSTALL_RESPONSE packet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApkInstallOutcome {
+    success: bool,
+    /// Legacy package manager status code, negative on failure
+    status_code: Option<i32>,
+    /// Package manager status message, e.g. `INSTALL_FAILED_VERSION_DOWNGRADE`
+    reason: Option<String>,
+}
+
+impl ApkInstallOutcome {
+    /// Decode a payload of the form `[success: u8][status_code: i32 BE][reason: String]`
+    /// Older clients only send the success byte; a missing or truncated tail
+    /// leaves the code and reason out rather than failing the whole response.
+    pub fn decode(payload: &[u8]) -> Option<Self> {
+        let mut cursor = Cursor::new(payload);
+        let success = cursor.read_u8().ok()? != 0;
+        let status_code = cursor.read_i32::<BigEndian>().ok();
+        let reason = status_code
+            .and_then(|_| cursor.read_string().ok())
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty());
+
+        Some(Self {
+            success,
+            status_code,
+            reason,
+        })
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.success
+    }
+
+    pub fn status_code(&self) -> Option<i32> {
+        self.status_code
+    }
+
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+
+    /// Kind of failure, `None` if the install succeeded
+    pub fn failure_kind(&self) -> Option<InstallFailureKind> {
+        (!self.success).then(|| InstallFailureKind::classify(self.status_code, self.reason()))
+    }
+
+    /// Message for the command result, with the device's reason and a hint when known
+    pub fn message(&self) -> String {
+        let Some(kind) = self.failure_kind() else {
+            return "APK installed successfully".to_string();
+        };
+
+        let mut message = match (self.reason(), self.status_code) {
+            (Some(reason), _) => format!("Failed to install APK: {}", reason),
+            (None, Some(code)) => format!("Failed to install APK (status {})", code),
+            (None, None) => "Failed to install APK".to_string(),
+        };
+        if let Some(hint) = kind.hint() {
+            message.push_str(". ");
+            message.push_str(hint);
+        }
+        message
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net::io::ProtocolWriteExt;
+    use byteorder::WriteBytesExt;
+
+    fn payload(success: bool, code: i32, reason: &str) -> Vec<u8> {
+        let mut payload = vec![success as u8];
+        payload.write_i32::<BigEndian>(code).unwrap();
+        payload.write_string(reason).unwrap();
+        payload
+    }
+
+    #[test]
+    fn downgrade_suggests_uninstalling_first() {
+        let outcome = ApkInstallOutcome::decode(&payload(false, -25, "INSTALL_FAILED_VERSION_DOWNGRADE")).unwrap();
+
+        assert_eq!(outcome.failure_kind(), Some(InstallFailureKind::VersionDowngrade));
+        assert_eq!(
+            outcome.message(),
+            "Failed to install APK: INSTALL_FAILED_VERSION_DOWNGRADE. Uninstall the existing version first"
+        );
+    }
+
+    #[test]
+    fn classifies_by_code_when_reason_is_unhelpful() {
+        let outcome = ApkInstallOutcome::decode(&payload(false, -4, "")).unwrap();
+
+        assert_eq!(outcome.reason(), None);
+        assert_eq!(outcome.failure_kind(), Some(InstallFailureKind::InsufficientStorage));
+        assert!(outcome.message().starts_with("Failed to install APK (status -4)"));
+    }
+
+    #[test]
+    fn tolerates_responses_from_older_clients() {
+        let outcome = ApkInstallOutcome::decode(&[0]).unwrap();
+        assert_eq!(outcome.status_code(), None);
+        assert_eq!(outcome.message(), "Failed to install APK");
+
+        // Code present but reason cut short
+        let mut truncated = payload(false, -7, "INSTALL_FAILED_UPDATE_INCOMPATIBLE");
+        truncated.truncate(8);
+        let outcome = ApkInstallOutcome::decode(&truncated).unwrap();
+        assert_eq!(outcome.reason(), None);
+        assert_eq!(outcome.failure_kind(), Some(InstallFailureKind::SignatureMismatch));
+
+        assert!(ApkInstallOutcome::decode(&[1]).unwrap().is_success());
+        assert!(ApkInstallOutcome::decode(&[]).is_none());
+    }
+}
@@ -0,0 +1,44 @@
+/// Command record entity
+/// The outcome of a single command sent to a device, as kept in its history.
+
+use chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommandRecord {
+    timestamp: DateTime<Utc>,
+    command_type: String,
+    success: bool,
+    message: String,
+}
+
+impl CommandRecord {
+    pub fn new(
+        timestamp: DateTime<Utc>,
+        command_type: impl Into<String>,
+        success: bool,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            timestamp,
+            command_type: command_type.into(),
+            success,
+            message: message.into(),
+        }
+    }
+
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+
+    pub fn command_type(&self) -> &str {
+        &self.command_type
+    }
+
+    pub fn success(&self) -> bool {
+        self.success
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
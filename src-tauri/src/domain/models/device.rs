@@ -1,7 +1,9 @@
 /// This is an immutable aggregate - all mutations return new instances.
-use super::{Battery, DeviceId, Serial, Volume};
+use super::{Battery, DeviceId, InstalledApp, LinkQuality, Serial, StorageInfo, Volume, WifiStatus};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::time::Duration;
 
 /// Device aggregate - the root entity for a connected device
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -15,18 +17,38 @@ pub struct Device {
     model: String,
     /// Snorlax client version
     version: String,
+    /// IP address the device connected from
+    ip_address: Option<IpAddr>,
     /// When the device first connected
     connected_at: DateTime<Utc>,
     /// When the device was last seen (heartbeat)
     last_seen: DateTime<Utc>,
     /// Optional custom name set by the user
     custom_name: Option<String>,
+    /// Normalized tags set by staff, e.g. "needs repair"
+    tags: Vec<String>,
+    /// Free-text note set by staff
+    note: Option<String>,
     /// Battery information (if available)
     battery: Option<Battery>,
     /// Volume information (if available)
     volume: Option<Volume>,
+    /// Screen brightness 0-100 (if available)
+    brightness: Option<u8>,
+    /// Storage information (if available)
+    storage: Option<StorageInfo>,
+    /// WiFi status from the last status response
+    wifi_status: Option<WifiStatus>,
+    /// Ping round-trip times and heartbeat jitter on the current connection
+    link_quality: LinkQuality,
     /// Currently running foreground application
     running_app: Option<String>,
+    /// Package name of the foreground application
+    running_package: Option<String>,
+    /// Package the device should stay in, set by staff
+    expected_package: Option<String>,
+    /// Installed applications from the last installed apps response
+    installed_apps: Option<Vec<InstalledApp>>,
 }
 
 impl Device {
@@ -37,12 +59,22 @@ impl Device {
             serial,
             model,
             version,
+            ip_address: None,
             connected_at: now,
             last_seen: now,
             custom_name: None,
+            tags: Vec::new(),
+            note: None,
             battery: None,
             volume: None,
+            brightness: None,
+            storage: None,
+            wifi_status: None,
+            link_quality: LinkQuality::default(),
             running_app: None,
+            running_package: None,
+            expected_package: None,
+            installed_apps: None,
         }
     }
 
@@ -58,14 +90,34 @@ impl Device {
         &self.model
     }
 
+    pub fn ip_address(&self) -> Option<IpAddr> {
+        self.ip_address
+    }
+
     pub fn connected_at(&self) -> DateTime<Utc> {
         self.connected_at
     }
 
+    pub fn last_seen(&self) -> DateTime<Utc> {
+        self.last_seen
+    }
+
     pub fn custom_name(&self) -> Option<&str> {
         self.custom_name.as_deref()
     }
 
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    pub fn note(&self) -> Option<&str> {
+        self.note.as_deref()
+    }
+
     pub fn battery(&self) -> Option<&Battery> {
         self.battery.as_ref()
     }
@@ -74,26 +126,97 @@ impl Device {
         self.volume.as_ref()
     }
 
+    pub fn brightness(&self) -> Option<u8> {
+        self.brightness
+    }
+
+    pub fn storage(&self) -> Option<&StorageInfo> {
+        self.storage.as_ref()
+    }
+
+    pub fn wifi_status(&self) -> Option<&WifiStatus> {
+        self.wifi_status.as_ref()
+    }
+
+    pub fn link_quality(&self) -> &LinkQuality {
+        &self.link_quality
+    }
+
     pub fn running_app(&self) -> Option<&str> {
         self.running_app.as_deref()
     }
 
+    pub fn running_package(&self) -> Option<&str> {
+        self.running_package.as_deref()
+    }
+
+    pub fn expected_package(&self) -> Option<&str> {
+        self.expected_package.as_deref()
+    }
+
+    /// Whether the foreground app is known and isn't the one the device should be in
+    pub fn has_left_expected_app(&self) -> bool {
+        match (self.expected_package(), self.running_package()) {
+            (Some(expected), Some(actual)) => expected != actual,
+            _ => false,
+        }
+    }
+
+    pub fn installed_apps(&self) -> Option<&[InstalledApp]> {
+        self.installed_apps.as_deref()
+    }
+
     pub fn version(&self) -> &str {
         &self.version
     }
 
+    /// Carry this device's state over to a new connection
+    /// Name, tags, note and the last reported status are kept; the session ID,
+    /// address, client details and connection time are those of the new connection.
+    pub fn reconnect(mut self, id: DeviceId, ip_address: Option<IpAddr>, model: String, version: String) -> Self {
+        let now = Utc::now();
+        self.id = id;
+        self.ip_address = ip_address;
+        self.model = model;
+        self.version = version;
+        self.connected_at = now;
+        self.last_seen = now;
+        self.running_app = None;
+        self.running_package = None;
+        self.link_quality = LinkQuality::default();
+        self
+    }
+
     /// Update the last seen timestamp (called on heartbeat)
     pub fn update_last_seen(mut self) -> Self {
         self.last_seen = Utc::now();
         self
     }
 
+    /// Set the IP address the device connected from
+    pub fn with_ip_address(mut self, ip_address: IpAddr) -> Self {
+        self.ip_address = Some(ip_address);
+        self
+    }
+
     /// Set a custom name for the device
     pub fn with_custom_name(mut self, name: Option<String>) -> Self {
         self.custom_name = name;
         self
     }
 
+    /// Set the staff tags for the device
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Set the staff note for the device
+    pub fn with_note(mut self, note: Option<String>) -> Self {
+        self.note = note;
+        self
+    }
+
     /// Update battery information
     pub fn with_battery(mut self, battery: Battery) -> Self {
         self.battery = Some(battery);
@@ -108,10 +231,108 @@ impl Device {
         self
     }
 
+    /// Update screen brightness
+    pub fn with_brightness(mut self, brightness: u8) -> Self {
+        self.brightness = Some(brightness);
+        self.last_seen = Utc::now();
+        self
+    }
+
+    /// Update storage information
+    pub fn with_storage(mut self, storage: StorageInfo) -> Self {
+        self.storage = Some(storage);
+        self.last_seen = Utc::now();
+        self
+    }
+
+    /// Update WiFi status
+    pub fn with_wifi_status(mut self, wifi_status: WifiStatus) -> Self {
+        self.wifi_status = Some(wifi_status);
+        self.last_seen = Utc::now();
+        self
+    }
+
+    /// Record a ping round-trip time
+    pub fn with_rtt(mut self, rtt: Duration) -> Self {
+        self.link_quality = self.link_quality.with_rtt(rtt);
+        self
+    }
+
+    /// Record the current heartbeat interval jitter
+    pub fn with_heartbeat_jitter(mut self, jitter: Duration) -> Self {
+        self.link_quality = self.link_quality.with_heartbeat_jitter(jitter);
+        self
+    }
+
     /// Update running application
     pub fn with_running_app(mut self, app_name: String) -> Self {
         self.running_app = Some(app_name);
         self.last_seen = Utc::now();
         self
     }
+
+    /// Update the package name of the running application
+    pub fn with_running_package(mut self, package_name: String) -> Self {
+        self.running_package = Some(package_name);
+        self
+    }
+
+    /// Set the package the device should stay in
+    pub fn with_expected_package(mut self, package_name: Option<String>) -> Self {
+        self.expected_package = package_name;
+        self
+    }
+
+    /// Update installed applications
+    pub fn with_installed_apps(mut self, apps: Vec<InstalledApp>) -> Self {
+        self.installed_apps = Some(apps);
+        self.last_seen = Utc::now();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device() -> Device {
+        Device::new(
+            DeviceId::new(),
+            Serial::new("1WMHH000000001".to_string()).unwrap(),
+            "Quest 3".to_string(),
+            "1.0.0".to_string(),
+        )
+    }
+
+    #[test]
+    fn leaving_the_expected_app_needs_both_packages_known() {
+        assert!(!device().has_left_expected_app());
+        assert!(!device()
+            .with_expected_package(Some("com.example.game".to_string()))
+            .has_left_expected_app());
+        assert!(!device()
+            .with_running_package("com.example.other".to_string())
+            .has_left_expected_app());
+
+        let pinned = device().with_expected_package(Some("com.example.game".to_string()));
+        assert!(!pinned
+            .clone()
+            .with_running_package("com.example.game".to_string())
+            .has_left_expected_app());
+        assert!(pinned
+            .with_running_package("com.oculus.vrshell".to_string())
+            .has_left_expected_app());
+    }
+
+    #[test]
+    fn reconnecting_forgets_the_running_package() {
+        let device = device()
+            .with_expected_package(Some("com.example.game".to_string()))
+            .with_running_package("com.oculus.vrshell".to_string())
+            .reconnect(DeviceId::new(), None, "Quest 3".to_string(), "1.0.1".to_string());
+
+        assert_eq!(device.running_package(), None);
+        assert_eq!(device.expected_package(), Some("com.example.game"));
+        assert!(!device.has_left_expected_app());
+    }
 }
@@ -0,0 +1,114 @@
+/// Installed application entity
+/// Represents an app reported by a device's installed apps list.
+
+use serde::{Deserialize, Serialize};
+
+/// Separator between fields of an installed app entry on the wire
+const FIELD_SEPARATOR: char = '|';
+
+/// An application installed on a device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstalledApp {
+    package_name: String,
+    label: Option<String>,
+    version_name: Option<String>,
+    version_code: Option<u32>,
+    is_system: bool,
+}
+
+impl InstalledApp {
+    /// Parse an entry of the form `package_name[|label[|version_name[|is_system[|version_code]]]]`
+    /// Older clients only send the package name; missing, empty or unparseable fields
+    /// become `None` and apps are treated as user apps unless flagged otherwise.
+    pub fn parse(entry: &str) -> Option<Self> {
+        let mut fields = entry.split(FIELD_SEPARATOR).map(str::trim);
+
+        let package_name = fields.next().filter(|s| !s.is_empty())?.to_string();
+        let mut optional = || {
+            fields
+                .next()
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+        };
+        let label = optional();
+        let version_name = optional();
+        let is_system = optional()
+            .map(|s| matches!(s.to_ascii_lowercase().as_str(), "1" | "true" | "system"))
+            .unwrap_or(false);
+        let version_code = optional().and_then(|s| s.parse().ok());
+
+        Some(Self {
+            package_name,
+            label,
+            version_name,
+            version_code,
+            is_system,
+        })
+    }
+
+    pub fn package_name(&self) -> &str {
+        &self.package_name
+    }
+
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    pub fn version_name(&self) -> Option<&str> {
+        self.version_name.as_deref()
+    }
+
+    pub fn version_code(&self) -> Option<u32> {
+        self.version_code
+    }
+
+    pub fn is_system(&self) -> bool {
+        self.is_system
+    }
+
+    /// Whether the app passes a package prefix filter and system app inclusion setting
+    pub fn matches(&self, package_prefix: Option<&str>, include_system: bool) -> bool {
+        if self.is_system && !include_system {
+            return false;
+        }
+
+        package_prefix.map_or(true, |prefix| self.package_name.starts_with(prefix))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_full_entry() {
+        let app = InstalledApp::parse("com.example.game|Example Game|1.4.2|0|10402").unwrap();
+
+        assert_eq!(app.package_name(), "com.example.game");
+        assert_eq!(app.label(), Some("Example Game"));
+        assert_eq!(app.version_name(), Some("1.4.2"));
+        assert_eq!(app.version_code(), Some(10402));
+        assert!(!app.is_system());
+    }
+
+    #[test]
+    fn parses_entries_from_older_clients() {
+        let app = InstalledApp::parse("com.example.game").unwrap();
+        assert_eq!(app.label(), None);
+        assert_eq!(app.version_code(), None);
+
+        let app = InstalledApp::parse("com.android.settings|Settings||true").unwrap();
+        assert_eq!(app.version_name(), None);
+        assert_eq!(app.version_code(), None);
+        assert!(app.is_system());
+    }
+
+    #[test]
+    fn rejects_missing_package_and_ignores_bad_version_code() {
+        assert!(InstalledApp::parse("|Label").is_none());
+        assert!(InstalledApp::parse("  ").is_none());
+
+        let app = InstalledApp::parse("com.example.game|Game|1.0|0|not-a-number").unwrap();
+        assert_eq!(app.version_code(), None);
+    }
+}
@@ -0,0 +1,119 @@
+/// IP Address value object
+/// Represents an IPv4 or IPv6 host address.
+/// Accepts bracketed IPv6 literals (e.g. "[fe80::1]") as they appear in URLs.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct IpAddress(IpAddr);
+
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid IP address: {0}")]
+pub struct IpAddressError(String);
+
+impl IpAddress {
+    /// Whether this is the wildcard address ("0.0.0.0" or "::")
+    pub fn is_unspecified(&self) -> bool {
+        self.0.is_unspecified()
+    }
+
+    pub fn is_ipv6(&self) -> bool {
+        self.0.is_ipv6()
+    }
+
+    /// Host portion for a URL, bracketing IPv6 literals
+    pub fn url_host(&self) -> String {
+        match self.0 {
+            IpAddr::V4(ip) => ip.to_string(),
+            IpAddr::V6(ip) => format!("[{}]", ip),
+        }
+    }
+
+    /// Base HTTP URL for a server on this address
+    pub fn http_base_url(&self, port: u16) -> String {
+        format!("http://{}:{}", self.url_host(), port)
+    }
+
+    pub fn socket_addr(&self, port: u16) -> SocketAddr {
+        SocketAddr::new(self.0, port)
+    }
+}
+
+impl FromStr for IpAddress {
+    type Err = IpAddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let unbracketed = trimmed
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+            .unwrap_or(trimmed);
+
+        unbracketed
+            .parse::<IpAddr>()
+            .map(Self)
+            .map_err(|_| IpAddressError(s.to_string()))
+    }
+}
+
+impl From<IpAddr> for IpAddress {
+    fn from(ip: IpAddr) -> Self {
+        Self(ip)
+    }
+}
+
+impl fmt::Display for IpAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_v4_and_v6() {
+        let v4: IpAddress = "192.168.1.10".parse().unwrap();
+        assert_eq!(v4.url_host(), "192.168.1.10");
+        assert!(!v4.is_ipv6());
+
+        let v6: IpAddress = "fe80::1".parse().unwrap();
+        assert_eq!(v6.url_host(), "[fe80::1]");
+        assert_eq!(v6.to_string(), "fe80::1");
+        assert!(v6.is_ipv6());
+    }
+
+    #[test]
+    fn accepts_bracketed_v6_literal() {
+        let ip: IpAddress = "[2001:db8::42]".parse().unwrap();
+        assert_eq!(ip, "2001:db8::42".parse().unwrap());
+        assert_eq!(ip.url_host().parse::<IpAddress>().unwrap(), ip);
+    }
+
+    #[test]
+    fn base_url_brackets_v6_literals() {
+        let v4: IpAddress = "10.0.0.5".parse().unwrap();
+        assert_eq!(v4.http_base_url(43573), "http://10.0.0.5:43573");
+
+        let v6: IpAddress = "fd00::5".parse().unwrap();
+        assert_eq!(v6.http_base_url(43573), "http://[fd00::5]:43573");
+    }
+
+    #[test]
+    fn wildcard_addresses_are_unspecified() {
+        assert!("0.0.0.0".parse::<IpAddress>().unwrap().is_unspecified());
+        assert!("::".parse::<IpAddress>().unwrap().is_unspecified());
+        assert!(!"::1".parse::<IpAddress>().unwrap().is_unspecified());
+    }
+
+    #[test]
+    fn rejects_hostnames() {
+        assert!("localhost".parse::<IpAddress>().is_err());
+        assert!("[localhost]".parse::<IpAddress>().is_err());
+    }
+}
@@ -0,0 +1,80 @@
+/// Link quality value object
+/// Round-trip times measured from ping responses and the jitter of a
+/// device's heartbeat interval, to spot headsets on bad WiFi.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// Round-trip times kept for the rolling average
+const RTT_WINDOW: usize = 10;
+
+/// Connection quality measurements for a device
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkQuality {
+    /// Most recent round-trip times in milliseconds, oldest first
+    recent_rtts_ms: VecDeque<u32>,
+    /// Smoothed variation between consecutive heartbeat intervals
+    heartbeat_jitter_ms: Option<u32>,
+}
+
+impl LinkQuality {
+    /// Add a round-trip time, dropping the oldest beyond the window
+    pub fn with_rtt(mut self, rtt: Duration) -> Self {
+        self.recent_rtts_ms.push_back(duration_ms(rtt));
+        while self.recent_rtts_ms.len() > RTT_WINDOW {
+            self.recent_rtts_ms.pop_front();
+        }
+        self
+    }
+
+    pub fn with_heartbeat_jitter(mut self, jitter: Duration) -> Self {
+        self.heartbeat_jitter_ms = Some(duration_ms(jitter));
+        self
+    }
+
+    pub fn last_rtt_ms(&self) -> Option<u32> {
+        self.recent_rtts_ms.back().copied()
+    }
+
+    /// Average of the last `RTT_WINDOW` round-trip times
+    pub fn avg_rtt_ms(&self) -> Option<u32> {
+        if self.recent_rtts_ms.is_empty() {
+            return None;
+        }
+        let total: u64 = self.recent_rtts_ms.iter().map(|&ms| ms as u64).sum();
+        Some((total / self.recent_rtts_ms.len() as u64) as u32)
+    }
+
+    pub fn heartbeat_jitter_ms(&self) -> Option<u32> {
+        self.heartbeat_jitter_ms
+    }
+}
+
+fn duration_ms(duration: Duration) -> u32 {
+    duration.as_millis().min(u32::MAX as u128) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn averages_only_the_recent_window() {
+        let mut quality = LinkQuality::default();
+        assert_eq!(quality.avg_rtt_ms(), None);
+
+        for _ in 0..RTT_WINDOW {
+            quality = quality.with_rtt(Duration::from_millis(500));
+        }
+        for _ in 0..RTT_WINDOW {
+            quality = quality.with_rtt(Duration::from_millis(20));
+        }
+        assert_eq!(quality.avg_rtt_ms(), Some(20));
+
+        let quality = quality.with_rtt(Duration::from_millis(130));
+        assert_eq!(quality.last_rtt_ms(), Some(130));
+        assert_eq!(quality.avg_rtt_ms(), Some(31));
+    }
+}
@@ -7,6 +7,13 @@ mod device;
 mod game_id;
 mod game;
 mod sensor;
+mod storage;
+mod installed_app;
+mod wifi;
+mod link_quality;
+mod ip_address;
+mod apk_install;
+mod command_record;
 
 pub use device_id::DeviceId;
 pub use serial::Serial;
@@ -16,4 +23,11 @@ pub use volume::Volume;
 pub use device::Device;
 pub use game_id::GameId;
 pub use game::{GameConfig, GameState};
-pub use sensor::{Sensor, SensorConnectionStatus};
+pub use sensor::{Sensor, SensorConnectionStatus, SensorFlashResult};
+pub use storage::StorageInfo;
+pub use installed_app::InstalledApp;
+pub use wifi::WifiStatus;
+pub use link_quality::LinkQuality;
+pub use ip_address::IpAddress;
+pub use apk_install::{ApkInstallOutcome, InstallFailureKind};
+pub use command_record::CommandRecord;
@@ -14,6 +14,16 @@ pub enum SensorConnectionStatus {
     Disconnected,
 }
 
+/// Outcome of flashing one sensor in a batch upload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensorFlashResult {
+    pub port: String,
+    pub device_name: String,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
 /// Represents a XIAO BLE nRF52840 sensor board
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Sensor {
@@ -24,6 +34,10 @@ pub struct Sensor {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub serial_number: Option<String>,
 
+    /// USB serial number reported by the OS, known without opening the port
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usb_serial: Option<String>,
+
     /// Hardware MAC address
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mac_address: Option<String>,
@@ -50,6 +64,7 @@ impl Sensor {
         Self {
             port,
             serial_number: None,
+            usb_serial: None,
             mac_address: None,
             ble_mac_address: None,
             device_name: None,
@@ -70,6 +85,11 @@ impl Sensor {
         )
     }
 
+    pub fn with_usb_serial(mut self, usb_serial: Option<String>) -> Self {
+        self.usb_serial = usb_serial;
+        self
+    }
+
     /// Populate optional fields from individual values (used after serial info read)
     pub fn with_info(
         mut self,
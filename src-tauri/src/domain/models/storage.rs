@@ -0,0 +1,53 @@
+/// Storage entity
+/// Represents the internal storage capacity of a device.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Storage information for a device
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageInfo {
+    total_bytes: u64,
+    free_bytes: u64,
+    last_updated: DateTime<Utc>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("Invalid storage: free={free} exceeds total={total}")]
+    FreeExceedsTotal { total: u64, free: u64 },
+}
+
+impl StorageInfo {
+    pub fn new(total_bytes: u64, free_bytes: u64) -> Result<Self, StorageError> {
+        if free_bytes > total_bytes {
+            return Err(StorageError::FreeExceedsTotal {
+                total: total_bytes,
+                free: free_bytes,
+            });
+        }
+
+        Ok(Self {
+            total_bytes,
+            free_bytes,
+            last_updated: Utc::now(),
+        })
+    }
+
+    pub fn total_bytes(&self) -> u64 {
+        self.total_bytes
+    }
+
+    pub fn free_bytes(&self) -> u64 {
+        self.free_bytes
+    }
+
+    pub fn last_updated(&self) -> DateTime<Utc> {
+        self.last_updated
+    }
+
+    /// Whether a file of `size_bytes` fits in the free space
+    pub fn can_fit(&self, size_bytes: u64) -> bool {
+        size_bytes <= self.free_bytes
+    }
+}
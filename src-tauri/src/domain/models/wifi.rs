@@ -0,0 +1,19 @@
+/// WiFi status value object
+/// Represents the network a device is currently associated with.
+
+use serde::{Deserialize, Serialize};
+
+/// Current WiFi state of a device
+/// A device behind a captive portal has no usable connection and is reported as `Disconnected`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "camelCase")]
+pub enum WifiStatus {
+    #[serde(rename_all = "camelCase")]
+    Connected {
+        ssid: String,
+        /// Signal strength in dBm
+        rssi: i32,
+        link_speed_mbps: u32,
+    },
+    Disconnected,
+}
@@ -2,8 +2,11 @@
 /// Abstraction for managing APK files.
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use tokio_util::sync::CancellationToken;
 
 use super::error::RepositoryError;
 
@@ -19,6 +22,151 @@ pub struct ApkInfo {
     pub size_bytes: u64,
     /// Download URL for devices to fetch this APK
     pub url: String,
+    /// Hex-encoded SHA-256 recorded when the APK was added
+    pub sha256: Option<String>,
+    /// Package details from the manifest, `None` if the APK couldn't be parsed
+    pub metadata: Option<ApkMetadata>,
+    /// When the file was added or last replaced (its modification time)
+    pub added_at: Option<DateTime<Utc>>,
+}
+
+/// Package details read from an APK's manifest and resources
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApkMetadata {
+    pub package_name: String,
+    pub version_name: Option<String>,
+    pub version_code: Option<u32>,
+    /// Application label in the default locale
+    pub label: Option<String>,
+    /// Base64-encoded launcher icon, when it is a PNG
+    pub icon_png_base64: Option<String>,
+}
+
+/// Field an APK listing is ordered by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ApkSortField {
+    #[default]
+    Name,
+    Size,
+    DateAdded,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+/// Ordering and paging for an APK listing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ApkListQuery {
+    pub sort_by: ApkSortField,
+    pub order: SortOrder,
+    pub offset: usize,
+    /// Page size, `None` for everything after `offset`
+    pub limit: Option<usize>,
+}
+
+/// One page of an APK listing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApkPage {
+    pub items: Vec<ApkInfo>,
+    /// Number of APKs across all pages
+    pub total: usize,
+}
+
+impl ApkListQuery {
+    /// Sort a full listing and cut out the requested page
+    /// Ties are broken by filename so pages are stable between calls.
+    pub fn apply(&self, mut apks: Vec<ApkInfo>) -> ApkPage {
+        apks.sort_by(|a, b| {
+            let primary = match self.sort_by {
+                ApkSortField::Name => a.filename.to_lowercase().cmp(&b.filename.to_lowercase()),
+                ApkSortField::Size => a.size_bytes.cmp(&b.size_bytes),
+                ApkSortField::DateAdded => a.added_at.cmp(&b.added_at),
+            };
+            let ordering = primary.then_with(|| a.filename.cmp(&b.filename));
+            match self.order {
+                SortOrder::Asc => ordering,
+                SortOrder::Desc => ordering.reverse(),
+            }
+        });
+
+        let total = apks.len();
+        let items = apks
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect();
+
+        ApkPage { items, total }
+    }
+}
+
+/// Stored APKs with identical contents
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateApks {
+    pub sha256: String,
+    /// Oldest first, so the first is the one most likely worth keeping
+    pub apks: Vec<ApkInfo>,
+}
+
+impl DuplicateApks {
+    /// Disk space freed by keeping only one copy
+    pub fn wasted_bytes(&self) -> u64 {
+        self.apks.iter().skip(1).map(|apk| apk.size_bytes).sum()
+    }
+}
+
+/// Group APKs by SHA-256, keeping only hashes stored more than once
+/// APKs without a recorded checksum are left out. Groups wasting the most space come first.
+pub fn find_duplicates(apks: Vec<ApkInfo>) -> Vec<DuplicateApks> {
+    let mut by_hash: HashMap<String, Vec<ApkInfo>> = HashMap::new();
+    for apk in apks {
+        if let Some(sha256) = apk.sha256.clone() {
+            by_hash.entry(sha256).or_default().push(apk);
+        }
+    }
+
+    let mut groups: Vec<DuplicateApks> = by_hash
+        .into_iter()
+        .filter(|(_, apks)| apks.len() > 1)
+        .map(|(sha256, mut apks)| {
+            apks.sort_by(|a, b| a.added_at.cmp(&b.added_at).then_with(|| a.filename.cmp(&b.filename)));
+            DuplicateApks { sha256, apks }
+        })
+        .collect();
+
+    groups.sort_by(|a, b| {
+        b.wasted_bytes()
+            .cmp(&a.wasted_bytes())
+            .then_with(|| a.apks[0].filename.cmp(&b.apks[0].filename))
+    });
+    groups
+}
+
+/// Result of re-hashing a stored APK against its recorded checksum
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApkVerification {
+    pub expected_sha256: String,
+    pub actual_sha256: String,
+}
+
+impl ApkVerification {
+    pub fn is_valid(&self) -> bool {
+        self.expected_sha256 == self.actual_sha256
+    }
+}
+
+/// Progress snapshot reported while copying an APK into the repository
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApkCopyProgress {
+    pub bytes_copied: u64,
+    pub total_bytes: u64,
 }
 
 /// Repository for managing APK files
@@ -30,16 +178,80 @@ pub trait ApkRepository: Send + Sync {
     /// Returns information about each APK file.
     async fn list_apks(&self) -> Result<Vec<ApkInfo>>;
 
+    /// List one sorted page of APK files, with the total count
+    async fn list_apks_page(&self, query: ApkListQuery) -> Result<ApkPage> {
+        Ok(query.apply(self.list_apks().await?))
+    }
+
     /// Add a new APK file from a source path
-    /// Copies the APK file from `source_path` into the repository.
-    /// Returns the filename of the added APK.
-    async fn add_apk(&self, source_path: PathBuf) -> Result<String>;
+    /// Copies the APK file from `source_path` into the repository and records its SHA-256.
+    /// With `dedupe`, returns `DuplicateApk` if a byte-identical APK is already stored.
+    /// A different APK with the same name is never overwritten; the new file is stored under
+    /// a numbered name instead. Returns the filename of the added APK.
+    /// The file is copied in chunks, calling progress_callback as it goes. If cancelled,
+    /// returns `Cancelled` and leaves nothing behind in the repository.
+    async fn add_apk(
+        &self,
+        source_path: PathBuf,
+        dedupe: bool,
+        cancel_token: CancellationToken,
+        progress_callback: Box<dyn Fn(ApkCopyProgress) + Send + Sync>,
+    ) -> Result<String>;
 
     /// Remove an APK file by filename
     /// Returns `Ok(())` even if the file doesn't exist (idempotent).
     async fn remove_apk(&self, filename: &str) -> Result<()>;
 
+    /// Re-hash a stored APK and compare it against the recorded SHA-256
+    /// Returns `NotFound` if the APK or its recorded checksum doesn't exist.
+    async fn verify_apk(&self, filename: &str) -> Result<ApkVerification>;
+
     /// Get the directory where APKs are stored
     /// Useful for operations that need direct filesystem access.
     fn get_storage_directory(&self) -> PathBuf;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apk(filename: &str, sha256: Option<&str>, size_bytes: u64, added_secs: i64) -> ApkInfo {
+        ApkInfo {
+            filename: filename.to_string(),
+            size_bytes,
+            url: format!("http://localhost/{}", filename),
+            sha256: sha256.map(str::to_string),
+            metadata: None,
+            added_at: DateTime::from_timestamp(added_secs, 0),
+        }
+    }
+
+    #[test]
+    fn duplicates_are_grouped_by_hash_oldest_first() {
+        let apks = vec![
+            apk("Game-v2.apk", Some("aaa"), 100, 30),
+            apk("Game.apk", Some("aaa"), 100, 10),
+            apk("Unique.apk", Some("bbb"), 500, 10),
+            apk("Unhashed.apk", None, 500, 10),
+            apk("Big (1).apk", Some("ccc"), 1000, 20),
+            apk("Big.apk", Some("ccc"), 1000, 20),
+            apk("Game copy.apk", Some("aaa"), 100, 20),
+        ];
+
+        let groups = find_duplicates(apks);
+        let names: Vec<Vec<&str>> = groups
+            .iter()
+            .map(|g| g.apks.iter().map(|a| a.filename.as_str()).collect())
+            .collect();
+
+        assert_eq!(
+            names,
+            vec![
+                vec!["Big (1).apk", "Big.apk"],
+                vec!["Game.apk", "Game copy.apk", "Game-v2.apk"],
+            ]
+        );
+        assert_eq!(groups[0].wasted_bytes(), 1000);
+        assert_eq!(groups[1].wasted_bytes(), 200);
+    }
+}
@@ -0,0 +1,26 @@
+/// Command history repository trait
+/// Abstraction for the per-device history of command results.
+
+use crate::domain::models::{CommandRecord, DeviceId};
+
+/// Repository keeping the most recent command results of each device
+/// The number of entries kept per device is up to the implementation.
+/// When a device disconnects its history can be parked under its serial and
+/// handed to its next connection.
+pub trait CommandHistoryRepository: Send + Sync {
+    /// Record a command result, dropping the oldest entries beyond the cap
+    fn record(&self, device_id: DeviceId, record: CommandRecord);
+
+    /// The most recent command results for a device, oldest first
+    /// At most `limit` entries are returned when given.
+    fn recent_commands(&self, device_id: &DeviceId, limit: Option<usize>) -> Vec<CommandRecord>;
+
+    /// Drop the history of a device that has disconnected
+    fn forget(&self, device_id: &DeviceId);
+
+    /// Keep the history of a disconnected device until it reconnects
+    fn park(&self, device_id: &DeviceId, serial: &str);
+
+    /// Hand a reconnected device the history parked under its serial
+    fn restore(&self, serial: &str, device_id: DeviceId);
+}
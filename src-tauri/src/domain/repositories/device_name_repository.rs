@@ -5,8 +5,28 @@ use super::error::RepositoryError;
 
 pub type Result<T> = std::result::Result<T, RepositoryError>;
 
-/// Repository for managing custom device names
-/// Device names are persisted separately from device state to ensure
+/// Staff-maintained tags and note for a device
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeviceAnnotations {
+    pub tags: Vec<String>,
+    pub note: Option<String>,
+}
+
+/// Trim and lowercase tags, dropping empty ones and duplicates
+/// Keeps the order in which tags were first given.
+pub fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+/// Repository for managing custom device names, tags and notes
+/// These are persisted separately from device state to ensure
 /// they survive across device disconnections and application restarts.
 #[async_trait]
 pub trait DeviceNameRepository: Send + Sync {
@@ -17,4 +37,36 @@ pub trait DeviceNameRepository: Send + Sync {
     /// Set a custom name for a device
     /// If `name` is `None`, the custom name will be cleared.
     async fn set_name(&self, serial: &Serial, name: Option<String>) -> Result<()>;
+
+    /// Get the tags and note for a device, empty if none were set
+    async fn get_annotations(&self, serial: &Serial) -> Result<DeviceAnnotations>;
+
+    /// Replace the tags of a device; expects tags already normalized
+    async fn set_tags(&self, serial: &Serial, tags: &[String]) -> Result<()>;
+
+    /// Set the note for a device
+    /// If `note` is `None`, the note will be cleared.
+    async fn set_note(&self, serial: &Serial, note: Option<String>) -> Result<()>;
+
+    /// Get the package a device is expected to stay in, if any
+    async fn get_expected_package(&self, serial: &Serial) -> Result<Option<String>>;
+
+    /// Set the package a device is expected to stay in
+    /// If `package_name` is `None`, the expectation will be cleared.
+    async fn set_expected_package(&self, serial: &Serial, package_name: Option<&str>) -> Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_are_trimmed_lowercased_and_deduplicated() {
+        let tags = ["Needs Repair", " needs repair ", "", "  ", "Loaner", "LOANER"]
+            .iter()
+            .map(|t| t.to_string())
+            .collect();
+
+        assert_eq!(normalize_tags(tags), vec!["needs repair", "loaner"]);
+    }
 }
@@ -9,6 +9,14 @@ use super::error::RepositoryError;
 
 pub type Result<T> = std::result::Result<T, RepositoryError>;
 
+/// Every known device as of a single instant
+#[derive(Debug, Clone, Default)]
+pub struct DeviceSnapshot {
+    pub connected: Vec<Arc<Device>>,
+    /// Last known state of devices that have disconnected
+    pub disconnected: Vec<Arc<Device>>,
+}
+
 /// Repository for managing device entities
 /// This trait abstracts the storage mechanism for devices, allowing
 /// different implementations (in-memory, Redis, PostgreSQL, etc.).
@@ -22,16 +30,24 @@ pub trait DeviceRepository: Send + Sync {
     /// Returns `None` if no device with the given serial exists.
     async fn find_by_serial(&self, serial: &Serial) -> Result<Option<Arc<Device>>>;
 
+    /// Find a connected device by serial, or the state it had when it last disconnected
+    /// Used to merge a reconnecting device into its previous state.
+    async fn find_last_known(&self, serial: &Serial) -> Result<Option<Arc<Device>>>;
+
     /// Find all devices
     /// Returns all devices currently stored in the repository.
     async fn find_all(&self) -> Result<Vec<Arc<Device>>>;
 
+    /// Take a consistent snapshot of connected and disconnected devices
+    /// No device is seen half-way through connecting or disconnecting.
+    async fn snapshot(&self) -> Result<DeviceSnapshot>;
+
     /// Save or update a device
     /// If a device with the same ID already exists, it will be updated.
     /// Otherwise, a new device entry will be created.
     async fn save(&self, device: Device) -> Result<()>;
 
-    /// Remove a device by ID
+    /// Remove a device by ID, keeping its state for `find_last_known`
     /// Returns `Ok(())` even if the device doesn't exist (idempotent).
     async fn remove(&self, id: DeviceId) -> Result<()>;
 
@@ -15,6 +15,12 @@ pub enum RepositoryError {
     #[error("Item not found: {item}")]
     NotFound { item: String },
 
+    #[error("An identical APK is already stored as {existing_filename}")]
+    DuplicateApk { existing_filename: String },
+
+    #[error("Operation cancelled")]
+    Cancelled,
+
     #[error("Repository capacity exceeded: current={current}, max={max}")]
     CapacityExceeded { current: usize, max: usize },
 
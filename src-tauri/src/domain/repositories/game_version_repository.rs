@@ -1,40 +1,54 @@
 use crate::application::dto::{GameAssignment, GameDownloadResponse, LocalGameMetadata};
 use async_trait::async_trait;
+use serde::Serialize;
 use std::path::PathBuf;
+use tokio_util::sync::CancellationToken;
 
 /// Repository for managing game versions
 /// Handles fetching game assignments, downloading game files, and tracking installed versions
 #[async_trait]
 pub trait GameVersionRepository: Send + Sync {
     /// Fetch all game assignments for this arcade from Alakazam
-    /// Returns list of games assigned to this arcade with version information
-    async fn fetch_game_assignments(&self) -> Result<Vec<GameAssignment>, GameVersionError>;
+    /// Returns list of games assigned to this arcade with version information.
+    /// With a `channel`, versions come from that release channel instead of the
+    /// arcade's own; games with nothing published there are left out.
+    async fn fetch_game_assignments(&self, channel: Option<&str>) -> Result<Vec<GameAssignment>, GameVersionError>;
 
     /// Fetch download URLs for a specific game from Alakazam
-    /// Returns signed URLs for all files in the game version
-    async fn fetch_download_urls(&self, game_id: i32) -> Result<GameDownloadResponse, GameVersionError>;
+    /// Returns signed URLs for all files in the game version, taken from
+    /// `channel` when given and the arcade's own channel otherwise
+    async fn fetch_download_urls(
+        &self,
+        game_id: i32,
+        channel: Option<&str>,
+    ) -> Result<GameDownloadResponse, GameVersionError>;
 
     /// Download all files for a game version
-    /// Downloads each file and saves it to the appropriate location
-    /// Calls progress_callback after each file with (downloaded_count, total_count, current_file)
+    /// Installed files that match the reported size and checksum are skipped; the rest
+    /// are written to a partial file and moved into place once verified. Files the
+    /// version no longer has are removed only after every new file has verified.
+    /// With `resume` set, partial files left by an interrupted download of the same
+    /// version are continued using HTTP range requests instead of starting over.
+    /// Calls progress_callback as data arrives, counting already-downloaded bytes.
+    /// Before the install is replaced every staged file is hashed again against
+    /// the file list, reported in the `Verifying` phase; a file that fails is
+    /// deleted and the download fails with `VerificationFailed`.
+    /// `metadata` names the game and version and is installed together with the
+    /// files, so the game directory never holds files of one version and the
+    /// metadata of another.
     async fn download_game_files(
         &self,
-        game_name: &str,
+        metadata: &LocalGameMetadata,
         files: &[crate::application::dto::GameFile],
-        progress_callback: Box<dyn Fn(usize, usize, String) + Send + Sync>,
+        resume: bool,
+        cancel_token: CancellationToken,
+        progress_callback: Box<dyn Fn(FileDownloadProgress) + Send + Sync>,
     ) -> Result<(), GameVersionError>;
 
     /// Get local metadata for an installed game
     /// Returns None if the game is not installed
     async fn get_local_metadata(&self, game_name: &str) -> Result<Option<LocalGameMetadata>, GameVersionError>;
 
-    /// Save local metadata after installing a game
-    async fn save_local_metadata(
-        &self,
-        game_name: &str,
-        metadata: &LocalGameMetadata,
-    ) -> Result<(), GameVersionError>;
-
     /// Report current version status to Alakazam
     /// Updates the server with the currently installed version
     async fn report_version_status(
@@ -46,11 +60,84 @@ pub trait GameVersionRepository: Send + Sync {
     /// Get the installation directory for a game
     fn get_game_directory(&self, game_name: &str) -> PathBuf;
 
+    /// Check installed files against the sizes and checksums reported by the server
+    /// Returns the files that are missing, don't match, or lie outside the game
+    /// directory; nothing is modified.
+    async fn verify_game_files(
+        &self,
+        game_name: &str,
+        files: &[crate::application::dto::GameFile],
+    ) -> Result<Vec<FileVerificationFailure>, GameVersionError>;
+
+    /// Work out which files an update to `files` would download and remove
+    /// Installed files are compared by size and checksum; nothing is modified.
+    /// Fails if a file lies outside the game directory.
+    async fn plan_update(
+        &self,
+        game_name: &str,
+        files: &[crate::application::dto::GameFile],
+    ) -> Result<UpdatePlan, GameVersionError>;
+
+    /// Delete the listed files (relative to the game directory) and the game's metadata
+    /// Anything else in the directory is left alone; directories emptied by the
+    /// deletion are removed. Returns the number of bytes freed.
+    async fn delete_game_files(&self, game_name: &str, files: &[String]) -> Result<u64, GameVersionError>;
+
+    /// Total size of every file in a game's directory, 0 if it isn't installed
+    async fn installed_size(&self, game_name: &str) -> Result<u64, GameVersionError>;
+
     /// Scan the games directory and discover all installed games
     /// Returns a list of LocalGameMetadata for all games found
     async fn scan_installed_games(&self) -> Result<Vec<LocalGameMetadata>, GameVersionError>;
 }
 
+/// What a game download is doing when it reports progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DownloadPhase {
+    Downloading,
+    /// Re-checking staged files before they replace the install; file and byte
+    /// counts are then the files verified so far
+    Verifying,
+}
+
+/// Progress snapshot reported while downloading game files
+#[derive(Debug, Clone)]
+pub struct FileDownloadProgress {
+    pub phase: DownloadPhase,
+    pub downloaded_files: usize,
+    pub total_files: usize,
+    pub downloaded_bytes: u64,
+    /// Total size of the version, known only when the server reports every file size
+    pub total_bytes: Option<u64>,
+    pub current_file: String,
+}
+
+/// Files an update would touch, relative to the game directory
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePlan {
+    /// Files not installed yet
+    pub added: Vec<String>,
+    /// Installed files whose size or checksum differs from the new version
+    pub changed: Vec<String>,
+    /// Installed files the new version no longer has
+    pub removed: Vec<String>,
+    /// Number of installed files that are kept as they are
+    pub unchanged: usize,
+    /// Bytes to download, known only when the server reports every file size
+    pub download_bytes: Option<u64>,
+}
+
+/// An installed file that failed verification
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileVerificationFailure {
+    pub path: String,
+    pub reason: String,
+}
+
+const BYTES_PER_GB: f64 = 1024.0 * 1024.0 * 1024.0;
+
 /// Errors that can occur during game version operations
 #[derive(Debug, thiserror::Error)]
 pub enum GameVersionError {
@@ -68,4 +155,44 @@ pub enum GameVersionError {
 
     #[error("Download failed for file {file}: {error}")]
     DownloadFailed { file: String, error: String },
+
+    #[error("Checksum mismatch for file {file}")]
+    ChecksumMismatch { file: String },
+
+    #[error("Downloaded file {file} failed verification: {reason}")]
+    VerificationFailed { file: String, reason: String },
+
+    #[error("Size mismatch for file {file}: expected {expected} bytes, got {actual}")]
+    SizeMismatch { file: String, expected: u64, actual: u64 },
+
+    #[error(
+        "Not enough disk space: the download needs {:.1} GB but only {:.1} GB is free",
+        *.required as f64 / BYTES_PER_GB,
+        *.available as f64 / BYTES_PER_GB
+    )]
+    InsufficientSpace { required: u64, available: u64 },
+
+    #[error("Download cancelled")]
+    Cancelled,
+
+    #[error("Download paused")]
+    Paused,
+
+    #[error("Download already in progress for game {0}")]
+    AlreadyDownloading(i32),
+
+    #[error("No download in progress for game {0}")]
+    NotDownloading(i32),
+
+    #[error("Download for game {0} is not paused")]
+    NotPaused(i32),
+
+    #[error("{0} is currently running; stop it before deleting")]
+    GameRunning(String),
+
+    #[error("Game {0} is being deleted")]
+    Deleting(i32),
+
+    #[error("Invalid release channel: {0}")]
+    InvalidChannel(String),
 }
@@ -4,10 +4,18 @@ pub mod device_name_repository;
 pub mod apk_repository;
 pub mod client_apk_repository;
 pub mod game_version_repository;
+pub mod command_history_repository;
 
 pub use error::RepositoryError;
-pub use device_repository::DeviceRepository;
-pub use device_name_repository::DeviceNameRepository;
-pub use apk_repository::{ApkRepository, ApkInfo};
+pub use device_repository::{DeviceRepository, DeviceSnapshot};
+pub use device_name_repository::{normalize_tags, DeviceAnnotations, DeviceNameRepository};
+pub use apk_repository::{
+    find_duplicates, ApkCopyProgress, ApkInfo, ApkListQuery, ApkMetadata, ApkPage, ApkRepository,
+    ApkSortField, ApkVerification, DuplicateApks, SortOrder,
+};
 pub use client_apk_repository::{ClientApkRepository, ClientApkError};
-pub use game_version_repository::{GameVersionRepository, GameVersionError};
+pub use command_history_repository::CommandHistoryRepository;
+pub use game_version_repository::{
+    DownloadPhase, FileDownloadProgress, FileVerificationFailure, GameVersionRepository, GameVersionError,
+    UpdatePlan,
+};
@@ -4,10 +4,11 @@
 use crate::domain::commands::{BatchResult, Command, CommandResponse};
 use crate::domain::models::DeviceId;
 use crate::domain::repositories::{DeviceRepository, RepositoryError};
-use crate::domain::services::SessionManager;
+use crate::domain::services::{ResponseTracker, SessionManager};
 use futures::stream::FuturesUnordered;
 use futures::StreamExt;
 use std::sync::Arc;
+use std::time::Duration;
 
 pub type Result<T> = std::result::Result<T, CommandError>;
 
@@ -58,31 +59,74 @@ pub enum CommandError {
 pub struct CommandExecutor {
     device_repo: Arc<dyn DeviceRepository>,
     session_manager: Arc<dyn SessionManager>,
+    response_tracker: Arc<ResponseTracker>,
+    /// How long to wait for a response when no explicit timeout is given
+    default_timeout: Duration,
+    /// Extra attempts made for idempotent commands that time out
+    max_retries: u32,
 }
 
 impl CommandExecutor {
     pub fn new(
         device_repo: Arc<dyn DeviceRepository>,
         session_manager: Arc<dyn SessionManager>,
+        response_tracker: Arc<ResponseTracker>,
+        default_timeout: Duration,
+        max_retries: u32,
     ) -> Self {
         Self {
             device_repo,
             session_manager,
+            response_tracker,
+            default_timeout,
+            max_retries,
         }
     }
 
-    /// Execute a command on a single device
+    /// Execute a command on a single device using the default timeout
     pub async fn execute_single(
         &self,
         device_id: DeviceId,
         cmd: Arc<dyn Command>,
+    ) -> Result<CommandResponse> {
+        self.execute_with_timeout(device_id, cmd, self.default_timeout)
+            .await
+    }
+
+    /// Execute a command on a single device, waiting up to `timeout` for its response
+    /// Idempotent commands are re-sent up to `max_retries` times if they time out.
+    pub async fn execute_with_timeout(
+        &self,
+        device_id: DeviceId,
+        cmd: Arc<dyn Command>,
+        timeout: Duration,
     ) -> Result<CommandResponse> {
         if let Err(e) = cmd.validate() {
             return Err(CommandError::ValidationFailed(e));
         }
 
-        // Execute command
-        self.execute_internal(device_id, cmd).await
+        let max_attempts = if cmd.is_idempotent() {
+            self.max_retries + 1
+        } else {
+            1
+        };
+
+        let mut attempt = 1;
+        loop {
+            match self.execute_internal(device_id, Arc::clone(&cmd), timeout).await {
+                Err(CommandError::Timeout { .. }) if attempt < max_attempts => {
+                    tracing::warn!(
+                        device_id = %device_id,
+                        command = cmd.name(),
+                        attempt,
+                        max_attempts,
+                        "Command timed out, retrying"
+                    );
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
     }
 
     /// Execute a command on multiple devices in parallel
@@ -120,11 +164,12 @@ impl CommandExecutor {
         result
     }
 
-    /// Internal execution logic (override this in tests)
+    /// Internal execution logic
     async fn execute_internal(
         &self,
         device_id: DeviceId,
         cmd: Arc<dyn Command>,
+        timeout: Duration,
     ) -> Result<CommandResponse> {
         // Verify device exists
         let _device = self
@@ -145,24 +190,66 @@ impl CommandExecutor {
             payload,
         };
 
+        // Register for the response before sending so a fast reply isn't missed
+        let pending = cmd
+            .response_opcode()
+            .map(|opcode| (opcode, self.response_tracker.register(device_id, opcode)));
+
         // Send packet to device via session manager
-        self.session_manager
-            .send_packet(device_id, packet)
-            .await
-            .map_err(|e| CommandError::ExecutionFailed {
+        let sent = match &pending {
+            Some((_, (request_id, _))) => self.session_manager.send_request(device_id, *request_id, packet).await,
+            None => self.session_manager.send_packet(device_id, packet).await,
+        };
+        if let Err(e) = sent {
+            if let Some((opcode, (request_id, _))) = &pending {
+                self.response_tracker.cancel(device_id, *opcode, *request_id);
+            }
+            return Err(CommandError::ExecutionFailed {
                 device_id,
                 command: cmd.name().to_string(),
                 reason: e.to_string(),
-            })?;
+            });
+        }
+
+        let Some((response_opcode, (request_id, receiver))) = pending else {
+            tracing::debug!(
+                device_id = %device_id,
+                command = cmd.name(),
+                "Command sent successfully"
+            );
+
+            // Fire-and-forget command - any response is handled by the packet handler
+            return Ok(CommandResponse::Success);
+        };
+
+        self.response_tracker
+            .mark_sent(device_id, response_opcode, request_id);
 
         tracing::debug!(
             device_id = %device_id,
             command = cmd.name(),
-            "Command sent successfully"
+            request_id,
+            "Command sent, awaiting response"
         );
 
-        // Return success - actual response will come via packet handler
-        Ok(CommandResponse::Success)
+        match tokio::time::timeout(timeout, receiver).await {
+            Ok(Ok(payload)) => Ok(CommandResponse::SuccessWithData(payload)),
+            Ok(Err(_)) => Err(CommandError::ExecutionFailed {
+                device_id,
+                command: cmd.name().to_string(),
+                reason: "Device disconnected before responding".to_string(),
+            }),
+            Err(_) => {
+                self.response_tracker
+                    .expire(device_id, response_opcode, request_id);
+
+                Err(CommandError::Timeout {
+                    device_id,
+                    command: cmd.name().to_string(),
+                    timeout_ms: timeout.as_millis() as u64,
+                })
+            }
+        }
     }
 
     /// Clone for parallel task execution
@@ -170,6 +257,208 @@ impl CommandExecutor {
         Self {
             device_repo: Arc::clone(&self.device_repo),
             session_manager: Arc::clone(&self.session_manager),
+            response_tracker: Arc::clone(&self.response_tracker),
+            default_timeout: self.default_timeout,
+            max_retries: self.max_retries,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::commands::{ExecuteShellCommand, PingCommand, RestartDeviceCommand, UninstallAppCommand};
+    use crate::domain::models::{Device, PackageName, Serial};
+    use crate::domain::services::{PacketTraceHook, SessionError};
+    use crate::infrastructure::protocol::{opcodes, RawPacket};
+    use crate::infrastructure::repositories::InMemoryDeviceRepository;
+    use async_trait::async_trait;
+    use parking_lot::Mutex;
+
+    /// Session manager that records sent packets but never answers them
+    #[derive(Default)]
+    struct DroppingSessionManager {
+        sent: Mutex<Vec<RawPacket>>,
+        /// Request id of each packet sent with `send_request`
+        request_ids: Mutex<Vec<u64>>,
+    }
+
+    #[async_trait]
+    impl SessionManager for DroppingSessionManager {
+        async fn send_packet(
+            &self,
+            _device_id: DeviceId,
+            packet: RawPacket,
+        ) -> std::result::Result<(), SessionError> {
+            self.sent.lock().push(packet);
+            Ok(())
+        }
+
+        fn has_session(&self, _device_id: &DeviceId) -> bool {
+            true
+        }
+
+        async fn send_request(
+            &self,
+            device_id: DeviceId,
+            request_id: u64,
+            packet: RawPacket,
+        ) -> std::result::Result<(), SessionError> {
+            self.request_ids.lock().push(request_id);
+            self.send_packet(device_id, packet).await
+        }
+
+        fn set_packet_trace(&self, _device_id: &DeviceId, _hook: Option<PacketTraceHook>) -> bool {
+            false
+        }
+    }
+
+    async fn setup(
+        max_retries: u32,
+    ) -> (CommandExecutor, Arc<DroppingSessionManager>, Arc<ResponseTracker>, DeviceId) {
+        let device_repo = Arc::new(InMemoryDeviceRepository::new());
+        let device_id = DeviceId::new();
+        let serial = Serial::new("aa:bb:cc:dd:ee:ff".to_string()).unwrap();
+        device_repo
+            .save(Device::new(device_id, serial, "Quest 3".to_string(), "1.0.0".to_string()))
+            .await
+            .unwrap();
+
+        let sessions = Arc::new(DroppingSessionManager::default());
+        let tracker = Arc::new(ResponseTracker::new());
+        let executor = CommandExecutor::new(
+            device_repo,
+            sessions.clone(),
+            tracker.clone(),
+            Duration::from_millis(50),
+            max_retries,
+        );
+
+        (executor, sessions, tracker, device_id)
+    }
+
+    #[tokio::test]
+    async fn dropped_response_times_out() {
+        let (executor, sessions, _, device_id) = setup(0).await;
+
+        let result = executor.execute_single(device_id, Arc::new(PingCommand)).await;
+
+        assert!(matches!(result, Err(CommandError::Timeout { timeout_ms: 50, .. })));
+        assert_eq!(sessions.sent.lock().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn idempotent_command_is_retried() {
+        let (executor, sessions, _, device_id) = setup(2).await;
+
+        let result = executor.execute_single(device_id, Arc::new(PingCommand)).await;
+
+        assert!(matches!(result, Err(CommandError::Timeout { .. })));
+        assert_eq!(sessions.sent.lock().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn non_idempotent_command_is_not_retried() {
+        let (executor, sessions, _, device_id) = setup(2).await;
+        let package = PackageName::new("com.example.app".to_string()).unwrap();
+
+        let result = executor
+            .execute_single(device_id, Arc::new(UninstallAppCommand::new(package)))
+            .await;
+
+        assert!(matches!(result, Err(CommandError::Timeout { .. })));
+        assert_eq!(sessions.sent.lock().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn fire_and_forget_command_does_not_wait() {
+        let (executor, _, _, device_id) = setup(0).await;
+
+        let result = executor
+            .execute_single(device_id, Arc::new(RestartDeviceCommand))
+            .await;
+
+        assert!(matches!(result, Ok(CommandResponse::Success)));
+    }
+
+    #[tokio::test]
+    async fn response_completes_pending_command() {
+        let (executor, _, tracker, device_id) = setup(0).await;
+
+        let responder = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            tracker.complete(device_id, opcodes::PING_RESPONSE, vec![1])
+        });
+
+        let result = executor
+            .execute_with_timeout(device_id, Arc::new(PingCommand), Duration::from_secs(1))
+            .await;
+
+        assert!(matches!(result, Ok(CommandResponse::SuccessWithData(ref data)) if data == &vec![1]));
+        assert!(responder.await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn late_response_is_not_matched_to_newer_command() {
+        let (executor, _, tracker, device_id) = setup(0).await;
+
+        let first = executor.execute_single(device_id, Arc::new(PingCommand)).await;
+        assert!(matches!(first, Err(CommandError::Timeout { .. })));
+
+        let late_tracker = tracker.clone();
+        let responder = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            // Late response for the expired first ping
+            late_tracker.complete(device_id, opcodes::PING_RESPONSE, vec![1]);
+        });
+
+        let second = executor
+            .execute_with_timeout(device_id, Arc::new(PingCommand), Duration::from_millis(100))
+            .await;
+        responder.await.unwrap();
+
+        assert!(matches!(second, Err(CommandError::Timeout { .. })));
+    }
+
+    #[tokio::test]
+    async fn concurrent_requests_get_their_own_responses() {
+        let (executor, sessions, tracker, device_id) = setup(0).await;
+        let executor = Arc::new(executor);
+
+        let shell = |command: &str| Arc::new(ExecuteShellCommand::new(command.to_string()));
+        let run = |command: Arc<ExecuteShellCommand>| {
+            let executor = executor.clone();
+            tokio::spawn(async move {
+                executor
+                    .execute_with_timeout(device_id, command, Duration::from_secs(1))
+                    .await
+            })
+        };
+        let first = run(shell("echo first"));
+        let second = run(shell("echo second"));
+
+        while sessions.sent.lock().len() < 2 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+
+        // Answer in the opposite order, echoing each command's payload as its output
+        let request_ids = sessions.request_ids.lock().clone();
+        let sent: Vec<_> = request_ids.into_iter().zip(sessions.sent.lock().clone()).collect();
+        for (request_id, command) in sent.into_iter().rev() {
+            assert!(tracker.complete_request(
+                device_id,
+                opcodes::SHELL_EXECUTION_RESPONSE,
+                request_id,
+                command.payload,
+            ));
+        }
+
+        for (handle, command) in [(first, "echo first"), (second, "echo second")] {
+            let expected = shell(command).serialize().unwrap();
+            assert!(matches!(
+                handle.await.unwrap(),
+                Ok(CommandResponse::SuccessWithData(ref data)) if data == &expected
+            ));
         }
     }
 }
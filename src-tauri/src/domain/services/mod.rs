@@ -1,7 +1,13 @@
 pub mod command_executor;
+pub mod response_tracker;
+pub mod screenshot_assembler;
 pub mod session_manager;
+pub mod shell_policy;
 
 pub use command_executor::{
     CommandError, CommandExecutor,
 };
-pub use session_manager::{SessionError, SessionManager};
+pub use response_tracker::{RequestId, ResponseTracker};
+pub use screenshot_assembler::{ScreenshotAssembler, ScreenshotError};
+pub use session_manager::{PacketDirection, PacketTraceHook, SessionError, SessionManager};
+pub use shell_policy::ShellPolicy;
@@ -0,0 +1,261 @@
+/// Response Tracker
+/// Correlates outgoing commands with the response packets that answer them.
+///
+/// Plain responses carry no request id, so they are matched in FIFO order per
+/// (device, response opcode). Each registered command gets a request id; when a
+/// command times out its slot is kept as a tombstone so a late response is
+/// consumed by the expired request instead of being matched to a newer command.
+/// Clients that echo request ids (`protocol::tagged`) are matched by id instead.
+
+use crate::domain::models::DeviceId;
+use parking_lot::Mutex;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use tokio::sync::oneshot;
+
+pub type RequestId = u64;
+
+/// How long an expired request keeps waiting for its late response
+const TOMBSTONE_GRACE: Duration = Duration::from_secs(60);
+
+struct PendingRequest {
+    request_id: RequestId,
+    /// `None` once the request has timed out (tombstone)
+    sender: Option<oneshot::Sender<Vec<u8>>>,
+    /// When the command went out, for round-trip time
+    sent_at: Instant,
+    expired_at: Option<Instant>,
+}
+
+/// Tracks commands that are awaiting a response from a device
+pub struct ResponseTracker {
+    next_id: AtomicU64,
+    pending: Mutex<HashMap<(DeviceId, u8), VecDeque<PendingRequest>>>,
+}
+
+impl ResponseTracker {
+    pub fn new() -> Self {
+        Self {
+            next_id: AtomicU64::new(1),
+            pending: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Register a command that expects `response_opcode` from `device_id`
+    /// Returns the request id and a receiver resolved with the response payload.
+    pub fn register(
+        &self,
+        device_id: DeviceId,
+        response_opcode: u8,
+    ) -> (RequestId, oneshot::Receiver<Vec<u8>>) {
+        let request_id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+
+        let mut pending = self.pending.lock();
+        let queue = pending.entry((device_id, response_opcode)).or_default();
+        Self::prune_tombstones(queue);
+        queue.push_back(PendingRequest {
+            request_id,
+            sender: Some(tx),
+            sent_at: Instant::now(),
+            expired_at: None,
+        });
+
+        (request_id, rx)
+    }
+
+    /// Deliver a response packet to the oldest request waiting for it
+    /// Returns the request id the response was matched to, if any.
+    pub fn complete(&self, device_id: DeviceId, opcode: u8, payload: Vec<u8>) -> Option<RequestId> {
+        let mut pending = self.pending.lock();
+        let key = (device_id, opcode);
+        let queue = pending.get_mut(&key)?;
+        Self::prune_tombstones(queue);
+
+        let request = queue.pop_front();
+        if queue.is_empty() {
+            pending.remove(&key);
+        }
+        let request = request?;
+
+        match request.sender {
+            Some(sender) => {
+                let _ = sender.send(payload);
+            }
+            None => {
+                tracing::debug!(
+                    device_id = %device_id,
+                    opcode,
+                    request_id = request.request_id,
+                    "Discarding late response for expired request"
+                );
+            }
+        }
+
+        Some(request.request_id)
+    }
+
+    /// Deliver a response tagged with the request id it answers
+    /// Returns false if no request with that id is waiting.
+    pub fn complete_request(
+        &self,
+        device_id: DeviceId,
+        opcode: u8,
+        request_id: RequestId,
+        payload: Vec<u8>,
+    ) -> bool {
+        let mut pending = self.pending.lock();
+        let key = (device_id, opcode);
+        let Some(queue) = pending.get_mut(&key) else {
+            return false;
+        };
+
+        let request = queue
+            .iter()
+            .position(|r| r.request_id == request_id)
+            .and_then(|index| queue.remove(index));
+        if queue.is_empty() {
+            pending.remove(&key);
+        }
+        let Some(request) = request else {
+            return false;
+        };
+
+        match request.sender {
+            Some(sender) => {
+                let _ = sender.send(payload);
+            }
+            None => {
+                tracing::debug!(device_id = %device_id, opcode, request_id, "Discarding late response for expired request");
+            }
+        }
+        true
+    }
+
+    /// Record that the command for a request has actually been sent
+    /// Registration happens before the send, which may wait for the connection.
+    pub fn mark_sent(&self, device_id: DeviceId, response_opcode: u8, request_id: RequestId) {
+        let mut pending = self.pending.lock();
+        if let Some(queue) = pending.get_mut(&(device_id, response_opcode)) {
+            if let Some(request) = queue.iter_mut().find(|r| r.request_id == request_id) {
+                request.sent_at = Instant::now();
+            }
+        }
+    }
+
+    /// The request the next untagged `opcode` response from `device_id` will be matched to
+    pub fn next_request_id(&self, device_id: DeviceId, opcode: u8) -> Option<RequestId> {
+        let mut pending = self.pending.lock();
+        let queue = pending.get_mut(&(device_id, opcode))?;
+        Self::prune_tombstones(queue);
+        queue.front().map(|request| request.request_id)
+    }
+
+    /// When the command for a still pending request was sent
+    pub fn sent_at(&self, device_id: DeviceId, response_opcode: u8, request_id: RequestId) -> Option<Instant> {
+        self.pending
+            .lock()
+            .get(&(device_id, response_opcode))?
+            .iter()
+            .find(|r| r.request_id == request_id)
+            .map(|r| r.sent_at)
+    }
+
+    /// Mark a request as timed out
+    /// Its slot is kept so that a late response is not matched to a newer request.
+    pub fn expire(&self, device_id: DeviceId, response_opcode: u8, request_id: RequestId) {
+        let mut pending = self.pending.lock();
+        if let Some(queue) = pending.get_mut(&(device_id, response_opcode)) {
+            if let Some(request) = queue.iter_mut().find(|r| r.request_id == request_id) {
+                request.sender = None;
+                request.expired_at = Some(Instant::now());
+            }
+        }
+    }
+
+    /// Forget a request whose packet was never sent
+    pub fn cancel(&self, device_id: DeviceId, response_opcode: u8, request_id: RequestId) {
+        let mut pending = self.pending.lock();
+        let key = (device_id, response_opcode);
+        if let Some(queue) = pending.get_mut(&key) {
+            queue.retain(|r| r.request_id != request_id);
+            if queue.is_empty() {
+                pending.remove(&key);
+            }
+        }
+    }
+
+    /// Number of requests still waiting for a response, not counting expired ones
+    pub fn in_flight(&self) -> usize {
+        self.pending
+            .lock()
+            .values()
+            .flatten()
+            .filter(|request| request.sender.is_some())
+            .count()
+    }
+
+    /// Drop all pending requests for a device (e.g. on disconnect)
+    /// Waiting commands observe a closed channel.
+    pub fn clear_device(&self, device_id: &DeviceId) {
+        self.pending.lock().retain(|(id, _), _| id != device_id);
+    }
+
+    fn prune_tombstones(queue: &mut VecDeque<PendingRequest>) {
+        while let Some(front) = queue.front() {
+            match front.expired_at {
+                Some(expired_at) if expired_at.elapsed() > TOMBSTONE_GRACE => {
+                    queue.pop_front();
+                }
+                _ => break,
+            }
+        }
+    }
+}
+
+impl Default for ResponseTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPCODE: u8 = 0x13;
+
+    #[test]
+    fn next_request_id_skips_stale_tombstones_like_complete() {
+        let tracker = ResponseTracker::new();
+        let device = DeviceId::new();
+        let (first, _rx1) = tracker.register(device, OPCODE);
+        let (second, _rx2) = tracker.register(device, OPCODE);
+
+        assert_eq!(tracker.next_request_id(device, OPCODE), Some(first));
+        tracker.expire(device, OPCODE, first);
+        // Still within its grace period, so a late response goes to the expired request
+        assert_eq!(tracker.next_request_id(device, OPCODE), Some(first));
+        assert_eq!(tracker.complete(device, OPCODE, vec![]), Some(first));
+        assert_eq!(tracker.next_request_id(device, OPCODE), Some(second));
+    }
+
+    #[test]
+    fn sent_at_is_looked_up_by_request_id() {
+        let tracker = ResponseTracker::new();
+        let device = DeviceId::new();
+        let (first, _rx1) = tracker.register(device, OPCODE);
+        std::thread::sleep(Duration::from_millis(5));
+        let (second, _rx2) = tracker.register(device, OPCODE);
+
+        let first_sent = tracker.sent_at(device, OPCODE, first).unwrap();
+        let second_sent = tracker.sent_at(device, OPCODE, second).unwrap();
+        assert!(second_sent > first_sent);
+
+        // A tagged response for the newer request leaves the older one pending
+        assert!(tracker.complete_request(device, OPCODE, second, vec![]));
+        assert_eq!(tracker.sent_at(device, OPCODE, second), None);
+        assert_eq!(tracker.sent_at(device, OPCODE, first), Some(first_sent));
+    }
+}
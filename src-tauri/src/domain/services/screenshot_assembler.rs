@@ -0,0 +1,274 @@
+/// Screenshot Assembler
+/// Reassembles screenshots that devices stream back in fragments.
+///
+/// A device answers REQUEST_SCREENSHOT with a series of SCREENSHOT_CHUNK
+/// packets, each carrying the total image size and the offset of its data.
+/// Chunks must arrive in order; once the last byte is received the image is
+/// delivered to whoever is waiting for that device's screenshot.
+
+use crate::domain::models::DeviceId;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use tokio::sync::oneshot;
+
+/// PNG file signature every screenshot must start with
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ScreenshotError {
+    #[error("Screenshot of {total_bytes} bytes exceeds the {max_bytes} byte limit")]
+    TooLarge { total_bytes: usize, max_bytes: usize },
+
+    #[error("Screenshot chunk out of order: expected offset {expected}, got {actual}")]
+    OutOfOrder { expected: usize, actual: usize },
+
+    #[error("Screenshot chunk overruns the announced size of {total_bytes} bytes")]
+    Overrun { total_bytes: usize },
+
+    #[error("Screenshot is not a PNG image")]
+    NotPng,
+}
+
+struct PartialScreenshot {
+    total_bytes: usize,
+    data: Vec<u8>,
+}
+
+/// Collects screenshot chunks per device and hands out completed images
+pub struct ScreenshotAssembler {
+    max_bytes: usize,
+    partial: Mutex<HashMap<DeviceId, PartialScreenshot>>,
+    waiters: Mutex<HashMap<DeviceId, oneshot::Sender<Result<Vec<u8>, ScreenshotError>>>>,
+}
+
+impl ScreenshotAssembler {
+    pub fn new(max_bytes: usize) -> Self {
+        Self {
+            max_bytes,
+            partial: Mutex::new(HashMap::new()),
+            waiters: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Wait for the next screenshot from `device_id`
+    /// Replaces any earlier waiter for the same device, whose receiver then closes.
+    pub fn expect(&self, device_id: DeviceId) -> oneshot::Receiver<Result<Vec<u8>, ScreenshotError>> {
+        let (tx, rx) = oneshot::channel();
+        self.partial.lock().remove(&device_id);
+        self.waiters.lock().insert(device_id, tx);
+        rx
+    }
+
+    /// Add a chunk of `total_bytes` screenshot data starting at `offset`
+    /// Returns the number of bytes received so far. Any error discards the
+    /// partial image and is forwarded to the waiter.
+    pub fn push_chunk(
+        &self,
+        device_id: DeviceId,
+        total_bytes: usize,
+        offset: usize,
+        chunk: &[u8],
+    ) -> Result<usize, ScreenshotError> {
+        match self.append(device_id, total_bytes, offset, chunk) {
+            Ok(Some(image)) => {
+                let received = image.len();
+                self.finish(device_id, Ok(image));
+                Ok(received)
+            }
+            Ok(None) => Ok(offset + chunk.len()),
+            Err(e) => {
+                self.partial.lock().remove(&device_id);
+                self.finish(device_id, Err(e.clone()));
+                Err(e)
+            }
+        }
+    }
+
+    /// Stop waiting for a screenshot and drop any partial data (e.g. on timeout or disconnect)
+    pub fn cancel(&self, device_id: &DeviceId) {
+        self.partial.lock().remove(device_id);
+        self.waiters.lock().remove(device_id);
+    }
+
+    /// Append a chunk, returning the full image once the last byte arrives
+    fn append(
+        &self,
+        device_id: DeviceId,
+        total_bytes: usize,
+        offset: usize,
+        chunk: &[u8],
+    ) -> Result<Option<Vec<u8>>, ScreenshotError> {
+        if total_bytes > self.max_bytes {
+            return Err(ScreenshotError::TooLarge {
+                total_bytes,
+                max_bytes: self.max_bytes,
+            });
+        }
+
+        let mut partial = self.partial.lock();
+
+        // A chunk at offset 0 always starts a fresh image
+        if offset == 0 {
+            partial.insert(
+                device_id,
+                PartialScreenshot {
+                    total_bytes,
+                    data: Vec::with_capacity(total_bytes),
+                },
+            );
+        }
+
+        let Some(screenshot) = partial.get_mut(&device_id) else {
+            return Err(ScreenshotError::OutOfOrder {
+                expected: 0,
+                actual: offset,
+            });
+        };
+
+        if offset != screenshot.data.len() {
+            return Err(ScreenshotError::OutOfOrder {
+                expected: screenshot.data.len(),
+                actual: offset,
+            });
+        }
+
+        if screenshot.total_bytes != total_bytes || offset + chunk.len() > total_bytes {
+            return Err(ScreenshotError::Overrun {
+                total_bytes: screenshot.total_bytes,
+            });
+        }
+
+        screenshot.data.extend_from_slice(chunk);
+
+        if screenshot.data.len() < screenshot.total_bytes {
+            return Ok(None);
+        }
+
+        let image = partial
+            .remove(&device_id)
+            .map(|s| s.data)
+            .unwrap_or_default();
+
+        if !image.starts_with(&PNG_SIGNATURE) {
+            return Err(ScreenshotError::NotPng);
+        }
+
+        Ok(Some(image))
+    }
+
+    fn finish(&self, device_id: DeviceId, result: Result<Vec<u8>, ScreenshotError>) {
+        match self.waiters.lock().remove(&device_id) {
+            Some(waiter) => {
+                let _ = waiter.send(result);
+            }
+            None => {
+                tracing::debug!(device_id = %device_id, "Discarding unrequested screenshot");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(len: usize) -> Vec<u8> {
+        let mut image = PNG_SIGNATURE.to_vec();
+        image.extend((0..len - PNG_SIGNATURE.len()).map(|i| i as u8));
+        image
+    }
+
+    #[test]
+    fn reassembles_in_order_chunks() {
+        let assembler = ScreenshotAssembler::new(1024);
+        let device = DeviceId::new();
+        let mut rx = assembler.expect(device);
+        let image = png(100);
+
+        assert_eq!(assembler.push_chunk(device, 100, 0, &image[..40]).unwrap(), 40);
+        assert!(rx.try_recv().is_err());
+        assert_eq!(assembler.push_chunk(device, 100, 40, &image[40..]).unwrap(), 100);
+
+        assert_eq!(rx.try_recv().unwrap().unwrap(), image);
+    }
+
+    #[test]
+    fn out_of_order_chunk_fails_the_screenshot() {
+        let assembler = ScreenshotAssembler::new(1024);
+        let device = DeviceId::new();
+        let mut rx = assembler.expect(device);
+        let image = png(100);
+
+        assembler.push_chunk(device, 100, 0, &image[..40]).unwrap();
+        assert!(matches!(
+            assembler.push_chunk(device, 100, 60, &image[60..]),
+            Err(ScreenshotError::OutOfOrder { expected: 40, actual: 60 })
+        ));
+        assert!(matches!(rx.try_recv().unwrap(), Err(ScreenshotError::OutOfOrder { .. })));
+
+        // The partial image was dropped, so continuing where it left off is out of order too
+        assert!(matches!(
+            assembler.push_chunk(device, 100, 40, &image[40..]),
+            Err(ScreenshotError::OutOfOrder { expected: 0, actual: 40 })
+        ));
+    }
+
+    #[test]
+    fn oversized_and_overrunning_screenshots_are_rejected() {
+        let assembler = ScreenshotAssembler::new(64);
+        let device = DeviceId::new();
+        let image = png(100);
+
+        assert!(matches!(
+            assembler.push_chunk(device, 100, 0, &image[..10]),
+            Err(ScreenshotError::TooLarge { total_bytes: 100, max_bytes: 64 })
+        ));
+        assert!(matches!(
+            assembler.push_chunk(device, 32, 0, &image[..40]),
+            Err(ScreenshotError::Overrun { total_bytes: 32 })
+        ));
+    }
+
+    #[test]
+    fn non_png_data_is_rejected_once_complete() {
+        let assembler = ScreenshotAssembler::new(1024);
+        let device = DeviceId::new();
+        let mut rx = assembler.expect(device);
+
+        assert!(matches!(
+            assembler.push_chunk(device, 16, 0, &[0u8; 16]),
+            Err(ScreenshotError::NotPng)
+        ));
+        assert!(matches!(rx.try_recv().unwrap(), Err(ScreenshotError::NotPng)));
+    }
+
+    #[test]
+    fn chunk_at_offset_zero_restarts_the_image() {
+        let assembler = ScreenshotAssembler::new(1024);
+        let device = DeviceId::new();
+        let mut rx = assembler.expect(device);
+        let image = png(50);
+
+        assembler.push_chunk(device, 80, 0, &png(80)[..30]).unwrap();
+        assembler.push_chunk(device, 50, 0, &image).unwrap();
+
+        assert_eq!(rx.try_recv().unwrap().unwrap(), image);
+    }
+
+    #[test]
+    fn cancel_drops_the_waiter_and_partial_data() {
+        let assembler = ScreenshotAssembler::new(1024);
+        let device = DeviceId::new();
+        let mut rx = assembler.expect(device);
+        let image = png(100);
+
+        assembler.push_chunk(device, 100, 0, &image[..40]).unwrap();
+        assembler.cancel(&device);
+
+        assert!(matches!(rx.try_recv(), Err(oneshot::error::TryRecvError::Closed)));
+        assert!(matches!(
+            assembler.push_chunk(device, 100, 40, &image[40..]),
+            Err(ScreenshotError::OutOfOrder { expected: 0, actual: 40 })
+        ));
+    }
+}
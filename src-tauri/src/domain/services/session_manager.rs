@@ -1,6 +1,8 @@
 use crate::domain::models::DeviceId;
 use crate::infrastructure::protocol::RawPacket;
 use async_trait::async_trait;
+use serde::Serialize;
+use std::sync::Arc;
 
 /// Error type for session operations
 #[derive(Debug, thiserror::Error)]
@@ -12,6 +14,17 @@ pub enum SessionError {
     SendError(String),
 }
 
+/// Which way a traced packet travelled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PacketDirection {
+    Inbound,
+    Outbound,
+}
+
+/// Callback invoked with every packet read from or written to a traced session
+pub type PacketTraceHook = Arc<dyn Fn(PacketDirection, &RawPacket) + Send + Sync>;
+
 /// Trait for managing device sessions
 /// This abstraction allows the domain layer to send packets to devices
 /// without depending on infrastructure implementation details.
@@ -22,4 +35,13 @@ pub trait SessionManager: Send + Sync {
 
     /// Check if a session exists for a device
     fn has_session(&self, device_id: &DeviceId) -> bool;
+
+    /// Send a command that expects a reply
+    /// Devices that echo request ids get it tagged with `request_id`, so replies
+    /// to concurrent commands can't cross; others get the plain packet.
+    async fn send_request(&self, device_id: DeviceId, request_id: u64, packet: RawPacket) -> Result<(), SessionError>;
+
+    /// Attach (`Some`) or detach (`None`) a packet trace hook on a device's session
+    /// Returns false if the device has no session.
+    fn set_packet_trace(&self, device_id: &DeviceId, hook: Option<PacketTraceHook>) -> bool;
 }
@@ -0,0 +1,142 @@
+/// Shell Policy
+/// Decides which shell commands may be sent to devices.
+///
+/// With safe mode off every command is allowed. With it on, a command must
+/// match one of the allowlist patterns, where `*` matches any run of characters
+/// and `?` a single one (so `pm list packages*` is a prefix match). Commands
+/// containing shell metacharacters are always refused in safe mode, since an
+/// allowed prefix could otherwise be chained with anything (`ls; rm -rf /`).
+
+/// Characters that let one shell command run, redirect or substitute another
+const SHELL_METACHARACTERS: &[char] = &[';', '&', '|', '`', '$', '<', '>', '(', ')', '\\', '\n', '\r'];
+
+#[derive(Debug, Clone, Default)]
+pub struct ShellPolicy {
+    safe_mode: bool,
+    allowlist: Vec<String>,
+}
+
+impl ShellPolicy {
+    pub fn new(safe_mode: bool, allowlist: Vec<String>) -> Self {
+        Self {
+            safe_mode,
+            allowlist,
+        }
+    }
+
+    /// Check a command, returning why it was refused
+    pub fn check(&self, command: &str) -> Result<(), String> {
+        if !self.safe_mode {
+            return Ok(());
+        }
+
+        let command = command.trim();
+
+        if let Some(c) = command.chars().find(|c| SHELL_METACHARACTERS.contains(c)) {
+            return Err(format!(
+                "Shell command blocked by safe mode: '{}' is not allowed",
+                c.escape_default()
+            ));
+        }
+
+        if self
+            .allowlist
+            .iter()
+            .any(|pattern| glob_matches(pattern.trim(), command))
+        {
+            Ok(())
+        } else {
+            Err(format!(
+                "Shell command blocked by safe mode: '{}' is not on the allowlist",
+                command
+            ))
+        }
+    }
+}
+
+/// Match `text` against a pattern where `*` is any run of characters and `?` any one
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` seen and the text position it is trying to cover
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                // Let the last `*` swallow one more character and retry
+                Some((star, covered)) => {
+                    p = star + 1;
+                    t = covered + 1;
+                    backtrack = Some((star, covered + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn safe_mode(allowlist: &[&str]) -> ShellPolicy {
+        ShellPolicy::new(true, allowlist.iter().map(|s| s.to_string()).collect())
+    }
+
+    #[test]
+    fn allows_listed_commands() {
+        let policy = safe_mode(&["pm list packages*", "getprop ro.build.version.?", "reboot"]);
+
+        assert!(policy.check("pm list packages").is_ok());
+        assert!(policy.check("pm list packages -3").is_ok());
+        assert!(policy.check("getprop ro.build.version.x").is_ok());
+        assert!(policy.check("  reboot ").is_ok());
+    }
+
+    #[test]
+    fn blocks_unlisted_commands() {
+        let policy = safe_mode(&["pm list packages*", "reboot"]);
+
+        let err = policy.check("rm -rf /sdcard").unwrap_err();
+        assert!(err.contains("not on the allowlist"), "{}", err);
+        assert!(policy.check("reboot now").is_err());
+        assert!(safe_mode(&[]).check("reboot").is_err());
+    }
+
+    #[test]
+    fn metacharacters_cannot_extend_an_allowed_prefix() {
+        let policy = safe_mode(&["pm list packages*"]);
+
+        for command in [
+            "pm list packages; rm -rf /sdcard",
+            "pm list packages && reboot",
+            "pm list packages | sh",
+            "pm list packages `reboot`",
+            "pm list packages $(reboot)",
+            "pm list packages > /sdcard/out",
+            "pm list packages\nreboot",
+        ] {
+            assert!(policy.check(command).is_err(), "{:?} was allowed", command);
+        }
+    }
+
+    #[test]
+    fn everything_is_allowed_outside_safe_mode() {
+        let policy = ShellPolicy::new(false, vec!["reboot".to_string()]);
+
+        assert!(policy.check("rm -rf /sdcard; reboot").is_ok());
+    }
+}
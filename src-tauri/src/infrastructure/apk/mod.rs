@@ -0,0 +1,303 @@
+/// APK inspection module
+/// Reads package metadata from the binary AndroidManifest.xml and
+/// resources.arsc inside an APK with the `apk-info` decoder, without needing
+/// the Android SDK.
+
+use crate::domain::repositories::ApkMetadata;
+use apk_info::{APKError, Apk};
+use base64::Engine;
+use std::path::Path;
+use thiserror::Error;
+
+/// Launcher icons larger than this are not inlined into listings
+const MAX_ICON_BYTES: usize = 256 * 1024;
+
+#[derive(Error, Debug)]
+pub enum ApkParseError {
+    #[error("Invalid APK: {0}")]
+    Invalid(#[from] APKError),
+
+    #[error("Manifest has no package name")]
+    MissingPackage,
+}
+
+pub type Result<T> = std::result::Result<T, ApkParseError>;
+
+/// Read package name, version, label and launcher icon from an APK
+/// Only the package name is required; everything else is best effort.
+/// This does blocking IO and should be called from a blocking task.
+pub fn read_apk_metadata(path: &Path) -> Result<ApkMetadata> {
+    let apk = Apk::new(path)?;
+
+    let package_name = apk
+        .get_package_name()
+        .filter(|name| !name.is_empty())
+        .ok_or(ApkParseError::MissingPackage)?;
+
+    // Labels and icons are usually resource references; the decoder resolves
+    // them through resources.arsc and leaves them out if it can't
+    let icon_png_base64 = apk
+        .get_application_icon()
+        .filter(|icon_path| icon_path.ends_with(".png"))
+        .and_then(|icon_path| read_icon(&apk, &icon_path));
+
+    Ok(ApkMetadata {
+        package_name,
+        version_name: apk.get_version_name(),
+        version_code: apk.get_version_code().and_then(|code| code.parse().ok()),
+        label: apk.get_application_label(),
+        icon_png_base64,
+    })
+}
+
+fn read_icon(apk: &Apk, icon_path: &str) -> Option<String> {
+    let (data, _) = apk.read(icon_path).ok()?;
+    if data.len() > MAX_ICON_BYTES {
+        return None;
+    }
+
+    Some(base64::engine::general_purpose::STANDARD.encode(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::io::Write;
+
+    const ANDROID_NAMESPACE: &str = "http://schemas.android.com/apk/res/android";
+
+    const RES_STRING_POOL_TYPE: u16 = 0x0001;
+    const RES_XML_TYPE: u16 = 0x0003;
+    const RES_XML_START_NAMESPACE_TYPE: u16 = 0x0100;
+    const RES_XML_END_NAMESPACE_TYPE: u16 = 0x0101;
+    const RES_XML_START_ELEMENT_TYPE: u16 = 0x0102;
+    const RES_XML_END_ELEMENT_TYPE: u16 = 0x0103;
+    const RES_XML_RESOURCE_MAP_TYPE: u16 = 0x0180;
+
+    const TYPE_STRING: u8 = 0x03;
+    const TYPE_INT_DEC: u8 = 0x10;
+
+    /// Framework attribute IDs
+    const ATTR_LABEL: u32 = 0x0101_0001;
+    const ATTR_VERSION_CODE: u32 = 0x0101_021b;
+    const ATTR_VERSION_NAME: u32 = 0x0101_021c;
+
+    /// Attribute value in a compiled manifest: a string or a decimal int
+    enum Value<'a> {
+        String(&'a str),
+        Int(u32),
+    }
+
+    /// Compiles a `<manifest>` with a nested `<application>` to binary XML, laid
+    /// out the way aapt2 does: string pool, resource map, then the node chunks
+    struct ManifestBuilder {
+        strings: Vec<String>,
+        resource_ids: Vec<u32>,
+    }
+
+    impl ManifestBuilder {
+        /// `android_attrs` are the framework attributes used, which aapt2
+        /// places first in the string pool so the resource map can index them
+        fn new(android_attrs: &[(&str, u32)]) -> Self {
+            Self {
+                strings: android_attrs.iter().map(|(name, _)| name.to_string()).collect(),
+                resource_ids: android_attrs.iter().map(|(_, id)| *id).collect(),
+            }
+        }
+
+        fn string(&mut self, s: &str) -> u32 {
+            if let Some(i) = self.strings.iter().position(|existing| existing == s) {
+                return i as u32;
+            }
+            self.strings.push(s.to_string());
+            (self.strings.len() - 1) as u32
+        }
+
+        fn build(mut self, manifest: &[(&str, Value)], application: &[(&str, Value)]) -> Vec<u8> {
+            let prefix = self.string("android");
+            let uri = self.string(ANDROID_NAMESPACE);
+            let nodes = [
+                namespace_chunk(RES_XML_START_NAMESPACE_TYPE, prefix, uri),
+                self.start_element("manifest", manifest, uri),
+                self.start_element("application", application, uri),
+                self.end_element("application"),
+                self.end_element("manifest"),
+                namespace_chunk(RES_XML_END_NAMESPACE_TYPE, prefix, uri),
+            ]
+            .concat();
+
+            let mut body = string_pool(&self.strings);
+            let mut map = chunk_header(RES_XML_RESOURCE_MAP_TYPE, 8, 8 + self.resource_ids.len() * 4);
+            for id in &self.resource_ids {
+                map.extend_from_slice(&id.to_le_bytes());
+            }
+            body.extend(map);
+            body.extend(nodes);
+
+            let mut document = chunk_header(RES_XML_TYPE, 8, 8 + body.len());
+            document.extend(body);
+            document
+        }
+
+        fn start_element(&mut self, name: &str, attributes: &[(&str, Value)], android_uri: u32) -> Vec<u8> {
+            let name = self.string(name);
+            let mut element = node_header(RES_XML_START_ELEMENT_TYPE, 16 + 20 + attributes.len() * 20);
+            element.extend_from_slice(&u32::MAX.to_le_bytes()); // namespace
+            element.extend_from_slice(&name.to_le_bytes());
+            for field in [20u16, 20, attributes.len() as u16, 0, 0, 0] {
+                element.extend_from_slice(&field.to_le_bytes());
+            }
+
+            for (attr_name, value) in attributes {
+                // Framework attributes are the ones declared up front
+                let android = self.strings.iter().take(self.resource_ids.len()).any(|s| s == attr_name);
+                let namespace = if android { android_uri } else { u32::MAX };
+                let attr_name = self.string(attr_name);
+                let (raw, data_type, data) = match value {
+                    Value::String(s) => {
+                        let index = self.string(s);
+                        (index, TYPE_STRING, index)
+                    }
+                    Value::Int(n) => (u32::MAX, TYPE_INT_DEC, *n),
+                };
+
+                element.extend_from_slice(&namespace.to_le_bytes());
+                element.extend_from_slice(&attr_name.to_le_bytes());
+                element.extend_from_slice(&raw.to_le_bytes());
+                element.extend_from_slice(&8u16.to_le_bytes());
+                element.push(0);
+                element.push(data_type);
+                element.extend_from_slice(&data.to_le_bytes());
+            }
+            element
+        }
+
+        fn end_element(&mut self, name: &str) -> Vec<u8> {
+            let name = self.string(name);
+            let mut element = node_header(RES_XML_END_ELEMENT_TYPE, 16 + 8);
+            element.extend_from_slice(&u32::MAX.to_le_bytes()); // namespace
+            element.extend_from_slice(&name.to_le_bytes());
+            element
+        }
+    }
+
+    fn chunk_header(chunk_type: u16, header_size: u16, size: usize) -> Vec<u8> {
+        let mut header = Vec::new();
+        header.extend_from_slice(&chunk_type.to_le_bytes());
+        header.extend_from_slice(&header_size.to_le_bytes());
+        header.extend_from_slice(&(size as u32).to_le_bytes());
+        header
+    }
+
+    /// Header of an XML node chunk, with its line number and comment
+    fn node_header(chunk_type: u16, size: usize) -> Vec<u8> {
+        let mut header = chunk_header(chunk_type, 16, size);
+        header.extend_from_slice(&1u32.to_le_bytes()); // line number
+        header.extend_from_slice(&u32::MAX.to_le_bytes()); // comment
+        header
+    }
+
+    fn namespace_chunk(chunk_type: u16, prefix: u32, uri: u32) -> Vec<u8> {
+        let mut chunk = node_header(chunk_type, 16 + 8);
+        chunk.extend_from_slice(&prefix.to_le_bytes());
+        chunk.extend_from_slice(&uri.to_le_bytes());
+        chunk
+    }
+
+    /// UTF-16 string pool chunk
+    fn string_pool(strings: &[String]) -> Vec<u8> {
+        let mut offsets = Vec::new();
+        let mut data = Vec::new();
+        for s in strings {
+            offsets.push(data.len() as u32);
+            let units: Vec<u16> = s.encode_utf16().collect();
+            data.extend_from_slice(&(units.len() as u16).to_le_bytes());
+            for unit in units {
+                data.extend_from_slice(&unit.to_le_bytes());
+            }
+            data.extend_from_slice(&0u16.to_le_bytes());
+        }
+        // Pad string data to a four-byte boundary
+        data.resize(data.len().next_multiple_of(4), 0);
+
+        let header_size = 28;
+        let strings_start = header_size + offsets.len() * 4;
+        let mut pool = chunk_header(RES_STRING_POOL_TYPE, header_size as u16, strings_start + data.len());
+        for field in [strings.len() as u32, 0, 0, strings_start as u32, 0] {
+            pool.extend_from_slice(&field.to_le_bytes());
+        }
+        for offset in offsets {
+            pool.extend_from_slice(&offset.to_le_bytes());
+        }
+        pool.extend(data);
+        pool
+    }
+
+    fn write_apk(entries: &[(&str, Vec<u8>)]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("arceus-apk-{}.apk", uuid::Uuid::new_v4()));
+        let mut zip = zip::ZipWriter::new(File::create(&path).unwrap());
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+
+        for (name, data) in entries {
+            zip.start_file(*name, options).unwrap();
+            zip.write_all(data).unwrap();
+        }
+        zip.finish().unwrap();
+        path
+    }
+
+    #[test]
+    fn reads_manifest_without_resources() {
+        let manifest = ManifestBuilder::new(&[
+            ("label", ATTR_LABEL),
+            ("versionCode", ATTR_VERSION_CODE),
+            ("versionName", ATTR_VERSION_NAME),
+        ])
+        .build(
+            &[
+                ("versionCode", Value::Int(10402)),
+                ("versionName", Value::String("1.4.2")),
+                ("package", Value::String("com.example.game")),
+            ],
+            &[("label", Value::String("Example Game"))],
+        );
+
+        let path = write_apk(&[("AndroidManifest.xml", manifest)]);
+        let metadata = read_apk_metadata(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            metadata,
+            ApkMetadata {
+                package_name: "com.example.game".to_string(),
+                version_name: Some("1.4.2".to_string()),
+                version_code: Some(10402),
+                label: Some("Example Game".to_string()),
+                icon_png_base64: None,
+            }
+        );
+    }
+
+    #[test]
+    fn manifest_without_package_is_rejected() {
+        let manifest = ManifestBuilder::new(&[("versionCode", ATTR_VERSION_CODE)])
+            .build(&[("versionCode", Value::Int(1))], &[]);
+
+        let path = write_apk(&[("AndroidManifest.xml", manifest)]);
+        assert!(matches!(read_apk_metadata(&path), Err(ApkParseError::MissingPackage)));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn unreadable_apks_are_errors() {
+        let path = write_apk(&[("AndroidManifest.xml", b"<manifest/>".to_vec())]);
+        assert!(matches!(read_apk_metadata(&path), Err(ApkParseError::Invalid(_))));
+        std::fs::remove_file(&path).unwrap();
+
+        let path = write_apk(&[("classes.dex", vec![0; 16])]);
+        assert!(matches!(read_apk_metadata(&path), Err(ApkParseError::Invalid(_))));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -37,6 +37,31 @@ impl Database {
         .execute(pool)
         .await?;
 
+        // Create device_annotations table (tags as a JSON array)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS device_annotations (
+                serial TEXT PRIMARY KEY,
+                tags TEXT NOT NULL DEFAULT '[]',
+                note TEXT
+            )
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
+        // Create device_expected_apps table (app a device should stay in)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS device_expected_apps (
+                serial TEXT PRIMARY KEY,
+                package_name TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
         // Create game_cache table
         sqlx::query(
             r#"
@@ -54,6 +79,32 @@ impl Database {
         .execute(pool)
         .await?;
 
+        // Create game_stats table (local-only data, untouched by server syncs)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS game_stats (
+                game_id INTEGER PRIMARY KEY,
+                installed_size_bytes INTEGER,
+                last_played TEXT
+            )
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
+        // Create game_channels table (release channel each game follows, when
+        // not the arcade's own)
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS game_channels (
+                game_id INTEGER PRIMARY KEY,
+                channel TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(pool)
+        .await?;
+
         Ok(())
     }
 
@@ -1,3 +1,4 @@
+pub mod apk;
 pub mod database;
 pub mod game;
 pub mod network;
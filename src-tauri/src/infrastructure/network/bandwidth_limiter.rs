@@ -0,0 +1,140 @@
+/// Bandwidth Limiter
+/// Token bucket shared by all HTTP download streams so that game and APK
+/// downloads together stay under a configured rate.
+
+use parking_lot::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// How much unused bandwidth may accumulate as a burst
+const BURST_DURATION: Duration = Duration::from_millis(100);
+
+struct Bucket {
+    /// Available tokens (bytes); negative while streams are paying back a debt
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// Aggregate rate limiter for download streams
+pub struct BandwidthLimiter {
+    bytes_per_sec: u64,
+    capacity: f64,
+    bucket: Mutex<Bucket>,
+}
+
+impl BandwidthLimiter {
+    /// Create a limiter allowing `bytes_per_sec` in total; 0 means unthrottled
+    pub fn new(bytes_per_sec: u64) -> Self {
+        let capacity = bytes_per_sec as f64 * BURST_DURATION.as_secs_f64();
+        Self {
+            bytes_per_sec,
+            capacity,
+            bucket: Mutex::new(Bucket {
+                tokens: capacity,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.bytes_per_sec == 0
+    }
+
+    /// Wait until `bytes` may be consumed
+    /// Call after reading each chunk; the wait pays for the chunk just received.
+    pub async fn acquire(&self, bytes: u64) {
+        if self.is_unlimited() {
+            return;
+        }
+
+        let rate = self.bytes_per_sec as f64;
+        let wait = {
+            let mut bucket = self.bucket.lock();
+            let now = Instant::now();
+            let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * rate).min(self.capacity);
+            bucket.last_refill = now;
+            bucket.tokens -= bytes as f64;
+
+            if bucket.tokens < 0.0 {
+                Some(Duration::from_secs_f64(-bucket.tokens / rate))
+            } else {
+                None
+            }
+        };
+
+        if let Some(wait) = wait {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    const CHUNK_SIZE: u64 = 4096;
+
+    /// Simulate a download read loop pulling `total` bytes through the limiter
+    async fn download(limiter: &BandwidthLimiter, total: u64) {
+        let mut remaining = total;
+        while remaining > 0 {
+            let chunk = remaining.min(CHUNK_SIZE);
+            limiter.acquire(chunk).await;
+            remaining -= chunk;
+        }
+    }
+
+    #[tokio::test]
+    async fn throttled_download_takes_minimum_time() {
+        let rate = 100_000;
+        let total = 60_000;
+        let limiter = BandwidthLimiter::new(rate);
+
+        let start = std::time::Instant::now();
+        download(&limiter, total).await;
+
+        // The initial burst is free, the rest is paid for at the configured rate
+        let burst = rate as f64 * BURST_DURATION.as_secs_f64();
+        let expected = Duration::from_secs_f64((total as f64 - burst) / rate as f64);
+        assert!(
+            start.elapsed() >= expected,
+            "download took {:?}, expected at least {:?}",
+            start.elapsed(),
+            expected
+        );
+    }
+
+    #[tokio::test]
+    async fn concurrent_downloads_share_the_limit() {
+        let rate = 100_000;
+        let limiter = Arc::new(BandwidthLimiter::new(rate));
+
+        let start = std::time::Instant::now();
+        let first = tokio::spawn({
+            let limiter = Arc::clone(&limiter);
+            async move { download(&limiter, 30_000).await }
+        });
+        let second = tokio::spawn({
+            let limiter = Arc::clone(&limiter);
+            async move { download(&limiter, 30_000).await }
+        });
+        first.await.unwrap();
+        second.await.unwrap();
+
+        let burst = rate as f64 * BURST_DURATION.as_secs_f64();
+        let expected = Duration::from_secs_f64((60_000.0 - burst) / rate as f64);
+        assert!(start.elapsed() >= expected);
+    }
+
+    #[tokio::test]
+    async fn zero_rate_is_unthrottled() {
+        let limiter = BandwidthLimiter::new(0);
+
+        let start = std::time::Instant::now();
+        download(&limiter, 100 * 1024 * 1024).await;
+
+        assert!(start.elapsed() < Duration::from_millis(100));
+    }
+}
@@ -1,40 +1,128 @@
 /// Connection Handler
 /// Manages device lifecycle for a single connection.
+use crate::app::error::ArceusError;
 use crate::app::{EventBus, Result};
 use crate::domain::models::DeviceId;
-use crate::domain::repositories::DeviceRepository;
-use crate::infrastructure::network::device_session::DeviceSession;
+use crate::domain::repositories::{
+    normalize_tags, CommandHistoryRepository, DeviceNameRepository, DeviceRepository,
+};
+use crate::infrastructure::network::device_session::{DeviceSession, SessionError};
 use crate::infrastructure::network::device_session_manager::DeviceSessionManager;
 use crate::infrastructure::network::packet_handler::PacketHandlerRegistry;
-use crate::infrastructure::protocol::RawPacket;
+use crate::infrastructure::protocol::{opcodes, RawPacket, RawPacketCodec};
+use std::collections::VecDeque;
+use std::io;
 use std::net::SocketAddr;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::time::timeout;
 
+/// Tag given to devices disconnected for sending too many malformed packets
+pub const MALFORMED_PACKETS_TAG: &str = "malformed-packets";
+
+/// Counts protocol errors on one connection over a sliding window
+#[derive(Debug, Clone)]
+pub struct ErrorBudget {
+    limit: u32,
+    window: Duration,
+    errors: VecDeque<Instant>,
+}
+
+impl ErrorBudget {
+    pub fn new(limit: u32, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            errors: VecDeque::new(),
+        }
+    }
+
+    /// Record an error at `now`, returning true once the budget is spent
+    pub fn record(&mut self, now: Instant) -> bool {
+        while let Some(&oldest) = self.errors.front() {
+            if now.duration_since(oldest) < self.window {
+                break;
+            }
+            self.errors.pop_front();
+        }
+
+        self.errors.push_back(now);
+        self.errors.len() >= self.limit as usize
+    }
+}
+
+/// Jitter of the heartbeat interval on one connection
+/// Smoothed mean of the difference between consecutive intervals, as in RFC 3550.
+#[derive(Debug, Default)]
+pub struct HeartbeatJitter {
+    last_heartbeat: Option<Instant>,
+    last_interval: Option<Duration>,
+    jitter_secs: f64,
+}
+
+impl HeartbeatJitter {
+    /// Record a heartbeat at `now`, returning the jitter once two intervals are known
+    pub fn record(&mut self, now: Instant) -> Option<Duration> {
+        let interval = self.last_heartbeat.map(|last| now.duration_since(last));
+        self.last_heartbeat = Some(now);
+
+        let interval = interval?;
+        let previous = self.last_interval.replace(interval)?;
+
+        let difference = (interval.as_secs_f64() - previous.as_secs_f64()).abs();
+        self.jitter_secs += (difference - self.jitter_secs) / 16.0;
+        Some(Duration::from_secs_f64(self.jitter_secs))
+    }
+}
+
+/// Whether a packet handler error means the device sent a payload we couldn't decode
+/// Handlers parse payloads from a cursor, so a short or garbled payload surfaces as
+/// an `UnexpectedEof` or `InvalidData` I/O error; other I/O errors are not the device's fault.
+fn is_malformed_payload(error: &ArceusError) -> bool {
+    match error {
+        ArceusError::Protocol(_) => true,
+        ArceusError::Io(e) => matches!(e.kind(), io::ErrorKind::UnexpectedEof | io::ErrorKind::InvalidData),
+        _ => false,
+    }
+}
+
 /// Handles the lifecycle of a device connection
 pub struct ConnectionHandler {
     device_repo: Arc<dyn DeviceRepository>,
+    device_name_repo: Arc<dyn DeviceNameRepository>,
     event_bus: Arc<EventBus>,
     packet_handler: Arc<PacketHandlerRegistry>,
     session_manager: Arc<DeviceSessionManager>,
+    command_history: Arc<dyn CommandHistoryRepository>,
     heartbeat_timeout: Duration,
+    /// Fresh copy handed to every connection
+    error_budget: ErrorBudget,
+    max_payload: usize,
 }
 
 impl ConnectionHandler {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         device_repo: Arc<dyn DeviceRepository>,
+        device_name_repo: Arc<dyn DeviceNameRepository>,
         event_bus: Arc<EventBus>,
         packet_handler: Arc<PacketHandlerRegistry>,
         session_manager: Arc<DeviceSessionManager>,
+        command_history: Arc<dyn CommandHistoryRepository>,
         heartbeat_timeout: Duration,
+        error_budget: ErrorBudget,
+        max_payload: usize,
     ) -> Self {
         Self {
             device_repo,
+            device_name_repo,
             event_bus,
             packet_handler,
             session_manager,
+            command_history,
             heartbeat_timeout,
+            error_budget,
+            max_payload,
         }
     }
 
@@ -78,7 +166,9 @@ impl ConnectionHandler {
         stream: tokio::net::TcpStream,
         addr: SocketAddr,
     ) -> Result<Arc<DeviceSession>> {
-        let session = Arc::new(DeviceSession::new(stream, device_id, addr));
+        let codec = RawPacketCodec::traced(self.session_manager.packet_log().clone(), device_id, addr)
+            .with_max_payload(self.max_payload);
+        let session = Arc::new(DeviceSession::new(stream, device_id, addr, codec));
         self.session_manager.add_session(device_id, session.clone());
         Ok(session)
     }
@@ -96,27 +186,60 @@ impl ConnectionHandler {
             "Starting message loop"
         );
 
+        let mut error_budget = self.error_budget.clone();
+        let mut heartbeat_jitter = HeartbeatJitter::default();
+
         loop {
             let packet_result = timeout(self.heartbeat_timeout, session.receive_packet()).await;
 
             match packet_result {
                 Ok(Ok(Some(packet))) => {
+                    let jitter = (packet.opcode == opcodes::HEARTBEAT)
+                        .then(|| heartbeat_jitter.record(Instant::now()))
+                        .flatten();
+
                     // Update device last_seen timestamp
-                    self.update_last_seen(device_id).await;
+                    self.update_last_seen(device_id, jitter).await;
 
                     // Handle the packet
+                    let opcode = packet.opcode;
                     if let Err(e) = self.handle_packet(device_id, &session, packet).await {
                         tracing::error!(
                             device_id = %device_id,
+                            opcode = format_args!("0x{:02X}", opcode),
                             error = %e,
                             "Error handling packet"
                         );
+
+                        if is_malformed_payload(&e) && error_budget.record(Instant::now()) {
+                            tracing::warn!(
+                                device_id = %device_id,
+                                "Too many malformed packets, disconnecting"
+                            );
+                            self.flag_device(device_id, "Disconnected after repeated malformed packets")
+                                .await;
+                            break;
+                        }
                     }
                 }
                 Ok(Ok(None)) => {
                     tracing::debug!(device_id = %device_id, "Connection closed by device");
                     break;
                 }
+                Ok(Err(SessionError::MalformedPacket(e))) => {
+                    // The stream can't be resynchronized after a bad frame, so the
+                    // connection closes either way; the budget decides whether to flag
+                    tracing::warn!(
+                        device_id = %device_id,
+                        error = %e,
+                        "Protocol violation, closing connection"
+                    );
+                    if error_budget.record(Instant::now()) {
+                        self.flag_device(device_id, "Disconnected after repeated malformed packets")
+                            .await;
+                    }
+                    break;
+                }
                 Ok(Err(e)) => {
                     tracing::error!(
                         device_id = %device_id,
@@ -139,13 +262,43 @@ impl ConnectionHandler {
         Ok(())
     }
 
-    async fn update_last_seen(&self, device_id: DeviceId) {
+    async fn update_last_seen(&self, device_id: DeviceId, heartbeat_jitter: Option<Duration>) {
         if let Ok(Some(device)) = self.device_repo.find_by_id(device_id).await {
-            let updated = device.as_ref().clone().update_last_seen();
+            let mut updated = device.as_ref().clone().update_last_seen();
+            if let Some(jitter) = heartbeat_jitter {
+                updated = updated.with_heartbeat_jitter(jitter);
+            }
             let _ = self.device_repo.save(updated).await;
         }
     }
 
+    /// Tag a misbehaving device so staff can find it, and tell the frontend
+    async fn flag_device(&self, device_id: DeviceId, reason: &str) {
+        let Ok(Some(device)) = self.device_repo.find_by_id(device_id).await else {
+            // Still handshaking, there is no serial to flag
+            return;
+        };
+        let serial = device.serial();
+
+        let mut tags = match self.device_name_repo.get_annotations(serial).await {
+            Ok(annotations) => annotations.tags,
+            Err(e) => {
+                tracing::warn!(serial = %serial, error = %e, "Failed to load device tags");
+                Vec::new()
+            }
+        };
+        tags.push(MALFORMED_PACKETS_TAG.to_string());
+        if let Err(e) = self.device_name_repo.set_tags(serial, &normalize_tags(tags)).await {
+            tracing::warn!(serial = %serial, error = %e, "Failed to flag device");
+        }
+
+        self.event_bus.device_flagged(
+            device_id.as_uuid(),
+            serial.as_str().to_string(),
+            reason.to_string(),
+        );
+    }
+
     async fn handle_packet(
         &self,
         device_id: DeviceId,
@@ -161,6 +314,7 @@ impl ConnectionHandler {
         let device_info = self.device_repo.find_by_id(device_id).await.ok().flatten();
 
         self.session_manager.remove_session(&device_id);
+        self.packet_handler.device_disconnected(&device_id);
 
         let _ = self.device_repo.remove(device_id).await;
 
@@ -170,6 +324,8 @@ impl ConnectionHandler {
                 serial = %device.serial().as_str(),
                 "Device disconnected"
             );
+            // Kept for the device's next connection
+            self.command_history.park(&device_id, device.serial().as_str());
             self.event_bus.emit(crate::app::events::ArceusEvent::DeviceDisconnected {
                 device_id: device_id.as_uuid().clone(),
                 serial: device.serial().as_str().to_string(),
@@ -179,3 +335,56 @@ impl ConnectionHandler {
         tracing::debug!(device_id = %device_id, "Device removed");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn budget_is_spent_by_errors_within_the_window() {
+        let start = Instant::now();
+        let mut budget = ErrorBudget::new(3, Duration::from_secs(10));
+
+        assert!(!budget.record(start));
+        assert!(!budget.record(start + Duration::from_secs(1)));
+        assert!(budget.record(start + Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn old_errors_fall_out_of_the_window() {
+        let start = Instant::now();
+        let mut budget = ErrorBudget::new(2, Duration::from_secs(10));
+
+        assert!(!budget.record(start));
+        assert!(!budget.record(start + Duration::from_secs(10)));
+        assert!(!budget.record(start + Duration::from_secs(25)));
+        assert!(budget.record(start + Duration::from_secs(26)));
+    }
+
+    #[test]
+    fn only_decode_errors_count_as_malformed() {
+        let short = io::Error::new(io::ErrorKind::UnexpectedEof, "payload too short");
+        let reset = io::Error::new(io::ErrorKind::ConnectionReset, "connection reset");
+
+        assert!(is_malformed_payload(&ArceusError::Io(short)));
+        assert!(is_malformed_payload(&ArceusError::Protocol(
+            crate::app::error::ProtocolError::InvalidMessageType(0xEE)
+        )));
+        assert!(!is_malformed_payload(&ArceusError::Io(reset)));
+        assert!(!is_malformed_payload(&ArceusError::Config("unrelated".to_string())));
+    }
+
+    #[test]
+    fn steady_heartbeats_have_no_jitter() {
+        let start = Instant::now();
+        let mut jitter = HeartbeatJitter::default();
+
+        assert_eq!(jitter.record(start), None);
+        assert_eq!(jitter.record(start + Duration::from_secs(5)), None);
+        assert_eq!(jitter.record(start + Duration::from_secs(10)), Some(Duration::ZERO));
+
+        // One late heartbeat moves the estimate a sixteenth of the way
+        let late = jitter.record(start + Duration::from_secs(17)).unwrap();
+        assert_eq!(late.as_millis(), 125);
+    }
+}
@@ -8,7 +8,7 @@ use futures::{SinkExt, StreamExt};
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::net::TcpStream;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, Notify};
 use tokio_util::codec::Framed;
 
 pub struct DeviceSession {
@@ -21,6 +21,8 @@ pub struct DeviceSession {
         Arc<Mutex<futures::stream::SplitSink<Framed<TcpStream, RawPacketCodec>, RawPacket>>>,
     /// Remote address of the device
     addr: SocketAddr,
+    /// Signalled when the server wants to drop this connection
+    close_signal: Notify,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -28,6 +30,10 @@ pub enum SessionError {
     #[error("Failed to receive packet: {0}")]
     ReceiveError(String),
 
+    /// The device sent a frame the codec rejected; the stream can't be resynchronized
+    #[error("Rejected packet: {0}")]
+    MalformedPacket(String),
+
     #[error("Failed to send packet: {0}")]
     SendError(String),
 
@@ -36,8 +42,8 @@ pub enum SessionError {
 }
 
 impl DeviceSession {
-    pub fn new(stream: TcpStream, id: DeviceId, addr: SocketAddr) -> Self {
-        let framed = Framed::new(stream, RawPacketCodec);
+    pub fn new(stream: TcpStream, id: DeviceId, addr: SocketAddr, codec: RawPacketCodec) -> Self {
+        let framed = Framed::new(stream, codec);
         let (write, read) = framed.split();
 
         Self {
@@ -45,15 +51,24 @@ impl DeviceSession {
             read_stream: Arc::new(Mutex::new(read)),
             write_stream: Arc::new(Mutex::new(write)),
             addr,
+            close_signal: Notify::new(),
         }
     }
 
     /// Receive a packet from the device
-    /// Returns `None` if the stream has closed gracefully.
+    /// Returns `None` if the stream has closed gracefully or the session was closed.
     pub async fn receive_packet(&self) -> Result<Option<RawPacket>, SessionError> {
         let mut stream = self.read_stream.lock().await;
 
-        match stream.next().await {
+        let next = tokio::select! {
+            next = stream.next() => next,
+            _ = self.close_signal.notified() => {
+                tracing::debug!(device_id = %self.id, "Session closed by server");
+                return Ok(None);
+            }
+        };
+
+        match next {
             Some(Ok(packet)) => {
                 tracing::trace!(
                     device_id = %self.id,
@@ -64,6 +79,9 @@ impl DeviceSession {
 
                 Ok(Some(packet))
             }
+            Some(Err(crate::app::error::ArceusError::Protocol(e))) => {
+                Err(SessionError::MalformedPacket(e.to_string()))
+            }
             Some(Err(e)) => {
                 tracing::error!(
                     device_id = %self.id,
@@ -97,6 +115,27 @@ impl DeviceSession {
 
         Ok(())
     }
+
+    /// Remote address of the device
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Close the session from the server side
+    /// The pending (or next) `receive_packet` call returns `None`, ending the message loop.
+    pub fn close(&self) {
+        self.close_signal.notify_one();
+    }
+
+    /// Close the session and shut down the write side of the socket
+    /// The device sees the connection end right away instead of when the
+    /// message loop finally drops the stream.
+    pub async fn shutdown(&self) {
+        self.close();
+        if let Err(e) = self.write_stream.lock().await.close().await {
+            tracing::debug!(device_id = %self.id, error = %e, "Failed to shut down socket");
+        }
+    }
 }
 
 // Implement Debug manually to avoid printing the entire stream state
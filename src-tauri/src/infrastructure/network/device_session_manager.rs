@@ -1,33 +1,69 @@
 /// Session Manager
 /// Manages active device sessions for command execution.
 
-use crate::domain::models::DeviceId;
-use crate::domain::services::SessionManager as SessionManagerTrait;
+use crate::app::events::ArceusEvent;
+use crate::app::EventBus;
+use crate::domain::models::{Device, DeviceId};
+use crate::domain::repositories::{CommandHistoryRepository, DeviceRepository};
+use crate::domain::services::{PacketTraceHook, ResponseTracker, SessionManager as SessionManagerTrait};
 use crate::infrastructure::network::device_session::DeviceSession;
-use crate::infrastructure::protocol::RawPacket;
+use crate::infrastructure::protocol::{opcodes, tagged, PacketLog, RawPacket};
+use crate::net::io::ProtocolWriteExt;
 use async_trait::async_trait;
+use chrono::Utc;
 use dashmap::DashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Lower bound on how often the heartbeat sweeper checks for stale devices
+const MIN_HEARTBEAT_SWEEP_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Reason sent to devices when the server shuts down gracefully
+const SHUTTING_DOWN_REASON: &str = "Server shutting down";
+
+/// How often a drain checks whether in-flight commands have finished
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(50);
 
 /// Metadata associated with a device session
 #[derive(Debug, Clone)]
 pub struct SessionMetadata {
     pub client_version: Option<String>,
+    /// Client advertised `CAP_REQUEST_IDS` in DEVICE_CONNECTED
+    pub request_ids: bool,
 }
 
 impl SessionMetadata {
     pub fn new() -> Self {
         Self {
             client_version: None,
+            request_ids: false,
         }
     }
 }
 
+/// Reserved connection slot
+/// Releases the slot when dropped, i.e. when the connection task ends.
+pub struct ConnectionPermit {
+    count: Arc<AtomicUsize>,
+}
+
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        self.count.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
 /// Manages active device sessions
 /// Provides O(1) lookup of sessions by device ID for command execution.
 pub struct DeviceSessionManager {
     sessions: Arc<DashMap<DeviceId, Arc<DeviceSession>>>,
     metadata: Arc<DashMap<DeviceId, SessionMetadata>>,
+    connection_count: Arc<AtomicUsize>,
+    /// Packets sent on behalf of the command executor
+    commands_sent: AtomicU64,
+    /// Shared by the codecs of every session, for server-wide and per-device tracing
+    packet_log: Arc<PacketLog>,
 }
 
 impl DeviceSessionManager {
@@ -36,9 +72,49 @@ impl DeviceSessionManager {
         Self {
             sessions: Arc::new(DashMap::new()),
             metadata: Arc::new(DashMap::new()),
+            connection_count: Arc::new(AtomicUsize::new(0)),
+            commands_sent: AtomicU64::new(0),
+            packet_log: Arc::new(PacketLog::default()),
         }
     }
 
+    /// Packet trace log the codecs of new sessions report to
+    pub fn packet_log(&self) -> &Arc<PacketLog> {
+        &self.packet_log
+    }
+
+    /// Reserve a connection slot if fewer than `max_connections` are open
+    /// Counts every accepted socket, including ones that haven't sent DEVICE_CONNECTED yet.
+    pub fn try_acquire_connection(&self, max_connections: usize) -> Option<ConnectionPermit> {
+        self.connection_count
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |current| {
+                (current < max_connections).then_some(current + 1)
+            })
+            .ok()
+            .map(|_| ConnectionPermit {
+                count: Arc::clone(&self.connection_count),
+            })
+    }
+
+    /// Number of currently open connections
+    pub fn connection_count(&self) -> usize {
+        self.connection_count.load(Ordering::SeqCst)
+    }
+
+    /// Number of devices with an active session
+    pub fn session_count(&self) -> usize {
+        self.sessions.len()
+    }
+
+    /// Number of commands sent since the last reset
+    pub fn commands_sent(&self) -> u64 {
+        self.commands_sent.load(Ordering::Relaxed)
+    }
+
+    pub fn reset_commands_sent(&self) {
+        self.commands_sent.store(0, Ordering::Relaxed);
+    }
+
     /// Add a session
     pub fn add_session(&self, device_id: DeviceId, session: Arc<DeviceSession>) {
         self.sessions.insert(device_id, session);
@@ -50,6 +126,7 @@ impl DeviceSessionManager {
     pub fn remove_session(&self, device_id: &DeviceId) {
         self.sessions.remove(device_id);
         self.metadata.remove(device_id);
+        self.packet_log.set_hook(*device_id, None);
         tracing::debug!(device_id = %device_id, "Session removed from manager");
     }
 
@@ -79,10 +156,171 @@ impl DeviceSessionManager {
             .and_then(|entry| entry.client_version.clone())
     }
 
+    /// Record whether the client echoes request ids
+    /// Called when DEVICE_CONNECTED is received
+    pub fn set_request_ids(&self, device_id: &DeviceId, enabled: bool) {
+        if let Some(mut entry) = self.metadata.get_mut(device_id) {
+            entry.request_ids = enabled;
+        }
+    }
+
+    /// Wrap a request for a device that echoes request ids, see `protocol::tagged`
+    fn request_packet(&self, device_id: &DeviceId, request_id: u64, packet: RawPacket) -> RawPacket {
+        let request_ids = self.metadata.get(device_id).is_some_and(|entry| entry.request_ids);
+        if request_ids {
+            tagged::tag_command(request_id, packet)
+        } else {
+            packet
+        }
+    }
+
     /// Check if a session exists for the given device ID
     pub fn has_session(&self, device_id: &DeviceId) -> bool {
         self.sessions.contains_key(device_id)
     }
+
+    /// Close and remove the session for a device that stopped responding
+    pub fn mark_disconnected(&self, device_id: &DeviceId) {
+        if let Some((_, session)) = self.sessions.remove(device_id) {
+            session.close();
+        }
+        self.metadata.remove(device_id);
+        tracing::debug!(device_id = %device_id, "Session marked disconnected");
+    }
+
+    /// Gracefully close every session
+    /// Each device is sent SERVER_SHUTTING_DOWN so it can reconnect cleanly later,
+    /// commands already sent get up to `grace` to be answered, then the sessions
+    /// are closed. New connections must already have stopped being accepted.
+    pub async fn drain(&self, response_tracker: &ResponseTracker, grace: Duration) {
+        let deadline = Instant::now() + grace;
+        let sessions: Vec<(DeviceId, Arc<DeviceSession>)> = self
+            .sessions
+            .iter()
+            .map(|entry| (*entry.key(), Arc::clone(entry.value())))
+            .collect();
+
+        tracing::info!(sessions = sessions.len(), grace_secs = grace.as_secs_f32(), "Draining device sessions");
+
+        let mut payload = Vec::new();
+        if payload.write_string(SHUTTING_DOWN_REASON).is_ok() {
+            let notices = sessions.iter().map(|(device_id, session)| {
+                let packet = RawPacket {
+                    opcode: opcodes::SERVER_SHUTTING_DOWN,
+                    payload: payload.clone(),
+                };
+                async move {
+                    // A device that stopped reading must not hold up the shutdown
+                    match tokio::time::timeout(grace, session.send_packet(packet)).await {
+                        Ok(Ok(())) => {}
+                        Ok(Err(e)) => tracing::debug!(device_id = %device_id, error = %e, "Failed to send shutdown notice"),
+                        Err(_) => tracing::debug!(device_id = %device_id, "Timed out sending shutdown notice"),
+                    }
+                }
+            });
+            futures::future::join_all(notices).await;
+        }
+
+        while response_tracker.in_flight() > 0 && Instant::now() < deadline {
+            tokio::time::sleep(DRAIN_POLL_INTERVAL).await;
+        }
+
+        let unanswered = response_tracker.in_flight();
+        if unanswered > 0 {
+            tracing::warn!(unanswered, "Grace period over, closing sessions with commands still in flight");
+        }
+
+        for (device_id, session) in sessions {
+            self.sessions.remove(&device_id);
+            self.metadata.remove(&device_id);
+            session.close();
+        }
+    }
+
+    /// Start the background sweeper that disconnects devices whose last_seen
+    /// is older than `heartbeat_timeout`
+    pub fn spawn_heartbeat_sweeper(
+        self: &Arc<Self>,
+        device_repo: Arc<dyn DeviceRepository>,
+        command_history: Arc<dyn CommandHistoryRepository>,
+        event_bus: Arc<EventBus>,
+        heartbeat_timeout: Duration,
+    ) -> tauri::async_runtime::JoinHandle<()> {
+        let manager = Arc::clone(self);
+
+        tauri::async_runtime::spawn(async move {
+            tracing::info!(
+                timeout_secs = heartbeat_timeout.as_secs(),
+                "Heartbeat sweeper started"
+            );
+
+            let mut interval_timer = tokio::time::interval(sweep_interval(heartbeat_timeout));
+
+            loop {
+                interval_timer.tick().await;
+
+                let reaped = manager
+                    .sweep_stale_devices(&device_repo, heartbeat_timeout)
+                    .await;
+
+                for device in reaped {
+                    command_history.park(&device.id(), device.serial().as_str());
+                    event_bus.emit(ArceusEvent::DeviceDisconnected {
+                        device_id: device.id().as_uuid(),
+                        serial: device.serial().as_str().to_string(),
+                    });
+                }
+            }
+        })
+    }
+
+    /// Tear down every device whose last_seen is older than `heartbeat_timeout`
+    /// Returns the devices that were disconnected so the caller can announce them.
+    async fn sweep_stale_devices(
+        &self,
+        device_repo: &Arc<dyn DeviceRepository>,
+        heartbeat_timeout: Duration,
+    ) -> Vec<Arc<Device>> {
+        let devices = match device_repo.find_all().await {
+            Ok(devices) => devices,
+            Err(e) => {
+                tracing::error!(error = %e, "Heartbeat sweep failed to list devices");
+                return Vec::new();
+            }
+        };
+
+        let timeout = chrono::Duration::from_std(heartbeat_timeout)
+            .unwrap_or_else(|_| chrono::Duration::MAX);
+        let now = Utc::now();
+        let mut reaped = Vec::new();
+
+        for device in devices {
+            if now - device.last_seen() <= timeout {
+                continue;
+            }
+
+            let device_id = device.id();
+
+            tracing::warn!(
+                device_id = %device_id,
+                serial = %device.serial(),
+                last_seen = %device.last_seen(),
+                "Heartbeat timeout exceeded, disconnecting device"
+            );
+
+            self.mark_disconnected(&device_id);
+            let _ = device_repo.remove(device_id).await;
+            reaped.push(device);
+        }
+
+        reaped
+    }
+}
+
+/// Sweep at half the heartbeat timeout so a silent device is reaped within
+/// at most 1.5x the timeout
+fn sweep_interval(heartbeat_timeout: Duration) -> Duration {
+    (heartbeat_timeout / 2).max(MIN_HEARTBEAT_SWEEP_INTERVAL)
 }
 
 impl Default for DeviceSessionManager {
@@ -100,10 +338,211 @@ impl SessionManagerTrait for DeviceSessionManager {
 
         session.send_packet(packet)
             .await
-            .map_err(|e| crate::domain::services::SessionError::SendError(e.to_string()))
+            .map_err(|e| crate::domain::services::SessionError::SendError(e.to_string()))?;
+
+        self.commands_sent.fetch_add(1, Ordering::Relaxed);
+        Ok(())
     }
 
     fn has_session(&self, device_id: &DeviceId) -> bool {
         self.sessions.contains_key(device_id)
     }
+
+    async fn send_request(&self, device_id: DeviceId, request_id: u64, packet: RawPacket) -> Result<(), crate::domain::services::SessionError> {
+        let packet = self.request_packet(&device_id, request_id, packet);
+        self.send_packet(device_id, packet).await
+    }
+
+    fn set_packet_trace(&self, device_id: &DeviceId, hook: Option<PacketTraceHook>) -> bool {
+        if !self.has_session(device_id) {
+            return false;
+        }
+
+        self.packet_log.set_hook(*device_id, hook);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::Serial;
+    use crate::infrastructure::protocol::RawPacketCodec;
+    use crate::infrastructure::repositories::InMemoryDeviceRepository;
+    use tokio::net::{TcpListener, TcpStream};
+
+    /// Open a loopback connection and wrap the server side in a session
+    async fn loopback_session(device_id: DeviceId) -> (Arc<DeviceSession>, TcpStream) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap()).await.unwrap();
+        let (server, addr) = listener.accept().await.unwrap();
+
+        (Arc::new(DeviceSession::new(server, device_id, addr, RawPacketCodec::default())), client)
+    }
+
+    #[tokio::test]
+    async fn oversized_frames_are_reported_as_malformed() {
+        use tokio::io::AsyncWriteExt;
+
+        let (session, mut client) = loopback_session(DeviceId::new()).await;
+        client.write_all(&[0x1D, 0xFF, 0xFF, 0x00]).await.unwrap();
+        assert!(matches!(
+            session.receive_packet().await,
+            Err(crate::infrastructure::network::device_session::SessionError::MalformedPacket(_))
+        ));
+
+        let (session, client) = loopback_session(DeviceId::new()).await;
+        drop(client);
+        assert!(matches!(session.receive_packet().await, Ok(None)));
+    }
+
+    #[tokio::test]
+    async fn shutdown_ends_the_read_loop_and_the_connection() {
+        use tokio::io::AsyncReadExt;
+
+        let (session, mut client) = loopback_session(DeviceId::new()).await;
+        session.shutdown().await;
+
+        assert!(matches!(session.receive_packet().await, Ok(None)));
+        let mut buf = [0u8; 1];
+        assert_eq!(client.read(&mut buf).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn requests_are_tagged_only_for_devices_that_echo_request_ids() {
+        let manager = DeviceSessionManager::new();
+        let device_id = DeviceId::new();
+        let (session, _client) = loopback_session(device_id).await;
+        manager.add_session(device_id, session);
+        let packet = || RawPacket {
+            opcode: opcodes::EXECUTE_SHELL,
+            payload: vec![0, 2, b'l', b's'],
+        };
+
+        let plain = manager.request_packet(&device_id, 42, packet());
+        assert_eq!(plain.opcode, opcodes::EXECUTE_SHELL);
+
+        manager.set_request_ids(&device_id, true);
+        let tagged = manager.request_packet(&device_id, 42, packet());
+        assert_eq!(tagged.opcode, opcodes::TAGGED_COMMAND);
+        let (request_id, inner) = tagged::untag_response(&tagged.payload).unwrap();
+        assert_eq!(request_id, 42);
+        assert_eq!(inner.payload, packet().payload);
+    }
+
+    #[test]
+    fn sweep_interval_is_half_the_timeout() {
+        assert_eq!(sweep_interval(Duration::from_secs(30)), Duration::from_secs(15));
+        assert_eq!(sweep_interval(Duration::ZERO), MIN_HEARTBEAT_SWEEP_INTERVAL);
+    }
+
+    #[tokio::test]
+    async fn silent_device_is_reaped_after_timeout() {
+        let manager = DeviceSessionManager::new();
+        let device_repo: Arc<dyn DeviceRepository> = Arc::new(InMemoryDeviceRepository::new());
+        let heartbeat_timeout = Duration::from_millis(50);
+
+        let device_id = DeviceId::new();
+        let serial = Serial::new("aa:bb:cc:dd:ee:ff".to_string()).unwrap();
+        device_repo
+            .save(Device::new(device_id, serial, "Quest 3".to_string(), "1.0.0".to_string()))
+            .await
+            .unwrap();
+
+        let (session, _client) = loopback_session(device_id).await;
+        manager.add_session(device_id, Arc::clone(&session));
+
+        // Still within the timeout: nothing is reaped
+        let reaped = manager.sweep_stale_devices(&device_repo, heartbeat_timeout).await;
+        assert!(reaped.is_empty());
+        assert!(manager.has_session(&device_id));
+
+        tokio::time::sleep(heartbeat_timeout * 2).await;
+
+        let reaped = manager.sweep_stale_devices(&device_repo, heartbeat_timeout).await;
+        assert_eq!(reaped.len(), 1);
+        assert_eq!(reaped[0].id(), device_id);
+        assert!(!manager.has_session(&device_id));
+        assert!(manager.get_client_version(&device_id).is_none());
+        assert!(device_repo.find_by_id(device_id).await.unwrap().is_none());
+
+        // The session's read loop observes the close
+        assert!(session.receive_packet().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn counts_only_delivered_commands() {
+        let manager = DeviceSessionManager::new();
+        let device_id = DeviceId::new();
+        let (session, _client) = loopback_session(device_id).await;
+        manager.add_session(device_id, session);
+
+        let ping = || RawPacket {
+            opcode: opcodes::PING,
+            payload: Vec::new(),
+        };
+        SessionManagerTrait::send_packet(&manager, device_id, ping()).await.unwrap();
+        SessionManagerTrait::send_packet(&manager, device_id, ping()).await.unwrap();
+        assert!(SessionManagerTrait::send_packet(&manager, DeviceId::new(), ping()).await.is_err());
+        assert_eq!(manager.session_count(), 1);
+        assert_eq!(manager.commands_sent(), 2);
+
+        manager.reset_commands_sent();
+        assert_eq!(manager.commands_sent(), 0);
+    }
+
+    #[tokio::test]
+    async fn drain_waits_for_in_flight_commands_before_closing() {
+        use futures::StreamExt;
+        use tokio_util::codec::Framed;
+
+        let manager = Arc::new(DeviceSessionManager::new());
+        let tracker = Arc::new(ResponseTracker::new());
+        let device_id = DeviceId::new();
+        let (session, client) = loopback_session(device_id).await;
+        manager.add_session(device_id, Arc::clone(&session));
+
+        // A command is waiting for its response when the drain starts
+        let (_, response) = tracker.register(device_id, opcodes::PING_RESPONSE);
+
+        let drain = tokio::spawn({
+            let manager = Arc::clone(&manager);
+            let tracker = Arc::clone(&tracker);
+            async move { manager.drain(&tracker, Duration::from_secs(5)).await }
+        });
+
+        let mut client = Framed::new(client, RawPacketCodec::default());
+        let notice = client.next().await.unwrap().unwrap();
+        assert_eq!(notice.opcode, opcodes::SERVER_SHUTTING_DOWN);
+        assert_eq!(&notice.payload[4..], SHUTTING_DOWN_REASON.as_bytes());
+
+        // The session stays open until the device answers
+        tokio::time::sleep(DRAIN_POLL_INTERVAL * 3).await;
+        assert!(!drain.is_finished());
+        assert!(manager.has_session(&device_id));
+
+        tracker.complete(device_id, opcodes::PING_RESPONSE, vec![1]);
+        assert_eq!(response.await.unwrap(), vec![1]);
+
+        tokio::time::timeout(Duration::from_secs(1), drain).await.unwrap().unwrap();
+        assert!(!manager.has_session(&device_id));
+        assert!(session.receive_packet().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn drain_gives_up_after_the_grace_period() {
+        let manager = DeviceSessionManager::new();
+        let tracker = ResponseTracker::new();
+        let device_id = DeviceId::new();
+        let (session, _client) = loopback_session(device_id).await;
+        manager.add_session(device_id, session);
+        let (_, _response) = tracker.register(device_id, opcodes::PING_RESPONSE);
+
+        let grace = Duration::from_millis(100);
+        let started = Instant::now();
+        manager.drain(&tracker, grace).await;
+
+        assert!(started.elapsed() >= grace);
+        assert_eq!(manager.session_count(), 0);
+    }
 }
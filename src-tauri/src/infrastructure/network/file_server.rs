@@ -0,0 +1,484 @@
+/// Static File Server
+/// Serves a directory over HTTP for devices to download APKs from. Single
+/// `Range` requests are answered with `206 Partial Content`, so a device whose
+/// download drops mid-install can resume instead of starting over. Responses
+/// carry an `ETag` and `Last-Modified`, and a resume whose `If-Range` no
+/// longer matches gets the whole new file instead of a splice of two versions.
+
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use chrono::{DateTime, Utc};
+use socket2::{Domain, Protocol, Socket, Type};
+use std::io::SeekFrom;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::UNIX_EPOCH;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio::net::TcpListener;
+use tokio_util::io::ReaderStream;
+
+const APK_CONTENT_TYPE: &str = "application/vnd.android.package-archive";
+
+/// Byte range requested with a `Range` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteRange {
+    /// No usable range; send the whole file
+    Full,
+    /// Inclusive first and last byte
+    Partial { start: u64, end: u64 },
+    /// Lies entirely past the end of the file
+    Unsatisfiable,
+}
+
+impl ByteRange {
+    /// Interpret a `Range` header value for a file of `len` bytes
+    /// Only a single `bytes` range is honored; anything else gets the full file,
+    /// which RFC 9110 allows a server to do.
+    pub fn parse(value: Option<&str>, len: u64) -> Self {
+        let Some(spec) = value.and_then(|v| v.trim().strip_prefix("bytes=")) else {
+            return ByteRange::Full;
+        };
+        if spec.contains(',') {
+            return ByteRange::Full;
+        }
+        let Some((first, last)) = spec.trim().split_once('-') else {
+            return ByteRange::Full;
+        };
+
+        match (first.trim(), last.trim()) {
+            // Suffix range: the last `n` bytes
+            ("", suffix) => match suffix.parse::<u64>() {
+                Ok(0) => ByteRange::Unsatisfiable,
+                Ok(_) if len == 0 => ByteRange::Unsatisfiable,
+                Ok(n) => ByteRange::Partial {
+                    start: len.saturating_sub(n),
+                    end: len - 1,
+                },
+                Err(_) => ByteRange::Full,
+            },
+            (first, last) => {
+                let Ok(start) = first.parse::<u64>() else {
+                    return ByteRange::Full;
+                };
+                let end = match last {
+                    "" => u64::MAX,
+                    last => match last.parse::<u64>() {
+                        Ok(end) if end >= start => end,
+                        _ => return ByteRange::Full,
+                    },
+                };
+                if start >= len {
+                    return ByteRange::Unsatisfiable;
+                }
+                ByteRange::Partial {
+                    start,
+                    end: end.min(len - 1),
+                }
+            }
+        }
+    }
+}
+
+/// HTTP server for the files in one directory
+pub struct FileServer {
+    listener: TcpListener,
+    directory: Arc<PathBuf>,
+}
+
+impl FileServer {
+    /// Listen on `port` on all interfaces, so devices on the LAN can reach it
+    /// Binds dual-stack `::` so IPv6 devices are served too, and falls back to
+    /// IPv4 only where the host has no IPv6.
+    pub async fn bind(port: u16, directory: PathBuf) -> std::io::Result<Self> {
+        let listener = match Self::bind_dual_stack(port) {
+            Ok(listener) => listener,
+            Err(e) => {
+                tracing::debug!(error = %e, "IPv6 unavailable, serving files over IPv4 only");
+                TcpListener::bind((Ipv4Addr::UNSPECIFIED, port)).await?
+            }
+        };
+        Ok(Self {
+            listener,
+            directory: Arc::new(directory),
+        })
+    }
+
+    fn bind_dual_stack(port: u16) -> std::io::Result<TcpListener> {
+        let addr = SocketAddr::from((Ipv6Addr::UNSPECIFIED, port));
+        let socket = Socket::new(Domain::IPV6, Type::STREAM, Some(Protocol::TCP))?;
+        // Accept IPv4 clients too, as IPv4-mapped addresses
+        socket.set_only_v6(false)?;
+        socket.set_reuse_address(true)?;
+        socket.set_nonblocking(true)?;
+        socket.bind(&addr.into())?;
+        socket.listen(128)?;
+        TcpListener::from_std(socket.into())
+    }
+
+    pub fn local_addr(&self) -> std::io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Serve files until the listener fails
+    pub async fn serve(self) -> std::io::Result<()> {
+        let router = Router::new()
+            .route("/{*path}", get(serve_file))
+            .with_state(self.directory);
+        axum::serve(self.listener, router).await
+    }
+}
+
+async fn serve_file(State(directory): State<Arc<PathBuf>>, uri: Uri, headers: HeaderMap) -> Response {
+    let Some(path) = resolve_contained_path(&directory, uri.path()).await else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let mut file = match tokio::fs::File::open(&path).await {
+        Ok(file) => file,
+        Err(_) => return StatusCode::NOT_FOUND.into_response(),
+    };
+    let metadata = match file.metadata().await {
+        Ok(metadata) if metadata.is_file() => metadata,
+        _ => return StatusCode::NOT_FOUND.into_response(),
+    };
+    let len = metadata.len();
+    let validators = Validators::of(&metadata);
+
+    // A range only makes sense against the version of the file it was taken from
+    let if_range_matches = headers
+        .get(header::IF_RANGE)
+        .and_then(|v| v.to_str().ok())
+        .is_none_or(|value| validators.matches_if_range(value));
+    let range = if if_range_matches {
+        ByteRange::parse(headers.get(header::RANGE).and_then(|v| v.to_str().ok()), len)
+    } else {
+        ByteRange::Full
+    };
+
+    let content_type = match path.extension().and_then(|e| e.to_str()) {
+        Some("apk") => APK_CONTENT_TYPE,
+        _ => "application/octet-stream",
+    };
+
+    let mut response_headers = HeaderMap::new();
+    response_headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    response_headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    validators.insert_into(&mut response_headers);
+
+    let (status, start, count) = match range {
+        ByteRange::Full => (StatusCode::OK, 0, len),
+        ByteRange::Partial { start, end } => {
+            response_headers.insert(
+                header::CONTENT_RANGE,
+                content_range(&format!("bytes {}-{}/{}", start, end, len)),
+            );
+            (StatusCode::PARTIAL_CONTENT, start, end - start + 1)
+        }
+        ByteRange::Unsatisfiable => {
+            response_headers.insert(header::CONTENT_RANGE, content_range(&format!("bytes */{}", len)));
+            return (StatusCode::RANGE_NOT_SATISFIABLE, response_headers).into_response();
+        }
+    };
+
+    if let Err(e) = file.seek(SeekFrom::Start(start)).await {
+        tracing::warn!(path = ?path, error = %e, "Failed to seek in served file");
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+    response_headers.insert(header::CONTENT_LENGTH, HeaderValue::from(count));
+
+    let body = Body::from_stream(ReaderStream::new(file.take(count)));
+    (status, response_headers, body).into_response()
+}
+
+fn content_range(value: &str) -> HeaderValue {
+    HeaderValue::from_str(value).expect("byte range is valid header text")
+}
+
+/// Strong validators for one version of a served file
+struct Validators {
+    etag: Option<String>,
+    last_modified: Option<DateTime<Utc>>,
+}
+
+impl Validators {
+    fn of(metadata: &std::fs::Metadata) -> Self {
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok());
+
+        Self {
+            etag: modified.map(|since_epoch| {
+                format!("\"{:x}-{:x}\"", metadata.len(), since_epoch.as_nanos())
+            }),
+            last_modified: modified
+                .and_then(|since_epoch| DateTime::from_timestamp(since_epoch.as_secs() as i64, 0)),
+        }
+    }
+
+    fn insert_into(&self, headers: &mut HeaderMap) {
+        if let Some(value) = self.etag.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+            headers.insert(header::ETAG, value);
+        }
+        if let Some(value) = self
+            .last_modified
+            .and_then(|t| HeaderValue::from_str(&http_date(t)).ok())
+        {
+            headers.insert(header::LAST_MODIFIED, value);
+        }
+    }
+
+    /// Whether an `If-Range` value, an entity tag or a date, names this version
+    /// Weak tags never match, as RFC 9110 requires a strong comparison here.
+    fn matches_if_range(&self, value: &str) -> bool {
+        let value = value.trim();
+        if value.starts_with('"') || value.starts_with("W/") {
+            return self.etag.as_deref() == Some(value);
+        }
+
+        match (DateTime::parse_from_rfc2822(value), self.last_modified) {
+            (Ok(date), Some(last_modified)) => date.with_timezone(&Utc) == last_modified,
+            _ => false,
+        }
+    }
+}
+
+/// Format a time as an HTTP date, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`
+fn http_date(time: DateTime<Utc>) -> String {
+    time.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Like `resolve_path`, but also refuses files that only lie under `directory`
+/// by name, such as symlinks pointing outside it
+async fn resolve_contained_path(directory: &Path, request_path: &str) -> Option<PathBuf> {
+    let path = resolve_path(directory, request_path)?;
+    let directory = tokio::fs::canonicalize(directory).await.ok()?;
+    let path = tokio::fs::canonicalize(&path).await.ok()?;
+
+    path.starts_with(&directory).then_some(path)
+}
+
+/// File under `directory` named by a request path, `None` if it would escape it
+fn resolve_path(directory: &Path, request_path: &str) -> Option<PathBuf> {
+    let decoded = percent_decode(request_path)?;
+    let mut path = directory.to_path_buf();
+    let mut segments = 0;
+
+    for segment in decoded.split('/').filter(|s| !s.is_empty()) {
+        if segment == "." || segment == ".." || segment.contains('\\') || segment.contains(':') {
+            return None;
+        }
+        path.push(segment);
+        segments += 1;
+    }
+
+    (segments > 0).then_some(path)
+}
+
+/// Decode `%XX` escapes; `None` if they are malformed or not UTF-8
+fn percent_decode(input: &str) -> Option<String> {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_single_byte_ranges() {
+        assert_eq!(ByteRange::parse(None, 100), ByteRange::Full);
+        assert_eq!(
+            ByteRange::parse(Some("bytes=10-19"), 100),
+            ByteRange::Partial { start: 10, end: 19 }
+        );
+        assert_eq!(
+            ByteRange::parse(Some("bytes=90-"), 100),
+            ByteRange::Partial { start: 90, end: 99 }
+        );
+        assert_eq!(
+            ByteRange::parse(Some("bytes=-30"), 100),
+            ByteRange::Partial { start: 70, end: 99 }
+        );
+        assert_eq!(
+            ByteRange::parse(Some("bytes=50-500"), 100),
+            ByteRange::Partial { start: 50, end: 99 }
+        );
+        assert_eq!(ByteRange::parse(Some("bytes=100-"), 100), ByteRange::Unsatisfiable);
+        assert_eq!(ByteRange::parse(Some("bytes=-0"), 100), ByteRange::Unsatisfiable);
+        assert_eq!(ByteRange::parse(Some("bytes=0-9,20-29"), 100), ByteRange::Full);
+        assert_eq!(ByteRange::parse(Some("bytes=20-10"), 100), ByteRange::Full);
+        assert_eq!(ByteRange::parse(Some("items=0-9"), 100), ByteRange::Full);
+    }
+
+    #[test]
+    fn request_paths_cannot_escape_the_directory() {
+        let dir = Path::new("/srv/apks");
+        assert_eq!(
+            resolve_path(dir, "/My%20Game.apk"),
+            Some(dir.join("My Game.apk"))
+        );
+        assert_eq!(resolve_path(dir, "/../secret"), None);
+        assert_eq!(resolve_path(dir, "/%2e%2e/secret"), None);
+        assert_eq!(resolve_path(dir, "/a%5C..%5Cb"), None);
+        assert_eq!(resolve_path(dir, "/"), None);
+    }
+
+    #[tokio::test]
+    async fn ranged_get_returns_the_requested_slice() {
+        let dir = std::env::temp_dir().join(format!("arceus-http-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let contents: Vec<u8> = (0..=255u8).cycle().take(4096).collect();
+        tokio::fs::write(dir.join("game.apk"), &contents).await.unwrap();
+
+        let server = FileServer::bind(0, dir.clone()).await.unwrap();
+        let url = format!("http://127.0.0.1:{}/game.apk", server.local_addr().unwrap().port());
+        tokio::spawn(server.serve());
+
+        let client = reqwest::Client::new();
+        let response = client
+            .get(&url)
+            .header("Range", "bytes=1000-1999")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 206);
+        assert_eq!(
+            response.headers()["content-range"],
+            "bytes 1000-1999/4096"
+        );
+        assert_eq!(response.bytes().await.unwrap().as_ref(), &contents[1000..2000]);
+
+        let response = client.get(&url).send().await.unwrap();
+        assert_eq!(response.status().as_u16(), 200);
+        assert_eq!(response.bytes().await.unwrap().as_ref(), &contents[..]);
+
+        let response = client
+            .get(&url)
+            .header("Range", "bytes=5000-")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 416);
+        assert_eq!(response.headers()["content-range"], "bytes */4096");
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[test]
+    fn if_range_needs_a_strong_match() {
+        let validators = Validators {
+            etag: Some("\"1000-abc\"".to_string()),
+            last_modified: DateTime::from_timestamp(784111777, 0),
+        };
+
+        assert!(validators.matches_if_range("\"1000-abc\""));
+        assert!(!validators.matches_if_range("\"1000-abd\""));
+        assert!(!validators.matches_if_range("W/\"1000-abc\""));
+        assert!(validators.matches_if_range("Sun, 06 Nov 1994 08:49:37 GMT"));
+        assert!(!validators.matches_if_range("Sun, 06 Nov 1994 08:49:38 GMT"));
+        assert!(!validators.matches_if_range("yesterday"));
+        assert_eq!(
+            http_date(validators.last_modified.unwrap()),
+            "Sun, 06 Nov 1994 08:49:37 GMT"
+        );
+    }
+
+    #[tokio::test]
+    async fn stale_if_range_gets_the_whole_file() {
+        let dir = std::env::temp_dir().join(format!("arceus-http-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let contents: Vec<u8> = (0..=255u8).cycle().take(4096).collect();
+        tokio::fs::write(dir.join("game.apk"), &contents).await.unwrap();
+
+        let server = FileServer::bind(0, dir.clone()).await.unwrap();
+        let url = format!("http://127.0.0.1:{}/game.apk", server.local_addr().unwrap().port());
+        tokio::spawn(server.serve());
+
+        let client = reqwest::Client::new();
+        let response = client.get(&url).send().await.unwrap();
+        let etag = response.headers()["etag"].to_str().unwrap().to_string();
+        let last_modified = response.headers()["last-modified"].to_str().unwrap().to_string();
+
+        for validator in [&etag, &last_modified] {
+            let response = client
+                .get(&url)
+                .header("Range", "bytes=1000-1999")
+                .header("If-Range", validator.as_str())
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status().as_u16(), 206);
+        }
+
+        let response = client
+            .get(&url)
+            .header("Range", "bytes=1000-1999")
+            .header("If-Range", "\"stale\"")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status().as_u16(), 200);
+        assert_eq!(response.bytes().await.unwrap().as_ref(), &contents[..]);
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn serves_ipv6_clients() {
+        if std::net::TcpListener::bind("[::1]:0").is_err() {
+            return;
+        }
+        let dir = std::env::temp_dir().join(format!("arceus-http-{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        tokio::fs::write(dir.join("game.apk"), b"apk").await.unwrap();
+
+        let server = FileServer::bind(0, dir.clone()).await.unwrap();
+        let url = format!("http://[::1]:{}/game.apk", server.local_addr().unwrap().port());
+        tokio::spawn(server.serve());
+
+        let response = reqwest::get(&url).await.unwrap();
+        assert_eq!(response.bytes().await.unwrap().as_ref(), b"apk");
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn symlinks_out_of_the_directory_are_not_served() {
+        let root = std::env::temp_dir().join(format!("arceus-http-{}", uuid::Uuid::new_v4()));
+        let dir = root.join("apks");
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        tokio::fs::write(root.join("secret.txt"), b"secret").await.unwrap();
+        tokio::fs::write(dir.join("game.apk"), b"apk").await.unwrap();
+        std::os::unix::fs::symlink(root.join("secret.txt"), dir.join("leak.apk")).unwrap();
+        std::os::unix::fs::symlink(dir.join("game.apk"), dir.join("alias.apk")).unwrap();
+
+        assert_eq!(resolve_contained_path(&dir, "/leak.apk").await, None);
+        assert_eq!(
+            resolve_contained_path(&dir, "/alias.apk").await,
+            Some(tokio::fs::canonicalize(dir.join("game.apk")).await.unwrap())
+        );
+
+        tokio::fs::remove_dir_all(&root).await.unwrap();
+    }
+}
@@ -1,7 +1,11 @@
+pub mod bandwidth_limiter;
 pub mod connection_handler;
 pub mod device_session;
 pub mod device_session_manager;
+pub mod file_server;
 pub mod packet_handler;
 pub mod tcp_server;
 
+pub use bandwidth_limiter::BandwidthLimiter;
+pub use file_server::FileServer;
 pub use tcp_server::TcpServer;
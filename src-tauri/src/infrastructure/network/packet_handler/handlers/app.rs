@@ -14,6 +14,8 @@ use super::super::{PacketHandler, Result};
 
 /// Handles FOREGROUND_APP_CHANGED (0x06) packets
 /// Payload: [package_name: String][app_name: String]
+/// Alerts when a device with an expected app switches away from it; bringing
+/// the app back is left to the `AppWatchdog`.
 pub struct ForegroundAppChangedHandler {
     device_repo: Arc<dyn DeviceRepository>,
     event_bus: Arc<EventBus>,
@@ -49,9 +51,31 @@ impl PacketHandler for ForegroundAppChangedHandler {
 
         // Update device with running app info
         if let Ok(Some(device)) = self.device_repo.find_by_id(device_id).await {
-            let updated_device = device.as_ref().clone().with_running_app(app_name.clone());
+            let was_running = device.running_package().map(str::to_string);
+            let updated_device = device
+                .as_ref()
+                .clone()
+                .with_running_app(app_name.clone())
+                .with_running_package(package_name.clone());
             self.device_repo.save(updated_device.clone()).await?;
 
+            // Alert once per switch, not on every repeat of the same package
+            if updated_device.has_left_expected_app() && was_running.as_deref() != Some(package_name.as_str()) {
+                let expected = updated_device.expected_package().unwrap_or_default().to_string();
+                tracing::warn!(
+                    device_id = %device_id,
+                    expected = %expected,
+                    actual = %package_name,
+                    "Device left its expected app"
+                );
+                self.event_bus.device_left_app(
+                    device_id.as_uuid(),
+                    updated_device.serial().as_str().to_string(),
+                    expected,
+                    package_name.clone(),
+                );
+            }
+
             // Emit event to frontend with full device state
             let device_state = DeviceStateDto::from(&Arc::new(updated_device));
             self.event_bus.device_updated(device_state);
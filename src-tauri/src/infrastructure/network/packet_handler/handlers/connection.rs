@@ -5,23 +5,26 @@ use crate::application::dto::DeviceStateDto;
 use crate::application::services::ClientApkService;
 use crate::domain::commands::{Command, InstallApkCommand};
 use crate::domain::models::{Device, DeviceId, Serial};
-use crate::domain::repositories::{DeviceNameRepository, DeviceRepository};
+use crate::domain::repositories::{CommandHistoryRepository, DeviceNameRepository, DeviceRepository};
 use crate::infrastructure::network::device_session_manager::DeviceSessionManager;
-use crate::infrastructure::protocol::{opcodes, RawPacket};
+use crate::infrastructure::protocol::{opcodes, tagged, RawPacket};
 use crate::net::io::ProtocolReadExt;
 use async_trait::async_trait;
+use byteorder::ReadBytesExt;
 use std::io::Cursor;
 use std::sync::Arc;
 
 use super::super::{PacketHandler, Result};
 
 /// Handles DEVICE_CONNECTED (0x01) packets
-/// Payload: [model: String][serial: String][foreground_app: String]
+/// Payload: [model: String][serial: String][foreground_app: String][capabilities: u8]
+/// Older clients don't send the capabilities byte.
 pub struct DeviceConnectedHandler {
     device_repo: Arc<dyn DeviceRepository>,
     device_name_repo: Arc<dyn DeviceNameRepository>,
     event_bus: Arc<EventBus>,
     session_manager: Arc<DeviceSessionManager>,
+    command_history: Arc<dyn CommandHistoryRepository>,
 }
 
 impl DeviceConnectedHandler {
@@ -30,15 +33,49 @@ impl DeviceConnectedHandler {
         device_name_repo: Arc<dyn DeviceNameRepository>,
         event_bus: Arc<EventBus>,
         session_manager: Arc<DeviceSessionManager>,
+        command_history: Arc<dyn CommandHistoryRepository>,
     ) -> Self {
         Self {
             device_repo,
             device_name_repo,
             event_bus,
             session_manager,
+            command_history,
         }
     }
 
+    /// Drop a device's previous connection if it is still registered
+    /// A device that roams to a new IP reconnects before its old connection times
+    /// out; that connection is closed and reported disconnected now so it isn't
+    /// listed twice or left holding a socket until the heartbeat timeout.
+    async fn retire_stale_connection(&self, previous: &Device, device_id: DeviceId) -> Result<()> {
+        let stale_id = previous.id();
+        if stale_id == device_id || self.device_repo.find_by_id(stale_id).await?.is_none() {
+            return Ok(());
+        }
+
+        tracing::info!(
+            device_id = %stale_id,
+            serial = %previous.serial().as_str(),
+            "Replacing stale connection of reconnected device"
+        );
+        // Removed first so the old connection's own cleanup finds nothing to report.
+        // Shutting the session down ends its receive loop, which releases the socket
+        // and pending requests, and tells the device the old connection is gone.
+        self.device_repo.remove(stale_id).await?;
+        if let Some(stale_session) = self.session_manager.get_session(&stale_id) {
+            self.session_manager.mark_disconnected(&stale_id);
+            // A write stuck on the dead socket mustn't hold up the new connection
+            tokio::spawn(async move { stale_session.shutdown().await });
+        }
+        self.command_history.park(&stale_id, previous.serial().as_str());
+        self.event_bus.emit(crate::app::events::ArceusEvent::DeviceDisconnected {
+            device_id: stale_id.as_uuid(),
+            serial: previous.serial().as_str().to_string(),
+        });
+        Ok(())
+    }
+
     /// Helper to send initial status requests to a newly connected device
     async fn send_initial_status_requests(device_id: DeviceId, session_manager: Arc<DeviceSessionManager>) {
         // Brief delay to ensure device is ready
@@ -78,6 +115,9 @@ impl PacketHandler for DeviceConnectedHandler {
         let serial_str = cursor.read_string()?;
         let foreground_app = cursor.read_string()?;
         let running_app = if foreground_app.is_empty() { None } else { Some(foreground_app) };
+        let capabilities = cursor.read_u8().unwrap_or(0);
+        self.session_manager
+            .set_request_ids(&device_id, capabilities & tagged::CAP_REQUEST_IDS != 0);
 
         // Get version from session metadata (set during VERSION_CHECK phase)
         let version = self.session_manager
@@ -101,16 +141,33 @@ impl PacketHandler for DeviceConnectedHandler {
             .map_err(|e| crate::app::error::ArceusError::DomainValidation(format!("Invalid serial: {}", e)))?;
 
         // Verify session exists (TCP connection was established and VERSION_CHECK was received)
-        if !self.session_manager.has_session(&device_id) {
+        let Some(session) = self.session_manager.get_session(&device_id) else {
             tracing::warn!(
                 device_id = %device_id,
                 "DEVICE_CONNECTED received without active session - ignoring"
             );
             return Ok(());
-        }
+        };
 
-        // Create device with real info from the packet (first time device is created!)
-        let mut device = Device::new(device_id, serial.clone(), model.clone(), version);
+        // A device seen before (reconnecting, or roaming to a new IP before its old
+        // connection timed out) keeps its state; otherwise it's created from the packet
+        let mut device = match self.device_repo.find_last_known(&serial).await? {
+            Some(previous) => {
+                self.retire_stale_connection(&previous, device_id).await?;
+                tracing::info!(
+                    device_id = %device_id,
+                    previous_device_id = %previous.id(),
+                    serial = %serial.as_str(),
+                    "Known device reconnected, reusing its state"
+                );
+                previous
+                    .as_ref()
+                    .clone()
+                    .reconnect(device_id, Some(session.addr().ip()), model.clone(), version)
+            }
+            None => Device::new(device_id, serial.clone(), model.clone(), version)
+                .with_ip_address(session.addr().ip()),
+        };
 
         // Apply foreground app from initial packet if present
         if let Some(app_name) = running_app {
@@ -119,7 +176,22 @@ impl PacketHandler for DeviceConnectedHandler {
 
         // Load custom name from database if exists
         let custom_name = self.device_name_repo.get_name(&serial).await.ok().flatten();
-        let device = device.with_custom_name(custom_name.clone());
+        let annotations = self
+            .device_name_repo
+            .get_annotations(&serial)
+            .await
+            .unwrap_or_default();
+        let expected_package = self
+            .device_name_repo
+            .get_expected_package(&serial)
+            .await
+            .ok()
+            .flatten();
+        let device = device
+            .with_custom_name(custom_name.clone())
+            .with_tags(annotations.tags)
+            .with_note(annotations.note)
+            .with_expected_package(expected_package);
 
         self.device_repo.save(device.clone()).await?;
 
@@ -129,8 +201,13 @@ impl PacketHandler for DeviceConnectedHandler {
             "Device connected"
         );
 
+        // A reconnecting device picks up the history of its previous connection
+        self.command_history.restore(serial.as_str(), device_id);
+        let history = self.command_history.recent_commands(&device_id, None);
+
         // Emit DeviceConnected event to frontend
-        let device_state = DeviceStateDto::from(&Arc::new(device.clone()));
+        let device_state =
+            DeviceStateDto::from(&Arc::new(device.clone())).with_command_history(&history);
         self.event_bus.device_connected(device_state);
 
         // Request initial connection data
@@ -6,6 +6,6 @@ pub mod app;
 pub mod responses;
 
 pub use connection::{DeviceConnectedHandler, HeartbeatHandler, VersionCheckHandler};
-pub use status::{BatteryStatusHandler, VolumeStatusHandler};
+pub use status::{BatteryStatusHandler, StorageStatusHandler, VolumeStatusHandler};
 pub use app::ForegroundAppChangedHandler;
 pub use responses::*;
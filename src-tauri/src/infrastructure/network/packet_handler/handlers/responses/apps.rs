@@ -1,8 +1,9 @@
 /// App-related response handlers (INSTALLED_APPS_RESPONSE, CLOSE_ALL_APPS_RESPONSE)
 
 use crate::app::EventBus;
-use crate::application::dto::CommandResultDto;
-use crate::domain::models::DeviceId;
+use crate::application::dto::{CommandResultDto, InstalledAppDto};
+use crate::domain::models::{DeviceId, InstalledApp};
+use crate::domain::repositories::DeviceRepository;
 use crate::net::io::ProtocolReadExt;
 use async_trait::async_trait;
 use byteorder::{BigEndian, ReadBytesExt};
@@ -11,14 +12,20 @@ use std::sync::Arc;
 
 use super::super::super::{PacketHandler, Result};
 
+/// Most installed app entries kept from one response; the rest are ignored
+const MAX_INSTALLED_APPS: usize = 4096;
+
 /// Handles INSTALLED_APPS_RESPONSE (0x12) packets
+/// Payload: [count: u32][entries: List<String>]
+/// Each entry is `package_name[|label[|version_name[|is_system[|version_code]]]]`
 pub struct InstalledAppsResponseHandler {
+    device_repo: Arc<dyn DeviceRepository>,
     event_bus: Arc<EventBus>,
 }
 
 impl InstalledAppsResponseHandler {
-    pub fn new(event_bus: Arc<EventBus>) -> Self {
-        Self { event_bus }
+    pub fn new(device_repo: Arc<dyn DeviceRepository>, event_bus: Arc<EventBus>) -> Self {
+        Self { device_repo, event_bus }
     }
 }
 
@@ -32,15 +39,36 @@ impl PacketHandler for InstalledAppsResponseHandler {
         let mut cursor = Cursor::new(payload);
         let count = cursor.read_u32::<BigEndian>()? as usize;
 
+        // The count comes from the device, so it must not size allocations unchecked
+        if count > MAX_INSTALLED_APPS {
+            tracing::warn!(
+                device_id = %device_id,
+                count,
+                max = MAX_INSTALLED_APPS,
+                "Installed apps response too long, keeping the first entries"
+            );
+        }
+        let count = count.min(MAX_INSTALLED_APPS);
+
         let mut apps = Vec::with_capacity(count);
         for _ in 0..count {
-            let package_name = cursor.read_string()?;
-            apps.push(package_name);
+            let entry = cursor.read_string()?;
+            match InstalledApp::parse(&entry) {
+                Some(app) => apps.push(app),
+                None => tracing::warn!(device_id = %device_id, entry = %entry, "Skipping malformed installed app entry"),
+            }
         }
 
         tracing::debug!(device_id = %device_id, app_count = count, "Installed apps response");
 
-        self.event_bus.installed_apps_received(device_id.as_uuid().clone(), apps);
+        let app_dtos = apps.iter().map(InstalledAppDto::from).collect();
+
+        if let Ok(Some(device)) = self.device_repo.find_by_id(device_id).await {
+            let updated_device = device.as_ref().clone().with_installed_apps(apps);
+            self.device_repo.save(updated_device).await?;
+        }
+
+        self.event_bus.installed_apps_received(device_id.as_uuid().clone(), app_dtos);
 
         Ok(())
     }
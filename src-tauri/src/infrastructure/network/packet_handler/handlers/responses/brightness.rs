@@ -0,0 +1,48 @@
+/// Brightness response handler
+
+use crate::app::EventBus;
+use crate::domain::models::DeviceId;
+use crate::domain::repositories::DeviceRepository;
+use async_trait::async_trait;
+use byteorder::ReadBytesExt;
+use std::io::Cursor;
+use std::sync::Arc;
+
+use super::super::super::{PacketHandler, Result};
+
+/// Handles BRIGHTNESS_RESPONSE (0x1B) packets
+/// Sent in reply to both SET_BRIGHTNESS and GET_BRIGHTNESS
+/// Payload: [brightness: u8]
+pub struct BrightnessResponseHandler {
+    device_repo: Arc<dyn DeviceRepository>,
+    event_bus: Arc<EventBus>,
+}
+
+impl BrightnessResponseHandler {
+    pub fn new(device_repo: Arc<dyn DeviceRepository>, event_bus: Arc<EventBus>) -> Self {
+        Self { device_repo, event_bus }
+    }
+}
+
+#[async_trait]
+impl PacketHandler for BrightnessResponseHandler {
+    fn opcode(&self) -> u8 {
+        crate::infrastructure::protocol::opcodes::BRIGHTNESS_RESPONSE
+    }
+
+    async fn handle(&self, device_id: DeviceId, payload: Vec<u8>) -> Result<()> {
+        let mut cursor = Cursor::new(payload);
+        let brightness = cursor.read_u8()?.min(100);
+
+        tracing::debug!(device_id = %device_id, brightness, "Brightness response");
+
+        if let Ok(Some(device)) = self.device_repo.find_by_id(device_id).await {
+            let updated_device = device.as_ref().clone().with_brightness(brightness);
+            self.device_repo.save(updated_device).await?;
+        }
+
+        self.event_bus.brightness_updated(device_id.as_uuid().clone(), brightness);
+
+        Ok(())
+    }
+}
@@ -4,11 +4,16 @@ pub mod simple;
 pub mod shell;
 pub mod apps;
 pub mod volume;
+pub mod brightness;
+pub mod wifi;
+pub mod screenshot;
 
 pub use simple::{
     LaunchAppResponseHandler,
     ApkInstallResponseHandler,
     UninstallAppResponseHandler,
+    LocateDeviceResponseHandler,
+    SetTimeResponseHandler,
     PingResponseHandler,
     ApkDownloadStartedHandler,
     ApkDownloadProgressHandler,
@@ -17,3 +22,6 @@ pub use simple::{
 pub use shell::ShellExecutionResponseHandler;
 pub use apps::{InstalledAppsResponseHandler, CloseAllAppsResponseHandler};
 pub use volume::VolumeSetResponseHandler;
+pub use brightness::BrightnessResponseHandler;
+pub use wifi::WifiStatusResponseHandler;
+pub use screenshot::ScreenshotChunkHandler;
@@ -0,0 +1,53 @@
+/// Screenshot chunk handler
+
+use crate::domain::models::DeviceId;
+use crate::domain::services::ScreenshotAssembler;
+use async_trait::async_trait;
+use byteorder::{BigEndian, ReadBytesExt};
+use std::io::Cursor;
+use std::sync::Arc;
+
+use super::super::super::{PacketHandler, Result};
+
+/// Handles SCREENSHOT_CHUNK (0x1D) packets
+/// Sent in sequence in reply to REQUEST_SCREENSHOT
+/// Payload: [total_bytes: u32 BE][offset: u32 BE][data: remaining bytes]
+pub struct ScreenshotChunkHandler {
+    assembler: Arc<ScreenshotAssembler>,
+}
+
+impl ScreenshotChunkHandler {
+    pub fn new(assembler: Arc<ScreenshotAssembler>) -> Self {
+        Self { assembler }
+    }
+}
+
+#[async_trait]
+impl PacketHandler for ScreenshotChunkHandler {
+    fn opcode(&self) -> u8 {
+        crate::infrastructure::protocol::opcodes::SCREENSHOT_CHUNK
+    }
+
+    async fn handle(&self, device_id: DeviceId, payload: Vec<u8>) -> Result<()> {
+        let mut cursor = Cursor::new(payload.as_slice());
+        let total_bytes = cursor.read_u32::<BigEndian>()? as usize;
+        let offset = cursor.read_u32::<BigEndian>()? as usize;
+        let chunk = &payload[cursor.position() as usize..];
+
+        match self.assembler.push_chunk(device_id, total_bytes, offset, chunk) {
+            Ok(received) => {
+                tracing::trace!(
+                    device_id = %device_id,
+                    received,
+                    total_bytes,
+                    "Screenshot chunk received"
+                );
+            }
+            Err(e) => {
+                tracing::warn!(device_id = %device_id, error = %e, "Discarding screenshot");
+            }
+        }
+
+        Ok(())
+    }
+}
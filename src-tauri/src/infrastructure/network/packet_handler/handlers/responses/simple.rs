@@ -2,9 +2,10 @@
 /// These handlers follow a common pattern: read success byte, emit event
 
 use crate::app::EventBus;
-use crate::application::dto::{CommandResultDto, OperationProgressDto};
-use crate::domain::models::DeviceId;
+use crate::application::dto::{CommandResultDto, DeviceStateDto, OperationProgressDto};
+use crate::domain::models::{ApkInstallOutcome, DeviceId};
 use crate::domain::repositories::DeviceRepository;
+use crate::domain::services::{RequestId, ResponseTracker};
 use crate::infrastructure::protocol::opcodes;
 use async_trait::async_trait;
 use byteorder::{BigEndian, ReadBytesExt};
@@ -59,15 +60,6 @@ simple_response_handler!(
     "Failed to launch app"
 );
 
-// Handles APK_INSTALL_RESPONSE (0x14) packets
-simple_response_handler!(
-    ApkInstallResponseHandler,
-    opcodes::APK_INSTALL_RESPONSE,
-    "apk_install",
-    "APK installed successfully",
-    "Failed to install APK"
-);
-
 // Handles UNINSTALL_APP_RESPONSE (0x15) packets
 simple_response_handler!(
     UninstallAppResponseHandler,
@@ -77,28 +69,126 @@ simple_response_handler!(
     "Failed to uninstall app"
 );
 
+// Handles LOCATE_DEVICE_RESPONSE (0x1E) packets
+simple_response_handler!(
+    LocateDeviceResponseHandler,
+    opcodes::LOCATE_DEVICE_RESPONSE,
+    "locate",
+    "Device is identifying itself",
+    "Device could not identify itself"
+);
+
+// Handles SET_TIME_RESPONSE (0x20) packets
+simple_response_handler!(
+    SetTimeResponseHandler,
+    opcodes::SET_TIME_RESPONSE,
+    "set_time",
+    "Clock synced with the server",
+    "Failed to set the device clock"
+);
+
 /// Handles PING_RESPONSE (0x13) packets
+/// Records the round-trip time of the ping this response answers.
 pub struct PingResponseHandler {
+    device_repo: Arc<dyn DeviceRepository>,
     event_bus: Arc<EventBus>,
+    response_tracker: Arc<ResponseTracker>,
 }
 
 impl PingResponseHandler {
-    pub fn new(event_bus: Arc<EventBus>) -> Self {
-        Self { event_bus }
+    pub fn new(
+        device_repo: Arc<dyn DeviceRepository>,
+        event_bus: Arc<EventBus>,
+        response_tracker: Arc<ResponseTracker>,
+    ) -> Self {
+        Self {
+            device_repo,
+            event_bus,
+            response_tracker,
+        }
     }
 }
 
 #[async_trait]
 impl PacketHandler for PingResponseHandler {
     fn opcode(&self) -> u8 {
-        crate::infrastructure::protocol::opcodes::PING_RESPONSE
+        opcodes::PING_RESPONSE
     }
 
-    async fn handle(&self, device_id: DeviceId, _payload: Vec<u8>) -> Result<()> {
-        tracing::debug!(device_id = %device_id, "Ping response received");
+    async fn handle(&self, device_id: DeviceId, payload: Vec<u8>) -> Result<()> {
+        self.handle_response(device_id, payload, None).await
+    }
+
+    async fn handle_response(
+        &self,
+        device_id: DeviceId,
+        _payload: Vec<u8>,
+        request_id: Option<RequestId>,
+    ) -> Result<()> {
+        // Runs before the tracker resolves the request, so it is still pending
+        let rtt = request_id
+            .and_then(|id| self.response_tracker.sent_at(device_id, opcodes::PING_RESPONSE, id))
+            .map(|sent_at| sent_at.elapsed());
+
+        tracing::debug!(device_id = %device_id, rtt_ms = ?rtt.map(|d| d.as_millis()), "Ping response received");
+
+        let message = match rtt {
+            Some(rtt) => {
+                if let Ok(Some(device)) = self.device_repo.find_by_id(device_id).await {
+                    let updated_device = device.as_ref().clone().with_rtt(rtt);
+                    self.device_repo.save(updated_device.clone()).await?;
+                    self.event_bus.device_updated(DeviceStateDto::from(&Arc::new(updated_device)));
+                }
+                format!("Ping successful ({} ms)", rtt.as_millis())
+            }
+            // Unsolicited, nothing to time it against
+            None => "Ping successful".to_string(),
+        };
 
         // Emit event to frontend
-        let result = CommandResultDto::success("ping", "Ping successful");
+        let result = CommandResultDto::success("ping", message);
+        self.event_bus.command_executed(device_id.as_uuid().clone(), result);
+
+        Ok(())
+    }
+}
+
+/// Handles APK_INSTALL_RESPONSE (0x14) packets
+/// Payload: [success: u8][status_code: i32 BE][reason: String], older clients send only the success byte
+pub struct ApkInstallResponseHandler {
+    event_bus: Arc<EventBus>,
+}
+
+impl ApkInstallResponseHandler {
+    pub fn new(event_bus: Arc<EventBus>) -> Self {
+        Self { event_bus }
+    }
+}
+
+#[async_trait]
+impl PacketHandler for ApkInstallResponseHandler {
+    fn opcode(&self) -> u8 {
+        opcodes::APK_INSTALL_RESPONSE
+    }
+
+    async fn handle(&self, device_id: DeviceId, payload: Vec<u8>) -> Result<()> {
+        let outcome = ApkInstallOutcome::decode(&payload).ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "empty APK install response")
+        })?;
+
+        tracing::debug!(
+            device_id = %device_id,
+            success = outcome.is_success(),
+            status_code = ?outcome.status_code(),
+            reason = ?outcome.reason(),
+            "apk_install response"
+        );
+
+        let result = if outcome.is_success() {
+            CommandResultDto::success("apk_install", outcome.message())
+        } else {
+            CommandResultDto::failure("apk_install", outcome.message())
+        };
         self.event_bus.command_executed(device_id.as_uuid().clone(), result);
 
         Ok(())
@@ -0,0 +1,76 @@
+/// WiFi status response handler
+
+use crate::app::EventBus;
+use crate::application::dto::WifiStatusDto;
+use crate::domain::models::{DeviceId, WifiStatus};
+use crate::domain::repositories::DeviceRepository;
+use crate::net::io::ProtocolReadExt;
+use async_trait::async_trait;
+use byteorder::{BigEndian, ReadBytesExt};
+use std::io::Cursor;
+use std::sync::Arc;
+
+use super::super::super::{PacketHandler, Result};
+
+/// Connection state reported by the device
+const WIFI_STATE_CONNECTED: u8 = 1;
+
+/// Handles WIFI_STATUS_RESPONSE (0x1C) packets
+/// Payload: [state: u8] then, only when state is connected (1):
+/// [ssid: String][rssi: i32 BE][link_speed_mbps: u32 BE]
+/// Any other state (0 = no connection, 2 = captive portal) is treated as disconnected.
+pub struct WifiStatusResponseHandler {
+    device_repo: Arc<dyn DeviceRepository>,
+    event_bus: Arc<EventBus>,
+}
+
+impl WifiStatusResponseHandler {
+    pub fn new(device_repo: Arc<dyn DeviceRepository>, event_bus: Arc<EventBus>) -> Self {
+        Self { device_repo, event_bus }
+    }
+
+    fn parse(payload: Vec<u8>) -> Result<WifiStatus> {
+        let mut cursor = Cursor::new(payload);
+
+        if cursor.read_u8()? != WIFI_STATE_CONNECTED {
+            return Ok(WifiStatus::Disconnected);
+        }
+
+        let ssid = cursor.read_string()?;
+        let rssi = cursor.read_i32::<BigEndian>()?;
+        let link_speed_mbps = cursor.read_u32::<BigEndian>()?;
+
+        if ssid.is_empty() {
+            return Ok(WifiStatus::Disconnected);
+        }
+
+        Ok(WifiStatus::Connected {
+            ssid,
+            rssi,
+            link_speed_mbps,
+        })
+    }
+}
+
+#[async_trait]
+impl PacketHandler for WifiStatusResponseHandler {
+    fn opcode(&self) -> u8 {
+        crate::infrastructure::protocol::opcodes::WIFI_STATUS_RESPONSE
+    }
+
+    async fn handle(&self, device_id: DeviceId, payload: Vec<u8>) -> Result<()> {
+        let wifi_status = Self::parse(payload)?;
+
+        tracing::debug!(device_id = %device_id, wifi_status = ?wifi_status, "WiFi status response");
+
+        if let Ok(Some(device)) = self.device_repo.find_by_id(device_id).await {
+            let updated_device = device.as_ref().clone().with_wifi_status(wifi_status.clone());
+            self.device_repo.save(updated_device).await?;
+        }
+
+        self.event_bus
+            .wifi_status_updated(device_id.as_uuid().clone(), WifiStatusDto::from(&wifi_status));
+
+        Ok(())
+    }
+}
@@ -1,12 +1,12 @@
-/// Status update packet handlers (BATTERY_STATUS, VOLUME_STATUS)
+/// Status update packet handlers (BATTERY_STATUS, VOLUME_STATUS, STORAGE_STATUS)
 
 use crate::app::EventBus;
-use crate::application::dto::{BatteryInfoDto, VolumeInfoDto};
-use crate::domain::models::{Battery, DeviceId, Volume};
+use crate::application::dto::{BatteryInfoDto, StorageInfoDto, VolumeInfoDto};
+use crate::domain::models::{Battery, DeviceId, StorageInfo, Volume};
 use crate::domain::repositories::DeviceRepository;
 use crate::infrastructure::protocol::opcodes;
 use async_trait::async_trait;
-use byteorder::ReadBytesExt;
+use byteorder::{BigEndian, ReadBytesExt};
 use std::io::Cursor;
 use std::sync::Arc;
 
@@ -138,3 +138,55 @@ impl PacketHandler for VolumeStatusHandler {
         Ok(())
     }
 }
+
+/// Handles STORAGE_STATUS (0x07) packets
+/// Payload: [total_bytes: u64 BE][free_bytes: u64 BE]
+pub struct StorageStatusHandler {
+    device_repo: Arc<dyn DeviceRepository>,
+    event_bus: Arc<EventBus>,
+}
+
+impl StorageStatusHandler {
+    pub fn new(device_repo: Arc<dyn DeviceRepository>, event_bus: Arc<EventBus>) -> Self {
+        Self {
+            device_repo,
+            event_bus,
+        }
+    }
+}
+
+#[async_trait]
+impl PacketHandler for StorageStatusHandler {
+    fn opcode(&self) -> u8 {
+        opcodes::STORAGE_STATUS
+    }
+
+    async fn handle(&self, device_id: DeviceId, payload: Vec<u8>) -> Result<()> {
+        let mut cursor = Cursor::new(payload);
+
+        let total_bytes = cursor.read_u64::<BigEndian>()?;
+        let free_bytes = cursor.read_u64::<BigEndian>()?;
+
+        tracing::debug!(
+            device_id = %device_id,
+            total_bytes = total_bytes,
+            free_bytes = free_bytes,
+            "Storage status received"
+        );
+
+        // Update device with storage info
+        let storage = StorageInfo::new(total_bytes, free_bytes)
+            .map_err(|e| crate::app::error::ArceusError::DomainValidation(format!("Invalid storage: {}", e)))?;
+
+        if let Ok(Some(device)) = self.device_repo.find_by_id(device_id).await {
+            let updated_device = device.as_ref().clone().with_storage(storage.clone());
+            self.device_repo.save(updated_device).await?;
+        }
+
+        // Emit event
+        self.event_bus
+            .storage_updated(device_id.as_uuid().clone(), StorageInfoDto::from(&storage));
+
+        Ok(())
+    }
+}
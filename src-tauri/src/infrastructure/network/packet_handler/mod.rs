@@ -2,7 +2,8 @@
 /// Handlers update the device repository based on received packets.
 
 use crate::domain::models::DeviceId;
-use crate::infrastructure::protocol::RawPacket;
+use crate::domain::services::{RequestId, ResponseTracker, ScreenshotAssembler};
+use crate::infrastructure::protocol::{opcodes, tagged, RawPacket};
 use async_trait::async_trait;
 use std::sync::Arc;
 
@@ -15,22 +16,41 @@ pub use handlers::*;
 pub trait PacketHandler: Send + Sync {
     fn opcode(&self) -> u8;
     async fn handle(&self, device_id: DeviceId, payload: Vec<u8>) -> Result<()>;
+
+    /// Handle a packet that answers `request_id`, when it could be matched to a request
+    /// Only handlers that care which command a response answers override this.
+    async fn handle_response(
+        &self,
+        device_id: DeviceId,
+        payload: Vec<u8>,
+        _request_id: Option<RequestId>,
+    ) -> Result<()> {
+        self.handle(device_id, payload).await
+    }
 }
 
 pub struct PacketHandlerRegistry {
     handlers: std::collections::HashMap<u8, Arc<dyn PacketHandler>>,
+    response_tracker: Arc<ResponseTracker>,
+    screenshot_assembler: Arc<ScreenshotAssembler>,
 }
 
 impl PacketHandlerRegistry {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         device_repo: Arc<dyn crate::domain::repositories::DeviceRepository>,
         device_name_repo: Arc<dyn crate::domain::repositories::DeviceNameRepository>,
         event_bus: Arc<crate::app::EventBus>,
         session_manager: Arc<crate::infrastructure::network::device_session_manager::DeviceSessionManager>,
+        command_history: Arc<dyn crate::domain::repositories::CommandHistoryRepository>,
         client_apk_service: Arc<crate::application::services::ClientApkService>,
+        response_tracker: Arc<ResponseTracker>,
+        screenshot_assembler: Arc<ScreenshotAssembler>,
     ) -> Self {
         let mut registry = Self {
             handlers: std::collections::HashMap::new(),
+            response_tracker,
+            screenshot_assembler: screenshot_assembler.clone(),
         };
 
         registry.register(Arc::new(VersionCheckHandler::new(
@@ -43,6 +63,7 @@ impl PacketHandlerRegistry {
             device_name_repo.clone(),
             event_bus.clone(),
             session_manager.clone(),
+            command_history,
         )));
         registry.register(Arc::new(HeartbeatHandler::new()));
         registry.register(Arc::new(BatteryStatusHandler::new(
@@ -53,6 +74,10 @@ impl PacketHandlerRegistry {
             device_repo.clone(),
             event_bus.clone(),
         )));
+        registry.register(Arc::new(StorageStatusHandler::new(
+            device_repo.clone(),
+            event_bus.clone(),
+        )));
         registry.register(Arc::new(ForegroundAppChangedHandler::new(
             device_repo.clone(),
             event_bus.clone(),
@@ -61,14 +86,32 @@ impl PacketHandlerRegistry {
         // Response handlers
         registry.register(Arc::new(LaunchAppResponseHandler::new(event_bus.clone())));
         registry.register(Arc::new(ShellExecutionResponseHandler::new(event_bus.clone())));
-        registry.register(Arc::new(InstalledAppsResponseHandler::new(event_bus.clone())));
-        registry.register(Arc::new(PingResponseHandler::new(event_bus.clone())));
+        registry.register(Arc::new(InstalledAppsResponseHandler::new(
+            device_repo.clone(),
+            event_bus.clone(),
+        )));
+        registry.register(Arc::new(PingResponseHandler::new(
+            device_repo.clone(),
+            event_bus.clone(),
+            registry.response_tracker.clone(),
+        )));
+        registry.register(Arc::new(LocateDeviceResponseHandler::new(event_bus.clone())));
+        registry.register(Arc::new(SetTimeResponseHandler::new(event_bus.clone())));
         registry.register(Arc::new(ApkInstallResponseHandler::new(event_bus.clone())));
         registry.register(Arc::new(UninstallAppResponseHandler::new(event_bus.clone())));
         registry.register(Arc::new(VolumeSetResponseHandler::new(
             device_repo.clone(),
             event_bus.clone(),
         )));
+        registry.register(Arc::new(BrightnessResponseHandler::new(
+            device_repo.clone(),
+            event_bus.clone(),
+        )));
+        registry.register(Arc::new(WifiStatusResponseHandler::new(
+            device_repo.clone(),
+            event_bus.clone(),
+        )));
+        registry.register(Arc::new(ScreenshotChunkHandler::new(screenshot_assembler)));
         registry.register(Arc::new(ApkDownloadStartedHandler::new(event_bus.clone())));
         registry.register(Arc::new(ApkDownloadProgressHandler::new(event_bus.clone(), device_repo.clone())));
         registry.register(Arc::new(ApkInstallProgressHandler::new(event_bus.clone(), device_repo.clone())));
@@ -84,11 +127,39 @@ impl PacketHandlerRegistry {
     }
 
     /// Handle a received packet
+    /// Also resolves any command awaiting this packet as its response, after the
+    /// handler has run so the caller observes the updated device state.
+    /// Tagged responses are unwrapped and resolve the request with their id.
     pub async fn handle(&self, device_id: DeviceId, packet: RawPacket) -> Result<()> {
+        if packet.opcode == opcodes::TAGGED_RESPONSE {
+            let (request_id, inner) = tagged::untag_response(&packet.payload)?;
+            let result = self.dispatch(device_id, &inner, Some(request_id)).await;
+
+            if !self
+                .response_tracker
+                .complete_request(device_id, inner.opcode, request_id, inner.payload)
+            {
+                tracing::debug!(device_id = %device_id, request_id, "Tagged response matched no pending request");
+            }
+            return result;
+        }
+
+        // Untagged responses go to the oldest pending request, as `complete` below does
+        let request_id = self.response_tracker.next_request_id(device_id, packet.opcode);
+        let result = self.dispatch(device_id, &packet, request_id).await;
+
+        self.response_tracker
+            .complete(device_id, packet.opcode, packet.payload);
+
+        result
+    }
+
+    async fn dispatch(&self, device_id: DeviceId, packet: &RawPacket, request_id: Option<RequestId>) -> Result<()> {
         match self.handlers.get(&packet.opcode) {
             Some(handler) => {
-                handler.handle(device_id, packet.payload).await?;
-                Ok(())
+                handler
+                    .handle_response(device_id, packet.payload.clone(), request_id)
+                    .await
             }
             None => {
                 tracing::debug!(
@@ -100,4 +171,10 @@ impl PacketHandlerRegistry {
             }
         }
     }
+
+    /// Drop pending command responses for a device that disconnected
+    pub fn device_disconnected(&self, device_id: &DeviceId) {
+        self.response_tracker.clear_device(device_id);
+        self.screenshot_assembler.cancel(device_id);
+    }
 }
@@ -2,61 +2,109 @@
 /// Accepts incoming TCP connections and delegates to ConnectionHandler.
 /// Focuses solely on TCP transport concerns.
 
+use crate::app::models::ServerStats;
 use crate::app::{error::NetworkError, EventBus, Result, ServerConfig};
-use crate::domain::repositories::{DeviceNameRepository, DeviceRepository};
-use crate::infrastructure::network::connection_handler::ConnectionHandler;
-use crate::infrastructure::network::device_session_manager::DeviceSessionManager;
+use crate::domain::models::IpAddress;
+use crate::domain::repositories::{CommandHistoryRepository, DeviceNameRepository, DeviceRepository};
+use crate::domain::services::{ResponseTracker, ScreenshotAssembler};
+use crate::infrastructure::network::connection_handler::{ConnectionHandler, ErrorBudget};
+use crate::infrastructure::network::device_session_manager::{
+    ConnectionPermit, DeviceSessionManager,
+};
 use crate::infrastructure::network::packet_handler::PacketHandlerRegistry;
+use crate::infrastructure::protocol::{opcodes, RawPacket, RawPacketCodec};
+use crate::net::io::ProtocolWriteExt;
+use futures::SinkExt;
 use socket2::{Domain, Protocol, Socket, Type};
 use std::net::SocketAddr;
+use std::path::Path;
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::net::TcpListener;
+use std::time::{Duration, Instant};
+use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::{broadcast, RwLock};
+use tokio_util::codec::Framed;
+
+/// Reason sent to clients rejected because the server is at capacity
+const SERVER_FULL_REASON: &str = "Server full";
+
+/// How long to wait for a rejection packet to be written before dropping the socket
+const REJECTION_WRITE_TIMEOUT: Duration = Duration::from_secs(2);
 
 pub struct TcpServer {
     config: ServerConfig,
     connection_handler: Arc<ConnectionHandler>,
     device_repo: Arc<dyn DeviceRepository>,
+    session_manager: Arc<DeviceSessionManager>,
+    command_history: Arc<dyn CommandHistoryRepository>,
     event_bus: Arc<EventBus>,
+    /// Consulted when draining, to let in-flight commands finish
+    response_tracker: Arc<ResponseTracker>,
     running: Arc<RwLock<bool>>,
+    /// When the listener was bound, `None` while stopped
+    started_at: parking_lot::RwLock<Option<Instant>>,
     shutdown_tx: broadcast::Sender<()>,
 }
 
 impl TcpServer {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         config: ServerConfig,
         device_repo: Arc<dyn DeviceRepository>,
         device_name_repo: Arc<dyn DeviceNameRepository>,
+        command_history: Arc<dyn CommandHistoryRepository>,
         event_bus: Arc<EventBus>,
         client_apk_service: Arc<crate::application::services::ClientApkService>,
+        response_tracker: Arc<ResponseTracker>,
+        screenshot_assembler: Arc<ScreenshotAssembler>,
     ) -> (Self, broadcast::Receiver<()>, Arc<DeviceSessionManager>) {
         let (shutdown_tx, shutdown_rx) = broadcast::channel(1);
 
         let session_manager = Arc::new(DeviceSessionManager::new());
 
+        let packet_log = session_manager.packet_log();
+        packet_log.set_enabled(config.packet_trace);
+        if let Some(path) = &config.packet_capture_path {
+            if let Err(e) = packet_log.set_capture_file(Some(path)) {
+                tracing::warn!(path = ?path, error = %e, "Failed to open packet capture file");
+            }
+        }
+
         let packet_handler = Arc::new(PacketHandlerRegistry::new(
             device_repo.clone(),
             device_name_repo.clone(),
             event_bus.clone(),
             session_manager.clone(),
+            command_history.clone(),
             client_apk_service,
+            response_tracker.clone(),
+            screenshot_assembler,
         ));
 
         let connection_handler = Arc::new(ConnectionHandler::new(
             device_repo.clone(),
+            device_name_repo,
             event_bus.clone(),
             packet_handler,
             session_manager.clone(),
+            command_history.clone(),
             Duration::from_secs(config.heartbeat_timeout),
+            ErrorBudget::new(
+                config.malformed_packet_limit,
+                Duration::from_secs(config.malformed_packet_window),
+            ),
+            config.max_packet_payload,
         ));
 
         let server = Self {
             config,
             connection_handler,
             device_repo,
+            session_manager: session_manager.clone(),
+            command_history,
             event_bus: event_bus.clone(),
+            response_tracker,
             running: Arc::new(RwLock::new(false)),
+            started_at: parking_lot::RwLock::new(None),
             shutdown_tx,
         };
 
@@ -64,8 +112,14 @@ impl TcpServer {
     }
 
     fn bind_listener(addr: SocketAddr) -> std::result::Result<TcpListener, NetworkError> {
-        let socket = Socket::new(Domain::IPV4, Type::STREAM, Some(Protocol::TCP))
+        let socket = Socket::new(Domain::for_address(addr), Type::STREAM, Some(Protocol::TCP))
             .map_err(|e| NetworkError::BindError(format!("{}", e)))?;
+        // "::" accepts IPv4 clients too, as IPv4-mapped addresses
+        if addr.is_ipv6() && addr.ip().is_unspecified() {
+            socket
+                .set_only_v6(false)
+                .map_err(|e| NetworkError::BindError(format!("{}", e)))?;
+        }
         socket
             .set_reuse_address(true)
             .map_err(|e| NetworkError::BindError(format!("{}", e)))?;
@@ -84,9 +138,12 @@ impl TcpServer {
     }
 
     pub async fn start(self: Arc<Self>) -> Result<()> {
-        let addr: SocketAddr = format!("{}:{}", self.config.tcp_host, self.config.tcp_port)
-            .parse()
-            .map_err(|e| NetworkError::BindError(format!("{}", e)))?;
+        let addr = self
+            .config
+            .tcp_host
+            .parse::<IpAddress>()
+            .map_err(|e| NetworkError::BindError(format!("{}", e)))?
+            .socket_addr(self.config.tcp_port);
 
         let listener = Self::bind_listener(addr)?;
 
@@ -97,6 +154,9 @@ impl TcpServer {
         );
 
         *self.running.write().await = true;
+        // Counters describe the current run only
+        *self.started_at.write() = Some(Instant::now());
+        self.session_manager.reset_commands_sent();
         self.event_bus
             .server_started(self.config.tcp_port, self.config.http_port);
 
@@ -107,20 +167,22 @@ impl TcpServer {
                 accept_result = listener.accept() => {
                     match accept_result {
                         Ok((stream, addr)) => {
-                            if !self.check_capacity() {
-                                let current_count = self.device_repo.count().unwrap_or(0);
-                                tracing::warn!(
-                                    addr = %addr,
-                                    current = current_count,
-                                    max = self.config.max_connections,
-                                    "Connection limit reached, rejecting connection"
+                            let Some((stream, permit)) = admit_connection(
+                                &self.session_manager,
+                                self.config.max_connections,
+                                stream,
+                                addr,
+                            ) else {
+                                self.event_bus.connection_rejected(
+                                    addr.ip().to_string(),
+                                    SERVER_FULL_REASON.to_string(),
                                 );
-                                drop(stream);
                                 continue;
-                            }
+                            };
 
                             let handler = Arc::clone(&self.connection_handler);
                             tokio::spawn(async move {
+                                let _permit = permit;
                                 if let Err(e) = handler.handle_connection(stream, addr).await {
                                     tracing::error!(
                                         addr = %addr,
@@ -144,18 +206,174 @@ impl TcpServer {
         }
 
         *self.running.write().await = false;
+        *self.started_at.write() = None;
         tracing::info!("TCP server stopped");
         self.event_bus.server_stopped();
         Ok(())
     }
 
-    fn check_capacity(&self) -> bool {
-        let current_count = self.device_repo.count().unwrap_or(0);
-        current_count < self.config.max_connections
+    /// Start the heartbeat sweeper that disconnects stale devices
+    pub fn spawn_heartbeat_sweeper(&self) -> tauri::async_runtime::JoinHandle<()> {
+        self.session_manager.spawn_heartbeat_sweeper(
+            self.device_repo.clone(),
+            self.command_history.clone(),
+            self.event_bus.clone(),
+            Duration::from_secs(self.config.heartbeat_timeout),
+        )
+    }
+
+    /// Current connection, uptime and command counters
+    pub fn stats(&self) -> ServerStats {
+        ServerStats {
+            connected_devices: self.session_manager.session_count(),
+            max_connections: self.config.max_connections,
+            uptime_secs: self.started_at.read().map(|started| started.elapsed().as_secs()),
+            commands_executed: self.session_manager.commands_sent(),
+        }
+    }
+
+    /// Turn packet tracing on or off for all connections, including open ones
+    /// Enabling with a path appends packets to that file; disabling closes it.
+    pub fn set_packet_trace(&self, enabled: bool, capture_path: Option<&Path>) -> std::io::Result<()> {
+        let packet_log = self.session_manager.packet_log();
+        packet_log.set_capture_file(capture_path.filter(|_| enabled))?;
+        packet_log.set_enabled(enabled);
+        tracing::info!(enabled, capture_path = ?capture_path, "Packet trace toggled");
+        Ok(())
     }
 
     /// Shutdown the server
     pub fn shutdown(&self) {
         let _ = self.shutdown_tx.send(());
     }
+
+    /// Stop accepting connections, then close existing ones once in-flight
+    /// commands finish or the configured grace period runs out
+    pub async fn drain(&self) {
+        self.shutdown();
+        self.session_manager
+            .drain(
+                &self.response_tracker,
+                Duration::from_secs(self.config.shutdown_grace_period),
+            )
+            .await;
+    }
+}
+
+/// Reserve a connection slot for an accepted socket
+/// When the server is full the socket is handed to a background task that sends
+/// CONNECTION_REJECTED and closes it, and `None` is returned.
+fn admit_connection(
+    session_manager: &DeviceSessionManager,
+    max_connections: usize,
+    stream: TcpStream,
+    addr: SocketAddr,
+) -> Option<(TcpStream, ConnectionPermit)> {
+    match session_manager.try_acquire_connection(max_connections) {
+        Some(permit) => Some((stream, permit)),
+        None => {
+            tracing::warn!(
+                addr = %addr,
+                current = session_manager.connection_count(),
+                max = max_connections,
+                "Connection limit reached, rejecting connection"
+            );
+            tokio::spawn(reject_connection(stream, SERVER_FULL_REASON));
+            None
+        }
+    }
+}
+
+/// Send a CONNECTION_REJECTED packet carrying `reason`, then close the socket
+/// Payload: [reason: String]
+async fn reject_connection(stream: TcpStream, reason: &'static str) {
+    let mut payload = Vec::new();
+    if payload.write_string(reason).is_err() {
+        return;
+    }
+
+    let mut framed = Framed::new(stream, RawPacketCodec::default());
+    let send = async {
+        framed
+            .send(RawPacket {
+                opcode: opcodes::CONNECTION_REJECTED,
+                payload,
+            })
+            .await?;
+        framed.close().await
+    };
+
+    match tokio::time::timeout(REJECTION_WRITE_TIMEOUT, send).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => tracing::debug!(error = %e, "Failed to send connection rejection"),
+        Err(_) => tracing::debug!("Timed out sending connection rejection"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    /// Connect a client and run the accepted socket through admission
+    async fn connect(
+        listener: &TcpListener,
+        session_manager: &DeviceSessionManager,
+        max_connections: usize,
+    ) -> (TcpStream, Option<(TcpStream, ConnectionPermit)>) {
+        let client = TcpStream::connect(listener.local_addr().unwrap()).await.unwrap();
+        let (stream, addr) = listener.accept().await.unwrap();
+        (client, admit_connection(session_manager, max_connections, stream, addr))
+    }
+
+    /// Assert the client was sent CONNECTION_REJECTED and then disconnected
+    async fn assert_rejected(client: TcpStream) {
+        let mut framed = Framed::new(client, RawPacketCodec::default());
+        let packet = framed.next().await.unwrap().unwrap();
+        assert_eq!(packet.opcode, opcodes::CONNECTION_REJECTED);
+        assert_eq!(&packet.payload[4..], SERVER_FULL_REASON.as_bytes());
+        assert!(framed.next().await.is_none(), "rejected socket should be closed");
+    }
+
+    #[tokio::test]
+    async fn rejects_connections_over_the_limit() {
+        let session_manager = DeviceSessionManager::new();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+
+        let (first_client, first) = connect(&listener, &session_manager, 1).await;
+        assert!(first.is_some());
+        assert_eq!(session_manager.connection_count(), 1);
+
+        let (second_client, second) = connect(&listener, &session_manager, 1).await;
+        assert!(second.is_none());
+        assert_rejected(second_client).await;
+
+        // Releasing the first connection frees its slot
+        drop(first);
+        drop(first_client);
+        assert_eq!(session_manager.connection_count(), 0);
+        assert!(session_manager.try_acquire_connection(1).is_some());
+    }
+
+    #[tokio::test]
+    async fn refuses_every_connection_past_the_limit() {
+        let session_manager = DeviceSessionManager::new();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let max_connections = 3;
+
+        let mut admitted = Vec::new();
+        for _ in 0..max_connections {
+            let (client, admission) = connect(&listener, &session_manager, max_connections).await;
+            assert!(admission.is_some());
+            admitted.push((client, admission));
+        }
+
+        for _ in 0..2 {
+            let (client, admission) = connect(&listener, &session_manager, max_connections).await;
+            assert!(admission.is_none());
+            assert_rejected(client).await;
+        }
+
+        assert_eq!(session_manager.connection_count(), max_connections);
+    }
 }
@@ -1,6 +1,9 @@
 /// Network protocol definitions
-/// Opcodes and binary codec for device communication
+/// Opcodes, binary codec and packet tracing for device communication
 pub mod opcodes;
+mod packet_log;
 mod raw_codec;
+pub mod tagged;
 
-pub use raw_codec::{RawPacket, RawPacketCodec};
+pub use packet_log::PacketLog;
+pub use raw_codec::{RawPacket, RawPacketCodec, MAX_PAYLOAD_LEN};
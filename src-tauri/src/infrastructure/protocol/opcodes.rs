@@ -12,9 +12,10 @@ pub const BATTERY_STATUS: u8 = 0x03;
 pub const VOLUME_STATUS: u8 = 0x04;
 pub const VERSION_CHECK: u8 = 0x05;
 pub const FOREGROUND_APP_CHANGED: u8 = 0x06;
+pub const STORAGE_STATUS: u8 = 0x07;
 
 // =============================================================================
-// CLIENT → SERVER (Responses to server commands) - 0x10-0x18
+// CLIENT → SERVER (Responses to server commands) - 0x10-0x20
 // =============================================================================
 
 pub const LAUNCH_APP_RESPONSE: u8 = 0x10;
@@ -28,9 +29,17 @@ pub const APK_DOWNLOAD_STARTED: u8 = 0x17;
 pub const CLOSE_ALL_APPS_RESPONSE: u8 = 0x18;
 pub const APK_DOWNLOAD_PROGRESS: u8 = 0x19;
 pub const APK_INSTALL_PROGRESS: u8 = 0x1A;
+pub const BRIGHTNESS_RESPONSE: u8 = 0x1B;
+pub const WIFI_STATUS_RESPONSE: u8 = 0x1C;
+pub const SCREENSHOT_CHUNK: u8 = 0x1D;
+pub const LOCATE_DEVICE_RESPONSE: u8 = 0x1E;
+/// Response wrapped with the request id it answers, see `protocol::tagged`
+pub const TAGGED_RESPONSE: u8 = 0x1F;
+/// Payload: [success: u8]
+pub const SET_TIME_RESPONSE: u8 = 0x20;
 
 // =============================================================================
-// SERVER → CLIENT (Commands from server) - 0x40-0x50
+// SERVER → CLIENT (Commands from server) - 0x40-0x59
 // =============================================================================
 
 pub const LAUNCH_APP: u8 = 0x40;
@@ -47,4 +56,17 @@ pub const GET_VOLUME: u8 = 0x4B;
 pub const CLOSE_ALL_APPS: u8 = 0x4C;
 pub const CONFIGURE_DEVICE: u8 = 0x4D;
 pub const CLEAR_WIFI_CREDENTIALS: u8 = 0x4E;
+pub const REQUEST_STORAGE: u8 = 0x4F;
 pub const DISPLAY_MESSAGE: u8 = 0x50;
+pub const SET_BRIGHTNESS: u8 = 0x51;
+pub const GET_BRIGHTNESS: u8 = 0x52;
+pub const CONNECTION_REJECTED: u8 = 0x53;
+pub const GET_WIFI_STATUS: u8 = 0x54;
+pub const REQUEST_SCREENSHOT: u8 = 0x55;
+pub const LOCATE_DEVICE: u8 = 0x56;
+/// Sent to every device before a graceful shutdown. Payload: [reason: String]
+pub const SERVER_SHUTTING_DOWN: u8 = 0x57;
+/// Command wrapped with a request id the client echoes back, see `protocol::tagged`
+pub const TAGGED_COMMAND: u8 = 0x58;
+/// Set the device clock, see `SetTimeCommand` for the payload
+pub const SET_TIME: u8 = 0x59;
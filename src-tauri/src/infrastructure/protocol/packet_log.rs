@@ -0,0 +1,191 @@
+/// Packet trace log
+/// Sees every raw packet passing through a traced `RawPacketCodec`. When enabled
+/// for all connections it logs them at debug level and, when a capture file is
+/// set, appends one line per packet:
+/// `<unix millis> <peer> <in|out> <opcode> <length> <hex payload>`
+/// Independently, a single device can be traced by attaching a hook to it.
+/// While nothing is traced a packet costs two atomic loads; nothing is formatted.
+
+use super::RawPacket;
+use crate::domain::models::DeviceId;
+use crate::domain::services::{PacketDirection, PacketTraceHook};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Payload bytes shown in the debug log; capture files get the whole payload
+const MAX_LOGGED_PAYLOAD_BYTES: usize = 64;
+
+#[derive(Default)]
+pub struct PacketLog {
+    enabled: AtomicBool,
+    capture: parking_lot::Mutex<Option<Arc<File>>>,
+    /// Per-device trace hooks, with their count kept alongside for the fast path
+    hooks: parking_lot::RwLock<HashMap<DeviceId, PacketTraceHook>>,
+    hook_count: AtomicUsize,
+}
+
+impl PacketLog {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Start appending packets to `path`, or with `None` stop capturing
+    pub fn set_capture_file(&self, path: Option<&Path>) -> std::io::Result<()> {
+        let file = match path {
+            Some(path) => Some(Arc::new(
+                OpenOptions::new().create(true).append(true).open(path)?,
+            )),
+            None => None,
+        };
+        *self.capture.lock() = file;
+        Ok(())
+    }
+
+    /// Attach or, with `None`, detach the trace hook of one device
+    pub fn set_hook(&self, device_id: DeviceId, hook: Option<PacketTraceHook>) {
+        let mut hooks = self.hooks.write();
+        match hook {
+            Some(hook) => hooks.insert(device_id, hook),
+            None => hooks.remove(&device_id),
+        };
+        self.hook_count.store(hooks.len(), Ordering::Relaxed);
+    }
+
+    pub fn record(
+        &self,
+        device_id: DeviceId,
+        peer: SocketAddr,
+        direction: PacketDirection,
+        packet: &RawPacket,
+    ) {
+        if self.hook_count.load(Ordering::Relaxed) > 0 {
+            // Clone the hook out so it never runs under the lock
+            let hook = self.hooks.read().get(&device_id).cloned();
+            if let Some(hook) = hook {
+                hook(direction, packet);
+            }
+        }
+
+        if self.is_enabled() {
+            self.log(peer, direction, packet.opcode, &packet.payload);
+        }
+    }
+
+    fn log(&self, peer: SocketAddr, direction: PacketDirection, opcode: u8, payload: &[u8]) {
+
+        let shown = &payload[..payload.len().min(MAX_LOGGED_PAYLOAD_BYTES)];
+        tracing::debug!(
+            peer = %peer,
+            direction = ?direction,
+            opcode = format_args!("0x{:02X}", opcode),
+            len = payload.len(),
+            truncated = shown.len() < payload.len(),
+            "Packet {}",
+            hex(shown)
+        );
+
+        let Some(file) = self.capture.lock().clone() else {
+            return;
+        };
+
+        let direction = match direction {
+            PacketDirection::Inbound => "in",
+            PacketDirection::Outbound => "out",
+        };
+        let line = format!(
+            "{} {} {} {:02X} {} {}\n",
+            chrono::Utc::now().timestamp_millis(),
+            peer,
+            direction,
+            opcode,
+            payload.len(),
+            hex(payload)
+        );
+
+        // Written outside the lock; append mode keeps each line in one piece.
+        // A broken capture file shouldn't take the connection down with it.
+        if let Err(e) = (&*file).write_all(line.as_bytes()) {
+            tracing::warn!(error = %e, "Failed to write packet capture, capture stopped");
+            let mut capture = self.capture.lock();
+            if capture.as_ref().is_some_and(|current| Arc::ptr_eq(current, &file)) {
+                *capture = None;
+            }
+        }
+    }
+}
+
+pub(super) fn hex(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        let _ = write!(out, "{:02x}", byte);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packet(opcode: u8, payload: &[u8]) -> RawPacket {
+        RawPacket {
+            opcode,
+            payload: payload.to_vec(),
+        }
+    }
+
+    #[test]
+    fn capture_file_gets_one_line_per_packet() {
+        let path = std::env::temp_dir().join(format!("arceus-packets-{}.log", uuid::Uuid::new_v4()));
+        let peer: SocketAddr = "10.0.0.7:5000".parse().unwrap();
+        let device_id = DeviceId::new();
+
+        let log = PacketLog::default();
+        log.set_enabled(true);
+        log.set_capture_file(Some(&path)).unwrap();
+        log.record(device_id, peer, PacketDirection::Outbound, &packet(0x12, &[0xde, 0xad]));
+
+        log.set_enabled(false);
+        log.record(device_id, peer, PacketDirection::Inbound, &packet(0x13, &[0x01]));
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].ends_with(" 10.0.0.7:5000 out 12 2 dead"), "{}", lines[0]);
+    }
+
+    #[test]
+    fn hooks_see_only_their_own_device_until_detached() {
+        let peer: SocketAddr = "10.0.0.7:5000".parse().unwrap();
+        let traced = DeviceId::new();
+        let other = DeviceId::new();
+
+        let seen = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let log = PacketLog::default();
+        log.set_hook(
+            traced,
+            Some(Arc::new(move |direction, packet: &RawPacket| {
+                sink.lock().push((direction, packet.opcode));
+            })),
+        );
+
+        log.record(traced, peer, PacketDirection::Inbound, &packet(0x01, &[]));
+        log.record(other, peer, PacketDirection::Inbound, &packet(0x02, &[]));
+        log.set_hook(traced, None);
+        log.record(traced, peer, PacketDirection::Outbound, &packet(0x03, &[]));
+
+        assert_eq!(*seen.lock(), [(PacketDirection::Inbound, 0x01)]);
+    }
+}
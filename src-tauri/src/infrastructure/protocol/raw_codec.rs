@@ -1,5 +1,11 @@
-use crate::app::error::{ArceusError, Result};
+use super::packet_log::hex;
+use super::PacketLog;
+use crate::app::error::{ArceusError, ProtocolError, Result};
+use crate::domain::models::DeviceId;
+use crate::domain::services::PacketDirection;
 use bytes::{Buf, BufMut, BytesMut};
+use std::net::SocketAddr;
+use std::sync::Arc;
 use tokio_util::codec::{Decoder, Encoder};
 
 /// Raw packet structure: [opcode: u8][length: u16 BE][payload: varies]
@@ -9,8 +15,51 @@ pub struct RawPacket {
     pub payload: Vec<u8>,
 }
 
+/// Largest payload the u16 length prefix can describe
+pub const MAX_PAYLOAD_LEN: usize = u16::MAX as usize;
+
+/// Bytes of a rejected frame included in the warning log
+const LOGGED_FRAME_BYTES: usize = 16;
+
 /// Simple codec for reading/writing raw packets
-pub struct RawPacketCodec;
+/// A traced codec hands every packet to a `PacketLog`, which drops it unless it is
+/// logging everything or the device is being traced.
+/// A length prefix above `max_payload` is a protocol violation: the stream can't be
+/// resynchronised after it, so decoding fails and the connection should be closed.
+pub struct RawPacketCodec {
+    trace: Option<(Arc<PacketLog>, DeviceId, SocketAddr)>,
+    max_payload: usize,
+}
+
+impl Default for RawPacketCodec {
+    fn default() -> Self {
+        Self {
+            trace: None,
+            max_payload: MAX_PAYLOAD_LEN,
+        }
+    }
+}
+
+impl RawPacketCodec {
+    pub fn traced(log: Arc<PacketLog>, device_id: DeviceId, peer: SocketAddr) -> Self {
+        Self {
+            trace: Some((log, device_id, peer)),
+            ..Self::default()
+        }
+    }
+
+    /// Reject inbound packets whose payload is longer than `max_payload` bytes
+    pub fn with_max_payload(mut self, max_payload: usize) -> Self {
+        self.max_payload = max_payload.min(MAX_PAYLOAD_LEN);
+        self
+    }
+
+    fn trace(&self, direction: PacketDirection, packet: &RawPacket) {
+        if let Some((log, device_id, peer)) = &self.trace {
+            log.record(*device_id, *peer, direction, packet);
+        }
+    }
+}
 
 impl Decoder for RawPacketCodec {
     type Item = RawPacket;
@@ -25,6 +74,23 @@ impl Decoder for RawPacketCodec {
         let opcode = src[0];
         let length = u16::from_be_bytes([src[1], src[2]]) as usize;
 
+        if length > self.max_payload {
+            let shown = &src[..src.len().min(LOGGED_FRAME_BYTES)];
+            tracing::warn!(
+                peer = ?self.trace.as_ref().map(|(_, _, peer)| *peer),
+                opcode,
+                length,
+                max = self.max_payload,
+                bytes = %hex(shown),
+                "Rejecting packet with implausible length"
+            );
+            return Err(ProtocolError::MalformedPacket(format!(
+                "payload length {} exceeds the maximum of {} bytes",
+                length, self.max_payload
+            ))
+            .into());
+        }
+
         // Check if we have the full packet
         let total_needed = 3 + length;
         if src.len() < total_needed {
@@ -35,8 +101,10 @@ impl Decoder for RawPacketCodec {
         // Extract the packet
         src.advance(3); // Skip opcode + length
         let payload = src.split_to(length).to_vec();
+        let packet = RawPacket { opcode, payload };
+        self.trace(PacketDirection::Inbound, &packet);
 
-        Ok(Some(RawPacket { opcode, payload }))
+        Ok(Some(packet))
     }
 }
 
@@ -44,13 +112,156 @@ impl Encoder<RawPacket> for RawPacketCodec {
     type Error = ArceusError;
 
     fn encode(&mut self, item: RawPacket, dst: &mut BytesMut) -> Result<()> {
-        let length = item.payload.len() as u16;
+        // Truncating the length prefix would desync the device's decoder
+        let length = u16::try_from(item.payload.len()).map_err(|_| {
+            ProtocolError::MalformedPacket(format!(
+                "payload of {} bytes is too large to send",
+                item.payload.len()
+            ))
+        })?;
         dst.reserve(3 + item.payload.len());
 
         dst.put_u8(item.opcode);
         dst.put_u16(length);
         dst.put_slice(&item.payload);
+        self.trace(PacketDirection::Outbound, &item);
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Small deterministic PRNG so the fuzz tests need no extra dependency
+    struct XorShift(u64);
+
+    impl XorShift {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            (self.next() % n as u64) as usize
+        }
+    }
+
+    fn encode(codec: &mut RawPacketCodec, opcode: u8, payload: Vec<u8>) -> BytesMut {
+        let mut buf = BytesMut::new();
+        codec.encode(RawPacket { opcode, payload }, &mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn round_trips_packets_split_at_any_point() {
+        let mut codec = RawPacketCodec::default();
+        let frame = encode(&mut codec, 0x12, b"com.example.game".to_vec());
+
+        for split in 0..frame.len() {
+            let mut buf = BytesMut::from(&frame[..split]);
+            assert!(codec.decode(&mut buf).unwrap().is_none());
+
+            buf.extend_from_slice(&frame[split..]);
+            let packet = codec.decode(&mut buf).unwrap().unwrap();
+            assert_eq!(packet.opcode, 0x12);
+            assert_eq!(packet.payload, b"com.example.game");
+            assert!(buf.is_empty());
+        }
+    }
+
+    #[test]
+    fn rejects_lengths_above_the_limit() {
+        let mut codec = RawPacketCodec::default().with_max_payload(8);
+
+        let mut buf = BytesMut::from(&[0x12, 0x00, 0x09][..]);
+        let err = codec.decode(&mut buf).unwrap_err();
+        assert!(err.to_string().contains("exceeds the maximum"), "{}", err);
+
+        let mut buf = BytesMut::from(&[0x12, 0x00, 0x08, 1, 2, 3, 4, 5, 6, 7, 8][..]);
+        assert_eq!(codec.decode(&mut buf).unwrap().unwrap().payload.len(), 8);
+    }
+
+    #[test]
+    fn default_limit_accepts_any_length_the_prefix_allows() {
+        // Large shell output and app lists are legitimate; garbage is left to the error budget
+        let mut codec = RawPacketCodec::default();
+
+        let mut buf = BytesMut::from(&[0x1D, 0xFF, 0xFF][..]);
+        assert!(codec.decode(&mut buf).unwrap().is_none());
+    }
+
+    #[test]
+    fn refuses_to_encode_oversized_payloads() {
+        let mut buf = BytesMut::new();
+        let packet = RawPacket {
+            opcode: 0x41,
+            payload: vec![0; MAX_PAYLOAD_LEN + 1],
+        };
+        assert!(RawPacketCodec::default().encode(packet, &mut buf).is_err());
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn random_bytes_never_panic_or_exceed_the_limit() {
+        let mut rng = XorShift(0x9E37_79B9_7F4A_7C15);
+
+        for _ in 0..2_000 {
+            let max_payload = rng.below(512);
+            let mut codec = RawPacketCodec::default().with_max_payload(max_payload);
+            let input: Vec<u8> = (0..rng.below(2048)).map(|_| rng.next() as u8).collect();
+
+            // Feed the bytes in random-sized pieces, as a socket would
+            let mut buf = BytesMut::new();
+            let mut fed = 0;
+            'stream: while fed < input.len() {
+                let piece = 1 + rng.below(64);
+                let end = (fed + piece).min(input.len());
+                buf.extend_from_slice(&input[fed..end]);
+                fed = end;
+
+                loop {
+                    match codec.decode(&mut buf) {
+                        Ok(Some(packet)) => assert!(packet.payload.len() <= max_payload),
+                        Ok(None) => break,
+                        // Framed stops reading after the first error
+                        Err(_) => break 'stream,
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn random_valid_streams_decode_exactly() {
+        let mut rng = XorShift(42);
+        let mut codec = RawPacketCodec::default();
+
+        let packets: Vec<(u8, Vec<u8>)> = (0..200)
+            .map(|_| {
+                let payload = (0..rng.below(300)).map(|_| rng.next() as u8).collect();
+                (rng.next() as u8, payload)
+            })
+            .collect();
+
+        let mut stream = BytesMut::new();
+        for (opcode, payload) in &packets {
+            stream.extend_from_slice(&encode(&mut codec, *opcode, payload.clone()));
+        }
+
+        let mut buf = BytesMut::new();
+        let mut decoded = Vec::new();
+        while !stream.is_empty() {
+            let piece = (1 + rng.below(97)).min(stream.len());
+            buf.extend_from_slice(&stream.split_to(piece));
+            while let Some(packet) = codec.decode(&mut buf).unwrap() {
+                decoded.push((packet.opcode, packet.payload));
+            }
+        }
+
+        assert_eq!(decoded, packets);
+    }
+}
@@ -0,0 +1,62 @@
+/// Tagged packets
+/// Clients that advertise `CAP_REQUEST_IDS` in DEVICE_CONNECTED get commands
+/// that expect a reply wrapped in TAGGED_COMMAND, and wrap their replies in
+/// TAGGED_RESPONSE. Both carry the request id so replies to concurrent commands
+/// of the same kind can't cross:
+/// `[request_id: u64 BE][opcode: u8][payload]`
+/// Older clients keep getting plain packets, matched in FIFO order.
+
+use super::{opcodes, RawPacket};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::io::{Cursor, Read};
+
+/// DEVICE_CONNECTED capability bit: the client echoes request ids
+pub const CAP_REQUEST_IDS: u8 = 0x01;
+
+/// Wrap a command in a TAGGED_COMMAND packet carrying `request_id`
+pub fn tag_command(request_id: u64, packet: RawPacket) -> RawPacket {
+    let mut payload = Vec::with_capacity(9 + packet.payload.len());
+    payload.write_u64::<BigEndian>(request_id).expect("writing to a Vec can't fail");
+    payload.push(packet.opcode);
+    payload.extend_from_slice(&packet.payload);
+
+    RawPacket {
+        opcode: opcodes::TAGGED_COMMAND,
+        payload,
+    }
+}
+
+/// Unwrap a TAGGED_RESPONSE payload into the request id and the response it carries
+pub fn untag_response(payload: &[u8]) -> std::io::Result<(u64, RawPacket)> {
+    let mut cursor = Cursor::new(payload);
+    let request_id = cursor.read_u64::<BigEndian>()?;
+    let opcode = cursor.read_u8()?;
+    let mut inner = Vec::new();
+    cursor.read_to_end(&mut inner)?;
+
+    Ok((request_id, RawPacket { opcode, payload: inner }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tagged_packets_round_trip() {
+        let packet = tag_command(
+            42,
+            RawPacket {
+                opcode: opcodes::EXECUTE_SHELL,
+                payload: vec![0, 2, b'l', b's'],
+            },
+        );
+        assert_eq!(packet.opcode, opcodes::TAGGED_COMMAND);
+
+        let (request_id, inner) = untag_response(&packet.payload).unwrap();
+        assert_eq!(request_id, 42);
+        assert_eq!(inner.opcode, opcodes::EXECUTE_SHELL);
+        assert_eq!(inner.payload, vec![0, 2, b'l', b's']);
+
+        assert!(untag_response(&[0, 0, 0, 1]).is_err());
+    }
+}
@@ -1,11 +1,87 @@
 /// Filesystem-based APK Repository Implementation
 ///
 /// Stores APK files in a directory and provides access via HTTP URLs.
+/// Each APK's SHA-256 is recorded in a `<filename>.sha256` file next to it,
+/// together with the size and modification time it was computed for, so a
+/// checksum is only recomputed when the file itself changes. Package metadata
+/// parsed from each APK is cached in memory on the same terms.
 
-use crate::domain::repositories::{ApkInfo, ApkRepository, RepositoryError};
+use crate::domain::repositories::{
+    ApkCopyProgress, ApkInfo, ApkMetadata, ApkRepository, ApkVerification, RepositoryError,
+};
+use crate::infrastructure::apk;
 use async_trait::async_trait;
-use std::path::PathBuf;
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
 use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio_util::sync::CancellationToken;
+
+const CHECKSUM_EXTENSION: &str = "sha256";
+/// Extension of an APK still being copied in; never listed, and removed on
+/// startup since any left over belong to a copy interrupted by a crash
+const PARTIAL_EXTENSION: &str = "part";
+/// Size of each chunk copied between progress reports
+const COPY_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Size and modification time a recorded checksum was computed for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    size: u64,
+    mtime_nanos: u128,
+}
+
+impl FileStamp {
+    fn of(metadata: &std::fs::Metadata) -> Self {
+        let mtime_nanos = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+
+        Self {
+            size: metadata.len(),
+            mtime_nanos,
+        }
+    }
+}
+
+/// Contents of a checksum file: `<sha256> <size> <mtime_nanos>`
+/// Files written before the stamp was recorded hold only the hash.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ChecksumRecord {
+    sha256: String,
+    stamp: Option<FileStamp>,
+}
+
+impl ChecksumRecord {
+    fn parse(contents: &str) -> Option<Self> {
+        let mut fields = contents.split_whitespace();
+        let sha256 = fields.next()?.to_string();
+        let stamp = match (fields.next(), fields.next()) {
+            (Some(size), Some(mtime)) => Some(FileStamp {
+                size: size.parse().ok()?,
+                mtime_nanos: mtime.parse().ok()?,
+            }),
+            _ => None,
+        };
+
+        Some(Self { sha256, stamp })
+    }
+
+    fn to_contents(&self) -> String {
+        match self.stamp {
+            Some(stamp) => format!("{} {} {}\n", self.sha256, stamp.size, stamp.mtime_nanos),
+            None => format!("{}\n", self.sha256),
+        }
+    }
+}
 
 /// Filesystem APK repository
 ///
@@ -13,6 +89,8 @@ use tokio::fs;
 pub struct FsApkRepository {
     storage_dir: PathBuf,
     base_url: String,
+    /// Parsed package metadata per filename, with the file state it was read from
+    metadata_cache: Mutex<HashMap<String, (FileStamp, Option<ApkMetadata>)>>,
 }
 
 impl FsApkRepository {
@@ -25,9 +103,57 @@ impl FsApkRepository {
         Self {
             storage_dir: storage_dir.into(),
             base_url,
+            metadata_cache: Mutex::new(HashMap::new()),
         }
     }
 
+    /// Remove files left behind by interrupted adds, returning their names
+    /// Deletes partial copies, empty APKs and checksum files whose APK is gone.
+    /// Complete APKs are never touched. Meant to run once at startup, before
+    /// any APK is being added.
+    pub async fn cleanup(&self) -> Result<Vec<String>, RepositoryError> {
+        let mut removed = Vec::new();
+
+        let mut entries = fs::read_dir(&self.storage_dir)
+            .await
+            .map_err(|e| RepositoryError::IoError(format!("Failed to read APK directory: {}", e)))?;
+
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|e| RepositoryError::IoError(format!("Failed to read directory entry: {}", e)))?
+        {
+            let metadata = entry.metadata().await?;
+            if !metadata.is_file() {
+                continue;
+            }
+
+            let path = entry.path();
+            let filename = entry.file_name().to_string_lossy().to_string();
+            let is_leftover = match path.extension().and_then(|s| s.to_str()) {
+                Some(PARTIAL_EXTENSION) => true,
+                Some("apk") => metadata.len() == 0,
+                Some(CHECKSUM_EXTENSION) => !path.with_extension("").exists(),
+                _ => false,
+            };
+
+            if !is_leftover {
+                continue;
+            }
+
+            match fs::remove_file(&path).await {
+                Ok(()) => {
+                    tracing::info!("Removed leftover APK file: {}", filename);
+                    removed.push(filename);
+                }
+                Err(e) => tracing::warn!("Failed to remove leftover APK file {}: {}", filename, e),
+            }
+        }
+
+        removed.sort();
+        Ok(removed)
+    }
+
     /// Get the full URL for an APK file
     fn get_apk_url(&self, filename: &str) -> String {
         format!("{}/{}", self.base_url, filename)
@@ -37,6 +163,222 @@ impl FsApkRepository {
     fn get_apk_path(&self, filename: &str) -> PathBuf {
         self.storage_dir.join(filename)
     }
+
+    /// Get the path of the file recording an APK's checksum
+    fn get_checksum_path(&self, filename: &str) -> PathBuf {
+        self.storage_dir
+            .join(format!("{}.{}", filename, CHECKSUM_EXTENSION))
+    }
+
+    async fn read_checksum(&self, filename: &str) -> Option<ChecksumRecord> {
+        fs::read_to_string(self.get_checksum_path(filename))
+            .await
+            .ok()
+            .and_then(|s| ChecksumRecord::parse(&s))
+    }
+
+    /// The recorded checksum of an APK, recomputed if the file changed since
+    async fn checksum(&self, filename: &str, metadata: &std::fs::Metadata) -> Result<String, RepositoryError> {
+        match self.read_checksum(filename).await {
+            Some(record) if record.stamp == Some(FileStamp::of(metadata)) => Ok(record.sha256),
+            _ => self.record_checksum(filename).await,
+        }
+    }
+
+    /// Hash an APK and record the checksum next to it
+    async fn record_checksum(&self, filename: &str) -> Result<String, RepositoryError> {
+        let sha256 = sha256_file(&self.get_apk_path(filename)).await?;
+        self.write_checksum(filename, sha256).await
+    }
+
+    /// Record an already known checksum for the APK as it is now on disk
+    async fn write_checksum(&self, filename: &str, sha256: String) -> Result<String, RepositoryError> {
+        let metadata = fs::metadata(self.get_apk_path(filename)).await?;
+        let record = ChecksumRecord {
+            sha256,
+            stamp: Some(FileStamp::of(&metadata)),
+        };
+        fs::write(self.get_checksum_path(filename), record.to_contents()).await?;
+
+        Ok(record.sha256)
+    }
+
+    /// Stored APK whose checksum is `sha256`
+    /// Compares against the recorded checksum files, so only APKs without an
+    /// up-to-date record are hashed, and package metadata is never parsed.
+    async fn find_by_checksum(&self, sha256: &str) -> Result<Option<String>, RepositoryError> {
+        let mut entries = fs::read_dir(&self.storage_dir)
+            .await
+            .map_err(|e| RepositoryError::IoError(format!("Failed to read APK directory: {}", e)))?;
+
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|e| RepositoryError::IoError(format!("Failed to read directory entry: {}", e)))?
+        {
+            if entry.path().extension().and_then(|s| s.to_str()) != Some("apk") {
+                continue;
+            }
+
+            let filename = entry.file_name().to_string_lossy().to_string();
+            let metadata = entry.metadata().await?;
+            match self.checksum(&filename, &metadata).await {
+                Ok(recorded) if recorded == sha256 => return Ok(Some(filename)),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Failed to record checksum for {}: {}", filename, e),
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Package metadata of an APK, parsed once per version of the file
+    /// APKs that can't be parsed are listed by filename only.
+    async fn apk_metadata(&self, filename: &str, stamp: FileStamp) -> Option<ApkMetadata> {
+        if let Some((cached_stamp, metadata)) = self.metadata_cache.lock().get(filename) {
+            if *cached_stamp == stamp {
+                return metadata.clone();
+            }
+        }
+
+        let path = self.get_apk_path(filename);
+        let metadata = match tokio::task::spawn_blocking(move || apk::read_apk_metadata(&path)).await {
+            Ok(Ok(metadata)) => Some(metadata),
+            Ok(Err(e)) => {
+                tracing::debug!("Could not read package metadata of {}: {}", filename, e);
+                None
+            }
+            Err(e) => {
+                tracing::warn!("APK metadata task for {} failed: {}", filename, e);
+                None
+            }
+        };
+
+        self.metadata_cache
+            .lock()
+            .insert(filename.to_string(), (stamp, metadata.clone()));
+        metadata
+    }
+
+    /// Get the path an APK is copied to before it is moved into place
+    fn get_partial_path(&self, filename: &str) -> PathBuf {
+        self.storage_dir
+            .join(format!("{}.{}", filename, PARTIAL_EXTENSION))
+    }
+
+    /// Copy an APK into the repository in chunks
+    /// The data goes to a partial file that is only renamed to `filename` once
+    /// complete, and is removed if the copy fails or is cancelled.
+    async fn copy_apk(
+        &self,
+        source_path: &Path,
+        filename: &str,
+        cancel_token: &CancellationToken,
+        progress_callback: &(dyn Fn(ApkCopyProgress) + Send + Sync),
+    ) -> Result<(), RepositoryError> {
+        let partial_path = self.get_partial_path(filename);
+
+        match copy_chunked(source_path, &partial_path, cancel_token, progress_callback).await {
+            Ok(()) => {
+                fs::rename(&partial_path, self.get_apk_path(filename)).await?;
+                Ok(())
+            }
+            Err(e) => {
+                if let Err(remove_err) = fs::remove_file(&partial_path).await {
+                    tracing::warn!("Failed to remove partial APK {}: {}", partial_path.display(), remove_err);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// First of `name.apk`, `name(1).apk`, `name(2).apk`, ... not already taken
+    fn available_filename(&self, filename: &str) -> String {
+        if !self.get_apk_path(filename).exists() {
+            return filename.to_string();
+        }
+
+        let stem = Path::new(filename)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or(filename);
+
+        (1..)
+            .map(|n| format!("{}({}).apk", stem, n))
+            .find(|candidate| !self.get_apk_path(candidate).exists())
+            .expect("unbounded range always yields a free name")
+    }
+}
+
+async fn copy_chunked(
+    source_path: &Path,
+    dest_path: &Path,
+    cancel_token: &CancellationToken,
+    progress_callback: &(dyn Fn(ApkCopyProgress) + Send + Sync),
+) -> Result<(), RepositoryError> {
+    let mut source = fs::File::open(source_path)
+        .await
+        .map_err(|e| RepositoryError::IoError(format!("Failed to open APK file: {}", e)))?;
+    let total_bytes = source.metadata().await?.len();
+
+    let mut dest = fs::File::create(dest_path)
+        .await
+        .map_err(|e| RepositoryError::IoError(format!("Failed to create APK file: {}", e)))?;
+
+    let mut buffer = vec![0u8; COPY_CHUNK_SIZE];
+    let mut bytes_copied = 0u64;
+    progress_callback(ApkCopyProgress {
+        bytes_copied,
+        total_bytes,
+    });
+
+    loop {
+        if cancel_token.is_cancelled() {
+            return Err(RepositoryError::Cancelled);
+        }
+
+        let read = source.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+
+        dest.write_all(&buffer[..read])
+            .await
+            .map_err(|e| RepositoryError::IoError(format!("Failed to copy APK file: {}", e)))?;
+
+        bytes_copied += read as u64;
+        progress_callback(ApkCopyProgress {
+            bytes_copied,
+            total_bytes,
+        });
+    }
+
+    dest.flush().await?;
+    Ok(())
+}
+
+/// Compute the hex-encoded SHA-256 of a file
+/// Hashing runs on the blocking pool so large APKs don't stall the runtime.
+async fn sha256_file(path: &Path) -> std::io::Result<String> {
+    let path = path.to_path_buf();
+
+    tokio::task::spawn_blocking(move || {
+        let mut file = std::fs::File::open(path)?;
+        let mut hasher = Sha256::new();
+        let mut buffer = vec![0u8; 64 * 1024];
+
+        loop {
+            let read = file.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+        }
+
+        Ok(format!("{:x}", hasher.finalize()))
+    })
+    .await
+    .map_err(std::io::Error::other)?
 }
 
 #[async_trait]
@@ -68,10 +410,28 @@ impl ApkRepository for FsApkRepository {
                 let size_bytes = metadata.len();
                 let url = self.get_apk_url(&filename);
 
+                // APKs copied into the folder by hand, or replaced since they were
+                // last hashed, get their checksum recorded on listing
+                let sha256 = match self.checksum(&filename, &metadata).await {
+                    Ok(sha256) => Some(sha256),
+                    Err(e) => {
+                        tracing::warn!("Failed to record checksum for {}: {}", filename, e);
+                        None
+                    }
+                };
+
+                let apk_metadata = self
+                    .apk_metadata(&filename, FileStamp::of(&metadata))
+                    .await;
+
                 apks.push(ApkInfo {
                     filename,
                     size_bytes,
                     url,
+                    sha256,
+                    metadata: apk_metadata,
+                    // From the stat already made for the size; no extra IO
+                    added_at: metadata.modified().ok().map(DateTime::<Utc>::from),
                 });
             }
         }
@@ -81,21 +441,36 @@ impl ApkRepository for FsApkRepository {
         Ok(apks)
     }
 
-    async fn add_apk(&self, source_path: PathBuf) -> Result<String, RepositoryError> {
-        let filename = source_path
+    async fn add_apk(
+        &self,
+        source_path: PathBuf,
+        dedupe: bool,
+        cancel_token: CancellationToken,
+        progress_callback: Box<dyn Fn(ApkCopyProgress) + Send + Sync>,
+    ) -> Result<String, RepositoryError> {
+        let source_filename = source_path
             .file_name()
             .and_then(|n| n.to_str())
             .ok_or_else(|| RepositoryError::IoError("Invalid source path".to_string()))?;
 
-        let dest_path = self.get_apk_path(filename);
+        let sha256 = sha256_file(&source_path).await?;
 
-        fs::copy(&source_path, &dest_path)
-            .await
-            .map_err(|e| RepositoryError::IoError(format!("Failed to copy APK file: {}", e)))?;
+        if dedupe {
+            if let Some(existing_filename) = self.find_by_checksum(&sha256).await? {
+                return Err(RepositoryError::DuplicateApk { existing_filename });
+            }
+        }
 
-        tracing::info!("Added APK: {}", filename);
+        // Never overwrite a stored APK that happens to share the name
+        let filename = self.available_filename(source_filename);
+        self.copy_apk(&source_path, &filename, &cancel_token, progress_callback.as_ref())
+            .await?;
 
-        Ok(filename.to_string())
+        let sha256 = self.write_checksum(&filename, sha256).await?;
+
+        tracing::info!("Added APK: {} (sha256 {})", filename, sha256);
+
+        Ok(filename)
     }
 
     async fn remove_apk(&self, filename: &str) -> Result<(), RepositoryError> {
@@ -110,12 +485,321 @@ impl ApkRepository for FsApkRepository {
             .await
             .map_err(|e| RepositoryError::IoError(format!("Failed to remove APK file: {}", e)))?;
 
+        let checksum_path = self.get_checksum_path(filename);
+        if checksum_path.exists() {
+            fs::remove_file(&checksum_path).await?;
+        }
+        self.metadata_cache.lock().remove(filename);
+
         tracing::info!("Removed APK: {}", filename);
 
         Ok(())
     }
 
+    async fn verify_apk(&self, filename: &str) -> Result<ApkVerification, RepositoryError> {
+        let path = self.get_apk_path(filename);
+        if !path.exists() {
+            return Err(RepositoryError::NotFound {
+                item: format!("APK '{}'", filename),
+            });
+        }
+
+        let expected_sha256 = self
+            .read_checksum(filename)
+            .await
+            .map(|record| record.sha256)
+            .ok_or_else(|| RepositoryError::NotFound {
+                item: format!("checksum for APK '{}'", filename),
+            })?;
+        let actual_sha256 = sha256_file(&path).await?;
+
+        Ok(ApkVerification {
+            expected_sha256,
+            actual_sha256,
+        })
+    }
+
     fn get_storage_directory(&self) -> PathBuf {
         self.storage_dir.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::IpAddress;
+    use crate::domain::repositories::{ApkListQuery, ApkPage, ApkSortField, SortOrder};
+
+    #[test]
+    fn apk_url_round_trips_v6_literal() {
+        let ip: IpAddress = "fe80::1ff:fe23:4567:890a".parse().unwrap();
+        let repo = FsApkRepository::new("/tmp/apks", ip.http_base_url(43573));
+
+        let url = repo.get_apk_url("client.apk");
+        assert_eq!(url, "http://[fe80::1ff:fe23:4567:890a]:43573/client.apk");
+
+        let authority = url
+            .strip_prefix("http://")
+            .and_then(|rest| rest.split('/').next())
+            .unwrap();
+        let (host, port) = authority.rsplit_once(':').unwrap();
+        assert_eq!(host.parse::<IpAddress>().unwrap(), ip);
+        assert_eq!(port, "43573");
+    }
+
+    #[test]
+    fn apk_url_keeps_v4_literal_unbracketed() {
+        let ip: IpAddress = "192.168.1.20".parse().unwrap();
+        let repo = FsApkRepository::new("/tmp/apks", ip.http_base_url(43573));
+
+        assert_eq!(repo.get_apk_url("client.apk"), "http://192.168.1.20:43573/client.apk");
+    }
+
+    #[test]
+    fn checksum_record_round_trips() {
+        let record = ChecksumRecord {
+            sha256: "ab".repeat(32),
+            stamp: Some(FileStamp {
+                size: 1024,
+                mtime_nanos: 1_700_000_000_123_456_789,
+            }),
+        };
+        assert_eq!(ChecksumRecord::parse(&record.to_contents()), Some(record));
+    }
+
+    #[test]
+    fn legacy_checksum_record_has_no_stamp() {
+        let record = ChecksumRecord::parse("deadbeef\n").unwrap();
+        assert_eq!(record.sha256, "deadbeef");
+        assert_eq!(record.stamp, None);
+        assert!(ChecksumRecord::parse("").is_none());
+    }
+
+    async fn temp_repo() -> (PathBuf, FsApkRepository) {
+        let dir = std::env::temp_dir().join(format!("arceus-apk-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(dir.join("incoming")).await.unwrap();
+        let repo = FsApkRepository::new(&dir, "http://127.0.0.1:43573".to_string());
+        (dir, repo)
+    }
+
+    fn no_progress() -> Box<dyn Fn(ApkCopyProgress) + Send + Sync> {
+        Box::new(|_| {})
+    }
+
+    async fn incoming_apk(dir: &Path, name: &str, contents: &[u8]) -> PathBuf {
+        let path = dir.join("incoming").join(name);
+        fs::write(&path, contents).await.unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn checksum_is_recomputed_when_apk_changes() {
+        let (dir, repo) = temp_repo().await;
+
+        fs::write(dir.join("client.apk"), b"first build").await.unwrap();
+        let first = repo.list_apks().await.unwrap()[0].sha256.clone().unwrap();
+        assert_eq!(repo.list_apks().await.unwrap()[0].sha256.as_deref(), Some(first.as_str()));
+
+        fs::write(dir.join("client.apk"), b"second, longer build").await.unwrap();
+        let second = repo.list_apks().await.unwrap()[0].sha256.clone().unwrap();
+        assert_ne!(first, second);
+
+        fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn add_apk_rejects_identical_file_when_deduping() {
+        let (dir, repo) = temp_repo().await;
+        let original = incoming_apk(&dir, "game.apk", b"game build 1").await;
+        assert_eq!(repo.add_apk(original, true, CancellationToken::new(), no_progress()).await.unwrap(), "game.apk");
+
+        let renamed = incoming_apk(&dir, "game-copy.apk", b"game build 1").await;
+        match repo.add_apk(renamed.clone(), true, CancellationToken::new(), no_progress()).await {
+            Err(RepositoryError::DuplicateApk { existing_filename }) => {
+                assert_eq!(existing_filename, "game.apk")
+            }
+            other => panic!("expected DuplicateApk, got {:?}", other),
+        }
+        assert_eq!(repo.list_apks().await.unwrap().len(), 1);
+
+        assert_eq!(repo.add_apk(renamed, false, CancellationToken::new(), no_progress()).await.unwrap(), "game-copy.apk");
+        assert_eq!(repo.list_apks().await.unwrap().len(), 2);
+
+        fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn dedupe_compares_against_recorded_checksums() {
+        let (dir, repo) = temp_repo().await;
+
+        // Copied in by hand: hashed once and recorded when the duplicate check runs
+        fs::write(dir.join("manual.apk"), b"manual build").await.unwrap();
+        let copy = incoming_apk(&dir, "manual-copy.apk", b"manual build").await;
+        assert!(matches!(
+            repo.add_apk(copy, true, CancellationToken::new(), no_progress()).await,
+            Err(RepositoryError::DuplicateApk { .. })
+        ));
+        let record = repo.read_checksum("manual.apk").await.unwrap();
+        assert_eq!(record.sha256, sha256_file(&dir.join("manual.apk")).await.unwrap());
+
+        // An up-to-date record is trusted rather than rehashing the stored APK
+        let stale = ChecksumRecord {
+            sha256: "0".repeat(64),
+            ..record
+        };
+        fs::write(repo.get_checksum_path("manual.apk"), stale.to_contents()).await.unwrap();
+        let copy = incoming_apk(&dir, "manual-copy.apk", b"manual build").await;
+        assert_eq!(
+            repo.add_apk(copy, true, CancellationToken::new(), no_progress()).await.unwrap(),
+            "manual-copy.apk"
+        );
+
+        fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn add_apk_keeps_different_file_with_same_name() {
+        let (dir, repo) = temp_repo().await;
+        let first = incoming_apk(&dir, "game.apk", b"game build 1").await;
+        repo.add_apk(first, false, CancellationToken::new(), no_progress()).await.unwrap();
+
+        let second = incoming_apk(&dir, "game.apk", b"game build 2").await;
+        assert_eq!(repo.add_apk(second, false, CancellationToken::new(), no_progress()).await.unwrap(), "game(1).apk");
+
+        let filenames: Vec<_> = repo
+            .list_apks()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|apk| apk.filename)
+            .collect();
+        assert_eq!(filenames, ["game(1).apk", "game.apk"]);
+        assert_eq!(fs::read(dir.join("game.apk")).await.unwrap(), b"game build 1");
+
+        fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn add_apk_reports_copy_progress() {
+        let (dir, repo) = temp_repo().await;
+        let contents = vec![7u8; COPY_CHUNK_SIZE * 2 + 10];
+        let source = incoming_apk(&dir, "big.apk", &contents).await;
+
+        let reports = std::sync::Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        repo.add_apk(
+            source,
+            false,
+            CancellationToken::new(),
+            Box::new(move |progress| sink.lock().push(progress.bytes_copied)),
+        )
+        .await
+        .unwrap();
+
+        let total = contents.len() as u64;
+        assert_eq!(*reports.lock(), [0, COPY_CHUNK_SIZE as u64, 2 * COPY_CHUNK_SIZE as u64, total]);
+
+        fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn list_apks_page_sorts_and_paginates() {
+        let (dir, repo) = temp_repo().await;
+        let epoch = std::time::SystemTime::UNIX_EPOCH;
+        for (name, contents, added_secs) in [
+            ("b.apk", &b"medium"[..], 300),
+            ("A.apk", &b"tiny"[..], 200),
+            ("c.apk", &b"the largest one"[..], 100),
+        ] {
+            let path = dir.join(name);
+            fs::write(&path, contents).await.unwrap();
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(epoch + std::time::Duration::from_secs(added_secs))
+                .unwrap();
+        }
+
+        let names = |page: ApkPage| page.items.into_iter().map(|apk| apk.filename).collect::<Vec<_>>();
+
+        let page = repo.list_apks_page(ApkListQuery::default()).await.unwrap();
+        assert_eq!(page.total, 3);
+        assert_eq!(names(page), ["A.apk", "b.apk", "c.apk"]);
+
+        let by_size = ApkListQuery {
+            sort_by: ApkSortField::Size,
+            order: SortOrder::Desc,
+            offset: 1,
+            limit: Some(1),
+        };
+        let page = repo.list_apks_page(by_size).await.unwrap();
+        assert_eq!(page.total, 3);
+        assert_eq!(names(page), ["b.apk"]);
+
+        let by_date = ApkListQuery {
+            sort_by: ApkSortField::DateAdded,
+            ..ApkListQuery::default()
+        };
+        assert_eq!(names(repo.list_apks_page(by_date).await.unwrap()), ["c.apk", "A.apk", "b.apk"]);
+
+        let past_end = ApkListQuery {
+            offset: 5,
+            ..ApkListQuery::default()
+        };
+        let page = repo.list_apks_page(past_end).await.unwrap();
+        assert_eq!((page.items.len(), page.total), (0, 3));
+
+        fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn cleanup_removes_leftovers_and_keeps_complete_apks() {
+        let (dir, repo) = temp_repo().await;
+        let source = incoming_apk(&dir, "game.apk", b"game build 1").await;
+        repo.add_apk(source, false, CancellationToken::new(), no_progress()).await.unwrap();
+
+        fs::write(dir.join("other.apk.part"), b"half a build").await.unwrap();
+        fs::write(dir.join("empty.apk"), b"").await.unwrap();
+        fs::write(dir.join("gone.apk.sha256"), b"deadbeef").await.unwrap();
+
+        let removed = repo.cleanup().await.unwrap();
+        assert_eq!(removed, ["empty.apk", "gone.apk.sha256", "other.apk.part"]);
+
+        assert_eq!(fs::read(dir.join("game.apk")).await.unwrap(), b"game build 1");
+        assert!(dir.join("game.apk.sha256").exists());
+        assert!(repo.cleanup().await.unwrap().is_empty());
+
+        fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn cancelled_add_apk_leaves_no_partial_file() {
+        let (dir, repo) = temp_repo().await;
+        let contents = vec![7u8; COPY_CHUNK_SIZE * 3];
+        let source = incoming_apk(&dir, "big.apk", &contents).await;
+
+        // Cancel once the first chunk has been copied
+        let cancel_token = CancellationToken::new();
+        let trigger = cancel_token.clone();
+        let result = repo
+            .add_apk(
+                source,
+                false,
+                cancel_token,
+                Box::new(move |progress| {
+                    if progress.bytes_copied > 0 {
+                        trigger.cancel();
+                    }
+                }),
+            )
+            .await;
+
+        assert!(matches!(result, Err(RepositoryError::Cancelled)));
+        assert!(repo.list_apks().await.unwrap().is_empty());
+        assert!(!dir.join("big.apk").exists());
+        assert!(!dir.join("big.apk.part").exists());
+
+        fs::remove_dir_all(&dir).await.unwrap();
+    }
+}
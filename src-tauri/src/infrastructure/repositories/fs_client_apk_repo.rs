@@ -7,6 +7,7 @@
 use async_trait::async_trait;
 use reqwest::Client;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use crate::app::config::{
     get_machine_id, CLIENT_APK_FILENAME, CLIENT_METADATA_FILENAME,
@@ -14,6 +15,7 @@ use crate::app::config::{
 use crate::app::models::AlakazamConfig;
 use crate::application::dto::{ClientApkMetadata, RemoteApkMetadata};
 use crate::domain::repositories::{ClientApkError, ClientApkRepository};
+use crate::infrastructure::network::BandwidthLimiter;
 
 pub struct FsClientApkRepository {
     /// Directory where APK files and metadata are stored
@@ -22,10 +24,16 @@ pub struct FsClientApkRepository {
     http_client: Client,
     /// Alakazam server configuration
     alakazam_config: AlakazamConfig,
+    /// Shared download rate limit
+    bandwidth_limiter: Arc<BandwidthLimiter>,
 }
 
 impl FsClientApkRepository {
-    pub fn new(apk_directory: PathBuf, alakazam_config: AlakazamConfig) -> Self {
+    pub fn new(
+        apk_directory: PathBuf,
+        alakazam_config: AlakazamConfig,
+        bandwidth_limiter: Arc<BandwidthLimiter>,
+    ) -> Self {
         let http_client = Client::builder()
             .timeout(std::time::Duration::from_secs(3600)) // 1h timeout for large APK downloads
             .build()
@@ -35,6 +43,7 @@ impl FsClientApkRepository {
             apk_directory,
             http_client,
             alakazam_config,
+            bandwidth_limiter,
         }
     }
 
@@ -93,7 +102,7 @@ impl ClientApkRepository for FsClientApkRepository {
     async fn download_apk(&self, download_url: &str) -> Result<Vec<u8>, ClientApkError> {
         tracing::info!("Downloading APK from signed URL");
 
-        let response = self
+        let mut response = self
             .http_client
             .get(download_url)
             .send()
@@ -107,11 +116,15 @@ impl ClientApkRepository for FsClientApkRepository {
             )));
         }
 
-        let bytes = response
-            .bytes()
+        let mut bytes = Vec::with_capacity(response.content_length().unwrap_or(0) as usize);
+        while let Some(chunk) = response
+            .chunk()
             .await
             .map_err(|e| ClientApkError::Network(e.to_string()))?
-            .to_vec();
+        {
+            bytes.extend_from_slice(&chunk);
+            self.bandwidth_limiter.acquire(chunk.len() as u64).await;
+        }
 
         tracing::info!("Downloaded APK: {} bytes", bytes.len());
         Ok(bytes)
@@ -1,20 +1,54 @@
 /// Filesystem-based Game Version Repository Implementation
 ///
 /// Manages game installations and version tracking on the filesystem.
-/// Downloads games from GCS via Alakazam signed URLs with smart updates (only changed files).
+/// Downloads games from GCS via Alakazam signed URLs with smart updates: installed files
+/// that still match the size and checksum reported by Alakazam are kept, anything else is
+/// downloaded again. Interrupted downloads are resumed with HTTP range requests.
+///
+/// Installs are assembled in `.staging/<version_id>` next to the games, with unchanged
+/// files hard-linked (or copied) from the current install, and only renamed over the
+/// game directory once every file is in place and has been hashed again. A crash never
+/// leaves a half-written game where an installed one is expected.
 
 use async_trait::async_trait;
-use reqwest::Client;
+use futures::StreamExt;
+use md5::{Digest, Md5};
+use parking_lot::Mutex;
+use reqwest::header::{CONTENT_RANGE, RANGE};
+use reqwest::{Client, StatusCode};
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio_util::sync::CancellationToken;
 
 use crate::app::config::get_machine_id;
 use crate::app::models::AlakazamConfig;
 use crate::application::dto::{GameAssignment, GameDownloadResponse, GameFile, LocalGameMetadata};
-use crate::domain::repositories::{GameVersionError, GameVersionRepository};
+use crate::domain::repositories::{
+    DownloadPhase, FileDownloadProgress, FileVerificationFailure, GameVersionError, GameVersionRepository,
+    UpdatePlan,
+};
+use crate::infrastructure::network::BandwidthLimiter;
 
 const GAME_METADATA_FILENAME: &str = "game_metadata.json";
+/// Download state left in game directories by installs made before staging existed
+const DOWNLOAD_STATE_FILENAME: &str = ".download_state.json";
+/// Directory under the games directory where installs are assembled
+const STAGING_DIRNAME: &str = ".staging";
+/// Suffix of the staging entry a replaced install is parked in during the swap
+const PREVIOUS_INSTALL_SUFFIX: &str = ".previous";
+/// Suffix for files that are still being downloaded
+const PART_FILE_EXTENSION: &str = ".part";
+/// Minimum number of new bytes between progress reports
+const PROGRESS_REPORT_INTERVAL_BYTES: u64 = 1024 * 1024;
+/// Attempts made for a single file before the whole download fails
+const MAX_FILE_DOWNLOAD_ATTEMPTS: u32 = 3;
+/// Free space kept on the games volume on top of what a download needs
+const DISK_SPACE_MARGIN_BYTES: u64 = 512 * 1024 * 1024;
+/// Pause before retrying a failed file, multiplied by the attempt number
+const FILE_RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
 
 pub struct FsGameVersionRepository {
     /// Base directory for game installations (e.g., C:/Combatica)
@@ -23,10 +57,19 @@ pub struct FsGameVersionRepository {
     http_client: Client,
     /// Alakazam server configuration
     alakazam_config: AlakazamConfig,
+    /// Shared download rate limit
+    bandwidth_limiter: Arc<BandwidthLimiter>,
+    /// Number of files of one game downloaded at once
+    file_concurrency: usize,
 }
 
 impl FsGameVersionRepository {
-    pub fn new(games_directory: PathBuf, alakazam_config: AlakazamConfig) -> Self {
+    pub fn new(
+        games_directory: PathBuf,
+        alakazam_config: AlakazamConfig,
+        bandwidth_limiter: Arc<BandwidthLimiter>,
+        file_concurrency: usize,
+    ) -> Self {
         let http_client = Client::builder()
             .timeout(std::time::Duration::from_secs(3600))
             .build()
@@ -36,6 +79,8 @@ impl FsGameVersionRepository {
             games_directory,
             http_client,
             alakazam_config,
+            bandwidth_limiter,
+            file_concurrency: file_concurrency.max(1),
         }
     }
 
@@ -45,7 +90,7 @@ impl FsGameVersionRepository {
             .join(GAME_METADATA_FILENAME)
     }
 
-    /// Recursively collect all files in a directory (excluding metadata file)
+    /// Recursively collect all files in a directory (excluding metadata and download state)
     async fn collect_local_files(&self, dir: &PathBuf) -> Result<HashSet<String>, GameVersionError> {
         let mut files = HashSet::new();
         let mut stack = vec![dir.clone()];
@@ -56,8 +101,12 @@ impl FsGameVersionRepository {
             while let Some(entry) = entries.next_entry().await? {
                 let path = entry.path();
 
-                // Skip metadata file
-                if path.file_name().and_then(|n| n.to_str()) == Some(GAME_METADATA_FILENAME) {
+                // Skip metadata, download state and partial files
+                let file_name = path.file_name().and_then(|n| n.to_str());
+                if file_name == Some(GAME_METADATA_FILENAME)
+                    || file_name == Some(DOWNLOAD_STATE_FILENAME)
+                    || is_part_file(&path)
+                {
                     continue;
                 }
 
@@ -76,11 +125,510 @@ impl FsGameVersionRepository {
 
         Ok(files)
     }
+
+    fn staging_root(&self) -> PathBuf {
+        self.games_directory.join(STAGING_DIRNAME)
+    }
+
+    /// Where a version is assembled; partial files there always belong to that version
+    fn staging_directory(&self, version_id: i32) -> PathBuf {
+        self.staging_root().join(version_id.to_string())
+    }
+
+    /// Clear out staging left behind by interrupted installs, returning what was removed
+    /// An install parked aside mid-swap whose game directory is missing is moved back,
+    /// so a crash during the swap restores the previous version. Meant to run once at
+    /// startup, before any download.
+    pub async fn cleanup_staging(&self) -> Result<Vec<String>, GameVersionError> {
+        let staging_root = self.staging_root();
+        let mut removed = Vec::new();
+
+        let mut entries = match fs::read_dir(&staging_root).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(removed),
+            Err(e) => return Err(e.into()),
+        };
+
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            if let Some(game_name) = name.strip_suffix(PREVIOUS_INSTALL_SUFFIX) {
+                let game_dir = self.get_game_directory(game_name);
+                if !game_dir.exists() {
+                    tracing::warn!("Restoring {} after an interrupted install", game_name);
+                    fs::rename(&path, &game_dir).await?;
+                    continue;
+                }
+            }
+
+            let result = if entry.metadata().await?.is_dir() {
+                fs::remove_dir_all(&path).await
+            } else {
+                fs::remove_file(&path).await
+            };
+            match result {
+                Ok(()) => removed.push(name),
+                Err(e) => tracing::warn!("Failed to remove staging entry {:?}: {}", path, e),
+            }
+        }
+
+        let _ = fs::remove_dir(&staging_root).await;
+
+        if !removed.is_empty() {
+            tracing::info!("Removed {} leftover staging entries: {:?}", removed.len(), removed);
+        }
+        Ok(removed)
+    }
+
+    /// Replace the game directory with a fully staged install
+    /// The current install is parked in the staging root until the staged one is in
+    /// place, then deleted.
+    async fn swap_in_staged(&self, game_name: &str, staging_dir: &Path) -> Result<(), GameVersionError> {
+        let game_dir = self.get_game_directory(game_name);
+        let previous = self
+            .staging_root()
+            .join(format!("{}{}", game_name, PREVIOUS_INSTALL_SUFFIX));
+
+        if previous.exists() {
+            fs::remove_dir_all(&previous).await?;
+        }
+
+        let had_previous = game_dir.exists();
+        if had_previous {
+            fs::rename(&game_dir, &previous).await?;
+        }
+
+        if let Err(e) = fs::rename(staging_dir, &game_dir).await {
+            if had_previous {
+                let _ = fs::rename(&previous, &game_dir).await;
+            }
+            return Err(e.into());
+        }
+
+        if had_previous {
+            if let Err(e) = fs::remove_dir_all(&previous).await {
+                tracing::warn!("Failed to remove replaced install of {}: {}", game_name, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Download a single file into its partial file, verify it and move it into place
+    /// Resumes from the end of an existing partial file when `resume` is set and falls
+    /// back to a full download if the server does not honor the range request.
+    async fn download_file(
+        &self,
+        file: &GameFile,
+        file_path: &Path,
+        resume: bool,
+        cancel_token: &CancellationToken,
+        progress: &Mutex<ProgressReporter<'_>>,
+    ) -> Result<(), GameVersionError> {
+        let download_error = |error: String| GameVersionError::DownloadFailed {
+            file: file.path.clone(),
+            error,
+        };
+        let part_path = part_path(file_path);
+
+        let mut offset = if resume {
+            fs::metadata(&part_path).await.map(|m| m.len()).unwrap_or(0)
+        } else {
+            0
+        };
+
+        // A partial file at or beyond the expected size is complete or corrupt
+        if let Some(size) = file.size {
+            if offset > size {
+                progress.lock().rewind(offset);
+                offset = 0;
+            }
+        }
+
+        let already_complete = offset > 0 && Some(offset) == file.size;
+        if !already_complete {
+            let mut response = self.request_file(file, offset).await?;
+
+            if offset > 0 && !range_honored(&response, offset) {
+                tracing::info!(
+                    "Server did not honor range request for {}, restarting download",
+                    file.path
+                );
+                progress.lock().rewind(offset);
+                if response.status() != StatusCode::OK {
+                    response = self.request_file(file, 0).await?;
+                }
+                offset = 0;
+            } else if offset > 0 {
+                tracing::info!("Resuming {} from byte {}", file.path, offset);
+            }
+
+            let mut output = if offset > 0 {
+                fs::OpenOptions::new().append(true).open(&part_path).await?
+            } else {
+                fs::File::create(&part_path).await?
+            };
+
+            let result = loop {
+                let chunk = tokio::select! {
+                    _ = cancel_token.cancelled() => None,
+                    chunk = response.chunk() => Some(chunk),
+                };
+
+                match chunk {
+                    Some(Ok(Some(bytes))) => {
+                        output.write_all(&bytes).await?;
+                        progress.lock().add_bytes(bytes.len() as u64, &file.path);
+                        self.bandwidth_limiter.acquire(bytes.len() as u64).await;
+                    }
+                    Some(Ok(None)) => break Ok(()),
+                    Some(Err(e)) => break Err(download_error(e.to_string())),
+                    None => break Err(GameVersionError::Cancelled),
+                }
+            };
+
+            // Keep what we have, even on failure, so the download can be resumed
+            output.flush().await?;
+            result?;
+        }
+
+        if let Err(e) = verify_file(file, &part_path).await {
+            // A corrupt partial file can't be resumed, start over next time
+            if let Ok(metadata) = fs::metadata(&part_path).await {
+                progress.lock().rewind(metadata.len());
+            }
+            let _ = fs::remove_file(&part_path).await;
+            return Err(e);
+        }
+
+        fs::rename(&part_path, file_path).await?;
+        Ok(())
+    }
+
+    /// Download a file, retrying network failures and corrupt downloads a few times
+    /// Retries continue from whatever the failed attempt left in the partial file.
+    async fn download_file_with_retries(
+        &self,
+        file: &GameFile,
+        file_path: &Path,
+        resume: bool,
+        cancel_token: &CancellationToken,
+        progress: &Mutex<ProgressReporter<'_>>,
+    ) -> Result<(), GameVersionError> {
+        let mut attempt = 1;
+        loop {
+            if cancel_token.is_cancelled() {
+                return Err(GameVersionError::Cancelled);
+            }
+
+            let resume = resume || attempt > 1;
+            match self
+                .download_file(file, file_path, resume, cancel_token, progress)
+                .await
+            {
+                Err(
+                    e @ (GameVersionError::DownloadFailed { .. }
+                    | GameVersionError::ChecksumMismatch { .. }
+                    | GameVersionError::SizeMismatch { .. }),
+                ) if attempt < MAX_FILE_DOWNLOAD_ATTEMPTS =>
+                {
+                    tracing::warn!(
+                        "Download of {} failed (attempt {}/{}), retrying: {}",
+                        file.path,
+                        attempt,
+                        MAX_FILE_DOWNLOAD_ATTEMPTS,
+                        e
+                    );
+
+                    tokio::select! {
+                        _ = cancel_token.cancelled() => return Err(GameVersionError::Cancelled),
+                        _ = tokio::time::sleep(FILE_RETRY_DELAY * attempt) => {}
+                    }
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Request a file, starting at `offset` bytes when resuming
+    async fn request_file(
+        &self,
+        file: &GameFile,
+        offset: u64,
+    ) -> Result<reqwest::Response, GameVersionError> {
+        let mut request = self.http_client.get(&file.download_url);
+        if offset > 0 {
+            request = request.header(RANGE, format!("bytes={}-", offset));
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| GameVersionError::DownloadFailed {
+                file: file.path.clone(),
+                error: e.to_string(),
+            })?;
+
+        // 416 means the partial file doesn't fit the remote file, the caller restarts
+        let status = response.status();
+        if !status.is_success() && !(offset > 0 && status == StatusCode::RANGE_NOT_SATISFIABLE) {
+            return Err(GameVersionError::DownloadFailed {
+                file: file.path.clone(),
+                error: format!("HTTP {}", status),
+            });
+        }
+
+        Ok(response)
+    }
+}
+
+/// Aggregates byte and file counts and throttles progress callbacks
+struct ProgressReporter<'a> {
+    callback: &'a (dyn Fn(FileDownloadProgress) + Send + Sync),
+    total_files: usize,
+    total_bytes: Option<u64>,
+    downloaded_files: usize,
+    downloaded_bytes: u64,
+    last_reported_bytes: u64,
+}
+
+impl<'a> ProgressReporter<'a> {
+    fn new(callback: &'a (dyn Fn(FileDownloadProgress) + Send + Sync), files: &[GameFile]) -> Self {
+        Self {
+            callback,
+            total_files: files.len(),
+            total_bytes: files.iter().map(|f| f.size).sum(),
+            downloaded_files: 0,
+            downloaded_bytes: 0,
+            last_reported_bytes: 0,
+        }
+    }
+
+    fn add_bytes(&mut self, bytes: u64, current_file: &str) {
+        self.downloaded_bytes += bytes;
+        if self.downloaded_bytes - self.last_reported_bytes >= PROGRESS_REPORT_INTERVAL_BYTES {
+            self.report(current_file.to_string());
+        }
+    }
+
+    /// Forget bytes of a partial file that has to be downloaded again
+    fn rewind(&mut self, bytes: u64) {
+        self.downloaded_bytes = self.downloaded_bytes.saturating_sub(bytes);
+        self.last_reported_bytes = self.last_reported_bytes.min(self.downloaded_bytes);
+    }
+
+    fn complete_file(&mut self, current_file: String) {
+        self.downloaded_files += 1;
+        self.report(current_file);
+    }
+
+    fn report(&mut self, current_file: String) {
+        self.last_reported_bytes = self.downloaded_bytes;
+        (self.callback)(FileDownloadProgress {
+            phase: DownloadPhase::Downloading,
+            downloaded_files: self.downloaded_files,
+            total_files: self.total_files,
+            downloaded_bytes: self.downloaded_bytes,
+            total_bytes: self.total_bytes,
+            current_file,
+        });
+    }
+}
+
+fn part_path(file_path: &Path) -> PathBuf {
+    let mut path = file_path.as_os_str().to_owned();
+    path.push(PART_FILE_EXTENSION);
+    PathBuf::from(path)
+}
+
+/// Join a manifest path onto the game directory, refusing anything that could
+/// point outside it (absolute paths, `..`, drive prefixes)
+fn manifest_file_path(game_dir: &Path, relative: &str) -> Option<PathBuf> {
+    let relative = Path::new(relative);
+    let is_plain = relative
+        .components()
+        .all(|component| matches!(component, std::path::Component::Normal(_)));
+    (is_plain && relative.components().next().is_some()).then(|| game_dir.join(relative))
+}
+
+/// Hard-link an already installed file into the staging directory
+/// Returns `false` if the volume doesn't support it and the file has to be copied.
+async fn link_installed_file(installed: &Path, staged: &Path) -> bool {
+    let _ = fs::remove_file(staged).await;
+    fs::hard_link(installed, staged).await.is_ok()
+}
+
+/// Write game metadata through a temporary file, so a crash never leaves half of it
+async fn write_metadata(path: &Path, metadata: &LocalGameMetadata) -> Result<(), GameVersionError> {
+    let json = serde_json::to_string_pretty(metadata)
+        .map_err(|e| GameVersionError::InvalidMetadata(e.to_string()))?;
+
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    fs::write(&temp_path, json).await?;
+    fs::rename(&temp_path, path).await?;
+    Ok(())
+}
+
+/// Resolve a manifest path inside a directory, or fail the download
+fn required_file_path(dir: &Path, file: &GameFile) -> Result<PathBuf, GameVersionError> {
+    manifest_file_path(dir, &file.path).ok_or_else(|| {
+        GameVersionError::InvalidMetadata(format!("File path {} is outside the game directory", file.path))
+    })
+}
+
+/// Remove a file if it exists, returning its size
+async fn remove_file_if_exists(path: &Path) -> Result<u64, GameVersionError> {
+    match fs::metadata(path).await {
+        Ok(metadata) if metadata.is_file() => {
+            fs::remove_file(path).await?;
+            Ok(metadata.len())
+        }
+        _ => Ok(0),
+    }
+}
+
+fn is_part_file(path: &Path) -> bool {
+    path.to_str()
+        .is_some_and(|p| p.ends_with(PART_FILE_EXTENSION))
+}
+
+/// Check that a ranged response actually starts at the requested offset
+fn range_honored(response: &reqwest::Response, offset: u64) -> bool {
+    if response.status() != StatusCode::PARTIAL_CONTENT {
+        return false;
+    }
+
+    response
+        .headers()
+        .get(CONTENT_RANGE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("bytes "))
+        .and_then(|v| v.split('-').next())
+        .and_then(|start| start.parse::<u64>().ok())
+        == Some(offset)
+}
+
+/// Fail if the volume holding `dir` can't take `required` more bytes plus a margin
+/// Files replaced by an update are only removed after the download, so their
+/// space isn't counted as available. If free space can't be read the check is skipped.
+fn ensure_disk_space(dir: &Path, required: u64) -> Result<(), GameVersionError> {
+    if required == 0 {
+        return Ok(());
+    }
+
+    let available = match fs2::available_space(dir) {
+        Ok(available) => available,
+        Err(e) => {
+            tracing::warn!("Could not read free space for {:?}, skipping check: {}", dir, e);
+            return Ok(());
+        }
+    };
+
+    if required.saturating_add(DISK_SPACE_MARGIN_BYTES) > available {
+        return Err(GameVersionError::InsufficientSpace { required, available });
+    }
+
+    Ok(())
+}
+
+/// Verify a downloaded file against the size and MD5 digest reported by the server
+async fn verify_file(file: &GameFile, path: &Path) -> Result<(), GameVersionError> {
+    if let Some(expected) = file.size {
+        let actual = fs::metadata(path).await?.len();
+        if actual != expected {
+            return Err(GameVersionError::SizeMismatch {
+                file: file.path.clone(),
+                expected,
+                actual,
+            });
+        }
+    }
+
+    let Some(expected) = &file.md5_hash else {
+        return Ok(());
+    };
+
+    let mut reader = fs::File::open(path).await?;
+    let mut hasher = Md5::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = reader.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    let actual = base64::Engine::encode(
+        &base64::engine::general_purpose::STANDARD,
+        hasher.finalize(),
+    );
+    if &actual != expected {
+        tracing::warn!("Checksum mismatch for {}: expected {}, got {}", file.path, expected, actual);
+        return Err(GameVersionError::ChecksumMismatch {
+            file: file.path.clone(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Re-hash every staged file against the file list before the install is swapped
+/// Catches anything that changed on disk after it was verified while downloading.
+/// A file that fails is deleted so the next attempt downloads it again.
+async fn verify_staged_files(
+    staging_dir: &Path,
+    files: &[GameFile],
+    cancel_token: &CancellationToken,
+    progress_callback: &(dyn Fn(FileDownloadProgress) + Send + Sync),
+) -> Result<(), GameVersionError> {
+    let total_bytes = files.iter().map(|f| f.size).sum();
+    let mut verified_bytes = 0;
+
+    for (index, file) in files.iter().enumerate() {
+        if cancel_token.is_cancelled() {
+            return Err(GameVersionError::Cancelled);
+        }
+
+        let staged_path = required_file_path(staging_dir, file)?;
+        let result = if staged_path.is_file() {
+            verify_file(file, &staged_path).await
+        } else {
+            Err(GameVersionError::DownloadFailed {
+                file: file.path.clone(),
+                error: "File is missing from the staging directory".to_string(),
+            })
+        };
+
+        if let Err(e) = result {
+            tracing::warn!("Staged file {} failed verification: {}", file.path, e);
+            remove_file_if_exists(&staged_path).await?;
+            return Err(GameVersionError::VerificationFailed {
+                file: file.path.clone(),
+                reason: e.to_string(),
+            });
+        }
+
+        verified_bytes += fs::metadata(&staged_path).await?.len();
+        progress_callback(FileDownloadProgress {
+            phase: DownloadPhase::Verifying,
+            downloaded_files: index + 1,
+            total_files: files.len(),
+            downloaded_bytes: verified_bytes,
+            total_bytes,
+            current_file: file.path.clone(),
+        });
+    }
+
+    Ok(())
 }
 
 #[async_trait]
 impl GameVersionRepository for FsGameVersionRepository {
-    async fn fetch_game_assignments(&self) -> Result<Vec<GameAssignment>, GameVersionError> {
+    async fn fetch_game_assignments(&self, channel: Option<&str>) -> Result<Vec<GameAssignment>, GameVersionError> {
         let url = format!("{}/api/arcade/games", self.alakazam_config.base_url);
 
         // Get machine ID for authentication
@@ -88,10 +636,12 @@ impl GameVersionRepository for FsGameVersionRepository {
             GameVersionError::Network(format!("Failed to get machine ID: {}", e))
         })?;
 
-        let response = self
-            .http_client
-            .get(&url)
-            .header("X-Machine-ID", machine_id)
+        let mut request = self.http_client.get(&url).header("X-Machine-ID", machine_id);
+        if let Some(channel) = channel {
+            request = request.query(&[("channel", channel)]);
+        }
+
+        let response = request
             .send()
             .await
             .map_err(|e| GameVersionError::Network(e.to_string()))?;
@@ -115,6 +665,7 @@ impl GameVersionRepository for FsGameVersionRepository {
     async fn fetch_download_urls(
         &self,
         game_id: i32,
+        channel: Option<&str>,
     ) -> Result<GameDownloadResponse, GameVersionError> {
         let url = format!(
             "{}/api/arcade/games/{}/download",
@@ -127,10 +678,12 @@ impl GameVersionRepository for FsGameVersionRepository {
             GameVersionError::Network(format!("Failed to get machine ID: {}", e))
         })?;
 
-        let response = self
-            .http_client
-            .get(&url)
-            .header("X-Machine-ID", machine_id)
+        let mut request = self.http_client.get(&url).header("X-Machine-ID", machine_id);
+        if let Some(channel) = channel {
+            request = request.query(&[("channel", channel)]);
+        }
+
+        let response = request
             .send()
             .await
             .map_err(|e| GameVersionError::Network(e.to_string()))?;
@@ -158,126 +711,167 @@ impl GameVersionRepository for FsGameVersionRepository {
 
     async fn download_game_files(
         &self,
-        game_name: &str,
+        metadata: &LocalGameMetadata,
         files: &[GameFile],
-        progress_callback: Box<dyn Fn(usize, usize, String) + Send + Sync>,
+        resume: bool,
+        cancel_token: CancellationToken,
+        progress_callback: Box<dyn Fn(FileDownloadProgress) + Send + Sync>,
     ) -> Result<(), GameVersionError> {
+        let game_name = metadata.game_name.as_str();
+        let version_id = metadata.installed_version_id;
         let game_dir = self.get_game_directory(game_name);
+        let staging_dir = self.staging_directory(version_id);
 
-        // Create game directory if it doesn't exist
-        fs::create_dir_all(&game_dir).await?;
+        // The staging directory only ever holds this version, so its partial files
+        // can be resumed as they are
+        if !resume && staging_dir.exists() {
+            tracing::info!("Discarding partial downloads for {}", game_name);
+            fs::remove_dir_all(&staging_dir).await?;
+        }
+        fs::create_dir_all(&staging_dir).await?;
+
+        // Files that are already staged or installed and match this version's size and
+        // checksum are kept; their bytes count toward progress along with any
+        // resumable partial files
+        let mut progress = ProgressReporter::new(progress_callback.as_ref(), files);
+        let mut verified_files = HashSet::new();
+        let mut linked = 0;
+        // Installed files that couldn't be hard-linked and have to be copied, once
+        // the space for them is known to be there
+        let mut to_copy = Vec::new();
+        // Bytes still to be written, for files whose size the server reports
+        let mut remaining_bytes = 0u64;
+        for file in files {
+            if cancel_token.is_cancelled() {
+                return Err(GameVersionError::Cancelled);
+            }
 
-        // Get list of currently installed files
-        let local_files = if game_dir.exists() {
-            self.collect_local_files(&game_dir).await?
-        } else {
-            HashSet::new()
-        };
+            let staged_path = required_file_path(&staging_dir, file)?;
+            let installed_path = required_file_path(&game_dir, file)?;
 
-        // Build set of new version files
-        let new_files: HashSet<String> = files.iter().map(|f| f.path.clone()).collect();
+            if staged_path.is_file() && verify_file(file, &staged_path).await.is_ok() {
+                progress.downloaded_bytes += fs::metadata(&staged_path).await?.len();
+                verified_files.insert(file.path.as_str());
+                continue;
+            }
 
-        // Find files to delete (exist locally but not in new version)
-        let files_to_delete: Vec<_> = local_files.difference(&new_files).collect();
-        let files_to_delete_count = files_to_delete.len();
+            if installed_path.is_file() && verify_file(file, &installed_path).await.is_ok() {
+                if let Some(parent) = staged_path.parent() {
+                    fs::create_dir_all(parent).await?;
+                }
+                let len = fs::metadata(&installed_path).await?.len();
+                if !link_installed_file(&installed_path, &staged_path).await {
+                    to_copy.push((installed_path, staged_path, len));
+                }
+                progress.downloaded_bytes += len;
+                verified_files.insert(file.path.as_str());
+                linked += 1;
+                continue;
+            }
 
-        if files_to_delete_count > 0 {
-            tracing::info!("Removing {} obsolete files", files_to_delete_count);
-            for file_path in files_to_delete {
-                let full_path = game_dir.join(file_path);
-                if let Err(e) = fs::remove_file(&full_path).await {
-                    tracing::warn!("Failed to remove obsolete file {}: {}", file_path, e);
-                } else {
-                    tracing::debug!("Removed obsolete file: {}", file_path);
+            let mut partial_bytes = 0;
+            if resume {
+                if let Ok(metadata) = fs::metadata(part_path(&staged_path)).await {
+                    partial_bytes = metadata.len();
+                    progress.downloaded_bytes += partial_bytes;
                 }
             }
+            remaining_bytes += file.size.unwrap_or(0).saturating_sub(partial_bytes);
         }
 
-        // Download new or changed files
-        let mut downloaded = 0;
-        let mut skipped = 0;
-        let total_files = files.len();
+        let copy_bytes: u64 = to_copy.iter().map(|(_, _, len)| len).sum();
+        ensure_disk_space(&self.games_directory, remaining_bytes.saturating_add(copy_bytes))?;
+        for (installed_path, staged_path, _) in &to_copy {
+            if cancel_token.is_cancelled() {
+                return Err(GameVersionError::Cancelled);
+            }
+            fs::copy(installed_path, staged_path).await?;
+        }
+        progress.report(String::new());
 
-        for (index, file) in files.iter().enumerate() {
-            let file_path = game_dir.join(&file.path);
-            let should_download = if !file_path.exists() {
-                // File doesn't exist, must download
-                true
+        let mut pending = Vec::new();
+        for file in files {
+            if verified_files.contains(file.path.as_str()) {
+                tracing::debug!("Skipping verified file: {}", file.path);
+                progress.complete_file(format!("Skipped: {}", file.path));
             } else {
-                // File exists - in future we could check size/hash here
-                // For now, we'll skip existing files to save bandwidth
-                // You can enhance this by comparing file sizes from server
-                false
-            };
+                pending.push(file);
+            }
+        }
+        let downloaded = pending.len();
+        let skipped = files.len() - downloaded;
 
-            if should_download {
-                tracing::info!(
-                    "Downloading file {}/{}: {}",
-                    index + 1,
-                    files.len(),
-                    file.path
-                );
+        // Download new or changed files, several at a time. The first file to fail for
+        // good stops the others; their partial files are kept for a later resume.
+        let progress = Mutex::new(progress);
+        let files_cancel = cancel_token.child_token();
+        let (staging_dir_ref, progress_ref, files_cancel_ref) = (&staging_dir, &progress, &files_cancel);
 
-                // Update progress before downloading
-                progress_callback(index, total_files, file.path.clone());
-
-                // Download file
-                let response = self
-                    .http_client
-                    .get(&file.download_url)
-                    .send()
-                    .await
-                    .map_err(|e| GameVersionError::DownloadFailed {
-                        file: file.path.clone(),
-                        error: e.to_string(),
-                    })?;
-
-                if !response.status().is_success() {
-                    return Err(GameVersionError::DownloadFailed {
-                        file: file.path.clone(),
-                        error: format!("HTTP {}", response.status()),
-                    });
-                }
-
-                let bytes = response
-                    .bytes()
-                    .await
-                    .map_err(|e| GameVersionError::DownloadFailed {
-                        file: file.path.clone(),
-                        error: e.to_string(),
-                    })?;
+        let mut downloads = futures::stream::iter(pending.into_iter().enumerate())
+            .map(move |(index, file)| async move {
+                let file_path = required_file_path(staging_dir_ref, file)?;
+                tracing::info!("Downloading file {}/{}: {}", index + 1, downloaded, file.path);
 
                 // Create parent directories if needed
                 if let Some(parent) = file_path.parent() {
                     fs::create_dir_all(parent).await?;
                 }
 
-                // Write file
-                fs::write(&file_path, &bytes).await.map_err(|e| {
-                    GameVersionError::DownloadFailed {
-                        file: file.path.clone(),
-                        error: e.to_string(),
-                    }
-                })?;
+                self.download_file_with_retries(file, &file_path, resume, files_cancel_ref, progress_ref)
+                    .await?;
 
                 tracing::debug!("Saved file: {}", file_path.display());
-                downloaded += 1;
-
-                // Update progress after downloading
-                progress_callback(index + 1, total_files, file.path.clone());
-            } else {
-                tracing::debug!("Skipping existing file: {}", file.path);
-                skipped += 1;
-                // Update progress for skipped files too
-                progress_callback(index + 1, total_files, format!("Skipped: {}", file.path));
+                progress_ref.lock().complete_file(file.path.clone());
+                Ok::<(), GameVersionError>(())
+            })
+            .buffer_unordered(self.file_concurrency);
+
+        let mut first_error = None;
+        while let Some(result) = downloads.next().await {
+            if let Err(e) = result {
+                files_cancel.cancel();
+                // Files stopped by that cancellation report Cancelled; keep the real cause
+                if first_error.is_none() || matches!(first_error, Some(GameVersionError::Cancelled)) {
+                    first_error = Some(e);
+                }
             }
         }
+        drop(downloads);
+
+        if let Some(e) = first_error {
+            return Err(e);
+        }
+
+        verify_staged_files(&staging_dir, files, &cancel_token, progress_callback.as_ref()).await?;
+
+        if cancel_token.is_cancelled() {
+            return Err(GameVersionError::Cancelled);
+        }
+
+        // Staged with the files, so the swap installs both or neither
+        write_metadata(&staging_dir.join(GAME_METADATA_FILENAME), metadata).await?;
+
+        // Obsolete files simply aren't staged, so the swap drops them
+        let removed = if game_dir.exists() {
+            let new_files: HashSet<&str> = files.iter().map(|f| f.path.as_str()).collect();
+            self.collect_local_files(&game_dir)
+                .await?
+                .iter()
+                .filter(|path| !new_files.contains(path.as_str()))
+                .count()
+        } else {
+            0
+        };
+
+        self.swap_in_staged(game_name, &staging_dir).await?;
+        let _ = fs::remove_dir(self.staging_root()).await;
 
         tracing::info!(
-            "Update complete: {} files downloaded, {} files skipped, {} files removed",
+            "Update complete: {} files downloaded, {} files kept ({} from the previous install), {} files removed",
             downloaded,
             skipped,
-            files_to_delete_count
+            linked,
+            removed
         );
         Ok(())
     }
@@ -299,25 +893,6 @@ impl GameVersionRepository for FsGameVersionRepository {
         Ok(Some(metadata))
     }
 
-    async fn save_local_metadata(
-        &self,
-        game_name: &str,
-        metadata: &LocalGameMetadata,
-    ) -> Result<(), GameVersionError> {
-        let metadata_path = self.metadata_path(game_name);
-        let json = serde_json::to_string_pretty(metadata)
-            .map_err(|e| GameVersionError::InvalidMetadata(e.to_string()))?;
-
-        // Ensure game directory exists
-        if let Some(parent) = metadata_path.parent() {
-            fs::create_dir_all(parent).await?;
-        }
-
-        fs::write(&metadata_path, json).await?;
-        tracing::info!("Saved metadata to {}", metadata_path.display());
-        Ok(())
-    }
-
     async fn report_version_status(
         &self,
         _game_id: i32,
@@ -371,6 +946,137 @@ impl GameVersionRepository for FsGameVersionRepository {
         self.games_directory.join(game_name)
     }
 
+    async fn verify_game_files(
+        &self,
+        game_name: &str,
+        files: &[GameFile],
+    ) -> Result<Vec<FileVerificationFailure>, GameVersionError> {
+        let game_dir = self.get_game_directory(game_name);
+        let mut failures = Vec::new();
+
+        for file in files {
+            let reason = match required_file_path(&game_dir, file) {
+                Err(e) => Some(e.to_string()),
+                Ok(file_path) if !file_path.is_file() => Some("File is missing".to_string()),
+                Ok(file_path) => verify_file(file, &file_path).await.err().map(|e| e.to_string()),
+            };
+
+            if let Some(reason) = reason {
+                tracing::warn!("Verification failed for {}: {}", file.path, reason);
+                failures.push(FileVerificationFailure {
+                    path: file.path.clone(),
+                    reason,
+                });
+            }
+        }
+
+        Ok(failures)
+    }
+
+    async fn plan_update(
+        &self,
+        game_name: &str,
+        files: &[GameFile],
+    ) -> Result<UpdatePlan, GameVersionError> {
+        let game_dir = self.get_game_directory(game_name);
+        let mut plan = UpdatePlan::default();
+        let mut download_bytes = Some(0u64);
+
+        for file in files {
+            let file_path = required_file_path(&game_dir, file)?;
+            let needed = if !file_path.is_file() {
+                plan.added.push(file.path.clone());
+                true
+            } else if verify_file(file, &file_path).await.is_err() {
+                plan.changed.push(file.path.clone());
+                true
+            } else {
+                plan.unchanged += 1;
+                false
+            };
+
+            if needed {
+                download_bytes = download_bytes.zip(file.size).map(|(total, size)| total + size);
+            }
+        }
+        plan.download_bytes = download_bytes;
+
+        if game_dir.exists() {
+            let new_files: HashSet<&str> = files.iter().map(|f| f.path.as_str()).collect();
+            plan.removed = self
+                .collect_local_files(&game_dir)
+                .await?
+                .into_iter()
+                .filter(|path| !new_files.contains(path.as_str()))
+                .collect();
+            plan.removed.sort();
+        }
+
+        Ok(plan)
+    }
+
+    async fn delete_game_files(&self, game_name: &str, files: &[String]) -> Result<u64, GameVersionError> {
+        let game_dir = self.get_game_directory(game_name);
+        let mut freed = 0;
+        let mut touched_dirs = HashSet::new();
+
+        for relative in files {
+            let Some(file_path) = manifest_file_path(&game_dir, relative) else {
+                tracing::warn!("Not deleting {}: path is outside the game directory", relative);
+                continue;
+            };
+
+            freed += remove_file_if_exists(&file_path).await?;
+            freed += remove_file_if_exists(&part_path(&file_path)).await?;
+
+            // Every directory between the file and the game directory may now be empty
+            for dir in file_path.ancestors().skip(1) {
+                if !dir.starts_with(&game_dir) || !touched_dirs.insert(dir.to_path_buf()) {
+                    break;
+                }
+            }
+        }
+
+        freed += remove_file_if_exists(&game_dir.join(GAME_METADATA_FILENAME)).await?;
+        freed += remove_file_if_exists(&game_dir.join(DOWNLOAD_STATE_FILENAME)).await?;
+        touched_dirs.insert(game_dir);
+
+        // Deepest first; `remove_dir` refuses directories that still hold other content
+        let mut touched_dirs: Vec<PathBuf> = touched_dirs.into_iter().collect();
+        touched_dirs.sort_by_key(|dir| std::cmp::Reverse(dir.components().count()));
+        for dir in touched_dirs {
+            let _ = fs::remove_dir(&dir).await;
+        }
+
+        tracing::info!("Deleted {} files of {} ({} bytes)", files.len(), game_name, freed);
+        Ok(freed)
+    }
+
+    async fn installed_size(&self, game_name: &str) -> Result<u64, GameVersionError> {
+        let game_dir = self.get_game_directory(game_name);
+        if !game_dir.is_dir() {
+            return Ok(0);
+        }
+
+        let mut total = 0;
+        let mut stack = vec![game_dir];
+
+        while let Some(current_dir) = stack.pop() {
+            let mut entries = fs::read_dir(&current_dir).await?;
+
+            while let Some(entry) = entries.next_entry().await? {
+                let metadata = entry.metadata().await?;
+                if metadata.is_dir() {
+                    stack.push(entry.path());
+                } else {
+                    total += metadata.len();
+                }
+            }
+        }
+
+        Ok(total)
+    }
+
     async fn scan_installed_games(&self) -> Result<Vec<LocalGameMetadata>, GameVersionError> {
         let mut discovered_games = Vec::new();
 
@@ -428,3 +1134,372 @@ impl GameVersionRepository for FsGameVersionRepository {
         Ok(discovered_games)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::StatusCode as HttpStatus;
+    use axum::routing::get;
+    use axum::Router;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
+
+    async fn temp_repo() -> (PathBuf, FsGameVersionRepository) {
+        temp_repo_with_concurrency(1).await
+    }
+
+    async fn temp_repo_with_concurrency(file_concurrency: usize) -> (PathBuf, FsGameVersionRepository) {
+        let dir = std::env::temp_dir().join(format!("arceus-games-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).await.unwrap();
+        let repo = FsGameVersionRepository::new(
+            dir.clone(),
+            AlakazamConfig::default(),
+            Arc::new(BandwidthLimiter::new(0)),
+            file_concurrency,
+        );
+        (dir, repo)
+    }
+
+    /// Serve `router` on a local port, returning its base URL
+    async fn serve(router: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        base_url
+    }
+
+    fn game_file(path: &str, download_url: String, contents: &[u8]) -> GameFile {
+        GameFile {
+            path: path.to_string(),
+            download_url,
+            size: Some(contents.len() as u64),
+            md5_hash: Some(base64::Engine::encode(
+                &base64::engine::general_purpose::STANDARD,
+                Md5::digest(contents),
+            )),
+        }
+    }
+
+    async fn download(
+        repo: &FsGameVersionRepository,
+        files: &[GameFile],
+    ) -> Result<(), GameVersionError> {
+        let metadata = LocalGameMetadata::new(1, "Game".to_string(), "1.0.0".to_string(), 7);
+        repo.download_game_files(&metadata, files, false, CancellationToken::new(), Box::new(|_: FileDownloadProgress| {}))
+            .await
+    }
+
+    #[tokio::test]
+    async fn failed_and_corrupt_downloads_are_retried() {
+        const CONTENTS: &[u8] = b"the real pak file";
+        let requests = Arc::new(AtomicU32::new(0));
+        let counter = requests.clone();
+        let base_url = serve(Router::new().route(
+            "/a.pak",
+            get(move || {
+                let attempt = counter.fetch_add(1, Ordering::SeqCst) + 1;
+                async move {
+                    match attempt {
+                        1 => (HttpStatus::INTERNAL_SERVER_ERROR, Vec::new()),
+                        2 => (HttpStatus::OK, b"corrupted pak file".to_vec()),
+                        _ => (HttpStatus::OK, CONTENTS.to_vec()),
+                    }
+                }
+            }),
+        ))
+        .await;
+        let (dir, repo) = temp_repo().await;
+
+        download(&repo, &[game_file("a.pak", format!("{}/a.pak", base_url), CONTENTS)])
+            .await
+            .unwrap();
+
+        assert_eq!(requests.load(Ordering::SeqCst), MAX_FILE_DOWNLOAD_ATTEMPTS);
+        assert_eq!(fs::read(dir.join("Game/a.pak")).await.unwrap(), CONTENTS);
+        // The metadata is swapped in with the files
+        let metadata = repo.get_local_metadata("Game").await.unwrap().unwrap();
+        assert_eq!(metadata.installed_version_id, 7);
+
+        fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn files_download_in_parallel() {
+        // Neither response is sent until both requests are in flight
+        let barrier = Arc::new(tokio::sync::Barrier::new(2));
+        let router = ["a", "b"].into_iter().fold(Router::new(), |router, name| {
+            let barrier = barrier.clone();
+            router.route(
+                &format!("/{}.pak", name),
+                get(move || {
+                    let barrier = barrier.clone();
+                    async move {
+                        barrier.wait().await;
+                        name.as_bytes().to_vec()
+                    }
+                }),
+            )
+        });
+        let base_url = serve(router).await;
+        let (dir, repo) = temp_repo_with_concurrency(2).await;
+
+        let files = [
+            game_file("a.pak", format!("{}/a.pak", base_url), b"a"),
+            game_file("b.pak", format!("{}/b.pak", base_url), b"b"),
+        ];
+        tokio::time::timeout(Duration::from_secs(10), download(&repo, &files))
+            .await
+            .expect("downloads ran one at a time")
+            .unwrap();
+
+        assert_eq!(fs::read(dir.join("Game/b.pak")).await.unwrap(), b"b");
+
+        fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn failing_file_cancels_its_siblings_and_keeps_their_partials() {
+        let base_url = serve(
+            Router::new()
+                .route("/broken.pak", get(|| async { HttpStatus::INTERNAL_SERVER_ERROR }))
+                .route(
+                    "/slow.pak",
+                    get(|| async {
+                        // Sends a first chunk, then never finishes
+                        let first = futures::stream::once(async {
+                            Ok::<_, std::io::Error>(bytes::Bytes::from_static(b"partial"))
+                        });
+                        Body::from_stream(first.chain(futures::stream::pending()))
+                    }),
+                ),
+        )
+        .await;
+        let (dir, repo) = temp_repo_with_concurrency(2).await;
+
+        let files = [
+            game_file("broken.pak", format!("{}/broken.pak", base_url), b"broken"),
+            game_file("slow.pak", format!("{}/slow.pak", base_url), b"partial and the rest"),
+        ];
+        let result = tokio::time::timeout(Duration::from_secs(30), download(&repo, &files))
+            .await
+            .expect("sibling download was not cancelled");
+
+        // The real cause is reported, not the cancellation it triggered
+        assert!(matches!(
+            result,
+            Err(GameVersionError::DownloadFailed { ref file, .. }) if file == "broken.pak"
+        ));
+        assert_eq!(
+            fs::read(repo.staging_directory(7).join("slow.pak.part")).await.unwrap(),
+            b"partial"
+        );
+        assert!(!dir.join("Game").exists());
+
+        fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn installed_files_are_verified_before_they_are_skipped() {
+        let requests = Arc::new(AtomicU32::new(0));
+        let counter = requests.clone();
+        let base_url = serve(
+            Router::new()
+                .route(
+                    "/intact.pak",
+                    get(|| async { (HttpStatus::INTERNAL_SERVER_ERROR, "intact.pak must not be downloaded") }),
+                )
+                .route(
+                    "/damaged.pak",
+                    get(move || {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                        async { b"damaged, fixed".to_vec() }
+                    }),
+                ),
+        )
+        .await;
+        let (dir, repo) = temp_repo().await;
+        fs::create_dir_all(dir.join("Game")).await.unwrap();
+        fs::write(dir.join("Game/intact.pak"), b"intact").await.unwrap();
+        // Same size as the real file, different bytes
+        fs::write(dir.join("Game/damaged.pak"), b"damaged, xxxxx").await.unwrap();
+
+        let files = [
+            game_file("intact.pak", format!("{}/intact.pak", base_url), b"intact"),
+            game_file("damaged.pak", format!("{}/damaged.pak", base_url), b"damaged, fixed"),
+        ];
+        download(&repo, &files).await.unwrap();
+
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        assert_eq!(fs::read(dir.join("Game/intact.pak")).await.unwrap(), b"intact");
+        assert_eq!(fs::read(dir.join("Game/damaged.pak")).await.unwrap(), b"damaged, fixed");
+
+        fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[test]
+    fn manifest_paths_cannot_escape_the_game_directory() {
+        let game_dir = Path::new("/games/Game");
+        assert_eq!(
+            manifest_file_path(game_dir, "Content/Paks/a.pak"),
+            Some(game_dir.join("Content/Paks/a.pak"))
+        );
+        assert_eq!(manifest_file_path(game_dir, "../Other/game.exe"), None);
+        assert_eq!(manifest_file_path(game_dir, "Content/../../Other/game.exe"), None);
+        assert_eq!(manifest_file_path(game_dir, "./game.exe"), None);
+        assert_eq!(manifest_file_path(game_dir, "/etc/passwd"), None);
+        assert_eq!(manifest_file_path(game_dir, ""), None);
+        #[cfg(windows)]
+        assert_eq!(manifest_file_path(game_dir, "C:\\Windows\\win.ini"), None);
+    }
+
+    #[tokio::test]
+    async fn verify_and_plan_reject_paths_outside_the_game_directory() {
+        let (dir, repo) = temp_repo().await;
+        fs::create_dir_all(dir.join("Game")).await.unwrap();
+        fs::write(dir.join("Game/game.exe"), b"game").await.unwrap();
+        fs::write(dir.join("Outside.txt"), b"outside").await.unwrap();
+
+        let files = [
+            game_file("game.exe", String::new(), b"game"),
+            game_file("../Outside.txt", String::new(), b"outside"),
+        ];
+
+        let failures = repo.verify_game_files("Game", &files).await.unwrap();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].path, "../Outside.txt");
+
+        assert!(matches!(
+            repo.plan_update("Game", &files).await,
+            Err(GameVersionError::InvalidMetadata(_))
+        ));
+
+        fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn delete_removes_only_listed_files_and_emptied_directories() {
+        let (dir, repo) = temp_repo().await;
+        fs::create_dir_all(dir.join("Game/Content/Paks")).await.unwrap();
+        fs::create_dir_all(dir.join("Game/Saved")).await.unwrap();
+        fs::write(dir.join("Game/game.exe"), vec![0u8; 100]).await.unwrap();
+        fs::write(dir.join("Game/Content/Paks/a.pak"), vec![0u8; 250]).await.unwrap();
+        fs::write(dir.join("Game/Content/Paks/b.pak.part"), vec![0u8; 50]).await.unwrap();
+        fs::write(dir.join("Game/Saved/settings.ini"), b"keep").await.unwrap();
+        fs::write(dir.join("Game").join(GAME_METADATA_FILENAME), b"{}").await.unwrap();
+        fs::write(dir.join("Outside.txt"), b"keep").await.unwrap();
+
+        let files = vec![
+            "game.exe".to_string(),
+            "Content/Paks/a.pak".to_string(),
+            "Content/Paks/b.pak".to_string(),
+            "../Outside.txt".to_string(),
+        ];
+        let freed = repo.delete_game_files("Game", &files).await.unwrap();
+
+        assert_eq!(freed, 100 + 250 + 50 + 2);
+        assert!(!dir.join("Game/game.exe").exists());
+        assert!(!dir.join("Game/Content").exists());
+        assert!(!dir.join("Game").join(GAME_METADATA_FILENAME).exists());
+        // Files the manifest doesn't list stay, and so does their directory
+        assert!(dir.join("Game/Saved/settings.ini").exists());
+        assert!(dir.join("Outside.txt").exists());
+
+        fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn installed_size_sums_nested_files() {
+        let (dir, repo) = temp_repo().await;
+        assert_eq!(repo.installed_size("Game").await.unwrap(), 0);
+
+        fs::create_dir_all(dir.join("Game/Content/Paks")).await.unwrap();
+        fs::write(dir.join("Game/game.exe"), vec![0u8; 100]).await.unwrap();
+        fs::write(dir.join("Game/Content/Paks/a.pak"), vec![0u8; 250]).await.unwrap();
+        fs::write(dir.join("Game/Content/b.pak"), vec![0u8; 50]).await.unwrap();
+
+        assert_eq!(repo.installed_size("Game").await.unwrap(), 400);
+
+        fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn swap_replaces_install_with_staged_files() {
+        let (dir, repo) = temp_repo().await;
+        fs::create_dir_all(dir.join("Game")).await.unwrap();
+        fs::write(dir.join("Game/old.pak"), b"old").await.unwrap();
+
+        let staging = repo.staging_directory(7);
+        fs::create_dir_all(&staging).await.unwrap();
+        fs::write(staging.join("new.pak"), b"new").await.unwrap();
+
+        repo.swap_in_staged("Game", &staging).await.unwrap();
+
+        assert!(!dir.join("Game/old.pak").exists());
+        assert_eq!(fs::read(dir.join("Game/new.pak")).await.unwrap(), b"new");
+        assert!(!staging.exists());
+        assert!(!repo.staging_root().join("Game.previous").exists());
+
+        fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn truncated_staged_file_fails_verification_and_is_deleted() {
+        let (dir, repo) = temp_repo().await;
+        let staging = repo.staging_directory(5);
+        fs::create_dir_all(&staging).await.unwrap();
+
+        let contents = b"level data";
+        let file = |path: &str| GameFile {
+            path: path.to_string(),
+            download_url: String::new(),
+            size: Some(contents.len() as u64),
+            md5_hash: Some(base64::Engine::encode(
+                &base64::engine::general_purpose::STANDARD,
+                Md5::digest(contents),
+            )),
+        };
+        let files = vec![file("intact.pak"), file("truncated.pak")];
+        fs::write(staging.join("intact.pak"), contents).await.unwrap();
+        fs::write(staging.join("truncated.pak"), &contents[..4]).await.unwrap();
+
+        let reports = std::sync::Mutex::new(Vec::new());
+        let result = verify_staged_files(&staging, &files, &CancellationToken::new(), &|update| {
+            reports.lock().unwrap().push((update.phase, update.downloaded_files));
+        })
+        .await;
+
+        assert!(
+            matches!(&result, Err(GameVersionError::VerificationFailed { file, .. }) if file == "truncated.pak"),
+            "{:?}",
+            result
+        );
+        assert!(!staging.join("truncated.pak").exists());
+        assert!(staging.join("intact.pak").exists());
+        assert_eq!(*reports.lock().unwrap(), vec![(DownloadPhase::Verifying, 1)]);
+
+        fs::remove_dir_all(&dir).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn cleanup_removes_partial_installs_and_restores_parked_ones() {
+        let (dir, repo) = temp_repo().await;
+
+        // Crashed mid-download
+        fs::create_dir_all(repo.staging_directory(3)).await.unwrap();
+        fs::write(repo.staging_directory(3).join("data.pak.part"), b"par").await.unwrap();
+        // Crashed mid-swap, after the current install was parked
+        let parked = repo.staging_root().join("Game.previous");
+        fs::create_dir_all(&parked).await.unwrap();
+        fs::write(parked.join(GAME_METADATA_FILENAME), b"{}").await.unwrap();
+
+        let removed = repo.cleanup_staging().await.unwrap();
+
+        assert_eq!(removed, vec!["3".to_string()]);
+        assert!(dir.join("Game").join(GAME_METADATA_FILENAME).exists());
+        assert!(!repo.staging_root().exists());
+        assert!(repo.cleanup_staging().await.unwrap().is_empty());
+
+        fs::remove_dir_all(&dir).await.unwrap();
+    }
+}
@@ -0,0 +1,143 @@
+/// In-memory command history repository implementation
+///
+/// The cap comes from `AppConfig::command_history_size`. History lives in
+/// memory only, so a smaller cap takes effect from the next start.
+
+use crate::domain::models::{CommandRecord, DeviceId};
+use crate::domain::repositories::CommandHistoryRepository;
+use parking_lot::Mutex;
+use std::collections::{HashMap, VecDeque};
+
+pub struct InMemoryCommandHistoryRepository {
+    max_entries: usize,
+    entries: Mutex<HashMap<DeviceId, VecDeque<CommandRecord>>>,
+    /// History of disconnected devices, by serial
+    parked: Mutex<HashMap<String, VecDeque<CommandRecord>>>,
+}
+
+impl InMemoryCommandHistoryRepository {
+    pub fn new(max_entries: usize) -> Self {
+        Self {
+            max_entries,
+            entries: Mutex::new(HashMap::new()),
+            parked: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl CommandHistoryRepository for InMemoryCommandHistoryRepository {
+    fn record(&self, device_id: DeviceId, record: CommandRecord) {
+        let mut entries = self.entries.lock();
+        let history = entries.entry(device_id).or_default();
+        history.push_back(record);
+        while history.len() > self.max_entries {
+            history.pop_front();
+        }
+    }
+
+    fn recent_commands(&self, device_id: &DeviceId, limit: Option<usize>) -> Vec<CommandRecord> {
+        let entries = self.entries.lock();
+        let Some(history) = entries.get(device_id) else {
+            return Vec::new();
+        };
+
+        let limit = limit.unwrap_or(self.max_entries).min(self.max_entries);
+        history
+            .iter()
+            .skip(history.len().saturating_sub(limit))
+            .cloned()
+            .collect()
+    }
+
+    fn forget(&self, device_id: &DeviceId) {
+        self.entries.lock().remove(device_id);
+    }
+
+    fn park(&self, device_id: &DeviceId, serial: &str) {
+        if let Some(history) = self.entries.lock().remove(device_id) {
+            self.parked.lock().insert(serial.to_string(), history);
+        }
+    }
+
+    fn restore(&self, serial: &str, device_id: DeviceId) {
+        if let Some(history) = self.parked.lock().remove(serial) {
+            let mut entries = self.entries.lock();
+            let current = entries.entry(device_id).or_default();
+            // Anything recorded on the new connection is newer than what was parked
+            let mut merged = history;
+            merged.extend(current.drain(..));
+            while merged.len() > self.max_entries {
+                merged.pop_front();
+            }
+            *current = merged;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn record_n(history: &InMemoryCommandHistoryRepository, device_id: DeviceId, n: usize) {
+        for i in 0..n {
+            history.record(device_id, CommandRecord::new(Utc::now(), "ping", true, format!("#{}", i)));
+        }
+    }
+
+    fn messages(history: &InMemoryCommandHistoryRepository, device_id: &DeviceId) -> Vec<String> {
+        history
+            .recent_commands(device_id, None)
+            .iter()
+            .map(|r| r.message().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn keeps_only_the_newest_entries() {
+        let history = InMemoryCommandHistoryRepository::new(3);
+        let device_id = DeviceId::new();
+        record_n(&history, device_id, 5);
+
+        assert_eq!(messages(&history, &device_id), ["#2", "#3", "#4"]);
+    }
+
+    #[test]
+    fn recent_commands_honours_limit() {
+        let history = InMemoryCommandHistoryRepository::new(10);
+        let device_id = DeviceId::new();
+        record_n(&history, device_id, 4);
+
+        let recent = history.recent_commands(&device_id, Some(2));
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent.last().unwrap().message(), "#3");
+
+        assert_eq!(history.recent_commands(&device_id, Some(100)).len(), 4);
+        assert!(history.recent_commands(&DeviceId::new(), None).is_empty());
+    }
+
+    #[test]
+    fn forget_clears_a_device() {
+        let history = InMemoryCommandHistoryRepository::new(10);
+        let device_id = DeviceId::new();
+        record_n(&history, device_id, 2);
+
+        history.forget(&device_id);
+        assert!(history.recent_commands(&device_id, None).is_empty());
+    }
+
+    #[test]
+    fn history_follows_a_device_across_reconnects() {
+        let history = InMemoryCommandHistoryRepository::new(3);
+        let (first, second) = (DeviceId::new(), DeviceId::new());
+        record_n(&history, first, 2);
+
+        history.park(&first, "1WMHH000000001");
+        assert!(history.recent_commands(&first, None).is_empty());
+
+        history.record(second, CommandRecord::new(Utc::now(), "ping", true, "new"));
+        history.restore("1WMHH000000001", second);
+
+        assert_eq!(messages(&history, &second), ["#0", "#1", "new"]);
+    }
+}
@@ -3,7 +3,7 @@
 /// Uses DashMap for thread-safe, lock-free access with dual indexing.
 
 use crate::domain::models::{Device, DeviceId, Serial};
-use crate::domain::repositories::{DeviceRepository, RepositoryError};
+use crate::domain::repositories::{DeviceRepository, DeviceSnapshot, RepositoryError};
 use async_trait::async_trait;
 use dashmap::DashMap;
 use std::sync::Arc;
@@ -16,13 +16,18 @@ use std::sync::Arc;
 /// - Thread-safe with DashMap (lock-free reads)
 /// - Configurable capacity limit
 /// - Uses Arc<Device> internally for efficient cloning
+/// - Keeps the last state of disconnected devices, by serial, for reconnects
 pub struct InMemoryDeviceRepository {
     /// Primary index: device_id -> Arc<Device>
     by_id: Arc<DashMap<DeviceId, Arc<Device>>>,
     /// Secondary index: serial -> device_id (for O(1) serial lookups)
     by_serial: Arc<DashMap<Serial, DeviceId>>,
+    /// Last state of devices that have disconnected, at most `max_capacity`
+    disconnected: Arc<DashMap<Serial, Arc<Device>>>,
     /// Maximum number of devices allowed
     max_capacity: usize,
+    /// Shared by writers, taken exclusively by `snapshot` so it sees all three maps at once
+    snapshot_lock: parking_lot::RwLock<()>,
 }
 
 impl InMemoryDeviceRepository {
@@ -36,7 +41,9 @@ impl InMemoryDeviceRepository {
         Self {
             by_id: Arc::new(DashMap::new()),
             by_serial: Arc::new(DashMap::new()),
+            disconnected: Arc::new(DashMap::new()),
             max_capacity,
+            snapshot_lock: parking_lot::RwLock::new(()),
         }
     }
 
@@ -69,6 +76,13 @@ impl DeviceRepository for InMemoryDeviceRepository {
         }
     }
 
+    async fn find_last_known(&self, serial: &Serial) -> Result<Option<Arc<Device>>, RepositoryError> {
+        if let Some(device) = self.find_by_serial(serial).await? {
+            return Ok(Some(device));
+        }
+        Ok(self.disconnected.get(serial).map(|entry| Arc::clone(entry.value())))
+    }
+
     async fn find_all(&self) -> Result<Vec<Arc<Device>>, RepositoryError> {
         Ok(self
             .by_id
@@ -77,7 +91,21 @@ impl DeviceRepository for InMemoryDeviceRepository {
             .collect())
     }
 
+    async fn snapshot(&self) -> Result<DeviceSnapshot, RepositoryError> {
+        let _exclusive = self.snapshot_lock.write();
+
+        Ok(DeviceSnapshot {
+            connected: self.by_id.iter().map(|entry| Arc::clone(entry.value())).collect(),
+            disconnected: self
+                .disconnected
+                .iter()
+                .map(|entry| Arc::clone(entry.value()))
+                .collect(),
+        })
+    }
+
     async fn save(&self, device: Device) -> Result<(), RepositoryError> {
+        let _writer = self.snapshot_lock.read();
         let id = device.id();
         let serial = device.serial().clone();
 
@@ -100,6 +128,9 @@ impl DeviceRepository for InMemoryDeviceRepository {
         // Update primary index
         self.by_id.insert(id, Arc::new(device));
 
+        // A connected device supersedes what was kept from its last connection
+        self.disconnected.remove(&serial);
+
         // Update secondary index
         self.by_serial.insert(serial, id);
 
@@ -107,9 +138,26 @@ impl DeviceRepository for InMemoryDeviceRepository {
     }
 
     async fn remove(&self, id: DeviceId) -> Result<(), RepositoryError> {
+        let _writer = self.snapshot_lock.read();
         if let Some((_, device)) = self.by_id.remove(&id) {
-            // Clean up secondary index
-            self.by_serial.remove(device.serial());
+            // Clean up secondary index, unless the device has already reconnected
+            self.by_serial.remove_if(device.serial(), |_, indexed_id| *indexed_id == id);
+
+            if self.by_serial.contains_key(device.serial()) {
+                return Ok(());
+            }
+
+            if self.disconnected.len() >= self.max_capacity {
+                let oldest = self
+                    .disconnected
+                    .iter()
+                    .min_by_key(|entry| entry.value().last_seen())
+                    .map(|entry| entry.key().clone());
+                if let Some(serial) = oldest {
+                    self.disconnected.remove(&serial);
+                }
+            }
+            self.disconnected.insert(device.serial().clone(), device);
         }
 
         Ok(())
@@ -119,3 +167,83 @@ impl DeviceRepository for InMemoryDeviceRepository {
         Ok(self.by_id.len())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::Battery;
+
+    fn quest(serial: &Serial, ip: &str) -> Device {
+        Device::new(DeviceId::new(), serial.clone(), "Quest 3".to_string(), "1.0.0".to_string())
+            .with_ip_address(ip.parse().unwrap())
+    }
+
+    #[tokio::test]
+    async fn reconnect_from_new_ip_keeps_device_state() {
+        let repo = InMemoryDeviceRepository::new();
+        let serial = Serial::new("2G0YC5ZF9K0123".to_string()).unwrap();
+
+        let first = quest(&serial, "10.0.0.5")
+            .with_custom_name(Some("Bay 3".to_string()))
+            .with_battery(Battery::new(64, false).unwrap());
+        let first_id = first.id();
+        repo.save(first).await.unwrap();
+        repo.remove(first_id).await.unwrap();
+        assert!(repo.find_by_serial(&serial).await.unwrap().is_none());
+
+        let previous = repo.find_last_known(&serial).await.unwrap().unwrap();
+        let second_id = DeviceId::new();
+        let second = previous.as_ref().clone().reconnect(
+            second_id,
+            Some("10.0.0.9".parse().unwrap()),
+            "Quest 3".to_string(),
+            "1.1.0".to_string(),
+        );
+        repo.save(second).await.unwrap();
+
+        let device = repo.find_by_serial(&serial).await.unwrap().unwrap();
+        assert_eq!(device.id(), second_id);
+        assert_eq!(device.custom_name(), Some("Bay 3"));
+        assert_eq!(device.battery().map(|b| b.level()), Some(64));
+        assert_eq!(device.ip_address(), Some("10.0.0.9".parse().unwrap()));
+        assert_eq!(device.version(), "1.1.0");
+    }
+
+    #[tokio::test]
+    async fn snapshot_includes_disconnected_devices() {
+        let repo = InMemoryDeviceRepository::new();
+        let online = Serial::new("2G0YC5ZF9K0001".to_string()).unwrap();
+        let offline = Serial::new("2G0YC5ZF9K0002".to_string()).unwrap();
+
+        repo.save(quest(&online, "10.0.0.5")).await.unwrap();
+        let gone = quest(&offline, "10.0.0.6").with_battery(Battery::new(12, false).unwrap());
+        let gone_id = gone.id();
+        repo.save(gone).await.unwrap();
+        repo.remove(gone_id).await.unwrap();
+
+        let snapshot = repo.snapshot().await.unwrap();
+        assert_eq!(snapshot.connected.len(), 1);
+        assert_eq!(snapshot.connected[0].serial(), &online);
+        assert_eq!(snapshot.disconnected.len(), 1);
+        assert_eq!(snapshot.disconnected[0].battery().map(|b| b.level()), Some(12));
+    }
+
+    #[tokio::test]
+    async fn stale_connection_closing_late_keeps_new_connection_indexed() {
+        let repo = InMemoryDeviceRepository::new();
+        let serial = Serial::new("2G0YC5ZF9K0123".to_string()).unwrap();
+
+        let stale = quest(&serial, "10.0.0.5");
+        let stale_id = stale.id();
+        repo.save(stale).await.unwrap();
+        let current = quest(&serial, "10.0.0.9");
+        let current_id = current.id();
+        repo.save(current).await.unwrap();
+
+        repo.remove(stale_id).await.unwrap();
+
+        let device = repo.find_by_serial(&serial).await.unwrap().unwrap();
+        assert_eq!(device.id(), current_id);
+        assert_eq!(repo.find_last_known(&serial).await.unwrap().unwrap().id(), current_id);
+    }
+}
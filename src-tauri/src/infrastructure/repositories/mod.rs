@@ -8,6 +8,7 @@ mod fs_apk_repo;
 mod fs_client_apk_repo;
 mod fs_game_version_repo;
 mod sqlite_game_cache_repo;
+mod in_memory_command_history_repo;
 
 // Re-export repository implementations
 pub use in_memory_device_repo::InMemoryDeviceRepository;
@@ -16,3 +17,4 @@ pub use fs_apk_repo::FsApkRepository;
 pub use fs_client_apk_repo::FsClientApkRepository;
 pub use fs_game_version_repo::FsGameVersionRepository;
 pub use sqlite_game_cache_repo::SqliteGameCacheRepository;
+pub use in_memory_command_history_repo::InMemoryCommandHistoryRepository;
@@ -1,5 +1,6 @@
 use crate::domain::models::Serial;
-use crate::domain::repositories::device_name_repository::{DeviceNameRepository, Result};
+use crate::domain::repositories::device_name_repository::{DeviceAnnotations, DeviceNameRepository, Result};
+use crate::domain::repositories::RepositoryError;
 use async_trait::async_trait;
 use sqlx::{Row, SqlitePool};
 
@@ -55,4 +56,122 @@ impl DeviceNameRepository for SqliteDeviceNameRepository {
 
         Ok(())
     }
+
+    async fn get_annotations(&self, serial: &Serial) -> Result<DeviceAnnotations> {
+        let row = sqlx::query("SELECT tags, note FROM device_annotations WHERE serial = ?")
+            .bind(serial.as_str())
+            .fetch_optional(&self.pool)
+            .await?;
+
+        let Some(row) = row else {
+            return Ok(DeviceAnnotations::default());
+        };
+
+        let tags: String = row.try_get("tags")?;
+        Ok(DeviceAnnotations {
+            tags: serde_json::from_str(&tags).map_err(|e| RepositoryError::SerializationError(e.to_string()))?,
+            note: row.try_get("note")?,
+        })
+    }
+
+    async fn set_tags(&self, serial: &Serial, tags: &[String]) -> Result<()> {
+        let tags = serde_json::to_string(tags).map_err(|e| RepositoryError::SerializationError(e.to_string()))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO device_annotations (serial, tags)
+            VALUES (?, ?)
+            ON CONFLICT(serial) DO UPDATE SET tags = excluded.tags
+            "#,
+        )
+        .bind(serial.as_str())
+        .bind(tags)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn set_note(&self, serial: &Serial, note: Option<String>) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO device_annotations (serial, note)
+            VALUES (?, ?)
+            ON CONFLICT(serial) DO UPDATE SET note = excluded.note
+            "#,
+        )
+        .bind(serial.as_str())
+        .bind(note)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_expected_package(&self, serial: &Serial) -> Result<Option<String>> {
+        let result: Option<String> =
+            sqlx::query("SELECT package_name FROM device_expected_apps WHERE serial = ?")
+                .bind(serial.as_str())
+                .fetch_optional(&self.pool)
+                .await?
+                .map(|row| row.try_get("package_name"))
+                .transpose()?;
+
+        Ok(result)
+    }
+
+    async fn set_expected_package(&self, serial: &Serial, package_name: Option<&str>) -> Result<()> {
+        match package_name {
+            Some(package_name) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO device_expected_apps (serial, package_name)
+                    VALUES (?, ?)
+                    ON CONFLICT(serial) DO UPDATE SET package_name = excluded.package_name
+                    "#,
+                )
+                .bind(serial.as_str())
+                .bind(package_name)
+                .execute(&self.pool)
+                .await?;
+            }
+            None => {
+                sqlx::query("DELETE FROM device_expected_apps WHERE serial = ?")
+                    .bind(serial.as_str())
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::database::Database;
+
+    #[tokio::test]
+    async fn expected_package_round_trips_and_clears() {
+        let dir = std::env::temp_dir().join(format!("arceus-names-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let database = Database::new(dir.join("arceus.db")).await.unwrap();
+        let repo = SqliteDeviceNameRepository::new(database.pool().clone());
+        let serial = Serial::new("1WMHH000000001".to_string()).unwrap();
+
+        assert_eq!(repo.get_expected_package(&serial).await.unwrap(), None);
+
+        repo.set_expected_package(&serial, Some("com.example.game")).await.unwrap();
+        repo.set_expected_package(&serial, Some("com.example.other")).await.unwrap();
+        assert_eq!(
+            repo.get_expected_package(&serial).await.unwrap().as_deref(),
+            Some("com.example.other")
+        );
+
+        repo.set_expected_package(&serial, None).await.unwrap();
+        assert_eq!(repo.get_expected_package(&serial).await.unwrap(), None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
@@ -1,7 +1,8 @@
-use crate::application::dto::game_version::{CachedGameEntry, GameAssignment, LocalGameMetadata};
+use crate::application::dto::game_version::{CachedGameEntry, GameAssignment, GameStats, LocalGameMetadata};
 use crate::domain::repositories::RepositoryError;
 use chrono::{DateTime, Utc};
 use sqlx::{Row, SqlitePool};
+use std::collections::HashMap;
 
 pub struct SqliteGameCacheRepository {
     pool: SqlitePool,
@@ -88,6 +89,30 @@ impl SqliteGameCacheRepository {
         Ok(())
     }
 
+    /// Mark a game as no longer installed, forgetting its measured size
+    pub async fn clear_local_metadata(&self, game_id: i32) -> Result<(), RepositoryError> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE game_cache
+            SET installed_version_id = NULL, installed_version = NULL, installed_at = NULL
+            WHERE game_id = ?
+            "#,
+        )
+        .bind(game_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("UPDATE game_stats SET installed_size_bytes = NULL WHERE game_id = ?")
+            .bind(game_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
     /// Sync cache from Alakazam assignments with local metadata lookup
     pub async fn sync_from_assignments<F>(
         &self,
@@ -136,6 +161,108 @@ impl SqliteGameCacheRepository {
         Ok(())
     }
 
+    /// Get the locally tracked size and play history of every game, by game ID
+    pub async fn get_game_stats(&self) -> Result<HashMap<i32, GameStats>, RepositoryError> {
+        let rows = sqlx::query("SELECT game_id, installed_size_bytes, last_played FROM game_stats")
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.into_iter()
+            .map(|r| -> Result<(i32, GameStats), RepositoryError> {
+                let game_id: i32 = r.try_get("game_id")?;
+                let installed_size_bytes: Option<i64> = r.try_get("installed_size_bytes")?;
+                let last_played_str: Option<String> = r.try_get("last_played")?;
+
+                let stats = GameStats {
+                    installed_size_bytes: installed_size_bytes.map(|bytes| bytes.max(0) as u64),
+                    last_played: Self::parse_timestamp(last_played_str)?,
+                };
+                Ok((game_id, stats))
+            })
+            .collect()
+    }
+
+    /// Store the measured on-disk size of a game
+    pub async fn set_installed_size(&self, game_id: i32, bytes: u64) -> Result<(), RepositoryError> {
+        sqlx::query(
+            r#"
+            INSERT INTO game_stats (game_id, installed_size_bytes) VALUES (?, ?)
+            ON CONFLICT(game_id) DO UPDATE SET installed_size_bytes = excluded.installed_size_bytes
+            "#,
+        )
+        .bind(game_id)
+        .bind(bytes.min(i64::MAX as u64) as i64)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Record that a game was launched at `played_at`
+    pub async fn record_played(&self, game_id: i32, played_at: DateTime<Utc>) -> Result<(), RepositoryError> {
+        sqlx::query(
+            r#"
+            INSERT INTO game_stats (game_id, last_played) VALUES (?, ?)
+            ON CONFLICT(game_id) DO UPDATE SET last_played = excluded.last_played
+            "#,
+        )
+        .bind(game_id)
+        .bind(played_at.to_rfc3339())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get the release channel each game follows, for games that don't use the arcade's own
+    pub async fn get_game_channels(&self) -> Result<HashMap<i32, String>, RepositoryError> {
+        let rows = sqlx::query("SELECT game_id, channel FROM game_channels")
+            .fetch_all(&self.pool)
+            .await?;
+
+        rows.into_iter()
+            .map(|r| -> Result<(i32, String), RepositoryError> {
+                Ok((r.try_get("game_id")?, r.try_get("channel")?))
+            })
+            .collect()
+    }
+
+    /// Get the release channel a game follows, `None` for the arcade's own
+    pub async fn get_game_channel(&self, game_id: i32) -> Result<Option<String>, RepositoryError> {
+        let channel = sqlx::query_scalar("SELECT channel FROM game_channels WHERE game_id = ?")
+            .bind(game_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(channel)
+    }
+
+    /// Make a game follow `channel`, or with `None` go back to the arcade's own
+    pub async fn set_game_channel(&self, game_id: i32, channel: Option<&str>) -> Result<(), RepositoryError> {
+        match channel {
+            Some(channel) => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO game_channels (game_id, channel) VALUES (?, ?)
+                    ON CONFLICT(game_id) DO UPDATE SET channel = excluded.channel
+                    "#,
+                )
+                .bind(game_id)
+                .bind(channel)
+                .execute(&self.pool)
+                .await?;
+            }
+            None => {
+                sqlx::query("DELETE FROM game_channels WHERE game_id = ?")
+                    .bind(game_id)
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Check if the cache is empty
     pub async fn is_empty(&self) -> Result<bool, RepositoryError> {
         let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM game_cache")
@@ -155,11 +282,7 @@ impl SqliteGameCacheRepository {
         let installed_version: Option<String> = r.try_get("installed_version")?;
         let installed_at_str: Option<String> = r.try_get("installed_at")?;
 
-        let installed_at = installed_at_str
-            .map(|s| DateTime::parse_from_rfc3339(&s)
-                .map_err(|e| RepositoryError::SerializationError(e.to_string()))
-                .map(|dt| dt.with_timezone(&Utc)))
-            .transpose()?;
+        let installed_at = Self::parse_timestamp(installed_at_str)?;
 
         let entry = CachedGameEntry {
             game_id,
@@ -173,4 +296,96 @@ impl SqliteGameCacheRepository {
 
         Ok(entry)
     }
+
+    fn parse_timestamp(value: Option<String>) -> Result<Option<DateTime<Utc>>, RepositoryError> {
+        value
+            .map(|s| DateTime::parse_from_rfc3339(&s)
+                .map_err(|e| RepositoryError::SerializationError(e.to_string()))
+                .map(|dt| dt.with_timezone(&Utc)))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::dto::game_version::VersionInfo;
+    use crate::infrastructure::database::Database;
+    use chrono::TimeZone;
+
+    async fn temp_repo() -> (std::path::PathBuf, SqliteGameCacheRepository) {
+        let dir = std::env::temp_dir().join(format!("arceus-cache-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let database = Database::new(dir.join("arceus.db")).await.unwrap();
+        (dir, SqliteGameCacheRepository::new(database.pool().clone()))
+    }
+
+    fn assignment(game_id: i32, version: &str) -> GameAssignment {
+        GameAssignment {
+            game_id,
+            game_name: format!("Game {}", game_id),
+            assigned_version: VersionInfo {
+                version_id: 1,
+                version: version.to_string(),
+                gcs_path: format!("games/{}/{}", game_id, version),
+                release_date: Utc::now(),
+            },
+            _current_version: None,
+            _background_image_url: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn size_and_last_played_are_stored_independently() {
+        let (dir, repo) = temp_repo().await;
+        let played_at = Utc.with_ymd_and_hms(2026, 3, 1, 18, 30, 0).unwrap();
+
+        repo.set_installed_size(1, 4096).await.unwrap();
+        repo.record_played(1, played_at).await.unwrap();
+        repo.record_played(2, played_at).await.unwrap();
+        repo.set_installed_size(1, 8192).await.unwrap();
+
+        let stats = repo.get_game_stats().await.unwrap();
+        assert_eq!(stats[&1].installed_size_bytes, Some(8192));
+        assert_eq!(stats[&1].last_played, Some(played_at));
+        assert_eq!(stats[&2].installed_size_bytes, None);
+        assert_eq!(stats[&2].last_played, Some(played_at));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn server_sync_leaves_stats_alone() {
+        let (dir, repo) = temp_repo().await;
+        let played_at = Utc.with_ymd_and_hms(2026, 3, 1, 18, 30, 0).unwrap();
+        repo.sync_from_assignments(vec![assignment(1, "1.0.0")], |_| None).await.unwrap();
+        repo.set_installed_size(1, 4096).await.unwrap();
+        repo.record_played(1, played_at).await.unwrap();
+
+        repo.sync_from_assignments(vec![assignment(1, "1.1.0")], |_| None).await.unwrap();
+
+        let stats = repo.get_game_stats().await.unwrap();
+        assert_eq!(stats[&1].installed_size_bytes, Some(4096));
+        assert_eq!(stats[&1].last_played, Some(played_at));
+        assert_eq!(repo.get_all_entries().await.unwrap()[0].assigned_version, "1.1.0");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[tokio::test]
+    async fn clearing_local_metadata_forgets_the_size_but_not_play_history() {
+        let (dir, repo) = temp_repo().await;
+        let played_at = Utc.with_ymd_and_hms(2026, 3, 1, 18, 30, 0).unwrap();
+        repo.sync_from_assignments(vec![assignment(1, "1.0.0")], |_| None).await.unwrap();
+        repo.set_installed_size(1, 4096).await.unwrap();
+        repo.record_played(1, played_at).await.unwrap();
+
+        repo.clear_local_metadata(1).await.unwrap();
+
+        let stats = repo.get_game_stats().await.unwrap();
+        assert_eq!(stats[&1].installed_size_bytes, None);
+        assert_eq!(stats[&1].last_played, Some(played_at));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
 }
@@ -18,6 +18,8 @@ pub struct XiaoPort {
     pub port: String,
     /// Operating mode
     pub mode: XiaoMode,
+    /// USB serial number, if the OS reports one
+    pub serial_number: Option<String>,
 }
 
 /// Detects connected XIAO BLE nRF52840 boards
@@ -43,6 +45,7 @@ impl XiaoDetector {
                         devices.push(XiaoPort {
                             port: port.port_name,
                             mode,
+                            serial_number: usb_info.serial_number.clone(),
                         });
                     }
                 }
@@ -8,6 +8,7 @@
 /// 2. Build init packet in memory (no ZIP intermediate)
 /// 3. 1200-baud touch to enter bootloader mode
 /// 4. Upload via serial DFU: START → INIT → DATA → STOP
+/// 5. Wait for the bootloader to accept the image CRC and boot it
 
 mod crc16;
 mod hci;
@@ -16,43 +17,298 @@ mod protocol;
 mod slip;
 mod transport;
 
-use super::{FirmwarePatcher, Result, SensorError, XiaoDetector, XiaoMode};
+use super::{FirmwarePatcher, Result, SensorError, SerialComm, XiaoDetector, XiaoMode};
+use crate::app::models::DfuConfig;
 use std::path::Path;
 use std::sync::Arc;
-use transport::DfuTransport;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use transport::{DfuTransport, UploadLimits};
 
-/// Maximum retry attempts for DFU upload (port may be temporarily busy)
-const MAX_UPLOAD_RETRIES: u32 = 3;
+/// Longest wait between retries, however far the backoff has grown
+const MAX_RETRY_DELAY_MS: u64 = 60_000;
 
-/// Delay between retry attempts in seconds
-const RETRY_DELAY_SECS: u64 = 3;
+/// Error text meaning the port is held by someone else or not back yet after
+/// the 1200-baud touch, as worded by Linux, macOS and Windows respectively.
+/// Matched case-insensitively.
+const PORT_BUSY_ERRORS: &[&str] = &[
+    "device or resource busy",
+    "resource busy",
+    "resource temporarily unavailable",
+    "could not open port",
+    "no such file or directory",
+    "permission denied",
+    "access is denied",
+    "the system cannot find the file specified",
+];
+
+/// Phase of the DFU protocol an upload is in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DfuPhase {
+    Init,
+    Data,
+    Stop,
+}
+
+impl DfuPhase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DfuPhase::Init => "init",
+            DfuPhase::Data => "data",
+            DfuPhase::Stop => "stop",
+        }
+    }
+}
+
+/// Progress of a single DFU upload attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DfuProgress {
+    pub phase: DfuPhase,
+    pub bytes_sent: usize,
+    pub total_bytes: usize,
+    /// 1-based upload attempt; progress restarts from 0 on each retry
+    pub attempt: u32,
+}
+
+/// Callback receiving DFU progress updates.
+pub type DfuProgressCallback = Arc<dyn Fn(DfuProgress) + Send + Sync>;
+
+/// A board to flash as part of a batch.
+#[derive(Debug, Clone)]
+pub struct BoardTarget {
+    pub port: String,
+    /// BLE device name patched into this board's firmware
+    pub device_name: String,
+}
+
+/// Outcome of flashing one board in a batch.
+#[derive(Debug)]
+pub struct BoardUploadResult {
+    pub port: String,
+    pub device_name: String,
+    pub result: Result<()>,
+}
+
+/// Whether an upload actually flashed the board.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UploadOutcome {
+    Flashed,
+    /// The board already runs the requested version with the requested name
+    AlreadyCurrent { version: String },
+}
 
 /// Handles DFU firmware upload to XIAO BLE nRF52840 boards.
 pub struct DfuUploader;
 
 impl DfuUploader {
     /// Complete firmware upload workflow: patch name, upload via serial DFU.
+    ///
+    /// With `skip_if_version`, a board that already reports that firmware
+    /// version and device name is left untouched.
     pub async fn upload_with_name(
         port: Option<&str>,
         firmware_path: &Path,
         device_name: &str,
-        on_progress: Arc<dyn Fn(f32) + Send + Sync>,
-    ) -> Result<()> {
+        skip_if_version: Option<&str>,
+        config: &DfuConfig,
+        cancel: &CancellationToken,
+        on_progress: DfuProgressCallback,
+    ) -> Result<UploadOutcome> {
+        FirmwarePatcher::validate_name(device_name)?;
+
         let firmware = tokio::fs::read(firmware_path)
             .await
             .map_err(SensorError::Io)?;
 
         tracing::info!("Loaded firmware: {} bytes", firmware.len());
 
-        let patched = FirmwarePatcher::patch_device_name(&firmware, device_name)?;
-        tracing::info!("Patched device name: '{}'", device_name);
-
         let port_name = match port {
             Some(p) => p.to_string(),
             None => XiaoDetector::find_first()?.port,
         };
 
-        Self::upload_with_retry(&patched, &port_name, on_progress).await?;
+        if let Some(version) = skip_if_version {
+            if Self::is_already_current(&port_name, version, device_name).await {
+                tracing::info!(
+                    "{} already runs firmware '{}' as '{}', skipping upload",
+                    port_name,
+                    version,
+                    device_name
+                );
+                return Ok(UploadOutcome::AlreadyCurrent {
+                    version: version.to_string(),
+                });
+            }
+        }
+
+        Self::patch_and_upload(&firmware, &port_name, device_name, config, cancel, on_progress)
+            .await?;
+        Ok(UploadOutcome::Flashed)
+    }
+
+    /// Whether the board on `port` reports `version` and `device_name`.
+    /// Boards in bootloader mode, or that can't be queried, are treated as outdated.
+    async fn is_already_current(port: &str, version: &str, device_name: &str) -> bool {
+        let in_bootloader = XiaoDetector::find_all()
+            .iter()
+            .any(|d| d.port == port && d.mode == XiaoMode::Bootloader);
+        if in_bootloader {
+            tracing::info!("{} is in bootloader mode, cannot read firmware version", port);
+            return false;
+        }
+
+        let port_name = port.to_string();
+        let identity = tokio::task::spawn_blocking(move || {
+            SerialComm::read_firmware_identity(&port_name)
+        })
+        .await;
+
+        match identity {
+            Ok(Ok(identity)) => {
+                tracing::info!(
+                    "{} reports firmware {:?} as {:?}",
+                    port,
+                    identity.version,
+                    identity.device_name
+                );
+                identity.version.as_deref() == Some(version)
+                    && identity.device_name.as_deref() == Some(device_name)
+            }
+            Ok(Err(e)) => {
+                tracing::warn!("Could not read firmware version from {}: {}", port, e);
+                false
+            }
+            Err(e) => {
+                tracing::warn!("Firmware version task panicked: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Build a batch covering every XIAO currently connected in normal mode.
+    ///
+    /// `name_fn` receives each board's 0-based index and port and returns the
+    /// device name to patch in. Boards already in bootloader mode are skipped,
+    /// since there's no way to tell which sensor they are.
+    pub fn targets_for_all(name_fn: impl Fn(usize, &str) -> String) -> Result<Vec<BoardTarget>> {
+        let mut ports: Vec<String> = XiaoDetector::find_normal()
+            .into_iter()
+            .map(|d| d.port)
+            .collect();
+
+        if ports.is_empty() {
+            return Err(SensorError::NoDeviceFound);
+        }
+
+        // Numbered in port order, so names only stay put while boards stay on the same ports
+        ports.sort_by(|a, b| port_order(a, b));
+
+        Ok(ports
+            .into_iter()
+            .enumerate()
+            .map(|(index, port)| BoardTarget {
+                device_name: name_fn(index, &port),
+                port,
+            })
+            .collect())
+    }
+
+    /// Flash several boards one after another, each with its own device name.
+    ///
+    /// Every board gets its own 1200-baud touch and retry budget; a failure on
+    /// one port is recorded and the batch moves on to the next board. Once
+    /// `cancel` fires, the board being flashed and all remaining ones fail.
+    pub async fn upload_batch(
+        firmware_path: &Path,
+        boards: &[BoardTarget],
+        config: &DfuConfig,
+        cancel: &CancellationToken,
+        on_progress: Arc<dyn Fn(&str, DfuProgress) + Send + Sync>,
+    ) -> Result<Vec<BoardUploadResult>> {
+        let firmware = tokio::fs::read(firmware_path)
+            .await
+            .map_err(SensorError::Io)?;
+
+        tracing::info!(
+            "Loaded firmware: {} bytes, flashing {} board(s)",
+            firmware.len(),
+            boards.len()
+        );
+
+        let mut results = Vec::with_capacity(boards.len());
+
+        for board in boards {
+            if cancel.is_cancelled() {
+                results.push(BoardUploadResult {
+                    port: board.port.clone(),
+                    device_name: board.device_name.clone(),
+                    result: Err(SensorError::UploadFailed("cancelled".to_string())),
+                });
+                continue;
+            }
+
+            let port = board.port.clone();
+            let progress = on_progress.clone();
+            let board_progress: DfuProgressCallback =
+                Arc::new(move |update| progress(&port, update));
+
+            let result = Self::patch_and_upload(
+                &firmware,
+                &board.port,
+                &board.device_name,
+                config,
+                cancel,
+                board_progress,
+            )
+            .await;
+
+            if let Err(e) = &result {
+                tracing::error!(
+                    "Firmware upload failed on {} ('{}'): {}",
+                    board.port,
+                    board.device_name,
+                    e
+                );
+            }
+
+            results.push(BoardUploadResult {
+                port: board.port.clone(),
+                device_name: board.device_name.clone(),
+                result,
+            });
+        }
+
+        Ok(results)
+    }
+
+    /// Read the current application image off a board.
+    ///
+    /// The legacy Nordic serial DFU protocol spoken by the XIAO bootloader is
+    /// write-only (START → INIT → DATA → STOP) and the application firmware has
+    /// no dump command, so this fails rather than returning a partial image.
+    pub async fn read_firmware(port: &str) -> Result<Vec<u8>> {
+        if !XiaoDetector::find_all().iter().any(|d| d.port == port) {
+            return Err(SensorError::NoDeviceFound);
+        }
+
+        Err(SensorError::UploadFailed(
+            "bootloader doesn't support read-back".to_string(),
+        ))
+    }
+
+    /// Patch the device name into `firmware` and flash it to `port`.
+    async fn patch_and_upload(
+        firmware: &[u8],
+        port: &str,
+        device_name: &str,
+        config: &DfuConfig,
+        cancel: &CancellationToken,
+        on_progress: DfuProgressCallback,
+    ) -> Result<()> {
+        let patched = FirmwarePatcher::patch_device_name(firmware, device_name)?;
+        tracing::info!("Patched device name: '{}'", device_name);
+
+        Self::upload_with_retry(&patched, port, config, cancel, on_progress).await?;
 
         tracing::info!(
             "Firmware upload complete for device '{}'",
@@ -66,28 +322,50 @@ impl DfuUploader {
     async fn upload_with_retry(
         firmware: &[u8],
         port: &str,
-        on_progress: Arc<dyn Fn(f32) + Send + Sync>,
+        config: &DfuConfig,
+        cancel: &CancellationToken,
+        on_progress: DfuProgressCallback,
     ) -> Result<()> {
+        let max_attempts = config.max_attempts.max(1);
         let mut last_error = None;
 
-        for attempt in 0..MAX_UPLOAD_RETRIES {
+        for attempt in 0..max_attempts {
             if attempt > 0 {
+                let delay = retry_delay(config, attempt);
                 tracing::info!(
-                    "Retrying DFU upload (attempt {}/{}), waiting {}s...",
+                    "Retrying DFU upload (attempt {}/{}), waiting {:.1}s...",
                     attempt + 1,
-                    MAX_UPLOAD_RETRIES,
-                    RETRY_DELAY_SECS
+                    max_attempts,
+                    delay.as_secs_f64()
                 );
-                tokio::time::sleep(std::time::Duration::from_secs(RETRY_DELAY_SECS)).await;
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {}
+                    _ = cancel.cancelled() => {
+                        return Err(SensorError::UploadFailed("cancelled".to_string()));
+                    }
+                }
             }
 
             // Clone data for the blocking task
             let fw = firmware.to_vec();
             let port_name = port.to_string();
             let progress = on_progress.clone();
+            let limits = UploadLimits::new(
+                Duration::from_secs(config.upload_timeout_secs),
+                cancel.clone(),
+            );
+
+            let attempt_number = attempt + 1;
 
             let result = tokio::task::spawn_blocking(move || {
-                Self::upload_blocking(&fw, &port_name, &*progress)
+                Self::upload_blocking(&fw, &port_name, limits, &|phase, bytes_sent, total_bytes| {
+                    progress(DfuProgress {
+                        phase,
+                        bytes_sent,
+                        total_bytes,
+                        attempt: attempt_number,
+                    })
+                })
             })
             .await
             .map_err(|e| {
@@ -97,12 +375,8 @@ impl DfuUploader {
             match result {
                 Ok(()) => return Ok(()),
                 Err(e) => {
-                    let err_msg = e.to_string();
-                    let is_port_busy = err_msg.contains("Device or resource busy")
-                        || err_msg.contains("could not open port");
-
-                    if is_port_busy && attempt < MAX_UPLOAD_RETRIES - 1 {
-                        tracing::warn!("Port busy, will retry: {}", err_msg);
+                    if is_port_busy(&e) && attempt < max_attempts - 1 {
+                        tracing::warn!("Port busy, will retry: {}", e);
                         last_error = Some(e);
                         continue;
                     }
@@ -118,16 +392,113 @@ impl DfuUploader {
     }
 
     /// Synchronous DFU upload — runs inside spawn_blocking.
-    fn upload_blocking(firmware: &[u8], port_name: &str, on_progress: &dyn Fn(f32)) -> Result<()> {
+    ///
+    /// Gives up with `UploadFailed` once `limits` time out or are cancelled,
+    /// closing the port so the board can be retried without replugging.
+    fn upload_blocking(
+        firmware: &[u8],
+        port_name: &str,
+        limits: UploadLimits,
+        on_progress: &dyn Fn(DfuPhase, usize, usize),
+    ) -> Result<()> {
         tracing::info!(
             "Starting DFU upload on {} (with 1200-baud touch)",
             port_name
         );
 
-        let mut transport = DfuTransport::open_with_touch(port_name)?;
+        let mut transport = DfuTransport::open_with_touch(port_name, limits)?;
+        let bootloader_port = transport.port_name().to_string();
+        let limits = transport.limits().clone();
         let result = protocol::run_dfu_upload(&mut transport, firmware, on_progress);
         transport.close();
 
-        result
+        // The bootloader only boots the image if its CRC matches the init packet
+        protocol::validate_image(&bootloader_port, result?, &limits)
+    }
+}
+
+/// Delay before the given retry (1 = first retry), growing by the backoff factor
+fn retry_delay(config: &DfuConfig, retry: u32) -> Duration {
+    let factor = config
+        .backoff_factor
+        .max(1.0)
+        .powi(retry.saturating_sub(1).min(i32::MAX as u32) as i32);
+    let delay_ms = (config.retry_delay_ms as f64 * factor).min(MAX_RETRY_DELAY_MS as f64);
+    Duration::from_millis(delay_ms as u64)
+}
+
+/// Whether an upload failed because the port was busy or not yet available,
+/// rather than the board rejecting the firmware
+fn is_port_busy(error: &SensorError) -> bool {
+    if let SensorError::Io(e) = error {
+        if matches!(
+            e.kind(),
+            std::io::ErrorKind::ResourceBusy | std::io::ErrorKind::WouldBlock
+        ) {
+            return true;
+        }
+    }
+
+    let message = error.to_string().to_ascii_lowercase();
+    PORT_BUSY_ERRORS.iter().any(|busy| message.contains(busy))
+}
+
+/// Order port names with their numbers compared numerically, so COM2 comes before COM10.
+fn port_order(a: &str, b: &str) -> std::cmp::Ordering {
+    fn split(port: &str) -> (&str, Option<u64>) {
+        let digits = port.len() - port.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+        let (name, number) = port.split_at(port.len() - digits);
+        (name, number.parse().ok())
+    }
+
+    split(a).cmp(&split(b)).then_with(|| a.cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_backs_off_up_to_the_cap() {
+        let config = DfuConfig {
+            max_attempts: 10,
+            retry_delay_ms: 2000,
+            backoff_factor: 2.0,
+            ..DfuConfig::default()
+        };
+
+        assert_eq!(retry_delay(&config, 1), Duration::from_secs(2));
+        assert_eq!(retry_delay(&config, 2), Duration::from_secs(4));
+        assert_eq!(retry_delay(&config, 3), Duration::from_secs(8));
+        assert_eq!(retry_delay(&config, 9), Duration::from_millis(MAX_RETRY_DELAY_MS));
+
+        // The default keeps the old fixed 3s delay
+        let config = DfuConfig::default();
+        assert_eq!(retry_delay(&config, 2), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn recognises_busy_ports_across_platforms() {
+        for message in [
+            "Device or resource busy",
+            "Resource busy",
+            "could not open port /dev/ttyACM0",
+            "Access is denied.",
+        ] {
+            let error = SensorError::UploadFailed(message.to_string());
+            assert!(is_port_busy(&error), "{}", message);
+        }
+
+        let busy = std::io::Error::from(std::io::ErrorKind::ResourceBusy);
+        assert!(is_port_busy(&SensorError::Io(busy)));
+
+        assert!(!is_port_busy(&SensorError::UploadFailed("CRC mismatch".to_string())));
+    }
+
+    #[test]
+    fn ports_sort_by_number_not_text() {
+        let mut ports = vec!["COM10", "COM2", "/dev/ttyACM1", "COM1", "/dev/ttyACM0"];
+        ports.sort_by(|a, b| port_order(a, b));
+        assert_eq!(ports, ["/dev/ttyACM0", "/dev/ttyACM1", "COM1", "COM2", "COM10"]);
     }
 }
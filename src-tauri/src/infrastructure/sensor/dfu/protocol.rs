@@ -3,10 +3,11 @@
 /// Orchestrates the full firmware upload sequence over a [`DfuTransport`].
 /// Opcodes and payload formats match `adafruit-nrfutil` (legacy Nordic DFU).
 
+use super::crc16::calc_crc16;
 use super::init_packet::build_init_packet;
-use super::transport::DfuTransport;
-use super::SensorError;
-use std::time::Duration;
+use super::transport::{DfuTransport, UploadLimits};
+use super::{DfuPhase, SensorError, XiaoDetector, XiaoMode};
+use std::time::{Duration, Instant};
 
 /// DFU packet opcodes (prepended as first u32 in every payload).
 const DFU_INIT_PACKET: u32 = 1;
@@ -26,18 +27,70 @@ const ERASE_MS_PER_PAGE: u64 = 90;
 const FLASH_PAGE_SIZE: usize = 4096;
 const MIN_ERASE_DELAY_MS: u64 = 500;
 
+/// How long the bootloader gets to check the image and boot it after STOP.
+const VALIDATION_TIMEOUT: Duration = Duration::from_secs(10);
+const VALIDATION_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Run the full upload, reporting `(phase, bytes_sent, total_bytes)` as it goes.
+///
+/// Returns the image CRC announced in the init packet, which the bootloader
+/// checks the flashed image against after STOP.
 pub fn run_dfu_upload(
     transport: &mut DfuTransport,
     firmware: &[u8],
-    on_progress: &dyn Fn(f32),
-) -> Result<(), SensorError> {
-    on_progress(0.0);
-    send_start(transport, firmware.len())?;
+    on_progress: &dyn Fn(DfuPhase, usize, usize),
+) -> Result<u16, SensorError> {
+    let total = firmware.len();
+    let image_crc = calc_crc16(firmware);
+
+    on_progress(DfuPhase::Init, 0, total);
+    send_start(transport, total)?;
     send_init(transport, firmware)?;
     send_data(transport, firmware, on_progress)?;
+    on_progress(DfuPhase::Stop, total, total);
     send_stop(transport)?;
-    on_progress(100.0);
-    Ok(())
+    Ok(image_crc)
+}
+
+/// Confirm the bootloader on `bootloader_port` accepted the flashed image.
+///
+/// Legacy serial DFU has no CRC readback: after STOP the bootloader computes
+/// the CRC of the received image, compares it with the one in the init packet
+/// and only boots it if they match. A board still in bootloader mode once the
+/// timeout passes rejected the image. The port must be closed before calling.
+pub fn validate_image(
+    bootloader_port: &str,
+    image_crc: u16,
+    limits: &UploadLimits,
+) -> Result<(), SensorError> {
+    let start = Instant::now();
+
+    loop {
+        limits.check()?;
+
+        let in_bootloader = XiaoDetector::find_all()
+            .iter()
+            .any(|d| d.port == bootloader_port && d.mode == XiaoMode::Bootloader);
+
+        if !in_bootloader {
+            tracing::info!(
+                "Image CRC 0x{:04X} accepted, bootloader left DFU mode after {:.1}s",
+                image_crc,
+                start.elapsed().as_secs_f32()
+            );
+            return Ok(());
+        }
+
+        if start.elapsed() > VALIDATION_TIMEOUT {
+            return Err(SensorError::UploadFailed(format!(
+                "Board on {} stayed in bootloader mode after upload; the flashed image \
+                 did not match CRC 0x{:04X}",
+                bootloader_port, image_crc
+            )));
+        }
+
+        std::thread::sleep(VALIDATION_POLL_INTERVAL);
+    }
 }
 
 /// START — announce application size so the bootloader can erase flash.
@@ -78,7 +131,11 @@ fn send_init(transport: &mut DfuTransport, firmware: &[u8]) -> Result<(), Sensor
 }
 
 /// DATA — stream firmware in 512-byte chunks with flash-write pacing.
-fn send_data(transport: &mut DfuTransport, firmware: &[u8], on_progress: &dyn Fn(f32)) -> Result<(), SensorError> {
+fn send_data(
+    transport: &mut DfuTransport,
+    firmware: &[u8],
+    on_progress: &dyn Fn(DfuPhase, usize, usize),
+) -> Result<(), SensorError> {
     let total_chunks = firmware.len().div_ceil(DATA_CHUNK_SIZE);
     let log_interval = (total_chunks / 10).max(1);
 
@@ -88,21 +145,28 @@ fn send_data(transport: &mut DfuTransport, firmware: &[u8], on_progress: &dyn Fn
         total_chunks
     );
 
+    let mut bytes_sent = 0;
+
     for (i, chunk) in firmware.chunks(DATA_CHUNK_SIZE).enumerate() {
         let mut payload = Vec::with_capacity(4 + chunk.len());
         payload.extend_from_slice(&DFU_DATA_PACKET.to_le_bytes());
         payload.extend_from_slice(chunk);
 
         transport.send_and_ack(&payload)?;
+        bytes_sent += chunk.len();
 
         if (i + 1) % CHUNKS_PER_FLASH_PAGE == 0 {
             std::thread::sleep(Duration::from_millis(FLASH_PAGE_DELAY_MS));
         }
 
+        // Report once per flash page to keep UI events at a sane rate
+        if (i + 1) % CHUNKS_PER_FLASH_PAGE == 0 || i + 1 == total_chunks {
+            on_progress(DfuPhase::Data, bytes_sent, firmware.len());
+        }
+
         if (i + 1) % log_interval == 0 || i + 1 == total_chunks {
             let pct = ((i + 1) as f32 / total_chunks as f32) * 100.0;
             tracing::info!("DFU progress: {:.0}% ({}/{})", pct, i + 1, total_chunks);
-            on_progress(pct);
         }
     }
 
@@ -136,6 +200,16 @@ mod tests {
         assert_eq!(u32::from_le_bytes(payload[16..20].try_into().unwrap()), 327680);
     }
 
+    #[test]
+    fn init_packet_announces_crc_of_the_image() {
+        // CRC-16/CCITT-FALSE check values
+        assert_eq!(calc_crc16(b"123456789"), 0x29B1);
+        assert_eq!(calc_crc16(b"A"), 0xB915);
+
+        let init_pkt = build_init_packet(b"123456789");
+        assert_eq!(u16::from_le_bytes([init_pkt[12], init_pkt[13]]), 0x29B1);
+    }
+
     #[test]
     fn init_payload_is_20_bytes() {
         use super::build_init_packet;
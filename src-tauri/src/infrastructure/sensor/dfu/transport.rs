@@ -8,6 +8,7 @@ use super::slip::slip_decode;
 use super::{SensorError, XiaoDetector, XiaoMode};
 use serialport::{ClearBuffer, SerialPort};
 use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
 
 const DFU_BAUD_RATE: u32 = 115200;
 const TOUCH_BAUD_RATE: u32 = 1200;
@@ -17,15 +18,59 @@ const SLIP_END: u8 = 0xC0;
 const BOOTLOADER_SCAN_TIMEOUT: Duration = Duration::from_secs(10);
 /// Interval between scans for the bootloader device.
 const BOOTLOADER_SCAN_INTERVAL: Duration = Duration::from_millis(250);
+/// How long the bootloader gets to answer a single packet.
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(30);
+/// Serial read timeout; blocking reads wake this often to check the upload's limits.
+const READ_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Overall time budget and cancellation for one upload attempt.
+#[derive(Debug, Clone)]
+pub struct UploadLimits {
+    deadline: Instant,
+    timeout: Duration,
+    cancel: CancellationToken,
+}
+
+impl UploadLimits {
+    pub fn new(timeout: Duration, cancel: CancellationToken) -> Self {
+        Self {
+            deadline: Instant::now() + timeout,
+            timeout,
+            cancel,
+        }
+    }
+
+    /// Fail if the upload was cancelled or ran out of time.
+    pub fn check(&self) -> Result<(), SensorError> {
+        if self.cancel.is_cancelled() {
+            return Err(SensorError::UploadFailed("cancelled".to_string()));
+        }
+        if Instant::now() >= self.deadline {
+            return Err(SensorError::UploadFailed(format!(
+                "timeout after {}s",
+                self.timeout.as_secs()
+            )));
+        }
+        Ok(())
+    }
+}
 
 pub struct DfuTransport {
     port: Box<dyn SerialPort>,
+    /// Port the bootloader enumerated on, which may differ from the application's
+    port_name: String,
     seq: HciSequence,
+    limits: UploadLimits,
 }
 
 impl DfuTransport {
     /// Open a serial port for DFU with 1200-baud touch to enter bootloader.
-    pub fn open_with_touch(port_name: &str) -> Result<Self, SensorError> {
+    ///
+    /// Every later operation fails once `limits` are exceeded; the port is
+    /// closed when the transport is dropped, so the board can be retried.
+    pub fn open_with_touch(port_name: &str, limits: UploadLimits) -> Result<Self, SensorError> {
+        limits.check()?;
+
         // 1200-baud touch to trigger bootloader entry
         tracing::info!("Sending 1200-baud touch on {}", port_name);
         {
@@ -45,7 +90,7 @@ impl DfuTransport {
         // Wait for device to re-enumerate in bootloader mode.
         // The device disconnects (normal PID) and reconnects (bootloader PID),
         // possibly on a different port name. Poll until we find it.
-        let bootloader_port = Self::wait_for_bootloader()?;
+        let bootloader_port = Self::wait_for_bootloader(&limits)?;
 
         tracing::info!(
             "Opening {} at {} baud for DFU",
@@ -53,7 +98,7 @@ impl DfuTransport {
             DFU_BAUD_RATE
         );
         let port = serialport::new(&bootloader_port, DFU_BAUD_RATE)
-            .timeout(Duration::from_millis(30_000))
+            .timeout(READ_POLL_INTERVAL)
             .open()
             .map_err(|e| {
                 SensorError::UploadFailed(format!(
@@ -66,18 +111,22 @@ impl DfuTransport {
 
         Ok(Self {
             port,
+            port_name: bootloader_port,
             seq: HciSequence::new(),
+            limits,
         })
     }
 
     /// Poll for a XIAO device in bootloader mode after 1200-baud touch.
-    fn wait_for_bootloader() -> Result<String, SensorError> {
+    fn wait_for_bootloader(limits: &UploadLimits) -> Result<String, SensorError> {
         let start = Instant::now();
 
         // Give the device a moment to begin re-enumeration
         std::thread::sleep(Duration::from_millis(500));
 
         loop {
+            limits.check()?;
+
             let devices = XiaoDetector::find_all();
             if let Some(bl) = devices.iter().find(|d| d.mode == XiaoMode::Bootloader) {
                 tracing::info!(
@@ -104,6 +153,8 @@ impl DfuTransport {
     /// Matches Python nrfutil behavior: send packet, read one SLIP frame as ACK,
     /// don't validate the ACK number.
     pub fn send_and_ack(&mut self, payload: &[u8]) -> Result<(), SensorError> {
+        self.limits.check()?;
+
         let packet = self.seq.build_packet(payload);
 
         // Discard any stale data so we read the real response.
@@ -123,6 +174,7 @@ impl DfuTransport {
     /// Read a single SLIP frame: bytes between two 0xC0 delimiters, SLIP-decoded.
     fn read_slip_frame(&mut self) -> Result<Vec<u8>, SensorError> {
         let mut buf = [0u8; 1];
+        let response_deadline = Instant::now() + RESPONSE_TIMEOUT;
 
         // Skip until first SLIP_END
         loop {
@@ -131,9 +183,12 @@ impl DfuTransport {
                 Ok(1) => continue,
                 Ok(_) => continue,
                 Err(e) if e.kind() == std::io::ErrorKind::TimedOut => {
-                    return Err(SensorError::UploadFailed(
-                        "Timeout waiting for DFU response".to_string(),
-                    ));
+                    self.limits.check()?;
+                    if Instant::now() >= response_deadline {
+                        return Err(SensorError::UploadFailed(
+                            "Timeout waiting for DFU response".to_string(),
+                        ));
+                    }
                 }
                 Err(e) => {
                     return Err(SensorError::UploadFailed(format!(
@@ -152,9 +207,12 @@ impl DfuTransport {
                 Ok(1) => raw.push(buf[0]),
                 Ok(_) => continue,
                 Err(e) if e.kind() == std::io::ErrorKind::TimedOut => {
-                    return Err(SensorError::UploadFailed(
-                        "Timeout reading DFU response body".to_string(),
-                    ));
+                    self.limits.check()?;
+                    if Instant::now() >= response_deadline {
+                        return Err(SensorError::UploadFailed(
+                            "Timeout reading DFU response body".to_string(),
+                        ));
+                    }
                 }
                 Err(e) => {
                     return Err(SensorError::UploadFailed(format!(
@@ -168,6 +226,14 @@ impl DfuTransport {
         Ok(slip_decode(&raw))
     }
 
+    pub fn port_name(&self) -> &str {
+        &self.port_name
+    }
+
+    pub fn limits(&self) -> &UploadLimits {
+        &self.limits
+    }
+
     /// Consume the transport, closing the port.
     pub fn close(self) {
         drop(self.port);
@@ -7,7 +7,7 @@ mod patcher;
 mod serial_comm;
 
 pub use detector::{XiaoDetector, XiaoMode};
-pub use dfu::DfuUploader;
+pub use dfu::{BoardTarget, DfuProgress, DfuUploader, UploadOutcome};
 pub use patcher::FirmwarePatcher;
 pub use serial_comm::SerialComm;
 
@@ -35,9 +35,15 @@ pub enum SensorError {
     #[error("Firmware placeholder not found")]
     PlaceholderNotFound,
 
+    #[error("Device name cannot be empty")]
+    EmptyName,
+
     #[error("Device name too long (max {max} characters)")]
     NameTooLong { max: usize },
 
+    #[error("Device name contains invalid character {character:?} (printable ASCII only)")]
+    InvalidNameCharacter { character: char },
+
     #[error("Firmware upload failed: {0}")]
     UploadFailed(String),
 }
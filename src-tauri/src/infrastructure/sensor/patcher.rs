@@ -5,26 +5,31 @@ use super::{Result, SensorError};
 /// Default placeholder string in firmware
 const DEFAULT_PLACEHOLDER: &str = "PLACEHOLDER_BLE_NAME_HERE";
 
+/// Prefix of the version line the firmware prints over serial; the same
+/// string literal carries the version inside the image.
+const VERSION_PREFIX: &str = "Firmware: ";
+
 /// Patches firmware binary to set a custom BLE device name
 pub struct FirmwarePatcher;
 
 impl FirmwarePatcher {
+    /// Longest device name the placeholder holds: 25 bytes, i.e. 25 ASCII characters.
+    /// Shorter names are null-padded to the placeholder's length.
+    pub const MAX_NAME_LEN: usize = DEFAULT_PLACEHOLDER.len();
+
     /// Patch the device name in firmware binary
     ///
     /// Finds the placeholder string and replaces it with the new name,
     /// padded with null bytes to maintain the same length.
     pub fn patch_device_name(firmware: &[u8], device_name: &str) -> Result<Vec<u8>> {
         let placeholder = DEFAULT_PLACEHOLDER.as_bytes();
-        let max_len = placeholder.len();
-
-        // Validate name length
-        if device_name.len() > max_len {
-            return Err(SensorError::NameTooLong { max: max_len });
-        }
 
         // Find placeholder in firmware
         let offset = Self::find_placeholder(firmware, placeholder)
             .ok_or(SensorError::PlaceholderNotFound)?;
+        let max_len = placeholder.len();
+
+        Self::validate_name(device_name)?;
 
         tracing::debug!(
             "Found placeholder at offset 0x{:08X}, replacing with '{}'",
@@ -57,8 +62,154 @@ impl FirmwarePatcher {
         Self::find_placeholder(firmware, DEFAULT_PLACEHOLDER.as_bytes()).is_some()
     }
 
-    /// Get the maximum allowed device name length
-    pub fn max_name_length() -> usize {
-        DEFAULT_PLACEHOLDER.len()
+    /// Read the firmware version an image reports once flashed
+    ///
+    /// `None` if the image has no `Firmware: <version>` literal, e.g. because
+    /// it formats the version at runtime.
+    pub fn embedded_version(firmware: &[u8]) -> Option<String> {
+        let prefix = VERSION_PREFIX.as_bytes();
+        let start = Self::find_placeholder(firmware, prefix)? + prefix.len();
+        let version: String = firmware[start..]
+            .iter()
+            .take_while(|b| b.is_ascii_graphic())
+            .map(|&b| b as char)
+            .collect();
+
+        (!version.is_empty() && !version.contains('%')).then_some(version)
+    }
+
+    /// Check a device name without any firmware at hand
+    ///
+    /// The name must be non-empty, at most [`Self::MAX_NAME_LEN`] bytes and
+    /// printable ASCII, so it fits the placeholder and the BLE name field.
+    pub fn validate_name(device_name: &str) -> Result<()> {
+        if device_name.trim().is_empty() {
+            return Err(SensorError::EmptyName);
+        }
+
+        if device_name.len() > Self::MAX_NAME_LEN {
+            return Err(SensorError::NameTooLong {
+                max: Self::MAX_NAME_LEN,
+            });
+        }
+
+        Self::validate_name_characters(device_name)
+    }
+
+    /// Reject names the BLE stack can't advertise: anything outside printable ASCII.
+    /// A null byte would also silently truncate the patched name.
+    pub fn validate_name_characters(device_name: &str) -> Result<()> {
+        match device_name
+            .chars()
+            .find(|c| !c.is_ascii() || c.is_ascii_control())
+        {
+            Some(character) => Err(SensorError::InvalidNameCharacter { character }),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn firmware() -> Vec<u8> {
+        let mut firmware = vec![0xFFu8; 64];
+        firmware.extend_from_slice(DEFAULT_PLACEHOLDER.as_bytes());
+        firmware.push(0);
+        firmware.extend_from_slice(&[0xAA; 16]);
+        firmware
+    }
+
+    fn patched_name(patched: &[u8]) -> &[u8] {
+        let name = &patched[64..64 + DEFAULT_PLACEHOLDER.len()];
+        let end = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+        &name[..end]
+    }
+
+    #[test]
+    fn firmware_without_placeholder_is_rejected() {
+        assert!(matches!(
+            FirmwarePatcher::patch_device_name(&[0u8; 32], "Sensor-1"),
+            Err(SensorError::PlaceholderNotFound)
+        ));
+    }
+
+    #[test]
+    fn name_exactly_at_limit_fills_placeholder() {
+        let firmware = firmware();
+        let name = "N".repeat(FirmwarePatcher::MAX_NAME_LEN);
+
+        let patched = FirmwarePatcher::patch_device_name(&firmware, &name).unwrap();
+        assert_eq!(patched_name(&patched), name.as_bytes());
+        assert_eq!(patched.len(), firmware.len());
+        assert_eq!(&patched[64 + name.len()..], &firmware[64 + name.len()..]);
+    }
+
+    #[test]
+    fn name_one_over_limit_is_rejected() {
+        let firmware = firmware();
+        let max = FirmwarePatcher::MAX_NAME_LEN;
+        let name = "N".repeat(max + 1);
+
+        assert!(matches!(
+            FirmwarePatcher::patch_device_name(&firmware, &name),
+            Err(SensorError::NameTooLong { max: m }) if m == max
+        ));
+    }
+
+    #[test]
+    fn short_name_is_null_padded() {
+        let patched = FirmwarePatcher::patch_device_name(&firmware(), "Sensor-1").unwrap();
+        assert_eq!(patched_name(&patched), b"Sensor-1");
+        assert!(patched[64 + 8..64 + DEFAULT_PLACEHOLDER.len()].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn embedded_null_is_rejected() {
+        assert!(matches!(
+            FirmwarePatcher::patch_device_name(&firmware(), "Sens\0or"),
+            Err(SensorError::InvalidNameCharacter { character: '\0' })
+        ));
+    }
+
+    #[test]
+    fn validate_name_needs_no_firmware() {
+        let max = FirmwarePatcher::MAX_NAME_LEN;
+        assert_eq!(max, 25);
+        assert!(FirmwarePatcher::validate_name("Sensor-1").is_ok());
+        assert!(FirmwarePatcher::validate_name(&"N".repeat(max)).is_ok());
+
+        assert!(matches!(FirmwarePatcher::validate_name("  "), Err(SensorError::EmptyName)));
+        assert!(matches!(
+            FirmwarePatcher::validate_name(&"N".repeat(max + 1)),
+            Err(SensorError::NameTooLong { max: m }) if m == max
+        ));
+        assert!(matches!(
+            FirmwarePatcher::validate_name("Capteur-é"),
+            Err(SensorError::InvalidNameCharacter { character: 'é' })
+        ));
+    }
+
+    #[test]
+    fn embedded_version_is_read_from_the_image() {
+        let mut image = firmware();
+        image.extend_from_slice(b"Firmware: 1.4.2\r\n\0");
+        assert_eq!(FirmwarePatcher::embedded_version(&image).as_deref(), Some("1.4.2"));
+
+        let mut formatted = firmware();
+        formatted.extend_from_slice(b"Firmware: %s\0");
+        assert_eq!(FirmwarePatcher::embedded_version(&formatted), None);
+        assert_eq!(FirmwarePatcher::embedded_version(&firmware()), None);
+    }
+
+    #[test]
+    fn non_ascii_and_control_characters_are_rejected() {
+        for name in ["Capteur-é", "Tab\tName", "Line\n"] {
+            assert!(matches!(
+                FirmwarePatcher::patch_device_name(&firmware(), name),
+                Err(SensorError::InvalidNameCharacter { .. })
+            ));
+        }
     }
 }
\ No newline at end of file
@@ -20,6 +20,13 @@ pub struct SensorInfo {
     pub firmware_version: Option<String>,
 }
 
+/// Firmware identity reported by a running board
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FirmwareIdentity {
+    pub version: Option<String>,
+    pub device_name: Option<String>,
+}
+
 /// Serial communication handler
 pub struct SerialComm {
     port: Box<dyn SerialPort>,
@@ -115,6 +122,18 @@ impl SerialComm {
         Ok(info)
     }
 
+    /// Query a running board for its firmware version and patched device name.
+    /// Only works in normal mode; a board in the bootloader has no serial console.
+    pub fn read_firmware_identity(port_name: &str) -> Result<FirmwareIdentity> {
+        let mut serial = SerialComm::open(port_name)?;
+        let info = serial.get_device_info()?;
+
+        Ok(FirmwareIdentity {
+            version: info.firmware_version,
+            device_name: info.device_name,
+        })
+    }
+
     pub fn open_and_get_info(port_name: &str, max_retries: u32) -> Result<SensorInfo> {
         let mut last_error = None;
 
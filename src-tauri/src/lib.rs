@@ -8,18 +8,22 @@ mod net;
 use std::path::PathBuf;
 
 use api::*;
-use app::{AppConfig, AppState, EventBus, ServerManager, setup_signal_handlers};
+use app::{AppConfig, AppState, EventBus, ServerManager, CONFIG_FILENAME, setup_signal_handlers};
+use app::models::SensorFirmwareConfig;
 use application::services::{
-    ApkApplicationService, BatteryMonitor, ClientApkService,
-    DeviceApplicationService, GameApplicationService, GameVersionService,
+    ApkApplicationService, AppWatchdog, BatteryMonitor, ClientApkService,
+    DeviceApplicationService, GameApplicationService, GameVersionService, PacketTraceService,
     SensorService, update_service::create_update_service,
 };
 use infrastructure::repositories::{
-    FsApkRepository, FsClientApkRepository, FsGameVersionRepository, InMemoryDeviceRepository,
-    SqliteDeviceNameRepository, SqliteGameCacheRepository,
+    FsApkRepository, FsClientApkRepository, FsGameVersionRepository,
+    InMemoryCommandHistoryRepository, InMemoryDeviceRepository, SqliteDeviceNameRepository,
+    SqliteGameCacheRepository,
 };
+use domain::models::IpAddress;
+use domain::repositories::CommandHistoryRepository;
 use infrastructure::database::Database;
-use infrastructure::network::TcpServer;
+use infrastructure::network::{BandwidthLimiter, TcpServer};
 use std::sync::Arc;
 use tauri::Manager;
 
@@ -38,9 +42,6 @@ pub fn run() {
         .setup(|app| {
             tracing::info!("Initializing Arceus application");
 
-            let update_service = create_update_service(app.handle().clone());
-            app.manage(update_service);
-
             let app_data_dir = app
                 .path()
                 .app_data_dir()
@@ -65,15 +66,30 @@ pub fn run() {
                 app_data_dir.join("apks"),
                 app_data_dir.join("arceus.db"),
                 games_directory,
-            );
+            )
+            .with_overrides_from(&app_data_dir.join(CONFIG_FILENAME))
+            .map_err(|e| format!("Failed to load configuration: {}", e))?;
             config.validate()
                 .map_err(|e| format!("Invalid configuration: {}", e))?;
+
+            let update_service = create_update_service(
+                app.handle().clone(),
+                app_data_dir.join("update_channel"),
+                config.update_channel,
+            );
+            app.manage(update_service);
             std::fs::create_dir_all(&config.apk_directory)
                 .map_err(|e| format!("Failed to create APK directory at {:?}: {}", config.apk_directory, e))?;
             std::fs::create_dir_all(&config.games_directory)
                 .map_err(|e| format!("Failed to create games directory at {:?}: {}", config.games_directory, e))?;
 
-            let event_bus = Arc::new(EventBus::new(app.handle().clone()));
+            let command_history: Arc<dyn CommandHistoryRepository> =
+                Arc::new(InMemoryCommandHistoryRepository::new(config.command_history_size));
+            let event_bus = Arc::new(EventBus::new(
+                app.handle().clone(),
+                std::time::Duration::from_millis(config.event_coalesce_window_ms),
+                command_history.clone(),
+            ));
             let device_repo = Arc::new(InMemoryDeviceRepository::new());
 
             // Initialize SQLite database
@@ -91,26 +107,31 @@ pub fn run() {
                 Ok::<_, String>((device_name_repo, game_cache_repo))
             })?;
 
-            let http_host = if config.server.tcp_host == "0.0.0.0" {
-                local_ip_address::local_ip()
-                    .map(|ip| ip.to_string())
-                    .unwrap_or_else(|_| {
-                        tracing::warn!("Could not detect local IP, using localhost");
-                        "127.0.0.1".to_string()
-                    })
-            } else {
-                config.server.tcp_host.clone()
+            let http_ip = match config.server.tcp_host.parse::<IpAddress>() {
+                Ok(ip) if ip.is_unspecified() => detect_local_ip(ip.is_ipv6()),
+                Ok(ip) => ip,
+                Err(e) => return Err(format!("Invalid TCP host: {}", e).into()),
             };
-            let base_url = format!("http://{}:{}", http_host, config.server.http_port);
+            // Bracketed for IPv6 so it can be embedded in URLs
+            let http_host = http_ip.url_host();
+            let base_url = http_ip.http_base_url(config.server.http_port);
             let apk_repo = Arc::new(FsApkRepository::new(
                 config.apk_directory.clone(),
                 base_url,
             ));
+            // Nothing is being added yet, so anything partial is from a crash
+            if let Err(e) = tauri::async_runtime::block_on(apk_repo.cleanup()) {
+                tracing::warn!("Failed to clean up APK directory: {}", e);
+            }
+
+            // Shared by every game and APK download stream
+            let bandwidth_limiter = Arc::new(BandwidthLimiter::new(config.max_download_bytes_per_sec));
 
             // Initialize client APK repository and service
             let client_apk_repo = Arc::new(FsClientApkRepository::new(
                 config.apk_directory.clone(),
                 config.alakazam.clone(),
+                bandwidth_limiter.clone(),
             ));
             let client_apk_service = Arc::new(ClientApkService::new(
                 client_apk_repo.clone() as Arc<dyn crate::domain::repositories::ClientApkRepository>,
@@ -118,38 +139,68 @@ pub fn run() {
                 config.server.http_port,
             ));
 
+            let response_tracker = Arc::new(crate::domain::services::ResponseTracker::new());
+            let screenshot_assembler = Arc::new(crate::domain::services::ScreenshotAssembler::new(
+                config.max_screenshot_bytes,
+            ));
+
             let (tcp_server, _, session_manager) = TcpServer::new(
                 config.server.clone(),
                 device_repo.clone(),
                 device_name_repo.clone(),
+                command_history.clone(),
                 event_bus.clone(),
                 client_apk_service.clone(),
+                response_tracker.clone(),
+                screenshot_assembler.clone(),
             );
             let tcp_server = Arc::new(tcp_server);
 
             let command_executor = Arc::new(crate::domain::services::CommandExecutor::new(
                 device_repo.clone(),
                 session_manager.clone(),
+                response_tracker,
+                std::time::Duration::from_secs(config.server.command_timeout),
+                config.server.command_retries,
             ));
 
             let device_service = Arc::new(DeviceApplicationService::new(
                 device_repo.clone(),
                 device_name_repo.clone(),
                 command_executor.clone(),
+                screenshot_assembler,
+                event_bus.clone(),
+                config.volume_presets.clone(),
+                crate::domain::services::ShellPolicy::new(config.shell_safe_mode, config.shell_allowlist.clone()),
+            ));
+            let packet_trace_service = Arc::new(PacketTraceService::new(
+                session_manager.clone(),
+                event_bus.clone(),
+            ));
+            let apk_service = Arc::new(ApkApplicationService::new(
+                apk_repo.clone(),
+                event_bus.clone(),
+                config.max_apk_bytes,
             ));
-            let apk_service = Arc::new(ApkApplicationService::new(apk_repo.clone()));
             let game_service = Arc::new(GameApplicationService::new(event_bus.clone()));
 
             // Initialize game version repository and service
             let game_version_repo = Arc::new(FsGameVersionRepository::new(
                 config.games_directory.clone(),
                 config.alakazam.clone(),
+                bandwidth_limiter.clone(),
+                config.file_download_concurrency,
             ));
+            // No download is running yet, so any staged install is from a crash
+            if let Err(e) = tauri::async_runtime::block_on(game_version_repo.cleanup_staging()) {
+                tracing::warn!("Failed to clean up game staging directory: {}", e);
+            }
             let game_version_service = Arc::new(GameVersionService::new(
                 game_version_repo as Arc<dyn crate::domain::repositories::GameVersionRepository>,
                 game_cache_repo,
                 event_bus.clone(),
                 config.games_directory.clone(),
+                config.max_concurrent_downloads,
             ));
 
             // Initialize cache from filesystem on first run
@@ -168,18 +219,39 @@ pub fn run() {
                 battery_interval,
             ));
 
+            let app_watchdog = Arc::new(AppWatchdog::new(
+                device_repo.clone(),
+                session_manager.clone(),
+                command_executor.clone(),
+                event_bus.clone(),
+                config.watchdog.clone(),
+                (config.server.expected_app_relaunch_secs > 0)
+                    .then(|| std::time::Duration::from_secs(config.server.expected_app_relaunch_secs)),
+            ));
+
             let app_state = Arc::new(AppState::new(tcp_server.clone()));
             let server_manager = Arc::new(ServerManager::new(
                 tcp_server.clone(),
                 config.clone(),
                 event_bus.clone(),
                 battery_monitor.clone(),
+                app_watchdog,
             ));
 
             // Initialize sensor service
-            let sensor_service = Arc::new(SensorService::new(event_bus.clone(), config.alakazam.clone()));
+            let sensor_firmware = SensorFirmwareConfig {
+                path: config
+                    .sensor_firmware
+                    .resolve_path(app.path().resource_dir().ok().as_deref()),
+            };
+            let sensor_service = Arc::new(SensorService::new(
+                event_bus.clone(),
+                config.alakazam.clone(),
+                config.dfu.clone(),
+                sensor_firmware,
+            ));
 
-            app.manage(device_service);
+            app.manage(device_service.clone());
             app.manage(apk_service);
             app.manage(game_service);
             app.manage(client_apk_service.clone());
@@ -187,6 +259,8 @@ pub fn run() {
             app.manage(sensor_service);
             app.manage(app_state.clone());
             app.manage(server_manager);
+            app.manage(command_history.clone());
+            app.manage(packet_trace_service);
 
             let game_version_service_startup = game_version_service.clone();
             tauri::async_runtime::spawn(async move {
@@ -206,6 +280,20 @@ pub fn run() {
                 }
             });
 
+            if config.remote_api.enabled {
+                let remote_api_state = RemoteApiState::new(
+                    device_service.clone(),
+                    command_history.clone(),
+                    config.remote_api.token.clone(),
+                );
+                let remote_api_config = config.remote_api.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = serve_remote_api(remote_api_config, remote_api_state).await {
+                        tracing::error!("Remote API error: {}", e);
+                    }
+                });
+            }
+
             setup_signal_handlers(app_state.clone());
 
             if let Some(updater_window) = app.get_webview_window("updater") {
@@ -228,29 +316,58 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             get_devices,
             get_device,
+            export_devices,
             set_device_name,
+            set_device_tags,
+            set_device_note,
+            set_expected_app,
+            list_devices_by_tag,
             launch_app,
+            launch_app_all,
+            launch_app_by_tag,
             uninstall_app,
             request_battery,
+            request_storage,
+            get_storage,
             ping_devices,
+            locate_device,
             set_volume,
+            set_volume_preset,
             get_volume,
+            set_brightness,
+            get_brightness,
             execute_shell,
             get_installed_apps,
+            get_wifi_status,
+            request_screenshot,
+            start_device_trace,
+            stop_device_trace,
             install_remote_apk,
+            install_remote_apk_batch,
+            install_apk_on_serials,
             install_local_apk,
             restart_devices,
             close_all_apps,
             configure_device,
             clear_wifi_credentials,
             display_message,
+            display_message_all,
+            sync_time,
+            sync_time_all,
             check_and_update_client_apk,
+            get_server_stats,
+            set_packet_trace,
             list_apks,
+            find_apk_duplicates,
             add_apk,
+            cancel_add_apk,
             remove_apk,
+            verify_apk,
             open_apk_folder,
             check_for_updates,
             download_and_install_update,
+            get_update_channel,
+            set_update_channel,
             skip_update,
             close_updater_and_show_main,
             start_game,
@@ -259,11 +376,24 @@ pub fn run() {
             get_game_list,
             download_game,
             cancel_download,
+            pause_download,
+            resume_download,
+            get_download_queue,
+            delete_game,
+            preview_update,
+            verify_game,
+            set_game_channel,
+            get_game_channel,
             force_refresh_games,
             list_sensors,
             get_sensor_info,
             upload_sensor_firmware,
+            upload_sensor_firmware_batch,
+            update_sensor_firmware,
+            cancel_firmware_upload,
+            backup_sensor_firmware,
             get_max_sensor_name_length,
+            validate_sensor_name,
             validate_sensor_firmware,
         ])
         .build(tauri::generate_context!())
@@ -272,6 +402,10 @@ pub fn run() {
     app.run(|app_handle, event| {
         if let tauri::RunEvent::Exit = event {
             tracing::info!("Application exiting");
+            // A wedged board would otherwise keep a blocking upload thread alive
+            if let Some(sensor_service) = app_handle.try_state::<Arc<SensorService>>() {
+                sensor_service.cancel_upload();
+            }
             if let Some(app_state) = app_handle.try_state::<Arc<AppState>>() {
                 app_state.shutdown();
             }
@@ -279,3 +413,23 @@ pub fn run() {
         }
     });
 }
+
+/// Address devices on the LAN can reach this machine at, for a wildcard listener
+/// A "::" listener is dual-stack, so it falls back to IPv4 when there is no IPv6 route.
+fn detect_local_ip(ipv6: bool) -> IpAddress {
+    let detected = if ipv6 {
+        local_ip_address::local_ipv6().or_else(|_| local_ip_address::local_ip())
+    } else {
+        local_ip_address::local_ip()
+    };
+
+    detected.map(IpAddress::from).unwrap_or_else(|_| {
+        let localhost = if ipv6 {
+            std::net::IpAddr::from(std::net::Ipv6Addr::LOCALHOST)
+        } else {
+            std::net::IpAddr::from(std::net::Ipv4Addr::LOCALHOST)
+        };
+        tracing::warn!("Could not detect local IP, using {}", localhost);
+        IpAddress::from(localhost)
+    })
+}
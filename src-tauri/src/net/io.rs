@@ -21,9 +21,17 @@ impl<W: Write + WriteBytesExt> ProtocolWriteExt for W {
 
 impl<R: Read + ReadBytesExt> ProtocolReadExt for R {
     fn read_string(&mut self) -> Result<String, std::io::Error> {
-        let length = self.read_u32::<byteorder::BigEndian>()? as usize;
-        let mut dst = vec![0; length];
-        self.read_exact(&mut dst)?;
+        let length = self.read_u32::<byteorder::BigEndian>()? as u64;
+
+        // Read through `take` so a bogus length can't force a huge allocation up front
+        let mut dst = Vec::new();
+        self.by_ref().take(length).read_to_end(&mut dst)?;
+        if dst.len() as u64 != length {
+            return Err(std::io::Error::new(
+                ErrorKind::UnexpectedEof,
+                "string shorter than its length prefix",
+            ));
+        }
 
         match String::from_utf8(dst) {
             Ok(str) => Ok(str),
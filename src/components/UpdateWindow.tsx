@@ -6,8 +6,11 @@ export function UpdateWindow() {
   const [status, setStatus] = useState('Checking for updates...');
   const [progress, setProgress] = useState(0);
   const [error, setError] = useState<string | null>(null);
+  const [channel, setChannel] = useState<string | null>(null);
+  const [releaseNotes, setReleaseNotes] = useState('');
 
   useEffect(() => {
+    invoke<string>('get_update_channel').then(setChannel).catch(() => setChannel(null));
     checkForUpdates();
   }, []);
 
@@ -18,8 +21,13 @@ export function UpdateWindow() {
       if (updateStatus && typeof updateStatus === 'object' && 'type' in updateStatus) {
         const status = updateStatus as any;
 
-        if (status.type === 'UpdateAvailable' && status.data) {
+        if (status.type === 'UpdateAvailable' && status.data?.isDowngrade) {
+          // Leaving the beta channel needs an explicit confirmation, never done unattended
+          setStatus(`Stable ${status.data.version} is older than the installed version`);
+          await checkClientApk();
+        } else if (status.type === 'UpdateAvailable' && status.data) {
           setStatus(`Update available: ${status.data.version}`);
+          setReleaseNotes(status.data.body ?? '');
           await downloadAndInstall();
         } else {
           setStatus('No server updates available');
@@ -90,6 +98,16 @@ export function UpdateWindow() {
           <>
             <p className="text-white text-base mb-6">{status}</p>
 
+            {channel && channel !== 'stable' && (
+              <p className="text-grey-300 text-sm mb-4">Update channel: {channel}</p>
+            )}
+
+            {releaseNotes.trim() && (
+              <div className="text-left text-grey-200 text-xs whitespace-pre-wrap max-h-32 overflow-y-auto mb-4">
+                {releaseNotes}
+              </div>
+            )}
+
             {progress > 0 && (
               <div className="w-full bg-grey-600 rounded-full h-3 mb-4">
                 <div
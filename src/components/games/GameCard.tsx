@@ -2,6 +2,12 @@ import { GameStatus } from '../../services/gameVersionService';
 import { Button } from '../ui/button';
 import { Progress } from '../ui/progress';
 
+function ordinal(n: number): string {
+  const suffixes = ['th', 'st', 'nd', 'rd'];
+  const v = n % 100;
+  return n + (suffixes[(v - 20) % 10] || suffixes[v] || suffixes[0]);
+}
+
 interface GameCardProps {
   game: GameStatus;
   onUpdate: (gameId: number) => void;
@@ -14,6 +20,7 @@ interface GameCardProps {
 export function GameCard({ game, onUpdate, onLaunch, onStop, isUpdating, isRunning }: GameCardProps) {
   const isDownloading = game.downloadProgress !== null;
   const progress = game.downloadProgress?.percentage || 0;
+  const queuePosition = game.downloadProgress?.queuePosition ?? null;
   const isInstalled = game.installedVersion !== null;
 
   // Use local cached background image (base64 data URL) if available, otherwise black background
@@ -48,10 +55,14 @@ export function GameCard({ game, onUpdate, onLaunch, onStop, isUpdating, isRunni
 
             {/* Status Text */}
             <div className="flex items-center justify-between">
-              <span className="text-sm text-grey-200">Downloading...</span>
-              <span className="font-semibold text-yellow-500">
-                {progress.toFixed(0)}%
+              <span className="text-sm text-grey-200">
+                {queuePosition !== null ? `Queued (${ordinal(queuePosition)})` : 'Downloading...'}
               </span>
+              {queuePosition === null && (
+                <span className="font-semibold text-yellow-500">
+                  {progress.toFixed(0)}%
+                </span>
+              )}
             </div>
 
             {/* Progress Bar */}
@@ -48,6 +48,8 @@ export function GamesSection() {
                 ...game,
                 downloadProgress: {
                   percentage: event.percentage,
+                  queuePosition: event.queuePosition,
+                  state: game.downloadProgress?.state,
                 },
               }
             : game
@@ -70,6 +72,21 @@ export function GamesSection() {
           loadGames();
         }, 2000);
       }
+    } else if (event.type === 'gameDownloadStateChanged') {
+      setGames((games) =>
+        games.map((game) => {
+          if (game.gameId !== event.gameId) return game;
+          if (event.state === 'cancelled') return { ...game, downloadProgress: null };
+          return {
+            ...game,
+            downloadProgress: {
+              percentage: game.downloadProgress?.percentage ?? 0,
+              queuePosition: null,
+              state: event.state,
+            },
+          };
+        })
+      );
     }
   });
 
@@ -102,6 +119,13 @@ export function GamesSection() {
       });
       loadGames();
     } catch (error) {
+      // Pausing ends the download call too, but keeps its progress to resume from
+      const progress = await gameVersionService.getDownloadProgress(gameToUpdate.id).catch(() => null);
+      if (progress?.state === 'paused') {
+        toast.info('Download Paused', { description: `${gameToUpdate.name} can be resumed later` });
+        return;
+      }
+
       const message = error instanceof Error ? error.message : 'Failed to install game';
       toast.error('Installation Failed', { description: message });
       setUpdatingGameIds((prev) => {
@@ -77,8 +77,8 @@ export function DevicesPage() {
 
   const loadApks = async () => {
     try {
-      const apks = await ApkService.listApks();
-      setAvailableApks(apks);
+      const { items } = await ApkService.listApks();
+      setAvailableApks(items);
     } catch (error) {
       console.error('Failed to load APKs:', error);
       toast.error('Failed to load APKs');
@@ -88,7 +88,7 @@ export function DevicesPage() {
   // Listen for installedAppsReceived event (page-specific state)
   useTauriEvent<ArceusEvent>('arceus://event', (event) => {
     if (event.type === 'installedAppsReceived') {
-      setInstalledApps(event.apps);
+      setInstalledApps(event.apps.map((app) => app.packageName));
       setLoading(false);
     }
   });
@@ -13,7 +13,7 @@ export function SensorsPage() {
   const [loading, setLoading] = useState(true);
   const [loadingInfo, setLoadingInfo] = useState(false);
   const [uploading, setUploading] = useState(false);
-  const [uploadProgress, setUploadProgress] = useState<{ stage: string; percentage: number } | null>(null);
+  const [uploadProgress, setUploadProgress] = useState<{ stage: string; percentage: number; attempt: number | null } | null>(null);
   const [firmwarePath, setFirmwarePath] = useState('');
   const [deviceName, setDeviceName] = useState('');
   const [maxNameLength, setMaxNameLength] = useState<number | null>(null);
@@ -69,10 +69,10 @@ export function SensorsPage() {
 
     const unsubscribe = eventService.subscribe((event) => {
       if (event.type === 'sensorUploadProgress') {
-        if (event.stage === 'completed' || event.stage === 'failed') {
+        if (event.stage === 'completed' || event.stage === 'failed' || event.stage === 'skipped') {
           setUploadProgress(null);
         } else {
-          setUploadProgress({ stage: event.stage, percentage: event.percentage });
+          setUploadProgress({ stage: event.stage, percentage: event.percentage, attempt: event.attempt });
         }
       }
     });
@@ -231,7 +231,10 @@ export function SensorsPage() {
           {uploading && uploadProgress && (
             <div className="space-y-2">
               <div className="flex items-center justify-between text-sm">
-                <span className="text-grey-300 capitalize">{uploadProgress.stage}...</span>
+                <span className="text-grey-300 capitalize">
+                  {uploadProgress.stage}...
+                  {uploadProgress.attempt !== null && uploadProgress.attempt > 1 && ` (attempt ${uploadProgress.attempt})`}
+                </span>
                 <span className="text-grey-300 font-mono">{Math.round(uploadProgress.percentage)}%</span>
               </div>
               <div className="w-full h-2 bg-grey-700 rounded-full overflow-hidden">
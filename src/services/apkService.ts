@@ -1,19 +1,34 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { ApkInfo } from "@/types/apk.types";
+import type { ApkDuplicateGroup, ApkListOptions, ApkPage } from "@/types/apk.types";
+import type { CommandResult } from "@/types/device.types";
 
 export class ApkService {
-  static async listApks(): Promise<ApkInfo[]> {
-    return await invoke<ApkInfo[]>("list_apks");
+  /** One page of stored APKs; without options, every APK sorted by name */
+  static async listApks(options: ApkListOptions = {}): Promise<ApkPage> {
+    return await invoke<ApkPage>("list_apks", { ...options });
   }
 
-  static async addApk(sourcePath: string): Promise<void> {
-    await invoke("add_apk", { sourcePath });
+  /** Identical APKs grouped by SHA-256, those wasting the most space first */
+  static async findApkDuplicates(): Promise<ApkDuplicateGroup[]> {
+    return await invoke<ApkDuplicateGroup[]>("find_apk_duplicates");
+  }
+
+  static async addApk(sourcePath: string, dedupe = false): Promise<string> {
+    return await invoke<string>("add_apk", { sourcePath, dedupe });
+  }
+
+  static async cancelAddApk(filename: string): Promise<void> {
+    await invoke("cancel_add_apk", { filename });
   }
 
   static async removeApk(filename: string): Promise<void> {
     await invoke("remove_apk", { filename });
   }
 
+  static async verifyApk(filename: string): Promise<CommandResult> {
+    return await invoke<CommandResult>("verify_apk", { filename });
+  }
+
   static async openApkFolder(): Promise<void> {
     await invoke("open_apk_folder");
   }
@@ -1,5 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { DeviceState } from "../types/device.types";
+import type { ApkBatchInstall, ApkDryRun, BatchResult, DeviceState, DeviceWifiStatus, DisplayMessageOptions, StorageInfo } from "../types/device.types";
 
 export class DeviceService {
   static async getDevices(): Promise<DeviceState[]> {
@@ -12,6 +12,14 @@ export class DeviceService {
     });
   }
 
+  /** Returns the rendered document, also written to `path` when one is given */
+  static async exportDevices(format: "csv" | "json", path?: string): Promise<string> {
+    return await invoke<string>("export_devices", {
+      format,
+      path
+    });
+  }
+
   static async restartDevices(deviceIds: string[]): Promise<void> {
     await invoke("restart_devices", {
       deviceIds
@@ -24,6 +32,40 @@ export class DeviceService {
     });
   }
 
+  static async requestStorage(deviceIds: string[]): Promise<void> {
+    await invoke("request_storage", {
+      deviceIds
+    });
+  }
+
+  static async getStorage(deviceId: string): Promise<StorageInfo | null> {
+    return await invoke<StorageInfo | null>("get_storage", {
+      deviceId
+    });
+  }
+
+  static async getWifiStatus(deviceIds: string[]): Promise<DeviceWifiStatus[]> {
+    const result = await invoke<{ devices: DeviceWifiStatus[] }>("get_wifi_status", {
+      deviceIds
+    });
+    return result.devices;
+  }
+
+  /** Packets are emitted on the `arceus://packet-trace` event while tracing */
+  static async startDeviceTrace(deviceId: string): Promise<void> {
+    await invoke("start_device_trace", { deviceId });
+  }
+
+  static async stopDeviceTrace(deviceId: string): Promise<void> {
+    await invoke("stop_device_trace", { deviceId });
+  }
+
+  static async requestScreenshot(deviceId: string): Promise<string> {
+    return await invoke<string>("request_screenshot", {
+      deviceId
+    });
+  }
+
   static async getVolume(deviceIds: string[]): Promise<void> {
     await invoke("get_volume", {
       deviceIds
@@ -37,12 +79,38 @@ export class DeviceService {
     });
   }
 
+  static async setVolumePreset(deviceIds: string[], preset: string): Promise<void> {
+    await invoke("set_volume_preset", {
+      deviceIds,
+      preset
+    });
+  }
+
+  static async getBrightness(deviceIds: string[]): Promise<void> {
+    await invoke("get_brightness", {
+      deviceIds
+    });
+  }
+
+  static async setBrightness(deviceIds: string[], level: number): Promise<void> {
+    await invoke("set_brightness", {
+      deviceIds,
+      level
+    });
+  }
+
   static async pingDevices(deviceIds: string[]): Promise<void> {
     await invoke("ping_devices", {
       deviceIds
     });
   }
 
+  static async locateDevices(deviceIds: string[]): Promise<void> {
+    await invoke("locate_device", {
+      deviceIds
+    });
+  }
+
   static async launchApp(
     deviceIds: string[],
     packageName: string
@@ -53,6 +121,26 @@ export class DeviceService {
     });
   }
 
+  /** Launch an app on every connected device, spacing launches by staggerMs */
+  static async launchAppAll(packageName: string, staggerMs = 0): Promise<BatchResult> {
+    return await invoke<BatchResult>("launch_app_all", {
+      packageName,
+      staggerMs
+    });
+  }
+
+  static async launchAppByTag(
+    tag: string,
+    packageName: string,
+    staggerMs = 0
+  ): Promise<BatchResult> {
+    return await invoke<BatchResult>("launch_app_by_tag", {
+      tag,
+      packageName,
+      staggerMs
+    });
+  }
+
   static async uninstallApp(
     deviceIds: string[],
     packageName: string
@@ -73,19 +161,67 @@ export class DeviceService {
     });
   }
 
-  static async getInstalledApps(deviceIds: string[]): Promise<void> {
+  static async getInstalledApps(
+    deviceIds: string[],
+    packagePrefix?: string,
+    includeSystem = false
+  ): Promise<void> {
     await invoke("get_installed_apps", {
-      deviceIds
+      deviceIds,
+      packagePrefix,
+      includeSystem
     });
   }
 
   static async installRemoteApk(
     deviceIds: string[],
-    url: string
+    url: string,
+    checkStorage = false,
+    expectedSha256?: string
   ): Promise<void> {
     await invoke("install_remote_apk", {
       deviceIds,
-      url
+      url,
+      checkStorage,
+      expectedSha256
+    });
+  }
+
+  /** Install on devices a few at a time, resolving once every device has finished */
+  static async installRemoteApkBatch(
+    deviceIds: string[],
+    url: string,
+    expectedSha256?: string
+  ): Promise<ApkBatchInstall> {
+    return await invoke<ApkBatchInstall>("install_remote_apk_batch", {
+      deviceIds,
+      url,
+      expectedSha256
+    });
+  }
+
+  /** Install on just the headsets with these serials, from a remote URL or a local APK filename */
+  static async installApkOnSerials(
+    serials: string[],
+    source: { url: string; expectedSha256?: string } | { filename: string }
+  ): Promise<ApkBatchInstall> {
+    return await invoke<ApkBatchInstall>("install_apk_on_serials", {
+      serials,
+      ...source
+    });
+  }
+
+  /** Validate a remote APK (reachability, size, SHA-256) without installing it */
+  static async dryRunRemoteApk(
+    deviceIds: string[],
+    url: string,
+    checkStorage = false
+  ): Promise<ApkDryRun> {
+    return await invoke<ApkDryRun>("install_remote_apk", {
+      deviceIds,
+      url,
+      checkStorage,
+      dryRun: true
     });
   }
 
@@ -109,6 +245,35 @@ export class DeviceService {
     });
   }
 
+  /** Replace a device's tags; resolves to the tags as stored after normalizing */
+  static async setDeviceTags(serial: string, tags: string[]): Promise<string[]> {
+    return await invoke<string[]>("set_device_tags", {
+      serial,
+      tags
+    });
+  }
+
+  static async setDeviceNote(serial: string, note: string | null): Promise<void> {
+    await invoke("set_device_note", {
+      serial,
+      note
+    });
+  }
+
+  /** Pin devices to an app (or unpin with null); leaving it raises a deviceLeftApp alert */
+  static async setExpectedApp(serials: string[], packageName: string | null): Promise<void> {
+    await invoke("set_expected_app", {
+      serials,
+      packageName
+    });
+  }
+
+  static async listDevicesByTag(tag: string): Promise<DeviceState[]> {
+    return await invoke<DeviceState[]>("list_devices_by_tag", {
+      tag
+    });
+  }
+
   static async closeAllApps(deviceIds: string[]): Promise<void> {
     await invoke("close_all_apps", {
       deviceIds
@@ -139,11 +304,39 @@ export class DeviceService {
 
   static async displayMessage(
     deviceIds: string[],
-    message: string
+    message: string,
+    options: DisplayMessageOptions = {}
   ): Promise<void> {
     await invoke("display_message", {
       deviceIds,
-      message
+      message,
+      severity: options.severity ?? null,
+      durationSecs: options.durationSecs ?? null
+    });
+  }
+
+  static async displayMessageAll(
+    message: string,
+    options: DisplayMessageOptions = {}
+  ): Promise<BatchResult> {
+    return await invoke<BatchResult>("display_message_all", {
+      message,
+      severity: options.severity ?? null,
+      durationSecs: options.durationSecs ?? null
+    });
+  }
+
+  /** Set device clocks to the server's UTC time, optionally with an IANA timezone */
+  static async syncTime(deviceIds: string[], timezone?: string): Promise<BatchResult> {
+    return await invoke<BatchResult>("sync_time", {
+      deviceIds,
+      timezone: timezone ?? null
+    });
+  }
+
+  static async syncTimeAll(timezone?: string): Promise<BatchResult> {
+    return await invoke<BatchResult>("sync_time_all", {
+      timezone: timezone ?? null
     });
   }
 }
@@ -45,6 +45,18 @@ class EventService {
         toast.info(`Device disconnected`);
         break;
 
+      case 'deviceFlagged':
+        toast.warning(`${event.serial}: ${event.reason}`);
+        break;
+
+      case 'deviceLeftApp':
+        toast.warning(`${event.serial} left ${event.expected} (now in ${event.actual || 'the home screen'})`);
+        break;
+
+      case 'gameVerificationFailed':
+        toast.error(`${event.gameName}: ${event.file} failed verification and will be downloaded again`);
+        break;
+
       case 'deviceNameChanged':
         const displayName = event.newName || event.serial;
         toast.success(`Renamed to "${displayName}"`);
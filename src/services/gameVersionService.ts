@@ -10,10 +10,65 @@ export interface GameStatus {
   downloadProgress: DownloadProgress | null;
   online: boolean;
   backgroundImagePath: string | null;
+  /** On-disk size of the installed files; null when not installed */
+  installedSizeBytes: number | null;
+  /** ISO 8601 timestamp of the last launch */
+  lastPlayed: string | null;
+  /** Release channel the game follows; null for the arcade's own */
+  channel: string | null;
+  /** The offered version is older than the installed one */
+  isDowngrade: boolean;
 }
 
+export type DownloadState = 'running' | 'paused' | 'cancelled';
+
 export interface DownloadProgress {
   percentage: number;
+  queuePosition?: number | null;
+  state?: DownloadState;
+}
+
+export type DownloadQueueStatus = 'queued' | 'active' | 'paused' | 'completed';
+
+export interface DownloadQueueEntry {
+  gameId: number;
+  gameName: string;
+  status: DownloadQueueStatus;
+  /** 1-based position while queued */
+  queuePosition: number | null;
+  percentage: number;
+  /** ISO 8601 timestamp of when a completed download finished */
+  finishedAt: string | null;
+}
+
+export interface FileVerificationFailure {
+  path: string;
+  reason: string;
+}
+
+export interface GameVerification {
+  gameId: number;
+  gameName: string;
+  version: string;
+  checkedFiles: number;
+  failures: FileVerificationFailure[];
+}
+
+export interface UpdatePlan {
+  added: string[];
+  changed: string[];
+  removed: string[];
+  unchanged: number;
+  /** Bytes to download; null when the server doesn't report every file size */
+  downloadBytes: number | null;
+}
+
+export interface UpdatePreview {
+  gameId: number;
+  gameName: string;
+  installedVersion: string | null;
+  version: string;
+  plan: UpdatePlan;
 }
 
 export const gameVersionService = {
@@ -26,9 +81,10 @@ export const gameVersionService = {
 
   /**
    * Download and install a game (or update it)
+   * Resumes a previously interrupted download unless resume is false
    */
-  async downloadGame(gameId: number): Promise<void> {
-    await invoke('download_game', { gameId });
+  async downloadGame(gameId: number, resume = true): Promise<void> {
+    await invoke('download_game', { gameId, resume });
   },
 
   /**
@@ -45,6 +101,62 @@ export const gameVersionService = {
     await invoke('cancel_download', { gameId });
   },
 
+  /**
+   * Get active, queued and recently completed downloads
+   */
+  async getDownloadQueue(): Promise<DownloadQueueEntry[]> {
+    return await invoke('get_download_queue');
+  },
+
+  /**
+   * Pause an ongoing or queued download, keeping its partial files
+   */
+  async pauseDownload(gameId: number): Promise<void> {
+    await invoke('pause_download', { gameId });
+  },
+
+  /**
+   * Resume a paused download; resolves once the download finishes
+   */
+  async resumeDownload(gameId: number): Promise<void> {
+    await invoke('resume_download', { gameId });
+  },
+
+  /**
+   * Delete an installed game's files; resolves with the number of bytes freed
+   */
+  async deleteGame(gameId: number): Promise<number> {
+    return await invoke('delete_game', { gameId });
+  },
+
+  /**
+   * Show which files updating a game would download and remove, without downloading
+   */
+  async previewUpdate(gameId: number): Promise<UpdatePreview> {
+    return await invoke('preview_update', { gameId });
+  },
+
+  /**
+   * Re-check an installed game's files against the server's sizes and checksums
+   */
+  async verifyGame(gameId: number): Promise<GameVerification> {
+    return await invoke('verify_game', { gameId });
+  },
+
+  /**
+   * Make a game follow a release channel such as 'beta', or with null the arcade's own
+   */
+  async setGameChannel(gameId: number, channel: string | null): Promise<void> {
+    await invoke('set_game_channel', { gameId, channel });
+  },
+
+  /**
+   * Get the release channel a game follows; null for the arcade's own
+   */
+  async getGameChannel(gameId: number): Promise<string | null> {
+    return await invoke('get_game_channel', { gameId });
+  },
+
   /**
    * Force refresh games from server (requires internet connection)
    */
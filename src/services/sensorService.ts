@@ -1,5 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { Sensor } from "../types/sensor.types";
+import type { Sensor, SensorFlashResult, SensorFlashTarget } from "../types/sensor.types";
 
 export class SensorService {
   static async listSensors(): Promise<Sensor[]> {
@@ -13,15 +13,74 @@ export class SensorService {
   static async uploadFirmware(
     port: string | null,
     firmwarePath: string,
-    deviceName: string
-  ): Promise<void> {
-    await invoke("upload_sensor_firmware", {
+    deviceName: string,
+    skipIfVersion?: string
+  ): Promise<boolean> {
+    return await invoke<boolean>("upload_sensor_firmware", {
       port,
       firmwarePath,
       deviceName,
+      skipIfVersion,
     });
   }
 
+  /** Returns whether the board was flashed; up-to-date boards are skipped unless `force` */
+  static async updateFirmware(options: {
+    port?: string;
+    firmwarePath?: string;
+    force?: boolean;
+  } = {}): Promise<boolean> {
+    return await invoke<boolean>("update_sensor_firmware", {
+      port: options.port ?? null,
+      firmwarePath: options.firmwarePath ?? null,
+      force: options.force,
+    });
+  }
+
+  static async uploadFirmwareBatch(
+    firmwarePath: string,
+    targets: SensorFlashTarget[]
+  ): Promise<SensorFlashResult[]> {
+    return await invoke<SensorFlashResult[]>("upload_sensor_firmware_batch", {
+      firmwarePath,
+      targets,
+    });
+  }
+
+  /** Flashes every connected sensor, named `<namePrefix>1`, `<namePrefix>2`, ... */
+  static async uploadFirmwareToAll(
+    firmwarePath: string,
+    namePrefix: string
+  ): Promise<SensorFlashResult[]> {
+    return await invoke<SensorFlashResult[]>("upload_sensor_firmware_batch", {
+      firmwarePath,
+      targets: null,
+      namePrefix,
+    });
+  }
+
+  /** Returns whether an upload was running */
+  static async cancelUpload(): Promise<boolean> {
+    return await invoke<boolean>("cancel_firmware_upload");
+  }
+
+  static async backupFirmware(port: string, outputPath: string): Promise<number> {
+    return await invoke<number>("backup_sensor_firmware", {
+      port,
+      outputPath,
+    });
+  }
+
+  /** Returns why `deviceName` can't be flashed, or null if it's valid */
+  static async validateName(deviceName: string): Promise<string | null> {
+    try {
+      await invoke("validate_sensor_name", { name: deviceName });
+      return null;
+    } catch (error) {
+      return String(error);
+    }
+  }
+
   static async getMaxNameLength(): Promise<number> {
     return await invoke<number>("get_max_sensor_name_length");
   }
@@ -0,0 +1,13 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { ServerStats } from "@/types/server.types";
+
+export class ServerService {
+  static async getStats(): Promise<ServerStats> {
+    return await invoke<ServerStats>("get_server_stats");
+  }
+
+  /** `capturePath` is a file name; captures are kept in the app data folder */
+  static async setPacketTrace(enabled: boolean, capturePath?: string): Promise<void> {
+    await invoke("set_packet_trace", { enabled, capturePath: capturePath ?? null });
+  }
+}
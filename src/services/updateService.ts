@@ -1,6 +1,6 @@
 import { invoke } from '@tauri-apps/api/core';
 import { listen, UnlistenFn } from '@tauri-apps/api/event';
-import { UpdateStatus } from '../types/update.types';
+import { UpdateChannel, UpdateStatus } from '../types/update.types';
 
 export class UpdateService {
   private statusListener?: UnlistenFn;
@@ -16,8 +16,17 @@ export class UpdateService {
     }
   }
 
-  async downloadAndInstall(): Promise<void> {
-    return await invoke('download_and_install_update');
+  /** Pass `allowDowngrade` to confirm installing an older stable release over a beta */
+  async downloadAndInstall(allowDowngrade = false): Promise<void> {
+    return await invoke('download_and_install_update', { allowDowngrade });
+  }
+
+  async getChannel(): Promise<UpdateChannel> {
+    return await invoke<UpdateChannel>('get_update_channel');
+  }
+
+  async setChannel(channel: UpdateChannel): Promise<void> {
+    return await invoke('set_update_channel', { channel });
   }
 
   async skipUpdate(): Promise<void> {
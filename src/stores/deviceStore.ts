@@ -133,6 +133,27 @@ const unsubscribeDeviceEvents = eventService.subscribe((event) => {
       }));
       break;
 
+    case 'brightnessUpdated':
+      updateDeviceField(event.deviceId, (device) => ({
+        ...device,
+        brightness: event.brightness,
+      }));
+      break;
+
+    case 'storageUpdated':
+      updateDeviceField(event.deviceId, (device) => ({
+        ...device,
+        storage: event.storageInfo,
+      }));
+      break;
+
+    case 'wifiStatusUpdated':
+      updateDeviceField(event.deviceId, (device) => ({
+        ...device,
+        wifiStatus: event.wifiStatus,
+      }));
+      break;
+
     case 'volumeUpdated':
       updateDeviceField(event.deviceId, (device) => ({
         ...device,
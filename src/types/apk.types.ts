@@ -2,9 +2,45 @@ export interface ApkInfo {
   filename: string;
   size_bytes: number;
   url: string;
+  sha256: string | null;
+  package_name: string | null;
+  version_name: string | null;
+  version_code: number | null;
+  label: string | null;
+  /** Base64-encoded PNG launcher icon */
+  icon: string | null;
+  /** ISO timestamp of when the file was added or last replaced */
+  added_at: string | null;
+}
+
+export interface ApkPage {
+  items: ApkInfo[];
+  /** Number of APKs across all pages */
+  total: number;
+}
+
+/** Stored APKs with identical contents */
+export interface ApkDuplicateGroup {
+  sha256: string;
+  /** Disk space freed by keeping only one copy */
+  wasted_bytes: number;
+  /** Oldest first */
+  files: ApkInfo[];
+}
+
+export type ApkSortField = 'name' | 'size' | 'dateAdded';
+
+export interface ApkListOptions {
+  sortBy?: ApkSortField;
+  order?: 'asc' | 'desc';
+  offset?: number;
+  limit?: number;
 }
 
 export interface InstalledApp {
   packageName: string;
-  name: string;
+  label: string | null;
+  versionName: string | null;
+  versionCode: number | null;
+  isSystem: boolean;
 }
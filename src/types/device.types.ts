@@ -4,9 +4,14 @@ export interface DeviceInfo {
   serial: string;
   version: string;
   customName: string | null;
+  /** Normalized (trimmed, lowercase) staff tags */
+  tags: string[];
+  note: string | null;
   connectedAt: string;
   lastSeen: string;
   runningApp: string | null;
+  /** Package the device should stay in; leaving it raises a deviceLeftApp alert */
+  expectedPackage: string | null;
 }
 
 export interface BatteryInfo {
@@ -20,6 +25,26 @@ export interface VolumeInfo {
   maxVolume: number;
 }
 
+export interface StorageInfo {
+  totalBytes: number;
+  freeBytes: number;
+  lastUpdated: string;
+}
+
+export type WifiStatus =
+  | {
+      state: 'connected';
+      ssid: string;
+      rssi: number;
+      linkSpeedMbps: number;
+    }
+  | { state: 'disconnected' };
+
+export interface DeviceWifiStatus {
+  deviceId: string;
+  status: WifiStatus;
+}
+
 export interface CommandResult {
   commandType: string;
   success: boolean;
@@ -27,6 +52,42 @@ export interface CommandResult {
   timestamp: string;
 }
 
+export interface ApkDryRun {
+  result: CommandResult;
+  url: string;
+  sizeBytes: number | null;
+  sha256: string | null;
+  deviceCount: number;
+}
+
+export type ApkInstallStatus = 'queued' | 'installing' | 'installed' | 'failed' | 'skipped';
+
+export interface ApkBatchDeviceResult {
+  /** Null for a serial with no connected device */
+  deviceId: string | null;
+  /** Null for a device ID that isn't connected */
+  serial: string | null;
+  status: ApkInstallStatus;
+  message: string | null;
+}
+
+export interface ApkBatchInstall {
+  batchId: string;
+  totalCount: number;
+  installedCount: number;
+  failedCount: number;
+  skippedCount: number;
+  devices: ApkBatchDeviceResult[];
+}
+
+export interface PacketTrace {
+  deviceId: string;
+  direction: 'inbound' | 'outbound';
+  opcode: number;
+  payloadHex: string;
+  timestamp: string;
+}
+
 export interface DeviceOperationProgress {
   operationType: 'download' | 'install';
   operationId: string;
@@ -34,10 +95,44 @@ export interface DeviceOperationProgress {
   percentage: number;
 }
 
+export interface LinkQuality {
+  lastRttMs: number | null;
+  avgRttMs: number | null;
+  heartbeatJitterMs: number | null;
+}
+
 export interface DeviceState {
   info: DeviceInfo;
   battery: BatteryInfo | null;
   volume: VolumeInfo | null;
+  brightness: number | null;
+  storage: StorageInfo | null;
+  wifiStatus: WifiStatus | null;
+  linkQuality: LinkQuality;
   commandHistory: CommandResult[];
   operationProgress: DeviceOperationProgress | null;
 }
+
+export interface FailedDevice {
+  deviceId: string;
+  errorMessage: string;
+  errorCode: string;
+  isRetriable: boolean;
+}
+
+export interface BatchResult {
+  successCount: number;
+  failureCount: number;
+  totalCount: number;
+  successRate: number;
+  succeeded: string[];
+  failed: FailedDevice[];
+}
+
+export type MessageSeverity = "info" | "warning";
+
+export interface DisplayMessageOptions {
+  severity?: MessageSeverity;
+  /** Seconds to show the message for (1-3600); omit to use the device default */
+  durationSecs?: number;
+}
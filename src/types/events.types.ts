@@ -1,4 +1,6 @@
-import type { DeviceState } from './device.types';
+import type { InstalledApp } from './apk.types';
+import type { ApkInstallStatus, DeviceState, WifiStatus } from './device.types';
+import type { DownloadState } from '@/services/gameVersionService';
 
 export interface CommandResult {
   timestamp: string;
@@ -17,6 +19,19 @@ export type ArceusEvent =
       deviceId: string;
       serial: string;
     }
+  | {
+      type: 'deviceFlagged';
+      deviceId: string;
+      serial: string;
+      reason: string;
+    }
+  | {
+      type: 'deviceLeftApp';
+      deviceId: string;
+      serial: string;
+      expected: string;
+      actual: string;
+    }
   | {
       type: 'deviceUpdated';
       device: DeviceState;
@@ -29,6 +44,25 @@ export type ArceusEvent =
         isCharging: boolean;
       };
     }
+  | {
+      type: 'brightnessUpdated';
+      deviceId: string;
+      brightness: number;
+    }
+  | {
+      type: 'storageUpdated';
+      deviceId: string;
+      storageInfo: {
+        totalBytes: number;
+        freeBytes: number;
+        lastUpdated: string;
+      };
+    }
+  | {
+      type: 'wifiStatusUpdated';
+      deviceId: string;
+      wifiStatus: WifiStatus;
+    }
   | {
       type: 'volumeUpdated';
       deviceId: string;
@@ -46,7 +80,7 @@ export type ArceusEvent =
   | {
       type: 'installedAppsReceived';
       deviceId: string;
-      apps: string[];
+      apps: InstalledApp[];
     }
   | {
       type: 'deviceNameChanged';
@@ -62,6 +96,11 @@ export type ArceusEvent =
   | {
       type: 'serverStopped';
     }
+  | {
+      type: 'connectionRejected';
+      ipAddress: string;
+      reason: string;
+    }
   | {
       type: 'httpServerStarted';
       port: number;
@@ -97,12 +136,59 @@ export type ArceusEvent =
       gameId: number;
       gameName: string;
       percentage: number;
+      queuePosition: number | null;
+    }
+  | {
+      type: 'gameVerificationProgress';
+      gameId: number;
+      gameName: string;
+      verifiedFiles: number;
+      totalFiles: number;
+      currentFile: string;
+    }
+  | {
+      type: 'gameVerificationFailed';
+      gameId: number;
+      gameName: string;
+      file: string;
+      reason: string;
+    }
+  | {
+      type: 'gameDownloadStateChanged';
+      gameId: number;
+      gameName: string;
+      state: DownloadState;
+    }
+  | {
+      type: 'gameDeleted';
+      gameId: number;
+      gameName: string;
+      freedBytes: number;
+    }
+  | {
+      type: 'apkAddProgress';
+      filename: string;
+      bytesCopied: number;
+      totalBytes: number;
+    }
+  | {
+      type: 'apkBatchInstallProgress';
+      batchId: string;
+      deviceId: string | null;
+      serial: string | null;
+      status: ApkInstallStatus;
+      message: string | null;
+      completed: number;
+      total: number;
     }
   | {
       type: 'sensorUploadProgress';
       port: string;
       stage: string;
       percentage: number;
+      bytesSent: number | null;
+      totalBytes: number | null;
+      attempt: number | null;
     };
 
 export interface OperationProgress {
@@ -3,9 +3,23 @@ export type SensorConnectionStatus = 'connected' | 'bootloader' | 'disconnected'
 export interface Sensor {
   port: string;
   serial_number?: string;
+  /** USB serial number, known without opening the port */
+  usb_serial?: string;
   mac_address?: string;
   ble_mac_address?: string;
   device_name?: string;
   firmware_version?: string;
   status: SensorConnectionStatus;
 }
+
+export interface SensorFlashTarget {
+  port: string;
+  deviceName: string;
+}
+
+export interface SensorFlashResult {
+  port: string;
+  device_name: string;
+  success: boolean;
+  error?: string;
+}
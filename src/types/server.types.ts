@@ -0,0 +1,7 @@
+export interface ServerStats {
+  connected_devices: number;
+  max_connections: number;
+  /** Seconds since the device server started; null while it is stopped */
+  uptime_secs: number | null;
+  commands_executed: number;
+}
@@ -1,9 +1,15 @@
+export type UpdateChannel = 'stable' | 'beta';
+
 export interface UpdateInfo {
   version: string;
   currentVersion: string;
+  /** Markdown release notes from the manifest */
   body?: string;
   date?: string;
   isAvailable: boolean;
+  channel: UpdateChannel;
+  /** Older than the installed version, e.g. stable after a beta */
+  isDowngrade: boolean;
 }
 
 export type UpdateStatus =